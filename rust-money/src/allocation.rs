@@ -0,0 +1,147 @@
+//! # Zero-based budgeting: income assigned to envelopes until none is left.
+//!
+//! Envelopes are tags; spending of an envelope is the expenses of its tag.
+use crate::amount::Amount;
+use crate::ext::RequestFailure;
+use crate::order::{Order, OrderKind};
+use serde::{Deserialize, Serialize};
+
+/// Part of an income assigned to an envelope.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Allocation {
+    /// Tag of the envelope.
+    pub envelope: String,
+    /// Positive amount.
+    pub amount: Amount,
+}
+
+/// Allocations of an income order.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct IncomeAllocation {
+    /// Identifier of the income order.
+    pub order: usize,
+    pub allocations: Vec<Allocation>,
+}
+
+/// Funds of an envelope.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct EnvelopeBalance {
+    pub envelope: String,
+    pub allocated: Amount,
+    /// Expenses of the tag, as a positive amount.
+    pub spent: Amount,
+    /// Negative once overspent.
+    pub available: Amount,
+}
+
+/// Overview of allocated income.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct AllocationSummary {
+    /// Income of visible orders, transfers excluded.
+    pub income: Amount,
+    pub allocated: Amount,
+    /// Income left to assign.
+    pub unallocated: Amount,
+    pub envelopes: Vec<EnvelopeBalance>,
+}
+
+/// Sums the amounts of allocations.
+pub fn total(allocations: &[Allocation]) -> Result<Amount, RequestFailure> {
+    Amount::checked_sum(allocations.iter().map(|allocation| allocation.amount))
+        .ok_or(RequestFailure::Overflow)
+}
+
+/// Checks allocations of an income before storing them.
+pub fn validate(
+    income: Amount,
+    allocations: &[Allocation],
+    envelopes: &[String],
+) -> Option<RequestFailure> {
+    if allocations
+        .iter()
+        .any(|allocation| !envelopes.contains(&allocation.envelope))
+    {
+        Some(RequestFailure::UnknownItem)
+    } else if !income.is_positive()
+        || allocations
+            .iter()
+            .any(|allocation| !allocation.amount.is_positive())
+    {
+        Some(RequestFailure::IncorrectArgument)
+    } else {
+        match total(allocations) {
+            Ok(allocated) if allocated > income => Some(RequestFailure::InsufficientFunds),
+            Ok(_) => None,
+            Err(failure) => Some(failure),
+        }
+    }
+}
+
+/// Computes envelope funds from allocations of visible income orders.
+pub fn summarize(
+    orders: &[Order],
+    allocations: &[IncomeAllocation],
+) -> Result<AllocationSummary, RequestFailure> {
+    let visible = orders
+        .iter()
+        .filter(|order| order.is_visible())
+        .collect::<Vec<&Order>>();
+    let income = Amount::checked_sum(
+        visible
+            .iter()
+            .filter(|order| order.effective_kind() == OrderKind::Income)
+            .map(|order| order.amount),
+    )
+    .ok_or(RequestFailure::Overflow)?;
+    // Allocations of deleted or hidden orders do not count
+    let counted = allocations
+        .iter()
+        .filter(|item| visible.iter().any(|order| order.id == item.order))
+        .flat_map(|item| item.allocations.iter().cloned())
+        .collect::<Vec<Allocation>>();
+    let mut names = counted
+        .iter()
+        .map(|allocation| allocation.envelope.clone())
+        .collect::<Vec<String>>();
+    names.sort();
+    names.dedup();
+
+    let envelopes = names
+        .into_iter()
+        .map(|envelope| {
+            let allocated = total(
+                &counted
+                    .iter()
+                    .filter(|allocation| allocation.envelope == envelope)
+                    .cloned()
+                    .collect::<Vec<Allocation>>(),
+            )?;
+            let spent = Amount::checked_sum(
+                visible
+                    .iter()
+                    .filter(|order| order.amount.is_negative() && order.has_tag(&envelope))
+                    .map(|order| order.amount.abs()),
+            )
+            .ok_or(RequestFailure::Overflow)?;
+
+            Ok(EnvelopeBalance {
+                available: allocated
+                    .checked_sub(spent)
+                    .ok_or(RequestFailure::Overflow)?,
+                envelope,
+                allocated,
+                spent,
+            })
+        })
+        .collect::<Result<Vec<EnvelopeBalance>, RequestFailure>>()?;
+    let allocated = total(&counted)?;
+
+    Ok(AllocationSummary {
+        income,
+        allocated,
+        unallocated: income
+            .checked_sub(allocated)
+            .ok_or(RequestFailure::Overflow)?,
+        envelopes,
+    })
+}
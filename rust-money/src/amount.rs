@@ -0,0 +1,350 @@
+//! # Monetary values without rounding errors.
+use crate::ext::RequestFailure;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// Number of minor units (*cents*) within a major unit.
+const CENTS_PER_UNIT: i64 = 100;
+
+/// Fixed-point amount stored as an integer number of *cents*.
+///
+/// Serialized as a decimal number (`-13.99`) to stay compatible with files
+/// written when amounts were floating-point values; integers and strings are
+/// also accepted when loading.
+///
+/// Values are bounded by `Amount::MAX`: operators panic beyond it, while
+/// `checked_*` functions return `None`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+pub struct Amount(i64);
+
+impl Amount {
+    /// Null amount.
+    pub const ZERO: Amount = Amount(0);
+    /// Largest amount, exactly represented as a floating-point value.
+    pub const MAX: Amount = Amount((1 << 53) - 1);
+    /// Smallest amount.
+    pub const MIN: Amount = Amount(-Amount::MAX.0);
+
+    /// Creates an amount from a number of *cents*.
+    pub const fn from_cents(cents: i64) -> Amount {
+        Amount(cents)
+    }
+
+    /// Returns the number of *cents*.
+    pub const fn cents(self) -> i64 {
+        self.0
+    }
+
+    /// Converts a floating-point value, rounded to the nearest *cent*.
+    /// Returns `None` if the value is not finite or out of bounds.
+    pub fn from_f64(value: f64) -> Option<Amount> {
+        let cents = (value * CENTS_PER_UNIT as f64).round();
+
+        if cents.is_finite() && cents.abs() <= Amount::MAX.0 as f64 {
+            Some(Amount(cents as i64))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the amount of *cents* if within bounds.
+    fn bounded(cents: i64) -> Option<Amount> {
+        if (Amount::MIN.0..=Amount::MAX.0).contains(&cents) {
+            Some(Amount(cents))
+        } else {
+            None
+        }
+    }
+
+    /// Adds two amounts, or returns `None` out of bounds.
+    pub fn checked_add(self, other: Amount) -> Option<Amount> {
+        self.0.checked_add(other.0).and_then(Amount::bounded)
+    }
+
+    /// Subtracts two amounts, or returns `None` out of bounds.
+    pub fn checked_sub(self, other: Amount) -> Option<Amount> {
+        self.0.checked_sub(other.0).and_then(Amount::bounded)
+    }
+
+    /// Sums amounts, or returns `None` as soon as a partial sum is out of
+    /// bounds.
+    pub fn checked_sum<I: IntoIterator<Item = Amount>>(amounts: I) -> Option<Amount> {
+        amounts
+            .into_iter()
+            .try_fold(Amount::ZERO, |total, amount| total.checked_add(amount))
+    }
+
+    /// Converts to a floating-point value, mainly for display purpose.
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64 / CENTS_PER_UNIT as f64
+    }
+
+    /// Returns the absolute value.
+    pub fn abs(self) -> Amount {
+        Amount(self.0.abs())
+    }
+
+    /// Returns `true` for strictly negative amounts.
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    /// Returns `true` for strictly positive amounts.
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+}
+
+impl Add for Amount {
+    type Output = Amount;
+
+    fn add(self, other: Amount) -> Amount {
+        self.checked_add(other).expect("amount overflow")
+    }
+}
+
+impl AddAssign for Amount {
+    fn add_assign(&mut self, other: Amount) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Amount {
+    type Output = Amount;
+
+    fn sub(self, other: Amount) -> Amount {
+        self.checked_sub(other).expect("amount overflow")
+    }
+}
+
+impl SubAssign for Amount {
+    fn sub_assign(&mut self, other: Amount) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Amount {
+    type Output = Amount;
+
+    fn neg(self) -> Amount {
+        Amount(-self.0)
+    }
+}
+
+impl Sum for Amount {
+    fn sum<I: Iterator<Item = Amount>>(iter: I) -> Amount {
+        iter.fold(Amount::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Amount> for Amount {
+    fn sum<I: Iterator<Item = &'a Amount>>(iter: I) -> Amount {
+        iter.copied().sum()
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.is_negative() { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+
+        write!(
+            f,
+            "{}{}.{:02}",
+            sign,
+            cents / CENTS_PER_UNIT as u64,
+            cents % CENTS_PER_UNIT as u64
+        )
+    }
+}
+
+impl FromStr for Amount {
+    type Err = RequestFailure;
+
+    /// Parses a decimal string with at most 2 fractional digits (`-13.99`).
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (units, fraction) = match digits.split_once('.') {
+            Some((units, fraction)) => (units, fraction),
+            None => (digits, ""),
+        };
+
+        if digits.is_empty() {
+            return Err(RequestFailure::EmptyArgument);
+        }
+        if (units.is_empty() && fraction.is_empty())
+            || fraction.len() > 2
+            || !units
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(RequestFailure::IncorrectArgument);
+        }
+
+        let units = if units.is_empty() {
+            0
+        } else {
+            units
+                .parse::<i64>()
+                .map_err(|_| RequestFailure::IncorrectArgument)?
+        };
+        let fraction = format!("{:0<2}", fraction)
+            .parse::<i64>()
+            .map_err(|_| RequestFailure::IncorrectArgument)?;
+        let cents = units
+            .checked_mul(CENTS_PER_UNIT)
+            .and_then(|cents| cents.checked_add(fraction))
+            .and_then(Amount::bounded)
+            .ok_or(RequestFailure::IncorrectArgument)?;
+
+        Ok(if negative { -cents } else { cents })
+    }
+}
+
+impl Serialize for Amount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_f64())
+    }
+}
+
+/// Accepts integers, floating-point values and decimal strings.
+struct AmountVisitor;
+
+impl<'de> Visitor<'de> for AmountVisitor {
+    type Value = Amount;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a decimal amount")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Amount, E> {
+        value
+            .checked_mul(CENTS_PER_UNIT)
+            .and_then(Amount::bounded)
+            .ok_or_else(|| E::custom("amount out of range"))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Amount, E> {
+        i64::try_from(value)
+            .map_err(|_| E::custom("amount out of range"))
+            .and_then(|value| self.visit_i64(value))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Amount, E> {
+        Amount::from_f64(value).ok_or_else(|| E::custom("amount out of range"))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Amount, E> {
+        Amount::from_str(value).map_err(|_| E::invalid_value(de::Unexpected::Str(value), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Amount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Amount, D::Error> {
+        deserializer.deserialize_any(AmountVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sum_without_rounding_errors() {
+        let amounts = [Amount::from_cents(10); 10];
+        let total: Amount = amounts.iter().sum();
+
+        assert_eq!(total, Amount::from_cents(100));
+        assert_eq!(
+            Amount::from_cents(-1399) + Amount::from_cents(1399),
+            Amount::ZERO
+        );
+        assert_eq!(-Amount::from_cents(20), Amount::from_cents(-20));
+    }
+
+    #[test]
+    fn detect_overflow() {
+        assert_eq!(Amount::MAX.checked_add(Amount::from_cents(1)), None);
+        assert_eq!(Amount::MIN.checked_sub(Amount::from_cents(1)), None);
+        assert_eq!(
+            Amount::checked_sum(vec![Amount::MAX, Amount::MAX, Amount::MIN]),
+            None
+        );
+        assert_eq!(
+            Amount::checked_sum(vec![Amount::MAX, Amount::MIN]),
+            Some(Amount::ZERO)
+        );
+        assert_eq!(Amount::from_f64(1e14), None);
+        assert_eq!(Amount::from_str("90071992547409.91"), Ok(Amount::MAX));
+        assert_eq!(
+            Amount::from_str("90071992547409.92"),
+            Err(RequestFailure::IncorrectArgument)
+        );
+    }
+
+    #[test]
+    fn convert_floating_point_value() {
+        assert_eq!(Amount::from_f64(-13.99), Some(Amount::from_cents(-1399)));
+        assert_eq!(Amount::from_f64(0.1 + 0.2), Some(Amount::from_cents(30)));
+        assert_eq!(Amount::from_f64(f64::NAN), None);
+        assert_eq!(Amount::from_cents(-1399).as_f64(), -13.99);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Amount::from_cents(-1399).to_string(), "-13.99");
+        assert_eq!(Amount::from_cents(5).to_string(), "0.05");
+        assert_eq!(Amount::from_cents(-5).to_string(), "-0.05");
+        assert_eq!(Amount::from_cents(100000).to_string(), "1000.00");
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("-13.99".parse(), Ok(Amount::from_cents(-1399)));
+        assert_eq!("+2.5".parse(), Ok(Amount::from_cents(250)));
+        assert_eq!(" 1000 ".parse(), Ok(Amount::from_cents(100000)));
+        assert_eq!(".5".parse(), Ok(Amount::from_cents(50)));
+        assert_eq!("".parse::<Amount>(), Err(RequestFailure::EmptyArgument));
+        assert_eq!(
+            "1.999".parse::<Amount>(),
+            Err(RequestFailure::IncorrectArgument)
+        );
+        assert_eq!(
+            "1,99".parse::<Amount>(),
+            Err(RequestFailure::IncorrectArgument)
+        );
+        assert_eq!(
+            "-.".parse::<Amount>(),
+            Err(RequestFailure::IncorrectArgument)
+        );
+    }
+
+    #[test]
+    fn load_legacy_and_textual_values() {
+        let amounts: Vec<Amount> = serde_yaml::from_str("[-13.99, 50, '12.30', 1000.0]").unwrap();
+
+        assert_eq!(
+            amounts,
+            vec![
+                Amount::from_cents(-1399),
+                Amount::from_cents(5000),
+                Amount::from_cents(1230),
+                Amount::from_cents(100000),
+            ]
+        );
+        assert_eq!(
+            serde_yaml::to_string(&Amount::from_cents(-1399)).unwrap(),
+            "---\n-13.99\n"
+        );
+    }
+}
@@ -0,0 +1,29 @@
+//! # Documents associated to orders (scanned receipts, invoices, ..).
+use serde::{Deserialize, Serialize};
+
+/// Reference to a document stored outside of the account file.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Attachment {
+    /// Unique name within an order.
+    pub filename: String,
+    /// Media type, such as `image/jpeg`.
+    pub mime: String,
+    /// Digest of the content, to detect changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    /// Location of a remote document.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl Attachment {
+    /// Creates a local attachment.
+    pub fn new(filename: &str, mime: &str) -> Attachment {
+        Attachment {
+            filename: filename.into(),
+            mime: mime.into(),
+            hash: None,
+            url: None,
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! # Reproducible random sampling of orders, to spot-check receipts.
+use crate::order::Order;
+
+/// Small pseudo-random generator (SplitMix64), stable across platforms.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number within `(0, 1]`.
+    fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Picks up to `count` distinct orders, returned by identifier in draw order.
+///
+/// If `weighted`, the chance of an order is proportional to its absolute
+/// amount and orders without amount come last. The same seed and orders
+/// always give the same sample.
+pub fn sample(orders: &[&Order], count: usize, weighted: bool, seed: u64) -> Vec<usize> {
+    let mut generator = SplitMix64(seed);
+    // Weighted sampling without replacement (Efraimidis-Spirakis)
+    let mut keys = orders
+        .iter()
+        .map(|order| {
+            let random = generator.next_f64();
+            let key = match order.amount.cents().unsigned_abs() {
+                _ if !weighted => random,
+                0 => f64::NEG_INFINITY,
+                weight => random.ln() / weight as f64,
+            };
+            (order.id, key)
+        })
+        .collect::<Vec<(usize, f64)>>();
+
+    keys.sort_by(|a, b| b.1.total_cmp(&a.1));
+    keys.into_iter().take(count).map(|(id, _)| id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::Amount;
+
+    #[test]
+    fn sample_orders_reproducibly() {
+        let orders = (0..20)
+            .map(|id| Order {
+                id,
+                amount: Amount::from_cents(if id == 7 { -1_000_000 } else { -100 }),
+                ..Order::default()
+            })
+            .collect::<Vec<Order>>();
+        let orders = orders.iter().collect::<Vec<&Order>>();
+        let first = sample(&orders, 5, false, 42);
+
+        assert_eq!(first.len(), 5);
+        assert_eq!(first, sample(&orders, 5, false, 42));
+        assert_ne!(first, sample(&orders, 5, false, 43));
+        assert_eq!(sample(&orders, 50, false, 1).len(), 20);
+        // Larger amounts are much more likely to be picked
+        assert_eq!(
+            (0..20)
+                .filter(|seed| sample(&orders, 1, true, *seed) == vec![7])
+                .count()
+                > 15,
+            true
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! # Timestamped copies of account files, taken before they are replaced.
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Name of the folder holding copies, next to the file.
+pub const BACKUP_FOLDER: &str = ".backups";
+/// Timestamp of a copy, after the name of the file.
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%.6f";
+const EXTENSION: &str = ".bak";
+
+/// Copies to keep when saving over a file.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct BackupPolicy {
+    /// Number of most recent copies kept.
+    pub keep: usize,
+    /// Copies are stored in a `.backups/` folder rather than next to the file.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub folder: bool,
+}
+
+/// Returns when a copy of a file was taken; `None` if it is not a copy.
+pub fn timestamp(path: &Path, copy: &Path) -> Option<NaiveDateTime> {
+    let timestamp = copy
+        .file_name()?
+        .to_str()?
+        .strip_prefix(path.file_name()?.to_str()?)?
+        .strip_prefix('.')?
+        .strip_suffix(EXTENSION)?;
+    NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()
+}
+
+/// Returns the copies of a file held by a folder, from the oldest to the most
+/// recent.
+pub(crate) fn list_in(directory: &Path, path: &Path) -> io::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let mut copies = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|copy| timestamp(path, copy).is_some())
+        .collect::<Vec<PathBuf>>();
+    // Timestamps have a fixed width
+    copies.sort();
+    Ok(copies)
+}
+
+/// Copies a file into a folder, created if missing. Returns the path of the
+/// copy, if the file exists.
+pub(crate) fn copy_into(
+    directory: &Path,
+    path: &Path,
+    now: NaiveDateTime,
+) -> io::Result<Option<PathBuf>> {
+    let name = match path.file_name() {
+        Some(name) if path.is_file() => name.to_string_lossy(),
+        _ => return Ok(None),
+    };
+    fs::create_dir_all(directory)?;
+    let copy = directory.join(format!(
+        "{}.{}{}",
+        name,
+        now.format(TIMESTAMP_FORMAT),
+        EXTENSION
+    ));
+    fs::copy(path, &copy)?;
+    Ok(Some(copy))
+}
+
+impl BackupPolicy {
+    /// Returns the folder of the copies of a file.
+    fn directory(&self, path: &Path) -> PathBuf {
+        let parent = path.parent().unwrap_or_else(|| Path::new(""));
+        if self.folder {
+            parent.join(BACKUP_FOLDER)
+        } else {
+            parent.into()
+        }
+    }
+
+    /// Returns the copies of a file, from the oldest to the most recent.
+    pub fn list(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        list_in(&self.directory(path), path)
+    }
+
+    /// Copies a file before it is replaced, then removes the oldest copies.
+    /// Returns the path of the new copy, if the file exists.
+    pub fn rotate(&self, path: &Path, now: NaiveDateTime) -> io::Result<Option<PathBuf>> {
+        let copy = match copy_into(&self.directory(path), path, now)? {
+            Some(copy) => copy,
+            None => return Ok(None),
+        };
+
+        let copies = self.list(path)?;
+        copies
+            .iter()
+            .take(copies.len().saturating_sub(self.keep))
+            .try_for_each(fs::remove_file)?;
+        Ok(Some(copy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_directory;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn keep_most_recent_copies() {
+        let directory = test_directory("keep_most_recent_copies");
+        let path = directory.join("money.yml");
+        let policy = BackupPolicy {
+            keep: 2,
+            folder: true,
+        };
+        let time = |second| NaiveDate::from_ymd(2021, 3, 2).and_hms(10, 0, second);
+
+        let missing = policy.rotate(&path, time(0)).unwrap();
+        let copies = (1..=3)
+            .map(|second| {
+                fs::write(&path, format!("label: {}", second)).unwrap();
+                policy.rotate(&path, time(second)).unwrap().unwrap()
+            })
+            .collect::<Vec<PathBuf>>();
+        fs::write(directory.join(BACKUP_FOLDER).join("other.yml.bak"), "").unwrap();
+        let listed = policy.list(&path).unwrap();
+        let content = fs::read_to_string(&listed[1]).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(missing, None);
+        assert_eq!(
+            copies[0],
+            directory
+                .join(BACKUP_FOLDER)
+                .join("money.yml.20210302-100001.000000.bak")
+        );
+        assert_eq!(listed, copies[1..]);
+        assert_eq!(content, "label: 3");
+    }
+}
@@ -0,0 +1,98 @@
+//! # Command-line interface of `money`.
+use std::path::Path;
+use std::process;
+
+const USAGE: &str = "Usage: money <command> <file>
+
+Commands:
+  sync    Fetches bank transactions, applies recurring rules and saves the file
+  lint    Lists suggestions to tidy the file up";
+
+/// Fetches transactions of all linked bank accounts.
+///
+/// Credentials are read from `GOCARDLESS_SECRET_ID`, `GOCARDLESS_SECRET_KEY`
+/// and `GOCARDLESS_REQUISITION_ID`. Bank accounts are assigned to the resource
+/// sharing their name, if any.
+#[cfg(feature = "network")]
+fn sync(path: &Path) -> Result<(), String> {
+    use chrono::{Duration, Local};
+    use rust_money::import::gocardless::GoCardlessConnector;
+    use rust_money::import::BankConnector;
+    use rust_money::sync::{SyncEngine, SyncSource};
+    use rust_money::Account;
+
+    let variable = |name: &str| std::env::var(name).map_err(|_| format!("{} is not set", name));
+    let mut connector = GoCardlessConnector::new(
+        &variable("GOCARDLESS_SECRET_ID")?,
+        &variable("GOCARDLESS_SECRET_KEY")?,
+        &variable("GOCARDLESS_REQUISITION_ID")?,
+    );
+    let mut account = Account::load_file(path).map_err(|error| error.to_string())?;
+    let now = Local::now().naive_local();
+    let bank_accounts = connector
+        .list_accounts()
+        .map_err(|error| error.to_string())?;
+    let mut engine = SyncEngine::new(connector);
+
+    bank_accounts.into_iter().for_each(|bank_account| {
+        engine.add_source(SyncSource {
+            resource: account
+                .resources()
+                .iter()
+                .find(|resource| **resource == bank_account.name)
+                .cloned(),
+            account_id: bank_account.id,
+            since: now.date() - Duration::days(90),
+        })
+    });
+    engine.set_path(path.into());
+
+    let result = engine.run(&mut account, now);
+    let status = engine.status();
+    println!(
+        "{} new orders, {} already known, {} retries",
+        status.imported, status.duplicates, status.retries
+    );
+
+    result.map_err(|error| error.to_string())
+}
+
+#[cfg(not(feature = "network"))]
+fn sync(_: &Path) -> Result<(), String> {
+    Err("money was built without the `network` feature".into())
+}
+
+/// Prints suggestions about the account, one per line.
+#[cfg(feature = "reporting")]
+fn lint(path: &Path) -> Result<(), String> {
+    let account = rust_money::Account::load_file(path).map_err(|error| error.to_string())?;
+    let issues = account.lint();
+
+    issues.iter().for_each(|issue| println!("{}", issue));
+    println!("{} suggestions", issues.len());
+
+    Ok(())
+}
+
+#[cfg(not(feature = "reporting"))]
+fn lint(_: &Path) -> Result<(), String> {
+    Err("money was built without the `reporting` feature".into())
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+    let result = match args.as_slice() {
+        [command, file] if command == "sync" => sync(Path::new(file)),
+        [command, file] if command == "lint" => lint(Path::new(file)),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(error) = result {
+        eprintln!("money: {}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,140 @@
+//! # Spending limits of tags per period, compared with actual spending.
+use crate::amount::Amount;
+use crate::ext::RequestFailure;
+use crate::order::Order;
+use crate::stats::Period;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Spending limit of a tag.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TagBudget {
+    pub tag: String,
+    /// Length of the periods the limit applies to.
+    pub period: Period,
+    /// Positive amount.
+    pub amount: Amount,
+}
+
+/// Budgeted and spent amounts of a tag within a period.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct BudgetLine {
+    pub tag: String,
+    /// First day of the period.
+    pub start: NaiveDate,
+    /// Last day of the period.
+    pub end: NaiveDate,
+    pub budgeted: Amount,
+    /// Expenses of the tag, as a positive amount.
+    pub spent: Amount,
+    /// Negative once overspent.
+    pub remaining: Amount,
+    /// Spent percentage of the budget, beyond 100 once overspent.
+    pub progress: f64,
+}
+
+/// Comparison of budgets with actual spending.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct BudgetReport {
+    /// Date whose periods are reported.
+    pub date: NaiveDate,
+    pub lines: Vec<BudgetLine>,
+    /// Tags over budget.
+    pub overspent: Vec<String>,
+}
+
+/// Compares each budget with the expenses of visible orders within its
+/// period holding a date.
+///
+/// Fails with `RequestFailure::Overflow` if a sum overflows.
+pub fn report(
+    orders: &[Order],
+    budgets: &[TagBudget],
+    date: NaiveDate,
+) -> Result<BudgetReport, RequestFailure> {
+    let lines = budgets
+        .iter()
+        .map(|budget| {
+            let (start, end) = budget.period.bounds(date);
+            let spent = Amount::checked_sum(
+                orders
+                    .iter()
+                    .filter(|order| {
+                        order.is_visible()
+                            && order.amount.is_negative()
+                            && order.has_tag(&budget.tag)
+                            && order.date.is_some_and(|date| date >= start && date <= end)
+                    })
+                    .map(|order| order.amount.abs()),
+            )
+            .ok_or(RequestFailure::Overflow)?;
+
+            Ok(BudgetLine {
+                tag: budget.tag.clone(),
+                start,
+                end,
+                budgeted: budget.amount,
+                spent,
+                remaining: budget
+                    .amount
+                    .checked_sub(spent)
+                    .ok_or(RequestFailure::Overflow)?,
+                progress: if budget.amount == Amount::ZERO {
+                    0.0
+                } else {
+                    spent.cents() as f64 * 100.0 / budget.amount.cents() as f64
+                },
+            })
+        })
+        .collect::<Result<Vec<BudgetLine>, RequestFailure>>()?;
+
+    Ok(BudgetReport {
+        date,
+        overspent: lines
+            .iter()
+            .filter(|line| line.remaining.is_negative())
+            .map(|line| line.tag.clone())
+            .collect(),
+        lines,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_budgets_with_spending() {
+        let order = |day: u32, cents: i64| Order {
+            date: NaiveDate::from_ymd_opt(2021, 3, day),
+            amount: Amount::from_cents(cents),
+            tags: vec!["Food".into()],
+            ..Order::default()
+        };
+        let orders = [order(1, -20000), order(8, -25000), order(9, 5000)];
+        let budgets = [
+            TagBudget {
+                tag: "Food".into(),
+                period: Period::Month,
+                amount: Amount::from_cents(40000),
+            },
+            TagBudget {
+                tag: "Food".into(),
+                period: Period::Week,
+                amount: Amount::from_cents(30000),
+            },
+        ];
+
+        let report = report(&orders, &budgets, NaiveDate::from_ymd(2021, 3, 10)).unwrap();
+
+        assert_eq!(
+            report
+                .lines
+                .iter()
+                .map(|line| (line.spent.cents(), line.remaining.cents(), line.progress))
+                .collect::<Vec<(i64, i64, f64)>>(),
+            [(45000, -5000, 112.5), (25000, 5000, 250.0 / 3.0)]
+        );
+        assert_eq!(report.overspent, ["Food"]);
+    }
+}
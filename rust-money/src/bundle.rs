@@ -0,0 +1,138 @@
+//! # Single-file backup of an `Account`.
+use crate::{Account, SCHEMA_VERSION};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::io::{self, Read, Seek, Write};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Name of the manifest within a bundle.
+const MANIFEST_FILE: &str = "manifest.yml";
+/// Name of the account file within a bundle.
+const ACCOUNT_FILE: &str = "account.yml";
+
+/// Describes the content of a bundle.
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct BundleManifest {
+    /// Schema version of the account file.
+    pub schema_version: u32,
+    /// Account file, which also holds the configuration (projects, budgets,
+    /// places).
+    pub account: String,
+    /// Documents attached to orders.
+    #[serde(default)]
+    pub attachments: Vec<String>,
+}
+
+/// Converts any error into an `InvalidData` one.
+fn invalid_data<E: ToString>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+/// Writes a zip bundle containing the account and its manifest.
+pub fn write_bundle<W: Write + Seek>(account: &Account, writer: W) -> io::Result<BundleManifest> {
+    let manifest = BundleManifest {
+        schema_version: SCHEMA_VERSION,
+        account: ACCOUNT_FILE.into(),
+        attachments: Vec::new(),
+    };
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(writer);
+
+    zip.start_file(MANIFEST_FILE, options)?;
+    zip.write_all(
+        serde_yaml::to_string(&manifest)
+            .map_err(invalid_data)?
+            .as_bytes(),
+    )?;
+    zip.start_file(ACCOUNT_FILE, options)?;
+    zip.write_all(
+        serde_yaml::to_string(account)
+            .map_err(invalid_data)?
+            .as_bytes(),
+    )?;
+    zip.finish()?;
+
+    Ok(manifest)
+}
+
+/// Reads a zip bundle written by `write_bundle`.
+///
+/// Bundles written by a newer version are rejected.
+pub fn read_bundle<R: Read + Seek>(reader: R) -> io::Result<(Account, BundleManifest)> {
+    let mut zip = ZipArchive::new(reader)?;
+    let mut content = String::new();
+
+    zip.by_name(MANIFEST_FILE)?.read_to_string(&mut content)?;
+    let manifest: BundleManifest = serde_yaml::from_str(&content).map_err(invalid_data)?;
+
+    if manifest.schema_version > SCHEMA_VERSION {
+        return Err(invalid_data(format!(
+            "unsupported schema version {}",
+            manifest.schema_version
+        )));
+    }
+
+    content.clear();
+    zip.by_name(&manifest.account)?
+        .read_to_string(&mut content)?;
+    let account = Account::try_from(content.as_str()).map_err(invalid_data)?;
+
+    Ok((account, manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn write_read_bundle() {
+        let mut account = Account::create();
+        account.set_label("Family");
+        account.add_tag("Food");
+        account.add_project("Kitchen");
+        account.set_project_budget("Kitchen", 3000.0);
+        account.add_order();
+
+        let mut buffer = Cursor::new(Vec::new());
+        let manifest = write_bundle(&account, &mut buffer).unwrap();
+
+        assert_eq!(manifest.schema_version, SCHEMA_VERSION);
+
+        buffer.set_position(0);
+        let (loaded_account, loaded_manifest) = read_bundle(buffer).unwrap();
+
+        assert_eq!(loaded_manifest, manifest);
+        assert_eq!(loaded_account, account);
+    }
+
+    #[test]
+    fn reject_newer_schema_version() {
+        let mut buffer = Cursor::new(Vec::new());
+        let mut zip = ZipWriter::new(&mut buffer);
+        zip.start_file(MANIFEST_FILE, FileOptions::default())
+            .unwrap();
+        write!(
+            zip,
+            "schema_version: {}\naccount: {}\n",
+            SCHEMA_VERSION + 1,
+            ACCOUNT_FILE
+        )
+        .unwrap();
+        zip.finish().unwrap();
+        drop(zip);
+
+        buffer.set_position(0);
+        let error = read_bundle(buffer).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn reject_invalid_archive() {
+        let buffer = Cursor::new(b"label: MONEY".to_vec());
+
+        assert_eq!(read_bundle(buffer).is_err(), true);
+    }
+}
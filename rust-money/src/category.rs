@@ -0,0 +1,298 @@
+//! # Tags and resources with their display details.
+use crate::amount::Amount;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "wasmbind")]
+use wasm_bindgen::prelude::*;
+
+/// Nature of the money held by a resource.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum ResourceKind {
+    Bank,
+    Cash,
+    /// Liability: its balance is the amount owed.
+    CreditCard,
+    Savings,
+}
+
+impl ResourceKind {
+    /// Returns `true` for money owed rather than held.
+    pub fn is_liability(self) -> bool {
+        self == ResourceKind::CreditCard
+    }
+
+    /// Returns `true` for money available right away.
+    pub fn is_liquid(self) -> bool {
+        matches!(self, ResourceKind::Bank | ResourceKind::Cash)
+    }
+}
+
+/// Balance of a resource when it started being tracked.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct OpeningBalance {
+    pub amount: Amount,
+    pub date: NaiveDate,
+}
+
+/// Bound the balance of a resource should respect.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum BalanceLimit {
+    /// Largest amount which can be owed.
+    Credit(Amount),
+    /// Lowest balance to keep.
+    Minimum(Amount),
+}
+
+impl BalanceLimit {
+    /// Returns `true` if a balance breaks the limit. Balances of liabilities
+    /// are amounts owed.
+    pub fn is_violated(self, balance: Amount, liability: bool) -> bool {
+        match self {
+            BalanceLimit::Credit(limit) => {
+                let owed = if liability { balance } else { -balance };
+                owed > limit
+            }
+            BalanceLimit::Minimum(minimum) => balance < minimum,
+        }
+    }
+}
+
+/// Tag or resource, with optional details to render it.
+///
+/// Stored as its bare name until some detail is set.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default, Debug)]
+#[serde(from = "StoredEntry", into = "StoredEntry")]
+pub struct CategoryEntry {
+    pub name: String,
+    /// Hexadecimal color code, like `#ff8800`.
+    pub color: Option<String>,
+    /// Emoji or icon name.
+    pub icon: Option<String>,
+    pub description: Option<String>,
+    /// Kind of a resource.
+    pub kind: Option<ResourceKind>,
+    /// Opening balance of a resource.
+    pub opening: Option<OpeningBalance>,
+    /// Currency code of a resource; the account one if `None`.
+    pub currency: Option<String>,
+    /// Hidden from pickers but kept on existing orders.
+    pub archived: bool,
+    /// Balance limit of a resource.
+    pub limit: Option<BalanceLimit>,
+}
+
+/// Serialized forms of a `CategoryEntry`.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum StoredEntry {
+    Name(String),
+    Details {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        color: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        icon: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        description: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        kind: Option<ResourceKind>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        opening: Option<OpeningBalance>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        currency: Option<String>,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        archived: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<BalanceLimit>,
+    },
+}
+
+impl From<StoredEntry> for CategoryEntry {
+    fn from(entry: StoredEntry) -> Self {
+        match entry {
+            StoredEntry::Name(name) => CategoryEntry::new(&name),
+            StoredEntry::Details {
+                name,
+                color,
+                icon,
+                description,
+                kind,
+                opening,
+                currency,
+                archived,
+                limit,
+            } => CategoryEntry {
+                name,
+                color,
+                icon,
+                description,
+                kind,
+                opening,
+                currency,
+                archived,
+                limit,
+            },
+        }
+    }
+}
+
+impl From<CategoryEntry> for StoredEntry {
+    fn from(entry: CategoryEntry) -> Self {
+        if entry.has_details() {
+            StoredEntry::Details {
+                name: entry.name,
+                color: entry.color,
+                icon: entry.icon,
+                description: entry.description,
+                kind: entry.kind,
+                opening: entry.opening,
+                currency: entry.currency,
+                archived: entry.archived,
+                limit: entry.limit,
+            }
+        } else {
+            StoredEntry::Name(entry.name)
+        }
+    }
+}
+
+impl PartialEq<str> for CategoryEntry {
+    fn eq(&self, name: &str) -> bool {
+        self.name == name
+    }
+}
+
+impl CategoryEntry {
+    /// Creates an entry without details.
+    pub fn new(name: &str) -> CategoryEntry {
+        CategoryEntry {
+            name: name.into(),
+            ..CategoryEntry::default()
+        }
+    }
+
+    /// Returns `true` if some detail is set.
+    pub fn has_details(&self) -> bool {
+        self.color.is_some()
+            || self.icon.is_some()
+            || self.description.is_some()
+            || self.kind.is_some()
+            || self.opening.is_some()
+            || self.currency.is_some()
+            || self.archived
+            || self.limit.is_some()
+    }
+
+    /// Sets all details; empty ones are cleared.
+    /// Returns `false` if the color is not like `#f80` or `#ff8800`.
+    pub fn set_details(&mut self, color: &str, icon: &str, description: &str) -> bool {
+        let color = color.trim();
+        let is_color = color.strip_prefix('#').is_some_and(|digits| {
+            (digits.len() == 3 || digits.len() == 6)
+                && digits.chars().all(|digit| digit.is_ascii_hexdigit())
+        });
+
+        if !color.is_empty() && !is_color {
+            return false;
+        }
+
+        let optional = |text: &str| Some(text.trim().to_string()).filter(|text| !text.is_empty());
+        self.color = optional(color).map(|color| color.to_lowercase());
+        self.icon = optional(icon);
+        self.description = optional(description);
+        true
+    }
+}
+
+/// Named set of tags, like "Essentials", whose amounts are summed together.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TagGroup {
+    pub name: String,
+    pub tags: Vec<String>,
+}
+
+/// Available tags which an order can own.
+pub trait TagResolver {
+    /// Returns the canonical name of an available tag or alias.
+    fn resolve_tag(&self, tag: &str) -> Option<String>;
+}
+
+impl<T: AsRef<[String]> + ?Sized> TagResolver for T {
+    fn resolve_tag(&self, tag: &str) -> Option<String> {
+        self.as_ref().iter().find(|item| *item == tag).cloned()
+    }
+}
+
+/// Names of tags along with their aliases.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TagCatalog {
+    pub tags: Vec<String>,
+    /// Canonical tag of each alias.
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl TagResolver for TagCatalog {
+    fn resolve_tag(&self, tag: &str) -> Option<String> {
+        self.tags
+            .resolve_tag(tag)
+            .or_else(|| self.aliases.get(tag).cloned())
+    }
+}
+
+/// Returns names of entries.
+pub fn names(entries: &[CategoryEntry]) -> Vec<String> {
+    entries.iter().map(|entry| entry.name.clone()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_bare_names_until_detailed() {
+        let mut entries =
+            serde_yaml::from_str::<Vec<CategoryEntry>>("- Food\n- name: Travel\n  icon: \"✈\"\n")
+                .unwrap();
+
+        assert_eq!(entries[0], CategoryEntry::new("Food"));
+        assert_eq!(entries[1].icon, Some("✈".into()));
+        assert_eq!(entries[0].set_details("#FF8800", "", " Groceries "), true);
+        assert_eq!(entries[1].set_details("orange", "", ""), false);
+        assert_eq!(entries[1].icon, Some("✈".into()));
+        assert_eq!(entries[1].set_details("", "", ""), true);
+        assert_eq!(
+            serde_yaml::to_string(&entries).unwrap(),
+            "---\n- name: Food\n  color: \"#ff8800\"\n  description: Groceries\n- Travel\n"
+        );
+    }
+
+    #[test]
+    fn resolve_tag_aliases() {
+        let catalog = TagCatalog {
+            tags: vec!["Restaurant".into()],
+            aliases: vec![("Restos".to_string(), "Restaurant".to_string())]
+                .into_iter()
+                .collect(),
+        };
+
+        assert_eq!(catalog.resolve_tag("Restos"), Some("Restaurant".into()));
+        assert_eq!(catalog.resolve_tag("Restaurant"), Some("Restaurant".into()));
+        assert_eq!(catalog.resolve_tag("Food"), None);
+        assert_eq!(catalog.tags.resolve_tag("Restos"), None);
+    }
+
+    #[test]
+    fn check_balance_limits() {
+        let credit = BalanceLimit::Credit(Amount::from_cents(50000));
+        let minimum = BalanceLimit::Minimum(Amount::from_cents(10000));
+
+        assert_eq!(credit.is_violated(Amount::from_cents(50000), true), false);
+        assert_eq!(credit.is_violated(Amount::from_cents(50001), true), true);
+        // Overdraft of an asset
+        assert_eq!(credit.is_violated(Amount::from_cents(-60000), false), true);
+        assert_eq!(minimum.is_violated(Amount::from_cents(9999), false), true);
+        assert_eq!(minimum.is_violated(Amount::from_cents(10000), false), false);
+    }
+}
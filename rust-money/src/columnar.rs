@@ -0,0 +1,546 @@
+//! # Column-oriented copy of orders, for fast whole-history aggregation.
+use crate::amount::Amount;
+use crate::ext::{CategoryAmount, CategoryStats, CategoryType, CurrencyAmount, RequestFailure};
+use crate::order::{Order, TransactionState};
+use chrono::{Datelike, NaiveDate};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use CategoryType::{Payee, Project, Resource, Tag};
+
+/// Stored day of undated orders, lower than any date.
+const NO_DATE: i32 = i32::MIN;
+/// Number of tags held by a bitset word.
+const WORD_BITS: usize = 64;
+
+/// Distinct names of a column, each stored once and known by its position.
+#[derive(Clone, Default, PartialEq, Debug)]
+struct Names {
+    names: Vec<String>,
+    positions: HashMap<String, usize>,
+}
+
+impl Names {
+    /// Returns the position of a name, added if unknown.
+    fn insert(&mut self, name: &str) -> usize {
+        if let Some(&position) = self.positions.get(name) {
+            return position;
+        }
+        self.positions.insert(name.to_string(), self.names.len());
+        self.names.push(name.to_string());
+        self.names.len() - 1
+    }
+
+    /// Returns the position of a known name.
+    fn position(&self, name: &str) -> Option<usize> {
+        self.positions.get(name).copied()
+    }
+}
+
+/// Visible orders split into one array per attribute.
+///
+/// Spending figures sum amounts as is, whereas *category* amounts tell
+/// currencies apart.
+#[derive(Clone, PartialEq, Debug)]
+pub struct OrderColumns {
+    /// Days since the common era.
+    days: Vec<i32>,
+    cents: Vec<i64>,
+    states: Vec<TransactionState>,
+    /// Positions of currencies, resources, projects and payees.
+    currencies: Vec<Option<usize>>,
+    /// Currency position and cents held by the resource, once converted.
+    conversions: Vec<Option<(usize, i64)>>,
+    resources: Vec<Option<usize>>,
+    projects: Vec<Option<usize>>,
+    payees: Vec<Option<usize>>,
+    /// Currencies, resources, projects and payees.
+    names: Names,
+    /// Known tags, indexed by their bit.
+    tags: Names,
+    /// Number of bitset words per order.
+    words: usize,
+    /// Tag bitsets of all orders, `words` after `words`.
+    tag_bits: Vec<u64>,
+}
+
+/// Currency position, sums and order amounts of a *category*.
+type CategoryTotal = (Option<usize>, CategoryAmount, Vec<Amount>);
+
+/// Converts an optional date into a stored day.
+fn day(date: Option<NaiveDate>) -> i32 {
+    date.map_or(NO_DATE, |date| date.num_days_from_ce())
+}
+
+/// Adds an amount depending on the state of its order.
+fn add_amount(
+    result: &mut CategoryAmount,
+    state: TransactionState,
+    amount: Amount,
+) -> Result<(), RequestFailure> {
+    let total = match state {
+        TransactionState::Pending => &mut result.pending,
+        TransactionState::InProgress => &mut result.in_progress,
+        TransactionState::Done => &mut result.current,
+    };
+
+    *total = total.checked_add(amount).ok_or(RequestFailure::Overflow)?;
+    result.expected = result
+        .expected
+        .checked_add(amount)
+        .ok_or(RequestFailure::Overflow)?;
+    Ok(())
+}
+
+impl From<&[Order]> for OrderColumns {
+    fn from(orders: &[Order]) -> Self {
+        let orders = orders
+            .iter()
+            .filter(|order| order.is_visible())
+            .collect::<Vec<&Order>>();
+        let mut tags = Names::default();
+        let bits = orders
+            .iter()
+            .map(|order| {
+                order
+                    .tags
+                    .iter()
+                    .map(|tag| tags.insert(tag))
+                    .collect::<Vec<usize>>()
+            })
+            .collect::<Vec<Vec<usize>>>();
+        let words = tags.names.len().div_ceil(WORD_BITS);
+        let mut tag_bits = vec![0; orders.len() * words];
+
+        bits.iter().enumerate().for_each(|(row, bits)| {
+            bits.iter()
+                .for_each(|bit| tag_bits[row * words + bit / WORD_BITS] |= 1 << (bit % WORD_BITS))
+        });
+
+        let mut names = Names::default();
+        let mut column = |name: Option<&str>| name.map(|name| names.insert(name));
+        let currencies = orders
+            .iter()
+            .map(|order| column(order.currency.as_deref()))
+            .collect();
+        let resources = orders
+            .iter()
+            .map(|order| column(order.resource.as_deref()))
+            .collect();
+        let projects = orders
+            .iter()
+            .map(|order| column(order.project.as_deref()))
+            .collect();
+        let payees = orders
+            .iter()
+            .map(|order| column(order.payee.as_deref()))
+            .collect();
+        let conversions = orders
+            .iter()
+            .map(|order| {
+                order.conversion.as_ref().map(|conversion| {
+                    (
+                        names.insert(&conversion.currency),
+                        conversion.amount.cents(),
+                    )
+                })
+            })
+            .collect();
+
+        OrderColumns {
+            days: orders.iter().map(|order| day(order.date)).collect(),
+            cents: orders.iter().map(|order| order.amount.cents()).collect(),
+            states: orders.iter().map(|order| order.state).collect(),
+            currencies,
+            conversions,
+            resources,
+            projects,
+            payees,
+            names,
+            tags,
+            words,
+            tag_bits,
+        }
+    }
+}
+
+impl OrderColumns {
+    /// Returns the number of orders.
+    pub fn len(&self) -> usize {
+        self.cents.len()
+    }
+
+    /// Returns `true` without orders.
+    pub fn is_empty(&self) -> bool {
+        self.cents.is_empty()
+    }
+
+    /// Returns known tags, in order of appearance.
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags.names
+    }
+
+    /// Returns `true` if an order owns the tag at a given bit.
+    fn has_tag(&self, row: usize, bit: usize) -> bool {
+        self.tag_bits[row * self.words + bit / WORD_BITS] & (1 << (bit % WORD_BITS)) != 0
+    }
+
+    /// Returns orders dated within a range, all bounds included. A range
+    /// ending before its start has no end, as for `NaiveDateFilter`.
+    pub(crate) fn rows_between(
+        &self,
+        start: Option<NaiveDate>,
+        end: Option<NaiveDate>,
+    ) -> impl Iterator<Item = usize> + '_ {
+        let end = end.filter(|&end| start.is_none_or(|start| end >= start));
+        let (first, last) = (
+            start.map(|date| day(Some(date))),
+            end.map(|date| day(Some(date))),
+        );
+        let dated = first.is_some() || last.is_some();
+
+        self.days
+            .iter()
+            .enumerate()
+            .filter(move |&(_, &day)| {
+                !(dated && day == NO_DATE)
+                    && first.is_none_or(|first| day >= first)
+                    && last.is_none_or(|last| day <= last)
+            })
+            .map(|(row, _)| row)
+    }
+
+    /// Returns the date of an order.
+    pub(crate) fn date(&self, row: usize) -> Option<NaiveDate> {
+        Some(self.days[row])
+            .filter(|&day| day != NO_DATE)
+            .and_then(NaiveDate::from_num_days_from_ce_opt)
+    }
+
+    /// Returns the amount of an order.
+    pub(crate) fn amount(&self, row: usize) -> Amount {
+        Amount::from_cents(self.cents[row])
+    }
+
+    /// Returns the tags of an order.
+    pub(crate) fn row_tags(&self, row: usize) -> impl Iterator<Item = &str> + '_ {
+        (0..self.tags.names.len())
+            .filter(move |&bit| self.has_tag(row, bit))
+            .map(move |bit| self.tags.names[bit].as_str())
+    }
+
+    /// Returns the names of an order for a *category* type.
+    pub(crate) fn row_categories(
+        &self,
+        row: usize,
+        kind: CategoryType,
+    ) -> impl Iterator<Item = &str> + '_ {
+        let name = match kind {
+            Tag => None,
+            Resource => self.resources[row],
+            Project => self.projects[row],
+            Payee => self.payees[row],
+        };
+
+        (kind == Tag)
+            .then(|| self.row_tags(row))
+            .into_iter()
+            .flatten()
+            .chain(name.map(|position| self.names.names[position].as_str()))
+    }
+
+    /// Returns the currency position and amount of an order for a
+    /// *category*: resources hold the converted amount, if any.
+    fn money(&self, row: usize, kind: CategoryType) -> (Option<usize>, Amount) {
+        match self.conversions[row] {
+            Some((currency, cents)) if kind == Resource => {
+                (Some(currency), Amount::from_cents(cents))
+            }
+            _ => (self.currencies[row], self.amount(row)),
+        }
+    }
+
+    /// Returns orders of a *category* dated within a range.
+    pub(crate) fn category_rows(
+        &self,
+        kind: CategoryType,
+        category: &str,
+        (start, end): (Option<NaiveDate>, Option<NaiveDate>),
+    ) -> impl Iterator<Item = usize> + '_ {
+        let position = match kind {
+            Tag => self.tags.position(category),
+            _ => self.names.position(category),
+        };
+
+        self.rows_between(start, end).filter(move |&row| {
+            position.is_some_and(|position| match kind {
+                Tag => self.has_tag(row, position),
+                Resource => self.resources[row] == Some(position),
+                Project => self.projects[row] == Some(position),
+                Payee => self.payees[row] == Some(position),
+            })
+        })
+    }
+
+    /// Sums amounts of orders for a *category* depending on their state.
+    fn sum(&self, rows: &[usize], kind: CategoryType) -> Result<CategoryAmount, RequestFailure> {
+        let mut result = CategoryAmount::default();
+
+        for &row in rows {
+            add_amount(&mut result, self.states[row], self.money(row, kind).1)?;
+        }
+        Ok(result)
+    }
+
+    /// Computes the amounts of a tag within a date range, whatever the
+    /// currencies.
+    ///
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    pub fn tag_amount(
+        &self,
+        tag: &str,
+        start: Option<NaiveDate>,
+        end: Option<NaiveDate>,
+    ) -> Result<CategoryAmount, RequestFailure> {
+        let mut result = CategoryAmount::default();
+
+        for row in self.category_rows(Tag, tag, (start, end)) {
+            add_amount(&mut result, self.states[row], self.amount(row))?;
+        }
+        Ok(result)
+    }
+
+    /// Computes the amounts of a *category* within a date range, for each
+    /// currency. Orders without currency are assigned to `base_currency`.
+    ///
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    pub fn category_amount_by_currency(
+        &self,
+        kind: CategoryType,
+        category: &str,
+        (start, end): (Option<NaiveDate>, Option<NaiveDate>),
+        base_currency: Option<&str>,
+    ) -> Result<Vec<CurrencyAmount>, RequestFailure> {
+        let mut groups: BTreeMap<Option<&str>, CategoryAmount> = BTreeMap::new();
+
+        for row in self.category_rows(kind, category, (start, end)) {
+            let (currency, amount) = self.money(row, kind);
+            let currency = currency
+                .map(|position| self.names.names[position].as_str())
+                .or(base_currency);
+            add_amount(
+                groups.entry(currency).or_default(),
+                self.states[row],
+                amount,
+            )?;
+        }
+
+        Ok(groups
+            .into_iter()
+            .map(|(currency, amount)| CurrencyAmount {
+                currency: currency.map(String::from),
+                amount,
+            })
+            .collect())
+    }
+
+    /// Computes the amounts of orders owning any of the given tags within a
+    /// date range, each order counted once.
+    ///
+    /// Returns `None` if there is no order, or if orders use different
+    /// currencies. Fails with `RequestFailure::Overflow` if a sum overflows.
+    pub fn tags_amount(
+        &self,
+        tags: &[String],
+        (start, end): (Option<NaiveDate>, Option<NaiveDate>),
+    ) -> Result<Option<CategoryAmount>, RequestFailure> {
+        let bits = tags
+            .iter()
+            .filter_map(|tag| self.tags.position(tag))
+            .collect::<Vec<usize>>();
+        let rows = self
+            .rows_between(start, end)
+            .filter(|&row| bits.iter().any(|&bit| self.has_tag(row, bit)))
+            .collect::<Vec<usize>>();
+
+        match rows.first() {
+            Some(&first)
+                if rows
+                    .iter()
+                    .all(|&row| self.currencies[row] == self.currencies[first]) =>
+            {
+                self.sum(&rows, Tag).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Computes the amounts and the distribution of order amounts of every
+    /// *category* within a date range, scanning orders once.
+    ///
+    /// Categories whose orders use different currencies are left out.
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    pub fn all_category_stats(
+        &self,
+        kind: CategoryType,
+        (start, end): (Option<NaiveDate>, Option<NaiveDate>),
+    ) -> Result<HashMap<String, CategoryStats>, RequestFailure> {
+        // `None` once orders of a category use different currencies
+        let mut totals: HashMap<&str, Option<CategoryTotal>> = HashMap::new();
+
+        for row in self.rows_between(start, end) {
+            let (currency, amount) = self.money(row, kind);
+
+            for category in self.row_categories(row, kind) {
+                let total = totals.entry(category).or_insert(Some((
+                    currency,
+                    CategoryAmount::default(),
+                    Vec::new(),
+                )));
+                match total {
+                    Some((total_currency, sum, amounts)) if *total_currency == currency => {
+                        add_amount(sum, self.states[row], amount)?;
+                        amounts.push(amount);
+                    }
+                    _ => *total = None,
+                }
+            }
+        }
+
+        Ok(totals
+            .into_iter()
+            .filter_map(|(category, total)| {
+                let (_, sum, amounts) = total?;
+                Some((category.to_string(), CategoryStats::new(sum, amounts)))
+            })
+            .collect())
+    }
+}
+
+/// Lazily built columns, dropped whenever orders change.
+///
+/// Ignored by comparisons as it only speeds up aggregations.
+#[derive(Default, Debug)]
+pub struct ColumnCache(RefCell<Option<Arc<OrderColumns>>>);
+
+impl PartialEq for ColumnCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl ColumnCache {
+    /// Drops built columns.
+    pub fn invalidate(&mut self) {
+        *self.0.get_mut() = None;
+    }
+
+    /// Returns up-to-date columns of orders.
+    pub fn get(&self, orders: &[Order]) -> Arc<OrderColumns> {
+        self.0
+            .borrow_mut()
+            .get_or_insert_with(|| Arc::new(OrderColumns::from(orders)))
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::{Conversion, HiddenReason};
+
+    #[test]
+    fn aggregate_tag_columns() {
+        let mut orders = (0..70)
+            .map(|index| Order {
+                date: Some(NaiveDate::from_ymd(2021, 1, 1 + index % 28)),
+                amount: Amount::from_cents(-100),
+                tags: vec![format!("Tag {}", index).into(), "Common".into()],
+                state: TransactionState::Done,
+                ..Order::default()
+            })
+            .collect::<Vec<Order>>();
+        orders[1].hide(HiddenReason::Duplicate);
+        orders.push(Order {
+            amount: Amount::from_cents(-500),
+            tags: vec!["Common".into()],
+            ..Order::default()
+        });
+
+        let columns = OrderColumns::from(orders.as_slice());
+
+        assert_eq!(columns.len(), 70);
+        assert_eq!(columns.tags().len(), 70);
+        assert_eq!(
+            columns.tag_amount("Common", None, None),
+            Ok(CategoryAmount {
+                current: Amount::from_cents(-6900),
+                pending: Amount::from_cents(-500),
+                in_progress: Amount::ZERO,
+                expected: Amount::from_cents(-7400),
+            })
+        );
+        assert_eq!(
+            columns
+                .tag_amount("Tag 65", None, Some(NaiveDate::from_ymd(2021, 1, 31)))
+                .unwrap()
+                .expected,
+            Amount::from_cents(-100)
+        );
+        assert_eq!(
+            columns
+                .tag_amount("Common", Some(NaiveDate::from_ymd(2021, 1, 28)), None)
+                .unwrap()
+                .expected,
+            Amount::from_cents(-200)
+        );
+        assert_eq!(
+            columns.row_tags(68).collect::<Vec<&str>>(),
+            ["Common", "Tag 69"]
+        );
+    }
+
+    #[test]
+    fn aggregate_category_columns() {
+        let orders = [
+            Order {
+                amount: Amount::from_cents(-1000),
+                resource: Some("Travel".into()),
+                project: Some("Travel".into()),
+                state: TransactionState::Done,
+                ..Order::default()
+            },
+            Order {
+                amount: Amount::from_cents(-2000),
+                currency: Some("USD".into()),
+                conversion: Some(Conversion {
+                    currency: "EUR".into(),
+                    amount: Amount::from_cents(-1800),
+                }),
+                resource: Some("Travel".into()),
+                ..Order::default()
+            },
+        ];
+        let columns = OrderColumns::from(&orders[..]);
+        let amounts = |kind, base_currency| {
+            columns
+                .category_amount_by_currency(kind, "Travel", (None, None), base_currency)
+                .unwrap()
+                .into_iter()
+                .map(|group| (group.currency, group.amount.expected))
+                .collect::<Vec<(Option<String>, Amount)>>()
+        };
+
+        // Resources hold converted amounts
+        assert_eq!(
+            amounts(Resource, Some("EUR")),
+            [(Some("EUR".into()), Amount::from_cents(-2800))]
+        );
+        assert_eq!(amounts(Project, None), [(None, Amount::from_cents(-1000))]);
+        assert_eq!(amounts(Payee, None), []);
+        // Without base currency, the resource mixes currencies
+        let stats = |kind| columns.all_category_stats(kind, (None, None)).unwrap();
+        assert_eq!(stats(Resource).contains_key("Travel"), false);
+        assert_eq!(stats(Project)["Travel"].count, 1);
+    }
+}
@@ -0,0 +1,59 @@
+//! # Gzip compression of account files.
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::borrow::Cow;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// Marks the start of gzip data.
+const MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Returns `true` if data is gzip-compressed.
+pub fn is_compressed(data: &[u8]) -> bool {
+    data.starts_with(&MAGIC)
+}
+
+/// Returns `true` if a path has a `.gz` extension.
+pub fn is_compressed_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("gz"))
+}
+
+/// Compresses data with gzip.
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    // Writing into memory does not fail
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+/// Decompresses gzip data, returning other data as it is.
+pub fn decompress(data: &[u8]) -> io::Result<Cow<'_, [u8]>> {
+    if is_compressed(data) {
+        let mut content = Vec::new();
+        GzDecoder::new(data).read_to_end(&mut content)?;
+        Ok(Cow::Owned(content))
+    } else {
+        Ok(Cow::Borrowed(data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compress_and_detect() {
+        let content = "- description: Groceries\n".repeat(100);
+        let compressed = compress(content.as_bytes());
+
+        assert_eq!(is_compressed(&compressed), true);
+        assert_eq!(compressed.len() < content.len() / 10, true);
+        assert_eq!(decompress(&compressed).unwrap(), content.as_bytes());
+        assert_eq!(decompress(b"label: A").unwrap(), &b"label: A"[..]);
+        assert_eq!(decompress(&compressed[..20]).is_err(), true);
+        assert_eq!(is_compressed_file(Path::new("money.yml.GZ")), true);
+        assert_eq!(is_compressed_file(Path::new("money.yml")), false);
+    }
+}
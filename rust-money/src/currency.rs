@@ -0,0 +1,32 @@
+//! # Currencies of monetary values.
+
+/// Normalizes an *ISO 4217* alphabetic code (`eur` becomes `EUR`).
+/// Returns `None` if the code is not made of 3 letters.
+pub fn normalize_code(code: &str) -> Option<String> {
+    let code = code.trim();
+
+    if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+        Some(code.to_ascii_uppercase())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_valid_code() {
+        assert_eq!(normalize_code("EUR"), Some("EUR".to_string()));
+        assert_eq!(normalize_code(" usd "), Some("USD".to_string()));
+    }
+
+    #[test]
+    fn reject_invalid_code() {
+        assert_eq!(normalize_code(""), None);
+        assert_eq!(normalize_code("EURO"), None);
+        assert_eq!(normalize_code("€"), None);
+        assert_eq!(normalize_code("U$D"), None);
+    }
+}
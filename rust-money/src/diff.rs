@@ -0,0 +1,121 @@
+//! # Changes between two versions of an account.
+use crate::order::Order;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+/// Order found in both versions with different data.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct ModifiedOrder {
+    pub before: Order,
+    pub after: Order,
+    /// Names of the changed fields, as serialized.
+    pub fields: Vec<String>,
+}
+
+/// Changes of a list of categories, by name.
+#[derive(Serialize, Clone, PartialEq, Default, Debug)]
+pub struct CategoryDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Categories whose details changed (color, opening balance, ..).
+    pub modified: Vec<String>,
+}
+
+impl CategoryDiff {
+    /// Compares categories identified by name.
+    pub(crate) fn new<T: PartialEq>(before: &[T], after: &[T], name: fn(&T) -> &str) -> Self {
+        let find = |list: &[T], item: &T| list.iter().position(|x| name(x) == name(item));
+        let names = |list: &[T], keep: &dyn Fn(&T) -> bool| {
+            list.iter()
+                .filter(|item| keep(item))
+                .map(|item| name(item).to_string())
+                .collect()
+        };
+
+        CategoryDiff {
+            added: names(after, &|item| find(before, item).is_none()),
+            removed: names(before, &|item| find(after, item).is_none()),
+            modified: names(after, &|item| {
+                find(before, item).is_some_and(|index| before[index] != *item)
+            }),
+        }
+    }
+
+    /// Returns `true` if categories are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Changes turning an account into another version, such as a backup into
+/// the current account.
+#[derive(Serialize, Clone, PartialEq, Default, Debug)]
+pub struct AccountDiff {
+    pub added: Vec<Order>,
+    pub removed: Vec<Order>,
+    pub modified: Vec<ModifiedOrder>,
+    pub tags: CategoryDiff,
+    pub resources: CategoryDiff,
+    pub payees: CategoryDiff,
+    pub projects: CategoryDiff,
+}
+
+impl AccountDiff {
+    /// Compares orders identified by id; creation and modification times
+    /// are ignored.
+    pub(crate) fn orders(&mut self, before: &[Order], after: &[Order]) {
+        let (previous, next) = (by_id(before), by_id(after));
+
+        after
+            .iter()
+            .for_each(|order| match previous.get(&order.id) {
+                None => self.added.push(order.clone()),
+                Some(&previous) if previous != order => self.modified.push(ModifiedOrder {
+                    fields: changed_fields(previous, order),
+                    before: previous.clone(),
+                    after: order.clone(),
+                }),
+                Some(_) => {}
+            });
+        self.removed = before
+            .iter()
+            .filter(|order| !next.contains_key(&order.id))
+            .cloned()
+            .collect();
+    }
+
+    /// Returns `true` if both versions hold the same data.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+            && self.tags.is_empty()
+            && self.resources.is_empty()
+            && self.payees.is_empty()
+            && self.projects.is_empty()
+    }
+}
+
+/// Indexes orders by id.
+fn by_id(orders: &[Order]) -> HashMap<usize, &Order> {
+    orders.iter().map(|order| (order.id, order)).collect()
+}
+
+/// Returns the serialized fields which differ between two orders.
+fn changed_fields(before: &Order, after: &Order) -> Vec<String> {
+    let (before, after) = match (serde_json::to_value(before), serde_json::to_value(after)) {
+        (Ok(Value::Object(before)), Ok(Value::Object(after))) => (before, after),
+        _ => return Vec::new(),
+    };
+
+    before
+        .keys()
+        .chain(after.keys())
+        .filter(|key| !matches!(key.as_str(), "created_at" | "updated_at"))
+        .filter(|key| before.get(*key) != after.get(*key))
+        .cloned()
+        .collect::<BTreeSet<String>>()
+        .into_iter()
+        .collect()
+}
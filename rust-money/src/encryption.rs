@@ -0,0 +1,135 @@
+//! # Passphrase-encrypted account data.
+//!
+//! Data is sealed with AES-256-GCM under a key derived from the passphrase
+//! with PBKDF2-HMAC-SHA256. Sealed data starts with a header holding the
+//! derivation rounds, the salt and the nonce.
+use crate::ext::RequestFailure;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+/// Marks the start of sealed data.
+const MAGIC: &[u8] = b"MONEYENC";
+/// Rounds of key derivation for new data; stored data keeps its own.
+#[cfg(not(test))]
+const ROUNDS: u32 = 600_000;
+#[cfg(test)]
+const ROUNDS: u32 = 1000;
+/// Bounds of rounds accepted from stored data, so that altered headers can
+/// neither weaken the key nor stall decryption.
+const MIN_ROUNDS: u32 = 1000;
+const MAX_ROUNDS: u32 = 10_000_000;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const HEADER_LENGTH: usize = MAGIC.len() + 4 + SALT_LENGTH + NONCE_LENGTH;
+
+/// Derives the key of a passphrase.
+fn derive_key(passphrase: &str, salt: &[u8], rounds: u32) -> Aes256Gcm {
+    let mut key = [0; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt, rounds, &mut key);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+}
+
+/// Returns `true` if data has been sealed by `encrypt`.
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// Seals data under a passphrase.
+/// Fails with `EmptyArgument` if the passphrase is empty.
+pub fn encrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, RequestFailure> {
+    if passphrase.is_empty() {
+        return Err(RequestFailure::EmptyArgument);
+    }
+    let mut random = [0; SALT_LENGTH + NONCE_LENGTH];
+    getrandom::getrandom(&mut random).expect("no source of randomness");
+    let (salt, nonce) = random.split_at(SALT_LENGTH);
+    // Only data too large for a single nonce is rejected
+    let sealed = derive_key(passphrase, salt, ROUNDS)
+        .encrypt(Nonce::from_slice(nonce), data)
+        .map_err(|_| RequestFailure::Overflow)?;
+
+    let mut result = Vec::with_capacity(HEADER_LENGTH + sealed.len());
+    result.extend_from_slice(MAGIC);
+    result.extend_from_slice(&ROUNDS.to_be_bytes());
+    result.extend_from_slice(&random);
+    result.extend_from_slice(&sealed);
+    Ok(result)
+}
+
+/// Opens data sealed under a passphrase.
+/// Fails with `IncorrectArgument` if the passphrase is wrong, the data is
+/// not sealed or altered, or its rounds are out of bounds.
+pub fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, RequestFailure> {
+    if !is_encrypted(data) || data.len() < HEADER_LENGTH {
+        return Err(RequestFailure::IncorrectArgument);
+    }
+    let (rounds, rest) = data[MAGIC.len()..].split_at(4);
+    let (salt, rest) = rest.split_at(SALT_LENGTH);
+    let (nonce, sealed) = rest.split_at(NONCE_LENGTH);
+    let rounds = u32::from_be_bytes([rounds[0], rounds[1], rounds[2], rounds[3]]);
+    if !(MIN_ROUNDS..=MAX_ROUNDS).contains(&rounds) {
+        return Err(RequestFailure::IncorrectArgument);
+    }
+
+    derive_key(passphrase, salt, rounds)
+        .decrypt(Nonce::from_slice(nonce), sealed)
+        .map_err(|_| RequestFailure::IncorrectArgument)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_and_open_data() {
+        let sealed = encrypt(b"label: Household", "correct horse").unwrap();
+
+        assert_eq!(is_encrypted(&sealed), true);
+        assert_eq!(
+            decrypt(&sealed, "correct horse"),
+            Ok(b"label: Household".to_vec())
+        );
+        assert_eq!(
+            decrypt(&sealed, "wrong horse"),
+            Err(RequestFailure::IncorrectArgument)
+        );
+        assert_eq!(
+            decrypt(b"label: Household", "correct horse"),
+            Err(RequestFailure::IncorrectArgument)
+        );
+
+        let mut altered = sealed.clone();
+        *altered.last_mut().unwrap() ^= 1;
+
+        assert_eq!(
+            decrypt(&altered, "correct horse"),
+            Err(RequestFailure::IncorrectArgument)
+        );
+        assert_ne!(
+            encrypt(b"label: Household", "correct horse").unwrap(),
+            sealed
+        );
+        assert_eq!(encrypt(b"", ""), Err(RequestFailure::EmptyArgument));
+    }
+
+    #[test]
+    fn reject_rounds_out_of_bounds() {
+        let sealed = encrypt(b"label: Household", "correct horse").unwrap();
+        let with_rounds = |rounds: u32| {
+            let mut altered = sealed.clone();
+            altered[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&rounds.to_be_bytes());
+            decrypt(&altered, "correct horse")
+        };
+
+        assert_eq!(with_rounds(0), Err(RequestFailure::IncorrectArgument));
+        assert_eq!(
+            with_rounds(MIN_ROUNDS - 1),
+            Err(RequestFailure::IncorrectArgument)
+        );
+        assert_eq!(
+            with_rounds(u32::MAX),
+            Err(RequestFailure::IncorrectArgument)
+        );
+        assert_eq!(with_rounds(ROUNDS), Ok(b"label: Household".to_vec()));
+    }
+}
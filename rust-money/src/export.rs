@@ -0,0 +1,135 @@
+//! # Incremental exports, such as monthly handoffs to an accountant.
+use crate::amount::Amount;
+use crate::order::Order;
+use crate::trash::TrashedOrder;
+use chrono::NaiveDateTime;
+use serde::Serialize;
+#[cfg(feature = "wasmbind")]
+use wasm_bindgen::prelude::*;
+
+/// Rendering of exported amounts.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AmountFormat {
+    /// Floating-point number (`-13.99`), which integrations may round.
+    Number,
+    /// Fixed-point string (`"-13.99"`), kept exact.
+    Text,
+}
+
+/// Columns of exported CSV files.
+const CSV_HEADER: &str = "id,date,description,amount,currency,state,resource,tags,payee";
+
+/// Orders added or changed since a previous export.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct DeltaExport {
+    /// Marker of the previous export; everything is exported if `None`.
+    pub since: Option<NaiveDateTime>,
+    /// Marker to store for the next export.
+    pub marker: NaiveDateTime,
+    pub orders: Vec<Order>,
+    /// Identifiers of orders deleted since the previous export, unless purged.
+    pub deleted: Vec<usize>,
+}
+
+/// Quotes a CSV field if needed.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.into()
+    }
+}
+
+/// Renders an amount as a CSV field.
+fn csv_amount(amount: Amount, format: AmountFormat) -> String {
+    match format {
+        AmountFormat::Number => amount.as_f64().to_string(),
+        AmountFormat::Text => format!("\"{}\"", amount),
+    }
+}
+
+/// Writes orders as CSV, with a header line; tags are separated by `;`.
+pub fn orders_to_csv<'a>(orders: impl Iterator<Item = &'a Order>, format: AmountFormat) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+
+    orders.for_each(|order| {
+        let fields = [
+            order.id().to_string(),
+            order.date.map_or(String::new(), |date| date.to_string()),
+            csv_field(&order.description),
+            csv_amount(order.amount, format),
+            order.currency.clone().unwrap_or_default(),
+            format!("{:?}", order.state()),
+            csv_field(order.resource.as_deref().unwrap_or_default()),
+            csv_field(&order.tags.join(";")),
+            csv_field(order.payee.as_deref().unwrap_or_default()),
+        ];
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    });
+
+    csv
+}
+
+/// Returns `true` if a time is after the marker.
+fn is_after(time: Option<NaiveDateTime>, since: Option<NaiveDateTime>) -> bool {
+    since.is_none_or(|since| time.is_some_and(|time| time > since))
+}
+
+/// Collects changes since a marker.
+/// Legacy orders without timestamps are only part of the first export.
+pub fn export_since(
+    orders: &[Order],
+    trash: &[TrashedOrder],
+    since: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+) -> DeltaExport {
+    DeltaExport {
+        since,
+        marker: now,
+        orders: orders
+            .iter()
+            .filter(|order| is_after(order.updated_at(), since))
+            .cloned()
+            .collect(),
+        deleted: trash
+            .iter()
+            .filter(|item| since.is_some() && is_after(Some(item.deleted_at), since))
+            .map(|item| item.order.id())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn export_orders_as_csv() {
+        let orders = [Order {
+            date: Some(NaiveDate::from_ymd(2021, 3, 2)),
+            description: "Dinner, \"Chez Paul\"".into(),
+            amount: Amount::from_cents(-1399),
+            tags: vec!["Food".into(), "Friends".into()],
+            ..Order::default()
+        }];
+
+        assert_eq!(
+            orders_to_csv(orders.iter(), AmountFormat::Number),
+            format!(
+                "{}\n0,2021-03-02,\"Dinner, \"\"Chez Paul\"\"\",-13.99,,Pending,,Food;Friends,\n",
+                CSV_HEADER
+            )
+        );
+        assert_eq!(
+            orders_to_csv(orders.iter(), AmountFormat::Text)
+                .lines()
+                .nth(1)
+                .unwrap()
+                .contains(",\"-13.99\","),
+            true
+        );
+    }
+}
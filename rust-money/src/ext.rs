@@ -1,15 +1,25 @@
 //! # Extensions.
 
+use crate::amount::Amount;
+use crate::category::CategoryEntry;
+use crate::columnar::OrderColumns;
 use crate::filter::{Filter, NaiveDateFilter, OptionNaiveDateRange};
-use crate::order::Order;
+use crate::intern::Interned;
 use crate::order::TransactionState::{Done, InProgress, Pending};
+use crate::order::{HiddenReason, Order, OrderKind};
+use crate::stats::Period;
+use chrono::NaiveDate;
 #[cfg(feature = "wasmbind")]
 use js_sys::Array;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 #[cfg(feature = "wasmbind")]
 use wasm_bindgen::prelude::*;
-use CategoryType::{Resource, Tag};
+use CategoryType::{Payee, Project, Resource, Tag};
 use OrderingDirection::Ascending;
-use OrderingPreference::{ByAmount, ByDate, ByDescription, ById};
+use OrderingPreference::{
+    ByAmount, ByDate, ByDescription, ByFirstTag, ById, ByPosition, ByResource, ByState, ByUpdate,
+};
 
 /// Defines error types.
 #[cfg_attr(feature = "wasmbind", wasm_bindgen)]
@@ -23,90 +33,545 @@ pub enum RequestFailure {
     UnknownItem,
     /// Specified item can not be added as it already did.
     ExistingItem,
+    /// Resulting amount exceeds `Amount::MAX`.
+    Overflow,
+    /// Requested amounts exceed available funds.
+    InsufficientFunds,
 }
 
 /// Defines available *category* types.
 #[cfg_attr(feature = "wasmbind", wasm_bindgen)]
-#[derive(PartialEq, Debug)]
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
 pub enum CategoryType {
     /// A **resource** identifies something which represents/holds money.
     Resource,
     /// A **tag** identifies a category of expense.
     /// Could be: an object, a person, a firm, .. it's up to you!
     Tag,
+    /// A **project** groups orders of a trip, an event, .. whatever their
+    /// tags and resources are.
+    Project,
+    /// A **payee** is the counterparty of an order: a merchant, an employer, ..
+    Payee,
 }
 
 /// Gather different amounts for a *category*.
 #[cfg_attr(feature = "wasmbind", wasm_bindgen)]
-#[derive(PartialEq, Debug)]
+#[derive(Serialize, Default, PartialEq, Debug)]
 pub struct CategoryAmount {
-    pub current: f32,
-    pub pending: f32,
-    pub in_progress: f32,
-    pub expected: f32,
+    #[cfg_attr(feature = "wasmbind", wasm_bindgen(skip))]
+    pub current: Amount,
+    #[cfg_attr(feature = "wasmbind", wasm_bindgen(skip))]
+    pub pending: Amount,
+    #[cfg_attr(feature = "wasmbind", wasm_bindgen(skip))]
+    pub in_progress: Amount,
+    #[cfg_attr(feature = "wasmbind", wasm_bindgen(skip))]
+    pub expected: Amount,
+}
+
+impl CategoryAmount {
+    /// Returns opposite amounts, such as the debt of a liability.
+    pub fn negated(self) -> CategoryAmount {
+        CategoryAmount {
+            current: -self.current,
+            pending: -self.pending,
+            in_progress: -self.in_progress,
+            expected: -self.expected,
+        }
+    }
+}
+
+/// Amounts of a *category* along with the distribution of its order amounts.
+#[derive(Serialize, PartialEq, Debug)]
+pub struct CategoryStats {
+    pub amount: CategoryAmount,
+    pub count: usize,
+    pub average: Amount,
+    /// Middle amount, or the average of both middle ones.
+    pub median: Amount,
+    pub min: Amount,
+    pub max: Amount,
+}
+
+impl CategoryStats {
+    /// Describes a non-empty list of order amounts, summed up in `amount`.
+    pub(crate) fn new(amount: CategoryAmount, mut amounts: Vec<Amount>) -> CategoryStats {
+        amounts.sort();
+        let count = amounts.len();
+        let middle = count / 2;
+
+        CategoryStats {
+            average: Amount::from_cents(amount.expected.cents() / count as i64),
+            median: if count.is_multiple_of(2) {
+                Amount::from_cents((amounts[middle - 1].cents() + amounts[middle].cents()) / 2)
+            } else {
+                amounts[middle]
+            },
+            min: amounts[0],
+            max: amounts[count - 1],
+            amount,
+            count,
+        }
+    }
+}
+
+/// Balance of a resource at a date.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct ResourceBalance {
+    pub resource: String,
+    /// Amount owed for a liability.
+    pub balance: Amount,
+    pub liability: bool,
+}
+
+/// Balances of all resources at a date.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct NetWorth {
+    /// Balance of resources holding money.
+    pub assets: Amount,
+    /// Amount owed on liabilities, as a positive value when owed.
+    pub liabilities: Amount,
+    pub net: Amount,
+    /// Balance of each resource, in their order.
+    pub resources: Vec<ResourceBalance>,
+}
+
+/// Amounts of a *category* in a single currency.
+#[derive(Serialize, PartialEq, Debug)]
+pub struct CurrencyAmount {
+    /// Currency code, if known.
+    pub currency: Option<String>,
+    pub amount: CategoryAmount,
+}
+
+/// Exposes amounts as numbers to *JavaScript*.
+#[cfg(feature = "wasmbind")]
+#[wasm_bindgen]
+impl CategoryAmount {
+    #[wasm_bindgen(getter = current)]
+    pub fn current_value(&self) -> f64 {
+        self.current.as_f64()
+    }
+
+    #[wasm_bindgen(getter = pending)]
+    pub fn pending_value(&self) -> f64 {
+        self.pending.as_f64()
+    }
+
+    #[wasm_bindgen(getter = in_progress)]
+    pub fn in_progress_value(&self) -> f64 {
+        self.in_progress.as_f64()
+    }
+
+    #[wasm_bindgen(getter = expected)]
+    pub fn expected_value(&self) -> f64 {
+        self.expected.as_f64()
+    }
+}
+
+/// Income and expenses of a period, as signed sums; transfers are excluded.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub struct IncomeExpense {
+    /// First day of the period.
+    pub start: NaiveDate,
+    /// Last day of the period.
+    pub end: NaiveDate,
+    pub income: Amount,
+    pub expenses: Amount,
+}
+
+/// Number of orders and sum of their absolute amounts.
+#[derive(Serialize, Clone, Copy, Default, PartialEq, Debug)]
+pub struct OrderTally {
+    pub count: usize,
+    pub amount: Amount,
+}
+
+impl OrderTally {
+    /// Counts an order; fails if the sum overflows.
+    fn add(&mut self, order: &Order) -> Result<(), RequestFailure> {
+        self.count += 1;
+        self.amount = self
+            .amount
+            .checked_add(order.amount.abs())
+            .ok_or(RequestFailure::Overflow)?;
+        Ok(())
+    }
+}
+
+/// Share of visible orders owning both tags and a resource.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub struct CategorizationProgress {
+    pub total: OrderTally,
+    pub categorized: OrderTally,
+    pub untagged: OrderTally,
+    pub without_resource: OrderTally,
+    /// Categorized share of orders, unknown without orders.
+    pub ratio: Option<f64>,
+}
+
+/// How much a *category* is used by counted orders.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct CategoryUsage {
+    pub name: String,
+    pub count: usize,
+    /// Earliest order date, unknown without dated orders.
+    pub first_used: Option<NaiveDate>,
+    /// Latest order date, unknown without dated orders.
+    pub last_used: Option<NaiveDate>,
+    pub total: Amount,
+}
+
+/// Number of orders of each state.
+#[derive(Serialize, Clone, Copy, PartialEq, Default, Debug)]
+pub struct StateCount {
+    pub pending: usize,
+    pub in_progress: usize,
+    pub done: usize,
+}
+
+/// Slice of sorted filtered orders.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FilteredPage<'a> {
+    pub orders: Vec<(usize, &'a Order)>,
+    /// Number of orders allowed by the filter, on all pages.
+    pub total: usize,
+}
+
+impl<'a> FilteredPage<'a> {
+    /// Keeps up to `limit` orders from `offset`.
+    pub(crate) fn new(orders: Vec<(usize, &'a Order)>, offset: usize, limit: usize) -> Self {
+        FilteredPage {
+            total: orders.len(),
+            orders: orders.into_iter().skip(offset).take(limit).collect(),
+        }
+    }
+}
+
+/// Number of orders dated within a given month.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub struct PeriodCount {
+    pub year: i32,
+    pub month: u32,
+    pub count: usize,
+}
+
+/// Outcome of a resource reconciliation against a bank statement.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub struct Reconciliation {
+    /// Sum of done orders until the statement date; amount owed for liabilities.
+    pub balance: Amount,
+    pub statement_balance: Amount,
+    /// Missing amount in the account, null once reconciled.
+    pub discrepancy: Amount,
+    /// Number of orders newly reconciled.
+    pub reconciled: usize,
 }
 
 /// All kinds of sorting preferences.
 #[cfg_attr(feature = "wasmbind", wasm_bindgen)]
-#[derive(Copy, Clone)]
+#[derive(Serialize, Deserialize, Copy, Clone)]
 pub enum OrderingPreference {
     ByDate,
     ByDescription,
     ByAmount,
     ById,
+    /// Most recently edited or created last.
+    ByUpdate,
+    /// Order of the account list, as arranged by the user.
+    ByPosition,
+    /// Orders without resource first, ignoring case.
+    ByResource,
+    /// Pending, in progress then done orders.
+    ByState,
+    /// Untagged orders first, ignoring case.
+    ByFirstTag,
 }
 
 /// Direction when sorting orders.
 #[cfg_attr(feature = "wasmbind", wasm_bindgen)]
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq)]
 pub enum OrderingDirection {
     Ascending,
     Descending,
 }
 
-/// Extension for `Vec<String>` to manage unique keys.
+/// How keys are compared to tell whether they are unique.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+pub enum KeyMatching {
+    /// Keys differing by their case are distinct.
+    #[default]
+    Exact,
+    /// "food" and "Food" are the same key.
+    CaseInsensitive,
+}
+
+impl KeyMatching {
+    /// Returns `true` for the default mode.
+    pub fn is_exact(&self) -> bool {
+        *self == KeyMatching::Exact
+    }
+
+    /// Reduces a key to what identifies it.
+    pub fn identity(self, key: &str) -> String {
+        match self {
+            KeyMatching::Exact => normalize_key(key),
+            KeyMatching::CaseInsensitive => normalize_key(key).to_lowercase(),
+        }
+    }
+}
+
+/// Keys of a *category* type identical once compared.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct CategoryCollision {
+    pub kind: CategoryType,
+    pub names: Vec<String>,
+}
+
+/// Issue found while loading a file, reported rather than fixed.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub enum LoadWarning {
+    /// Categories whose names only differ by case or spacing.
+    CategoryCollisions(Vec<CategoryCollision>),
+}
+
+/// Trims a key and collapses its inner whitespace into single spaces.
+pub fn normalize_key(key: &str) -> String {
+    key.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Returns groups of keys identical once compared, in order of appearance.
+pub fn find_collisions(keys: &[String], matching: KeyMatching) -> Vec<Vec<String>> {
+    let identities = keys
+        .iter()
+        .map(|key| matching.identity(key))
+        .collect::<Vec<String>>();
+
+    (0..keys.len())
+        .filter(|&i| !identities[..i].contains(&identities[i]))
+        .map(|i| {
+            (i..keys.len())
+                .filter(|&j| identities[j] == identities[i])
+                .map(|j| keys[j].clone())
+                .collect::<Vec<String>>()
+        })
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+/// Extension for `Vec<String>` and `Vec<CategoryEntry>` to manage unique keys.
 pub trait ExclusiveItemExt {
-    /// Adds a new item if not exists yet.
-    fn add_exclusive(&mut self, key: &str) -> Option<RequestFailure>;
+    /// Adds a new item, with normalized whitespace, if not exists yet.
+    fn add_exclusive(&mut self, key: &str) -> Option<RequestFailure> {
+        self.add_exclusive_matching(key, KeyMatching::Exact)
+    }
+
+    /// Same as `add_exclusive` but compares keys according to `matching`.
+    fn add_exclusive_matching(
+        &mut self,
+        key: &str,
+        matching: KeyMatching,
+    ) -> Option<RequestFailure>;
 
     /// Removes an existing item.
     fn remove_exclusive(&mut self, key: &str) -> Option<RequestFailure>;
 
-    // Export sorted filter keys.
+    // Export sorted filter keys, archived ones only if `include_archived`.
     #[cfg(feature = "wasmbind")]
-    fn sorted_keys(&self) -> Array;
+    fn sorted_keys(&self, include_archived: bool) -> Array;
 }
 
 /// Extension for `Vec<Order>` to interpret existing data.
 pub trait OrderListExt {
     /// Computes the different amounts of a *category* between a given range.
+    /// Hidden orders are excluded.
+    ///
+    /// Returns `None` if there is no order, or if orders use different
+    /// currencies as they can not be summed without conversion.
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
     fn calculate_category_amount(
         &self,
         kind: CategoryType,
         category: &str,
         date_range: OptionNaiveDateRange,
-    ) -> Option<CategoryAmount>;
+    ) -> Result<Option<CategoryAmount>, RequestFailure> {
+        self.calculate_category_amount_including(kind, category, date_range, &[])
+    }
+
+    /// Computes the amounts of every *category* of counted orders between a
+    /// given range, scanning orders once.
+    ///
+    /// Categories whose orders use different currencies are left out.
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    fn calculate_all_category_amounts(
+        &self,
+        kind: CategoryType,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<HashMap<String, CategoryAmount>, RequestFailure>;
+
+    /// Same as `calculate_all_category_amounts`, along with the distribution
+    /// of order amounts of each *category*.
+    fn calculate_all_category_stats(
+        &self,
+        kind: CategoryType,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<HashMap<String, CategoryStats>, RequestFailure>;
+
+    /// Computes the different amounts of orders owning any of the given tags
+    /// between a given range, each order counted once. Hidden orders are
+    /// excluded.
+    ///
+    /// Returns `None` if there is no order, or if orders use different
+    /// currencies. Fails with `RequestFailure::Overflow` if a sum overflows.
+    fn calculate_tags_amount(
+        &self,
+        tags: &[String],
+        date_range: OptionNaiveDateRange,
+    ) -> Result<Option<CategoryAmount>, RequestFailure>;
+
+    /// Same as `calculate_category_amount` but also counts orders hidden for
+    /// one of the `included` reasons.
+    fn calculate_category_amount_including(
+        &self,
+        kind: CategoryType,
+        category: &str,
+        date_range: OptionNaiveDateRange,
+        included: &[HiddenReason],
+    ) -> Result<Option<CategoryAmount>, RequestFailure>;
+
+    /// Computes the different amounts of a *category* for each currency.
+    /// Orders without currency are assigned to `base_currency`.
+    ///
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    fn calculate_category_amount_by_currency(
+        &self,
+        kind: CategoryType,
+        category: &str,
+        date_range: OptionNaiveDateRange,
+        base_currency: Option<&str>,
+    ) -> Result<Vec<CurrencyAmount>, RequestFailure>;
+
+    /// Computes income and expenses of each period overlapping a date range,
+    /// according to the nature of visible orders. Currencies are ignored.
+    ///
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    fn calculate_income_expense(
+        &self,
+        period: Period,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<IncomeExpense>, RequestFailure>;
+
+    /// Counts visible orders missing tags or resource within a date range.
+    ///
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    fn calculate_categorization_progress(
+        &self,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<CategorizationProgress, RequestFailure>;
+
+    /// Computes the usage of each named *category* by counted orders.
+    /// Currencies are ignored.
+    ///
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    fn calculate_category_usage(
+        &self,
+        kind: CategoryType,
+        names: &[String],
+    ) -> Result<Vec<CategoryUsage>, RequestFailure>;
 
     /// Returns selected orders with their associated id.
     fn apply_filter(&self, filter: &Filter) -> Vec<(usize, &Order)>;
+
+    /// Returns up to `limit` selected orders from `offset`, along with the
+    /// number of selected ones.
+    fn apply_filter_paged(&self, filter: &Filter, offset: usize, limit: usize) -> FilteredPage<'_>;
+
+    /// Same as `apply_filter`, along with the balance after each order.
+    /// See `with_running_balance`.
+    ///
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    fn apply_filter_with_running_balance(
+        &self,
+        filter: &Filter,
+        opening: Amount,
+    ) -> Result<Vec<(usize, &Order, Amount)>, RequestFailure>;
 }
 
-impl ExclusiveItemExt for Vec<String> {
-    fn add_exclusive(&mut self, key: &str) -> Option<RequestFailure> {
-        if !key.is_empty() {
-            if !key.chars().all(char::is_whitespace) {
-                if !self.iter().any(|item| item == key) {
-                    self.push(key.into());
-                    None
-                } else {
-                    Some(RequestFailure::ExistingItem)
-                }
-            } else {
-                Some(RequestFailure::IncorrectArgument)
-            }
+/// Checks a new key against existing ones.
+pub(crate) fn check_new_key(key: &str, exists: bool) -> Option<RequestFailure> {
+    if key.is_empty() {
+        Some(RequestFailure::EmptyArgument)
+    } else if key.chars().all(char::is_whitespace) {
+        Some(RequestFailure::IncorrectArgument)
+    } else if exists {
+        Some(RequestFailure::ExistingItem)
+    } else {
+        None
+    }
+}
+
+/// Strings usable as unique keys.
+pub trait KeyString: AsRef<str> + From<String> + Clone {}
+
+impl KeyString for String {}
+impl KeyString for Interned {}
+
+impl<T: KeyString> ExclusiveItemExt for Vec<T> {
+    fn add_exclusive_matching(
+        &mut self,
+        key: &str,
+        matching: KeyMatching,
+    ) -> Option<RequestFailure> {
+        let identity = matching.identity(key);
+        let failure = check_new_key(
+            key,
+            self.iter()
+                .any(|item| matching.identity(item.as_ref()) == identity),
+        );
+        if failure.is_none() {
+            self.push(normalize_key(key).into());
+        }
+        failure
+    }
+
+    fn remove_exclusive(&mut self, key: &str) -> Option<RequestFailure> {
+        if let Some(index) = self.iter().position(|item| item.as_ref() == key) {
+            self.remove(index);
+            None
         } else {
-            Some(RequestFailure::EmptyArgument)
+            Some(RequestFailure::UnknownItem)
+        }
+    }
+
+    #[cfg(feature = "wasmbind")]
+    fn sorted_keys(&self, _include_archived: bool) -> Array {
+        let mut sorted_vec = self.clone();
+        sorted_vec.sort_by_key(|a| a.as_ref().to_lowercase());
+        sorted_vec
+            .iter()
+            .map(|item| JsValue::from(item.as_ref()))
+            .collect()
+    }
+}
+
+impl ExclusiveItemExt for Vec<CategoryEntry> {
+    fn add_exclusive_matching(
+        &mut self,
+        key: &str,
+        matching: KeyMatching,
+    ) -> Option<RequestFailure> {
+        let identity = matching.identity(key);
+        let failure = check_new_key(
+            key,
+            self.iter()
+                .any(|item| matching.identity(&item.name) == identity),
+        );
+        if failure.is_none() {
+            self.push(CategoryEntry::new(&normalize_key(key)));
         }
+        failure
     }
 
     fn remove_exclusive(&mut self, key: &str) -> Option<RequestFailure> {
@@ -119,131 +584,364 @@ impl ExclusiveItemExt for Vec<String> {
     }
 
     #[cfg(feature = "wasmbind")]
-    fn sorted_keys(&self) -> Array {
-        let mut sorted_vec = self.clone();
-        sorted_vec.sort_by_key(|a| a.to_lowercase());
-        sorted_vec.iter().map(JsValue::from).collect()
+    fn sorted_keys(&self, include_archived: bool) -> Array {
+        self.iter()
+            .filter(|entry| include_archived || !entry.archived)
+            .map(|entry| entry.name.clone())
+            .collect::<Vec<String>>()
+            .sorted_keys(true)
     }
 }
 
 impl OrderListExt for Vec<Order> {
-    fn calculate_category_amount(
+    fn calculate_category_amount_including(
         &self,
         kind: CategoryType,
         category: &str,
         date_range: OptionNaiveDateRange,
-    ) -> Option<CategoryAmount> {
-        let mut result = CategoryAmount {
-            current: 0.0,
-            pending: 0.0,
-            in_progress: 0.0,
-            expected: 0.0,
-        };
-        let mut nb_orders = 0;
-        let mut update_amount = |order: &Order| {
-            match order.state {
-                Pending => result.pending += order.amount,
-                InProgress => result.in_progress += order.amount,
-                Done => result.current += order.amount,
+        included: &[HiddenReason],
+    ) -> Result<Option<CategoryAmount>, RequestFailure> {
+        let orders = category_orders(self, kind, category, date_range, included);
+
+        match orders.first() {
+            Some(first)
+                if orders.iter().all(|order| {
+                    category_money(order, kind).0 == category_money(first, kind).0
+                }) =>
+            {
+                sum_amounts(&orders, kind)
+                    .map(Some)
+                    .ok_or(RequestFailure::Overflow)
             }
+            _ => Ok(None),
+        }
+    }
 
-            result.expected += order.amount;
-            nb_orders += 1;
-        };
-        let date_filter = NaiveDateFilter::from(date_range);
+    fn calculate_all_category_amounts(
+        &self,
+        kind: CategoryType,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<HashMap<String, CategoryAmount>, RequestFailure> {
+        Ok(self
+            .calculate_all_category_stats(kind, date_range)?
+            .into_iter()
+            .map(|(category, stats)| (category, stats.amount))
+            .collect())
+    }
 
-        match kind {
-            Resource => {
-                self.iter()
-                    .filter(|order| order.visible && order.resource == Some(category.into()))
-                    .filter(|order| date_filter.is_date_allowed(order.date))
-                    .for_each(|order| update_amount(order));
+    fn calculate_all_category_stats(
+        &self,
+        kind: CategoryType,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<HashMap<String, CategoryStats>, RequestFailure> {
+        let OptionNaiveDateRange(start, end) = date_range;
+        OrderColumns::from(self.as_slice()).all_category_stats(kind, (start, end))
+    }
 
-                if nb_orders > 0 {
-                    Some(result)
-                } else {
-                    None
-                }
+    fn calculate_tags_amount(
+        &self,
+        tags: &[String],
+        date_range: OptionNaiveDateRange,
+    ) -> Result<Option<CategoryAmount>, RequestFailure> {
+        let OptionNaiveDateRange(start, end) = date_range;
+        OrderColumns::from(self.as_slice()).tags_amount(tags, (start, end))
+    }
+
+    fn calculate_category_amount_by_currency(
+        &self,
+        kind: CategoryType,
+        category: &str,
+        date_range: OptionNaiveDateRange,
+        base_currency: Option<&str>,
+    ) -> Result<Vec<CurrencyAmount>, RequestFailure> {
+        let OptionNaiveDateRange(start, end) = date_range;
+        OrderColumns::from(self.as_slice()).category_amount_by_currency(
+            kind,
+            category,
+            (start, end),
+            base_currency,
+        )
+    }
+
+    fn calculate_income_expense(
+        &self,
+        period: Period,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<IncomeExpense>, RequestFailure> {
+        let mut result = Vec::new();
+
+        for bounds in period.split(start, end) {
+            let mut totals = IncomeExpense {
+                start: bounds.0,
+                end: bounds.1,
+                income: Amount::ZERO,
+                expenses: Amount::ZERO,
+            };
+
+            for order in self.iter().filter(|order| {
+                order.is_visible()
+                    && order
+                        .date
+                        .is_some_and(|date| date >= bounds.0 && date <= bounds.1)
+            }) {
+                let total = match order.effective_kind() {
+                    OrderKind::Income => &mut totals.income,
+                    OrderKind::Expense => &mut totals.expenses,
+                    OrderKind::Transfer => continue,
+                };
+                *total = total
+                    .checked_add(order.amount)
+                    .ok_or(RequestFailure::Overflow)?;
             }
-            Tag => {
-                self.iter()
-                    .filter(|order| order.visible && order.tags.contains(&category.to_string()))
-                    .filter(|order| date_filter.is_date_allowed(order.date))
-                    .for_each(|order| update_amount(order));
-
-                if nb_orders > 0 {
-                    Some(result)
-                } else {
-                    None
-                }
+
+            result.push(totals);
+        }
+
+        Ok(result)
+    }
+
+    fn calculate_categorization_progress(
+        &self,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<CategorizationProgress, RequestFailure> {
+        let date_filter = NaiveDateFilter::from(date_range);
+        let mut progress = CategorizationProgress {
+            total: OrderTally::default(),
+            categorized: OrderTally::default(),
+            untagged: OrderTally::default(),
+            without_resource: OrderTally::default(),
+            ratio: None,
+        };
+
+        for order in self
+            .iter()
+            .filter(|order| order.is_visible() && date_filter.is_date_allowed(order.date))
+        {
+            progress.total.add(order)?;
+            if order.tags.is_empty() {
+                progress.untagged.add(order)?;
+            }
+            if order.resource.is_none() {
+                progress.without_resource.add(order)?;
             }
+            if !order.tags.is_empty() && order.resource.is_some() {
+                progress.categorized.add(order)?;
+            }
+        }
+
+        if progress.total.count > 0 {
+            progress.ratio = Some(progress.categorized.count as f64 / progress.total.count as f64);
         }
+
+        Ok(progress)
+    }
+
+    fn calculate_category_usage(
+        &self,
+        kind: CategoryType,
+        names: &[String],
+    ) -> Result<Vec<CategoryUsage>, RequestFailure> {
+        names
+            .iter()
+            .map(|name| {
+                let orders =
+                    category_orders(self, kind, name, OptionNaiveDateRange(None, None), &[]);
+                let dates = orders.iter().filter_map(|order| order.date);
+
+                Ok(CategoryUsage {
+                    name: name.clone(),
+                    count: orders.len(),
+                    first_used: dates.clone().min(),
+                    last_used: dates.max(),
+                    total: Amount::checked_sum(orders.iter().map(|order| order.amount))
+                        .ok_or(RequestFailure::Overflow)?,
+                })
+            })
+            .collect()
     }
 
     fn apply_filter(&self, filter: &Filter) -> Vec<(usize, &Order)> {
         // Retain matching orders
+        let dates = filter.dates();
         let mut filtered_vector = self
             .iter()
-            .enumerate()
-            .filter(|(_, order)| filter.is_order_allowed(order))
+            .filter(|order| filter.is_order_allowed_on(order, &dates))
+            .map(|order| (order.id, order))
             .collect::<Vec<(usize, &Order)>>();
 
-        // Sort filtered orders by ordering preference
-        match filter.ordering {
-            ByDate => {
-                if filter.direction == Ascending {
-                    filtered_vector.sort_by(|a, b| a.1.date.cmp(&b.1.date));
-                } else {
-                    filtered_vector.sort_by(|a, b| b.1.date.cmp(&a.1.date));
-                }
-            }
-            ByDescription => {
-                if filter.direction == Ascending {
-                    filtered_vector.sort_by(|a, b| {
-                        a.1.description
-                            .to_lowercase()
-                            .cmp(&b.1.description.to_lowercase())
-                    });
-                } else {
-                    filtered_vector.sort_by(|a, b| {
-                        b.1.description
-                            .to_lowercase()
-                            .cmp(&a.1.description.to_lowercase())
-                    });
-                }
-            }
-            ByAmount => {
-                if filter.direction == Ascending {
-                    filtered_vector.sort_by(|a, b| {
-                        a.1.amount
-                            .partial_cmp(&b.1.amount)
-                            .expect("Something goes wrong..")
-                    });
-                } else {
-                    filtered_vector.sort_by(|a, b| {
-                        b.1.amount
-                            .partial_cmp(&a.1.amount)
-                            .expect("Something goes wrong..")
-                    });
-                }
-            }
-            ById => {
-                if filter.direction == Ascending {
-                    filtered_vector.sort_by(|a, b| a.0.cmp(&b.0));
+        sort_filtered_orders(filter, &mut filtered_vector);
+        filtered_vector
+    }
+
+    fn apply_filter_paged(&self, filter: &Filter, offset: usize, limit: usize) -> FilteredPage<'_> {
+        FilteredPage::new(self.apply_filter(filter), offset, limit)
+    }
+
+    fn apply_filter_with_running_balance(
+        &self,
+        filter: &Filter,
+        opening: Amount,
+    ) -> Result<Vec<(usize, &Order, Amount)>, RequestFailure> {
+        with_running_balance(self.apply_filter(filter), opening)
+    }
+}
+
+/// Adds to sorted orders the balance after each one: `opening` plus amounts
+/// of counted orders up to it, in chronological order whatever the sorting.
+/// Hidden orders keep the previous balance. Currencies are ignored.
+///
+/// Fails with `RequestFailure::Overflow` if a sum overflows.
+pub(crate) fn with_running_balance(
+    filtered: Vec<(usize, &Order)>,
+    opening: Amount,
+) -> Result<Vec<(usize, &Order, Amount)>, RequestFailure> {
+    let mut chronological = (0..filtered.len()).collect::<Vec<usize>>();
+    chronological.sort_by_key(|&index| (filtered[index].1.date, filtered[index].0));
+
+    let mut balances = vec![Amount::ZERO; filtered.len()];
+    let mut balance = opening;
+    for index in chronological {
+        let order = filtered[index].1;
+        if order.is_counted(&[]) {
+            balance = balance
+                .checked_add(order.amount)
+                .ok_or(RequestFailure::Overflow)?;
+        }
+        balances[index] = balance;
+    }
+
+    Ok(filtered
+        .into_iter()
+        .zip(balances)
+        .map(|((id, order), balance)| (id, order, balance))
+        .collect())
+}
+
+/// Compares two orders, along with their position in the list, by a single
+/// preference.
+fn compare_orders(
+    ordering: OrderingPreference,
+    (a_position, (a_id, a)): (usize, (usize, &Order)),
+    (b_position, (b_id, b)): (usize, (usize, &Order)),
+) -> std::cmp::Ordering {
+    match ordering {
+        ByDate => a.date.cmp(&b.date),
+        ByDescription => a
+            .description
+            .to_lowercase()
+            .cmp(&b.description.to_lowercase()),
+        ByAmount => a.amount.cmp(&b.amount),
+        ById => a_id.cmp(&b_id),
+        ByUpdate => a.updated_at().cmp(&b.updated_at()),
+        // Orders are already listed by position
+        ByPosition => a_position.cmp(&b_position),
+        ByResource => a
+            .resource
+            .as_ref()
+            .map(|resource| resource.to_lowercase())
+            .cmp(&b.resource.as_ref().map(|resource| resource.to_lowercase())),
+        ByState => (a.state() as u8).cmp(&(b.state() as u8)),
+        ByFirstTag => a
+            .tags
+            .first()
+            .map(|tag| tag.to_lowercase())
+            .cmp(&b.tags.first().map(|tag| tag.to_lowercase())),
+    }
+}
+
+/// Sorts filtered orders by ordering preferences, the next one breaking ties
+/// of the previous ones.
+pub(crate) fn sort_filtered_orders(filter: &Filter, filtered_vector: &mut [(usize, &Order)]) {
+    let orderings = filter.orderings();
+    let mut positioned = filtered_vector
+        .iter()
+        .copied()
+        .enumerate()
+        .collect::<Vec<(usize, (usize, &Order))>>();
+
+    positioned.sort_by(|a, b| {
+        orderings
+            .iter()
+            .map(|(ordering, direction)| {
+                let ordering = compare_orders(*ordering, *a, *b);
+                if *direction == Ascending {
+                    ordering
                 } else {
-                    filtered_vector.sort_by(|a, b| b.0.cmp(&a.0));
+                    ordering.reverse()
                 }
-            }
-        }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    filtered_vector
+        .iter_mut()
+        .zip(positioned)
+        .for_each(|(item, (_, sorted))| *item = sorted);
+}
 
-        filtered_vector
+/// Returns counted orders of a *category* within a date range.
+fn category_orders<'a>(
+    orders: &'a [Order],
+    kind: CategoryType,
+    category: &str,
+    date_range: OptionNaiveDateRange,
+    included: &[HiddenReason],
+) -> Vec<&'a Order> {
+    let date_filter = NaiveDateFilter::from(date_range);
+
+    orders
+        .iter()
+        .filter(|order| order.is_counted(included))
+        .filter(|order| match kind {
+            Resource => order.resource.as_deref() == Some(category),
+            Tag => order.tags.iter().any(|tag| tag == category),
+            Project => order.project.as_deref() == Some(category),
+            Payee => order.payee.as_deref() == Some(category),
+        })
+        .filter(|order| date_filter.is_date_allowed(order.date))
+        .collect()
+}
+
+/// Returns the currency and amount of an order for a *category*: resources
+/// hold the converted amount, if any.
+pub(crate) fn category_money(order: &Order, kind: CategoryType) -> (Option<&str>, Amount) {
+    match &order.conversion {
+        Some(conversion) if kind == Resource => (Some(&conversion.currency), conversion.amount),
+        _ => (order.currency.as_deref(), order.amount),
     }
 }
 
+/// Adds the amount of an order depending on its state.
+/// Returns `None` if a sum overflows.
+fn add_amount(result: &mut CategoryAmount, order: &Order, amount: Amount) -> Option<()> {
+    let total = match order.state {
+        Pending => &mut result.pending,
+        InProgress => &mut result.in_progress,
+        Done => &mut result.current,
+    };
+
+    *total = total.checked_add(amount)?;
+    result.expected = result.expected.checked_add(amount)?;
+    Some(())
+}
+
+/// Sums amounts of orders depending on their state.
+/// Returns `None` if a sum overflows.
+fn sum_amounts(orders: &[&Order], kind: CategoryType) -> Option<CategoryAmount> {
+    let mut result = CategoryAmount::default();
+
+    for order in orders {
+        add_amount(&mut result, order, category_money(order, kind).1)?;
+    }
+
+    Some(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::filter::NaiveDate;
+    use crate::order::TransactionState;
     use OrderingDirection::Descending;
 
     #[test]
@@ -258,6 +956,28 @@ mod tests {
         assert_eq!(list, items);
     }
 
+    #[test]
+    fn normalize_added_keys() {
+        let mut list = vec![String::from("Food")];
+
+        assert_eq!(
+            list.add_exclusive(" Food "),
+            Some(RequestFailure::ExistingItem)
+        );
+        assert_eq!(list.add_exclusive("food"), None);
+        assert_eq!(
+            list.add_exclusive_matching("FOOD", KeyMatching::CaseInsensitive),
+            Some(RequestFailure::ExistingItem)
+        );
+        assert_eq!(list.add_exclusive("  Video \t Games "), None);
+        assert_eq!(list, vec!["Food", "food", "Video Games"]);
+        assert_eq!(
+            find_collisions(&list, KeyMatching::CaseInsensitive),
+            vec![vec!["Food".to_string(), "food".to_string()]]
+        );
+        assert_eq!(find_collisions(&list, KeyMatching::Exact).len(), 0);
+    }
+
     #[test]
     fn remove_known_key() {
         let items = (0..3)
@@ -323,36 +1043,36 @@ mod tests {
     fn compute_overall_resource_amount() {
         let resources = [String::from("Bank"), String::from("Cash")];
         let tuples = vec![
-            (resources[0].clone(), -65.4, Done),
-            (resources[1].clone(), -32.83, Done),
-            (resources[1].clone(), -13.99, Pending),
-            (resources[1].clone(), -7.44, InProgress),
-            (resources[1].clone(), 15.00, Pending),
-            (resources[1].clone(), -69.99, InProgress),
-            (resources[1].clone(), 7.99, Done),
+            (resources[0].clone(), Amount::from_cents(-6540), Done),
+            (resources[1].clone(), Amount::from_cents(-3283), Done),
+            (resources[1].clone(), Amount::from_cents(-1399), Pending),
+            (resources[1].clone(), Amount::from_cents(-744), InProgress),
+            (resources[1].clone(), Amount::from_cents(1500), Pending),
+            (resources[1].clone(), Amount::from_cents(-6999), InProgress),
+            (resources[1].clone(), Amount::from_cents(799), Done),
         ];
         let result = CategoryAmount {
             current: tuples
                 .iter()
                 .filter(|x| x.0 == resources[1] && x.2 == Done)
-                .fold(0.0, |acc, x| acc + x.1),
+                .fold(Amount::ZERO, |acc, x| acc + x.1),
             pending: tuples
                 .iter()
                 .filter(|x| x.0 == resources[1] && x.2 == Pending)
-                .fold(0.0, |acc, x| acc + x.1),
+                .fold(Amount::ZERO, |acc, x| acc + x.1),
             in_progress: tuples
                 .iter()
                 .filter(|x| x.0 == resources[1] && x.2 == InProgress)
-                .fold(0.0, |acc, x| acc + x.1),
+                .fold(Amount::ZERO, |acc, x| acc + x.1),
             expected: tuples
                 .iter()
                 .filter(|x| x.0 == resources[1])
-                .fold(0.0, |acc, x| acc + x.1),
+                .fold(Amount::ZERO, |acc, x| acc + x.1),
         };
         let orders = tuples
             .into_iter()
             .map(|x| Order {
-                resource: Some(x.0),
+                resource: Some(x.0.into()),
                 amount: x.1,
                 state: x.2,
                 ..Order::default()
@@ -365,7 +1085,7 @@ mod tests {
                 resources[1].as_str(),
                 OptionNaiveDateRange(None, None)
             ),
-            Some(result)
+            Ok(Some(result))
         );
     }
 
@@ -376,43 +1096,43 @@ mod tests {
             (
                 Some(NaiveDate::from_ymd(2020, 1, 1)),
                 resources[0].clone(),
-                -65.4,
+                Amount::from_cents(-6540),
                 Pending,
             ),
             (
                 Some(NaiveDate::from_ymd(2020, 2, 1)),
                 resources[0].clone(),
-                -32.83,
+                Amount::from_cents(-3283),
                 InProgress,
             ),
             (
                 Some(NaiveDate::from_ymd(2020, 3, 1)),
                 resources[0].clone(),
-                -13.99,
+                Amount::from_cents(-1399),
                 Done,
             ),
             (
                 Some(NaiveDate::from_ymd(2020, 4, 1)),
                 resources[0].clone(),
-                -7.44,
+                Amount::from_cents(-744),
                 Done,
             ),
             (
                 Some(NaiveDate::from_ymd(2020, 5, 1)),
                 resources[0].clone(),
-                15.00,
+                Amount::from_cents(1500),
                 Pending,
             ),
             (
                 Some(NaiveDate::from_ymd(2020, 6, 1)),
                 resources[0].clone(),
-                -69.99,
+                Amount::from_cents(-6999),
                 Pending,
             ),
             (
                 Some(NaiveDate::from_ymd(2020, 7, 1)),
                 resources[0].clone(),
-                7.99,
+                Amount::from_cents(799),
                 Pending,
             ),
         ];
@@ -423,31 +1143,31 @@ mod tests {
                 .filter(|x| {
                     desired_date.signed_duration_since(x.0.unwrap()).num_days() >= 0 && x.3 == Done
                 })
-                .fold(0.0, |acc, x| acc + x.2),
+                .fold(Amount::ZERO, |acc, x| acc + x.2),
             pending: tuples
                 .iter()
                 .filter(|x| {
                     desired_date.signed_duration_since(x.0.unwrap()).num_days() >= 0
                         && x.3 == Pending
                 })
-                .fold(0.0, |acc, x| acc + x.2),
+                .fold(Amount::ZERO, |acc, x| acc + x.2),
             in_progress: tuples
                 .iter()
                 .filter(|x| {
                     desired_date.signed_duration_since(x.0.unwrap()).num_days() >= 0
                         && x.3 == InProgress
                 })
-                .fold(0.0, |acc, x| acc + x.2),
+                .fold(Amount::ZERO, |acc, x| acc + x.2),
             expected: tuples
                 .iter()
                 .filter(|x| desired_date.signed_duration_since(x.0.unwrap()).num_days() >= 0)
-                .fold(0.0, |acc, x| acc + x.2),
+                .fold(Amount::ZERO, |acc, x| acc + x.2),
         };
         let orders = tuples
             .into_iter()
             .map(|x| Order {
                 date: x.0,
-                resource: Some(x.1),
+                resource: Some(x.1.into()),
                 amount: x.2,
                 state: x.3,
                 ..Order::default()
@@ -460,7 +1180,7 @@ mod tests {
                 resources[0].as_str(),
                 OptionNaiveDateRange(None, Some(desired_date))
             ),
-            Some(result)
+            Ok(Some(result))
         );
     }
 
@@ -471,13 +1191,13 @@ mod tests {
             (
                 Some(NaiveDate::from_ymd(2020, 1, 1)),
                 resources[0].clone(),
-                -65.4,
+                Amount::from_cents(-6540),
                 Pending,
             ),
             (
                 Some(NaiveDate::from_ymd(2020, 2, 1)),
                 resources[0].clone(),
-                -32.83,
+                Amount::from_cents(-3283),
                 InProgress,
             ),
         ];
@@ -486,7 +1206,7 @@ mod tests {
             .into_iter()
             .map(|x| Order {
                 date: x.0,
-                resource: Some(x.1),
+                resource: Some(x.1.into()),
                 amount: x.2,
                 state: x.3,
                 ..Order::default()
@@ -499,26 +1219,370 @@ mod tests {
                 "Cash",
                 OptionNaiveDateRange(None, Some(desired_date))
             ),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn compute_project_amount() {
+        let orders = vec![
+            Order {
+                project: Some("Japan Trip".into()),
+                amount: Amount::from_cents(-85000),
+                state: Done,
+                ..Order::default()
+            },
+            Order {
+                project: Some("Japan Trip".into()),
+                amount: Amount::from_cents(-12000),
+                state: Pending,
+                ..Order::default()
+            },
+            Order {
+                project: Some("Kitchen".into()),
+                amount: Amount::from_cents(-300000),
+                state: Pending,
+                ..Order::default()
+            },
+            Order {
+                amount: Amount::from_cents(-1000),
+                ..Order::default()
+            },
+        ];
+
+        assert_eq!(
+            orders.calculate_category_amount(
+                Project,
+                "Japan Trip",
+                OptionNaiveDateRange(None, None)
+            ),
+            Ok(Some(CategoryAmount {
+                current: Amount::from_cents(-85000),
+                pending: Amount::from_cents(-12000),
+                in_progress: Amount::from_cents(0),
+                expected: Amount::from_cents(-97000),
+            }))
+        );
+    }
+
+    #[test]
+    fn compute_amount_including_hidden_reasons() {
+        let orders = vec![
+            Order {
+                resource: Some("Bank".into()),
+                amount: Amount::from_cents(-5000),
+                state: Done,
+                ..Order::default()
+            },
+            Order {
+                resource: Some("Bank".into()),
+                amount: Amount::from_cents(-20000),
+                state: Done,
+                hidden: Some(HiddenReason::InternalTransfer),
+                ..Order::default()
+            },
+            Order {
+                resource: Some("Bank".into()),
+                amount: Amount::from_cents(-5000),
+                state: Done,
+                hidden: Some(HiddenReason::Duplicate),
+                ..Order::default()
+            },
+        ];
+        let amount = |included: &[HiddenReason]| {
+            orders
+                .calculate_category_amount_including(
+                    Resource,
+                    "Bank",
+                    OptionNaiveDateRange(None, None),
+                    included,
+                )
+                .unwrap()
+                .map(|amount| amount.current)
+        };
+
+        assert_eq!(amount(&[]), Some(Amount::from_cents(-5000)));
+        assert_eq!(
+            amount(&[HiddenReason::InternalTransfer]),
+            Some(Amount::from_cents(-25000))
+        );
+    }
+
+    #[test]
+    fn group_amounts_by_currency() {
+        let orders = vec![
+            Order {
+                tags: vec!["Travel".into()],
+                amount: Amount::from_cents(-5000),
+                state: Done,
+                ..Order::default()
+            },
+            Order {
+                tags: vec!["Travel".into()],
+                amount: Amount::from_cents(-12000),
+                currency: Some("USD".into()),
+                state: Pending,
+                ..Order::default()
+            },
+            Order {
+                tags: vec!["Travel".into()],
+                amount: Amount::from_cents(-3000),
+                currency: Some("EUR".into()),
+                state: Done,
+                ..Order::default()
+            },
+        ];
+        // Mixing currencies without conversion is rejected
+        assert_eq!(
+            orders.calculate_category_amount(Tag, "Travel", OptionNaiveDateRange(None, None)),
+            Ok(None)
+        );
+        assert_eq!(
+            orders.calculate_category_amount_by_currency(
+                Tag,
+                "Travel",
+                OptionNaiveDateRange(None, None),
+                Some("EUR")
+            ),
+            Ok(vec![
+                CurrencyAmount {
+                    currency: Some("EUR".into()),
+                    amount: CategoryAmount {
+                        current: Amount::from_cents(-8000),
+                        pending: Amount::ZERO,
+                        in_progress: Amount::ZERO,
+                        expected: Amount::from_cents(-8000),
+                    },
+                },
+                CurrencyAmount {
+                    currency: Some("USD".into()),
+                    amount: CategoryAmount {
+                        current: Amount::ZERO,
+                        pending: Amount::from_cents(-12000),
+                        in_progress: Amount::ZERO,
+                        expected: Amount::from_cents(-12000),
+                    },
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn report_income_and_expenses_per_month() {
+        let order = |month, cents, kind| Order {
+            date: Some(NaiveDate::from_ymd(2021, month, 10)),
+            amount: Amount::from_cents(cents),
+            kind,
+            ..Order::default()
+        };
+        let orders = vec![
+            order(1, 250000, None),
+            order(1, -9000, None),
+            order(1, -50000, Some(OrderKind::Transfer)),
+            // Refunded purchase
+            order(3, 1500, Some(OrderKind::Expense)),
+        ];
+
+        assert_eq!(
+            orders
+                .calculate_income_expense(
+                    Period::Month,
+                    NaiveDate::from_ymd(2021, 1, 20),
+                    NaiveDate::from_ymd(2021, 3, 1)
+                )
+                .unwrap()
+                .iter()
+                .map(|totals| (totals.start, totals.income, totals.expenses))
+                .collect::<Vec<(NaiveDate, Amount, Amount)>>(),
+            [
+                (
+                    NaiveDate::from_ymd(2021, 1, 1),
+                    Amount::from_cents(250000),
+                    Amount::from_cents(-9000)
+                ),
+                (NaiveDate::from_ymd(2021, 2, 1), Amount::ZERO, Amount::ZERO),
+                (
+                    NaiveDate::from_ymd(2021, 3, 1),
+                    Amount::ZERO,
+                    Amount::from_cents(1500)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn measure_categorization_progress() {
+        let orders = vec![
+            Order {
+                amount: Amount::from_cents(-1000),
+                resource: Some("Bank".into()),
+                tags: vec!["Food".into()],
+                ..Order::default()
+            },
+            Order {
+                amount: Amount::from_cents(-3000),
+                resource: Some("Bank".into()),
+                ..Order::default()
+            },
+            Order {
+                amount: Amount::from_cents(500),
+                tags: vec!["Gift".into()],
+                ..Order::default()
+            },
+            Order {
+                amount: Amount::from_cents(-2000),
+                ..Order::default()
+            },
+        ];
+
+        let progress = orders
+            .calculate_categorization_progress(OptionNaiveDateRange(None, None))
+            .unwrap();
+
+        assert_eq!(
+            progress.total,
+            OrderTally {
+                count: 4,
+                amount: Amount::from_cents(6500)
+            }
+        );
+        assert_eq!(
+            progress.categorized,
+            OrderTally {
+                count: 1,
+                amount: Amount::from_cents(1000)
+            }
+        );
+        assert_eq!(progress.untagged.count, 2);
+        assert_eq!(progress.without_resource.amount, Amount::from_cents(2500));
+        assert_eq!(progress.ratio, Some(0.25));
+        assert_eq!(
+            Vec::<Order>::new()
+                .calculate_categorization_progress(OptionNaiveDateRange(None, None))
+                .unwrap()
+                .ratio,
             None
         );
     }
 
+    #[test]
+    fn calculate_all_category_amounts() {
+        let order = |tags: &[&str], cents: i64, currency: Option<&str>| Order {
+            date: Some(NaiveDate::from_ymd(2021, 3, 1)),
+            tags: tags.iter().map(|&tag| tag.into()).collect(),
+            amount: Amount::from_cents(cents),
+            currency: currency.map(String::from),
+            state: Done,
+            ..Order::default()
+        };
+        let mut orders = vec![
+            order(&["Food", "Travel"], -2000, None),
+            order(&["Food"], -500, None),
+            order(&["Travel"], -3000, Some("USD")),
+            order(&["Savings"], 1000, None),
+            order(&["Savings"], 2000, Some("USD")),
+        ];
+        orders[1].state = Pending;
+        let amounts = orders
+            .calculate_all_category_amounts(Tag, OptionNaiveDateRange(None, None))
+            .unwrap();
+
+        assert_eq!(amounts.len(), 1);
+        ["Food", "Travel", "Savings"].iter().for_each(|tag| {
+            assert_eq!(
+                amounts.get(*tag),
+                orders
+                    .calculate_category_amount(Tag, tag, OptionNaiveDateRange(None, None))
+                    .unwrap()
+                    .as_ref(),
+                "{}",
+                tag
+            )
+        });
+        assert_eq!(amounts["Food"].pending, Amount::from_cents(-500));
+        assert_eq!(amounts["Food"].expected, Amount::from_cents(-2500));
+
+        orders.push(order(&["Food"], -1001, None));
+        let stats = orders
+            .calculate_all_category_stats(Tag, OptionNaiveDateRange(None, None))
+            .unwrap();
+        let food = &stats["Food"];
+        assert_eq!(food.count, 3);
+        assert_eq!(food.average, Amount::from_cents(-1167));
+        assert_eq!(food.median, Amount::from_cents(-1001));
+        assert_eq!(
+            (food.min, food.max),
+            (Amount::from_cents(-2000), Amount::from_cents(-500))
+        );
+        orders.pop();
+        assert_eq!(
+            orders
+                .calculate_all_category_stats(Tag, OptionNaiveDateRange(None, None))
+                .unwrap()["Food"]
+                .median,
+            Amount::from_cents(-1250)
+        );
+        assert_eq!(
+            orders
+                .calculate_all_category_amounts(
+                    Tag,
+                    OptionNaiveDateRange(Some(NaiveDate::from_ymd(2021, 4, 1)), None)
+                )
+                .map(|amounts| amounts.len()),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn detect_category_amount_overflow() {
+        let orders = vec![
+            Order {
+                tags: vec!["Savings".into()],
+                amount: Amount::MAX,
+                ..Order::default()
+            };
+            2
+        ];
+
+        assert_eq!(
+            orders.calculate_category_amount(Tag, "Savings", OptionNaiveDateRange(None, None)),
+            Err(RequestFailure::Overflow)
+        );
+        assert_eq!(
+            orders.calculate_all_category_amounts(Tag, OptionNaiveDateRange(None, None)),
+            Err(RequestFailure::Overflow)
+        );
+        assert_eq!(
+            orders.calculate_category_amount_by_currency(
+                Tag,
+                "Savings",
+                OptionNaiveDateRange(None, None),
+                None
+            ),
+            Err(RequestFailure::Overflow)
+        );
+    }
+
     #[test]
     fn sort_orders_by_date() {
         let orders = vec![
             Order {
+                id: 0,
                 date: Some(NaiveDate::from_ymd(2020, 6, 3)),
                 ..Order::default()
             },
             Order {
+                id: 1,
                 date: Some(NaiveDate::from_ymd(2020, 10, 11)),
                 ..Order::default()
             },
             Order {
+                id: 2,
                 date: None,
                 ..Order::default()
             },
             Order {
+                id: 3,
                 date: Some(NaiveDate::from_ymd(2020, 8, 23)),
                 ..Order::default()
             },
@@ -556,18 +1620,22 @@ mod tests {
     fn sort_orders_by_description() {
         let orders = vec![
             Order {
+                id: 0,
                 description: "Shopping 🛍".into(),
                 ..Order::default()
             },
             Order {
+                id: 1,
                 description: "Restaurant 🥘".into(),
                 ..Order::default()
             },
             Order {
+                id: 2,
                 description: "Cinema 🍿".into(),
                 ..Order::default()
             },
             Order {
+                id: 3,
                 description: "Tennis 🎾".into(),
                 ..Order::default()
             },
@@ -606,18 +1674,22 @@ mod tests {
     fn sort_orders_by_id() {
         let orders = vec![
             Order {
+                id: 0,
                 description: "Shopping 🛍".into(),
                 ..Order::default()
             },
             Order {
+                id: 1,
                 description: "Restaurant 🥘".into(),
                 ..Order::default()
             },
             Order {
+                id: 2,
                 description: "Cinema 🍿".into(),
                 ..Order::default()
             },
             Order {
+                id: 3,
                 description: "Tennis 🎾".into(),
                 ..Order::default()
             },
@@ -656,19 +1728,23 @@ mod tests {
     fn sort_orders_by_amount() {
         let orders = vec![
             Order {
-                amount: 34.99,
+                id: 0,
+                amount: Amount::from_cents(3499),
                 ..Order::default()
             },
             Order {
-                amount: -5.5,
+                id: 1,
+                amount: Amount::from_cents(-550),
                 ..Order::default()
             },
             Order {
-                amount: -69.99,
+                id: 2,
+                amount: Amount::from_cents(-6999),
                 ..Order::default()
             },
             Order {
-                amount: 15.00,
+                id: 3,
+                amount: Amount::from_cents(1500),
                 ..Order::default()
             },
         ];
@@ -701,4 +1777,143 @@ mod tests {
             result
         );
     }
+
+    #[test]
+    fn sort_orders_by_category() {
+        let orders = [
+            (Some("card"), vec![], TransactionState::Done),
+            (None, vec!["Travel", "Food"], TransactionState::Pending),
+            (Some("Bank"), vec!["food"], TransactionState::InProgress),
+            (Some("Bank"), vec!["Drinks"], TransactionState::Pending),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(id, (resource, tags, state))| {
+            let mut order = Order {
+                id,
+                resource: resource.map(Interned::from),
+                tags: tags.iter().map(|&tag| tag.into()).collect(),
+                ..Order::default()
+            };
+            order.set_state(*state);
+            order
+        })
+        .collect::<Vec<Order>>();
+        let sorted = |ordering| {
+            orders
+                .apply_filter(&Filter {
+                    ordering,
+                    ..Filter::default()
+                })
+                .iter()
+                .map(|(id, _)| *id)
+                .collect::<Vec<usize>>()
+        };
+
+        assert_eq!(sorted(ByResource), vec![1, 2, 3, 0]);
+        assert_eq!(sorted(ByState), vec![1, 3, 2, 0]);
+        assert_eq!(sorted(ByFirstTag), vec![0, 3, 2, 1]);
+    }
+
+    #[test]
+    fn page_filtered_orders() {
+        let orders = (0..5)
+            .map(|id| Order {
+                id,
+                ..Order::default()
+            })
+            .collect::<Vec<Order>>();
+        let filter = Filter {
+            direction: Descending,
+            ..Filter::default()
+        };
+        let page = orders.apply_filter_paged(&filter, 1, 2);
+
+        assert_eq!(page.total, 5);
+        assert_eq!(page.orders, vec![(3, &orders[3]), (2, &orders[2])]);
+        assert_eq!(orders.apply_filter_paged(&filter, 4, 2).orders.len(), 1);
+        assert_eq!(orders.apply_filter_paged(&filter, 9, 2).orders.len(), 0);
+    }
+
+    #[test]
+    fn add_running_balance() {
+        let orders = [(2, 250000), (1, 10000), (3, -9000), (3, -1500)]
+            .iter()
+            .enumerate()
+            .map(|(id, &(day, cents))| Order {
+                id,
+                date: NaiveDate::from_ymd_opt(2021, 3, day),
+                amount: Amount::from_cents(cents),
+                hidden: if id == 3 {
+                    Some(HiddenReason::Manual)
+                } else {
+                    None
+                },
+                ..Order::default()
+            })
+            .collect::<Vec<Order>>();
+        let filter = Filter {
+            visibility: crate::filter::VisibilityFilter::VisibilityIgnored,
+            ordering: ByDate,
+            direction: Descending,
+            ..Filter::default()
+        };
+
+        assert_eq!(
+            orders
+                .apply_filter_with_running_balance(&filter, Amount::from_cents(500))
+                .unwrap()
+                .iter()
+                .map(|&(id, _, balance)| (id, balance.cents()))
+                .collect::<Vec<(usize, i64)>>(),
+            [(2, 251500), (3, 251500), (0, 260500), (1, 10500)]
+        );
+        assert_eq!(
+            orders.apply_filter_with_running_balance(&filter, Amount::MAX),
+            Err(RequestFailure::Overflow)
+        );
+    }
+
+    #[test]
+    fn sort_orders_by_several_criteria() {
+        let day = |day| NaiveDate::from_ymd_opt(2021, 3, day);
+        let orders = [
+            (day(2), 1500),
+            (day(1), -550),
+            (day(2), -6999),
+            (day(1), 3499),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(id, &(date, cents))| Order {
+            id,
+            date,
+            amount: Amount::from_cents(cents),
+            ..Order::default()
+        })
+        .collect::<Vec<Order>>();
+        let mut filter = Filter {
+            ordering: ByDate,
+            direction: Descending,
+            ..Filter::default()
+        };
+        filter.add_then_ordering(ByAmount, Ascending);
+
+        let result = [2, 0, 1, 3]
+            .iter()
+            .map(|&x| (x, &orders[x]))
+            .collect::<Vec<(usize, &Order)>>();
+
+        assert_eq!(orders.apply_filter(&filter), result);
+
+        // Ties keep the list order
+        filter.clear_then_orderings();
+        filter.add_then_ordering(ByPosition, Descending);
+        let result = [2, 0, 3, 1]
+            .iter()
+            .map(|&x| (x, &orders[x]))
+            .collect::<Vec<(usize, &Order)>>();
+
+        assert_eq!(orders.apply_filter(&filter), result);
+    }
 }
@@ -0,0 +1,93 @@
+//! # User-defined fields of orders ("Reimbursable by", "Invoice", ..).
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasmbind")]
+use wasm_bindgen::prelude::*;
+
+/// Type of values held by a custom field.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum FieldType {
+    Text,
+    Number,
+    Bool,
+}
+
+/// Field declared by an account.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct CustomField {
+    /// Unique name within an account.
+    pub name: String,
+    pub kind: FieldType,
+}
+
+/// Value of a custom field.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum FieldValue {
+    Bool(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl FieldType {
+    /// Converts a textual value, such as a form input.
+    /// Returns `None` if it does not match the type.
+    pub fn parse(self, text: &str) -> Option<FieldValue> {
+        match self {
+            FieldType::Text => Some(FieldValue::Text(text.into())),
+            FieldType::Number => text
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .map(FieldValue::Number),
+            FieldType::Bool => text.trim().parse::<bool>().ok().map(FieldValue::Bool),
+        }
+    }
+}
+
+impl FieldValue {
+    /// Returns the type of the value.
+    pub fn kind(&self) -> FieldType {
+        match self {
+            FieldValue::Bool(_) => FieldType::Bool,
+            FieldValue::Number(_) => FieldType::Number,
+            FieldValue::Text(_) => FieldType::Text,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_field_values() {
+        assert_eq!(
+            FieldType::Number.parse(" 12.5"),
+            Some(FieldValue::Number(12.5))
+        );
+        assert_eq!(FieldType::Number.parse("NaN"), None);
+        assert_eq!(FieldType::Bool.parse("true"), Some(FieldValue::Bool(true)));
+        assert_eq!(FieldType::Bool.parse("yes"), None);
+        assert_eq!(
+            FieldType::Text.parse("true"),
+            Some(FieldValue::Text("true".into()))
+        );
+    }
+
+    #[test]
+    fn load_untyped_values() {
+        let values: Vec<FieldValue> = serde_yaml::from_str("[true, 3, 'true', Alice]").unwrap();
+
+        assert_eq!(
+            values,
+            [
+                FieldValue::Bool(true),
+                FieldValue::Number(3.0),
+                FieldValue::Text("true".into()),
+                FieldValue::Text("Alice".into()),
+            ]
+        );
+    }
+}
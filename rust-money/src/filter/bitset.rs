@@ -0,0 +1,161 @@
+//! Tags of orders pre-computed as bitsets, to evaluate tag filters with bit
+//! operations.
+use super::category::CategoryFilter;
+use super::ItemSelector;
+use crate::order::Order;
+use std::cell::RefCell;
+
+/// Number of tags held by a bitset word.
+const WORD_BITS: usize = 64;
+
+/// Bitsets of orders against a list of tags.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TagBitsets {
+    tags: Vec<String>,
+    /// Number of words per order.
+    words: usize,
+    /// Bitsets of all orders, in list order.
+    bits: Vec<u64>,
+}
+
+impl TagBitsets {
+    /// Computes bitsets of orders; their tags missing from the list are
+    /// ignored.
+    pub fn new(tags: &[String], orders: &[Order]) -> TagBitsets {
+        let words = tags.len().div_ceil(WORD_BITS).max(1);
+        let mut bits = vec![0; orders.len() * words];
+
+        orders.iter().enumerate().for_each(|(row, order)| {
+            order
+                .tags
+                .iter()
+                .filter_map(|tag| tags.iter().position(|item| item == tag))
+                .for_each(|bit| bits[row * words + bit / WORD_BITS] |= 1 << (bit % WORD_BITS))
+        });
+
+        TagBitsets {
+            tags: tags.to_vec(),
+            words,
+            bits,
+        }
+    }
+
+    /// Returns the number of orders.
+    pub fn len(&self) -> usize {
+        self.bits.len() / self.words
+    }
+
+    /// Returns `true` without orders.
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Returns the bitset of selected tags, or `None` if one of them is unknown.
+    pub fn selected_mask(&self, filter: &CategoryFilter) -> Option<Vec<u64>> {
+        let mut mask = vec![0; self.words];
+
+        if let CategoryFilter::Enabled(categories) = filter {
+            for category in categories
+                .iter()
+                .filter(|category| category.1 == ItemSelector::Selected)
+            {
+                let bit = self.tags.iter().position(|tag| *tag == category.0)?;
+                mask[bit / WORD_BITS] |= 1 << (bit % WORD_BITS);
+            }
+        }
+
+        Some(mask)
+    }
+
+    /// Returns `true` if the order at a given position owns each tag of the
+    /// mask.
+    pub fn with_each(&self, row: usize, mask: &[u64]) -> bool {
+        self.bits[row * self.words..(row + 1) * self.words]
+            .iter()
+            .zip(mask)
+            .all(|(bits, mask)| bits & mask == *mask)
+    }
+}
+
+/// Lazily computed bitsets, dropped whenever tags or orders change.
+///
+/// Ignored by comparisons as it only speeds up filtering.
+#[derive(Default, Debug)]
+pub struct TagBitsetCache(RefCell<Option<TagBitsets>>);
+
+impl PartialEq for TagBitsetCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl TagBitsetCache {
+    /// Drops computed bitsets.
+    pub fn invalidate(&mut self) {
+        *self.0.get_mut() = None;
+    }
+
+    /// Runs a function with up-to-date bitsets.
+    pub fn with<T>(
+        &self,
+        tags: &[String],
+        orders: &[Order],
+        function: impl FnOnce(&TagBitsets) -> T,
+    ) -> T {
+        let mut cache = self.0.borrow_mut();
+
+        if cache
+            .as_ref()
+            .is_none_or(|bitsets| bitsets.len() != orders.len())
+        {
+            *cache = Some(TagBitsets::new(tags, orders));
+        }
+
+        function(cache.as_ref().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::category::Category;
+    use ItemSelector::{Discarded, Selected};
+
+    #[test]
+    fn match_selected_tags() {
+        let tags = (0..100)
+            .map(|index| format!("Tag {}", index))
+            .collect::<Vec<String>>();
+        let orders = [
+            Order {
+                tags: vec![tags[3].clone().into(), tags[70].clone().into()],
+                ..Order::default()
+            },
+            Order {
+                tags: vec![tags[70].clone().into()],
+                ..Order::default()
+            },
+        ];
+        let bitsets = TagBitsets::new(&tags, &orders);
+        let mask = bitsets
+            .selected_mask(&CategoryFilter::Enabled(vec![
+                Category(tags[3].clone(), Selected),
+                Category(tags[70].clone(), Selected),
+                Category(tags[4].clone(), Discarded),
+            ]))
+            .unwrap();
+
+        assert_eq!(bitsets.len(), 2);
+        assert_eq!(bitsets.with_each(0, &mask), true);
+        assert_eq!(bitsets.with_each(1, &mask), false);
+        assert_eq!(
+            bitsets
+                .selected_mask(&CategoryFilter::Enabled(vec![Category(
+                    "Unknown".into(),
+                    Selected
+                )]))
+                .is_none(),
+            true
+        );
+    }
+}
@@ -1,13 +1,14 @@
 //! Filtering option which allows or not an `Order` according to its *category* subscription.
 use super::ItemSelector;
+use serde::{Deserialize, Serialize};
 use CategoryFilter::{CategoryIgnored, Enabled};
 
 /// Key-value tuple struct which manages either *tag* or *resource*.
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 pub struct Category(pub String, pub ItemSelector);
 
 /// Filtering options for tags or resources.
-#[derive(PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub enum CategoryFilter {
     CategoryIgnored,
     Enabled(Vec<Category>),
@@ -66,28 +67,57 @@ impl CategoryFilter {
         }
     }
 
+    /// Selects each category.
+    pub fn select_all(&mut self) {
+        self.for_each_selector(|selector| *selector = ItemSelector::Selected);
+    }
+
+    /// Discards each category.
+    pub fn select_none(&mut self) {
+        self.for_each_selector(|selector| *selector = ItemSelector::Discarded);
+    }
+
+    /// Toggles the state of each category.
+    pub fn invert(&mut self) {
+        self.for_each_selector(ItemSelector::toggle);
+    }
+
+    fn for_each_selector(&mut self, function: impl FnMut(&mut ItemSelector)) {
+        if let Enabled(items) = self {
+            items.iter_mut().map(|item| &mut item.1).for_each(function);
+        }
+    }
+
     /// Returns true if input list holds (at leat) all selected categories, false otherwise.
-    pub fn with_each_selected(&self, category_names: &[String]) -> bool {
+    pub fn with_each_selected<T: AsRef<str>>(&self, category_names: &[T]) -> bool {
         match self {
             CategoryIgnored => true,
             Enabled(categories) => categories
                 .iter()
                 .filter(|category| category.1 == ItemSelector::Selected)
-                .all(|category| category_names.contains(&category.0)),
+                .all(|category| {
+                    category_names
+                        .iter()
+                        .any(|name| name.as_ref() == category.0)
+                }),
         }
     }
 
     /// Returns true if *some* input category name is among selected ones.
-    pub fn among_any_selected(&self, category_name: &Option<String>) -> bool {
+    pub fn among_any_selected<T: AsRef<str>>(&self, category_name: &Option<T>) -> bool {
         match self {
             CategoryIgnored => true,
-            Enabled(categories) if category_name == &None => categories
+            Enabled(categories) if category_name.is_none() => categories
                 .iter()
                 .all(|category| category.1 == ItemSelector::Discarded),
             Enabled(categories) => categories
                 .iter()
                 .filter(|category| category.1 == ItemSelector::Selected)
-                .any(|category| category.0 == *category_name.as_ref().unwrap()),
+                .any(|category| {
+                    category_name
+                        .as_ref()
+                        .is_some_and(|name| name.as_ref() == category.0)
+                }),
         }
     }
 }
@@ -97,6 +127,35 @@ mod tests {
     use super::ItemSelector::{Discarded, Selected};
     use super::*;
 
+    #[test]
+    fn select_all_categories() {
+        let mut category_filter = Enabled(vec![
+            Category("Food".into(), Selected),
+            Category("Travel".into(), Discarded),
+        ]);
+
+        category_filter.invert();
+        assert_eq!(
+            category_filter,
+            Enabled(vec![
+                Category("Food".into(), Discarded),
+                Category("Travel".into(), Selected),
+            ])
+        );
+
+        category_filter.select_all();
+        assert_eq!(category_filter.among_any_selected(&Some("Food")), true);
+        assert_eq!(category_filter.among_any_selected(&Some("Travel")), true);
+
+        category_filter.select_none();
+        assert_eq!(category_filter.among_any_selected(&None::<String>), true);
+        assert_eq!(category_filter.among_any_selected(&Some("Food")), false);
+
+        let mut category_filter = CategoryIgnored;
+        category_filter.select_all();
+        assert_eq!(category_filter, CategoryIgnored);
+    }
+
     #[test]
     fn toggle_selection() {
         let categories = [
@@ -225,7 +284,7 @@ mod tests {
     #[test]
     fn allow_any_category() {
         let category_filter = CategoryIgnored;
-        let allowed_category_1 = None;
+        let allowed_category_1 = None::<String>;
         let allowed_category_2 = Some("Car".to_string());
 
         assert_eq!(
@@ -247,7 +306,7 @@ mod tests {
         let allowed_category = Some(categories[0].0.clone());
         let rejected_category_1 = Some(categories[1].0.clone());
         let rejected_category_2 = Some("Unknown".to_string());
-        let rejected_category_3 = None;
+        let rejected_category_3 = None::<String>;
         let category_filter = Enabled(categories);
 
         assert_eq!(category_filter.among_any_selected(&allowed_category), true);
@@ -271,7 +330,7 @@ mod tests {
             Category("Bank".to_string(), Discarded),
             Category("Cash".to_string(), Discarded),
         ];
-        let allowed_category = None;
+        let allowed_category = None::<String>;
         let rejected_category_1 = Some(categories[0].0.clone());
         let rejected_category_2 = Some(categories[1].0.clone());
         let rejected_category_3 = Some("Unknown".to_string());
@@ -295,7 +354,7 @@ mod tests {
     #[test]
     fn allow_any_list() {
         let category_filter = CategoryIgnored;
-        let allowed_category_1 = [];
+        let allowed_category_1: [String; 0] = [];
         let allowed_category_2 = ["Car".to_string(), "Insurance".to_string()];
 
         assert_eq!(
@@ -323,7 +382,7 @@ mod tests {
         let allowed_category_2 = [categories[0].0.clone(), categories[2].0.clone()];
         let rejected_category_1 = [categories[1].0.clone()];
         let rejected_category_2 = ["Unknown".to_string()];
-        let rejected_category_3 = [];
+        let rejected_category_3: [String; 0] = [];
         let rejected_category_4 = [categories[0].0.clone()];
         let rejected_category_5 = [categories[2].0.clone()];
         let category_filter = Enabled(categories);
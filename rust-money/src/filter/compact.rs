@@ -0,0 +1,503 @@
+//! Short URL-safe encoding of a `Filter`, to restore a view from a link.
+//!
+//! Options differing from the default ones are written as segments separated
+//! by `.`; each one starts with a letter identifying the option. Names only
+//! keep ASCII alphanumerics, other bytes are escaped as `_` and two hex digits.
+use super::category::{Category, CategoryFilter};
+use super::date::{NaiveDateFilter, RelativePeriod};
+use super::expr::FilterExpr;
+use super::field::FieldFilter;
+use super::metadata::MetadataFilter;
+use super::state::StateFilter;
+use super::{Filter, ItemSelector, SignFilter, VisibilityFilter};
+use crate::ext::{OrderingDirection, OrderingPreference};
+use crate::field::{FieldType, FieldValue};
+use chrono::NaiveDate;
+#[cfg(feature = "wasmbind")]
+use wasm_bindgen::prelude::*;
+
+/// Separates options.
+const SEGMENT: char = '.';
+/// Separates the items of an option.
+const ITEM: char = '~';
+/// Separates the parts of an item.
+const PART: char = '-';
+/// Format of dates.
+const DATE_FORMAT: &str = "%Y%m%d";
+
+/// Sorting preferences, indexed by their code.
+const ORDERINGS: [OrderingPreference; 9] = [
+    OrderingPreference::ByDate,
+    OrderingPreference::ByDescription,
+    OrderingPreference::ByAmount,
+    OrderingPreference::ById,
+    OrderingPreference::ByUpdate,
+    OrderingPreference::ByPosition,
+    OrderingPreference::ByResource,
+    OrderingPreference::ByState,
+    OrderingPreference::ByFirstTag,
+];
+
+/// Relative periods by code.
+const RELATIVE_PERIODS: [RelativePeriod; 6] = [
+    RelativePeriod::ThisWeek,
+    RelativePeriod::ThisMonth,
+    RelativePeriod::LastMonth,
+    RelativePeriod::Last30Days,
+    RelativePeriod::YearToDate,
+    RelativePeriod::LastYear,
+];
+
+/// Escapes a name so that it only holds ASCII alphanumerics and `_`.
+fn escape(name: &str) -> String {
+    name.bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() {
+                (byte as char).to_string()
+            } else {
+                format!("_{:02x}", byte)
+            }
+        })
+        .collect()
+}
+
+/// Reverts `escape`; returns `None` for malformed input.
+fn unescape(text: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut input = text.bytes();
+
+    while let Some(byte) = input.next() {
+        if byte == b'_' {
+            let digits = [input.next()?, input.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&digits).ok()?, 16).ok()?);
+        } else if byte.is_ascii_alphanumeric() {
+            bytes.push(byte);
+        } else {
+            return None;
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Converts a field type into its code.
+fn field_code(kind: FieldType) -> char {
+    match kind {
+        FieldType::Text => 'T',
+        FieldType::Number => 'N',
+        FieldType::Bool => 'B',
+    }
+}
+
+/// Converts a field value into text understood by `FieldType::parse`.
+fn field_text(value: &FieldValue) -> String {
+    match value {
+        FieldValue::Bool(value) => value.to_string(),
+        FieldValue::Number(value) => value.to_string(),
+        FieldValue::Text(value) => value.clone(),
+    }
+}
+
+/// Encodes the categories of an enabled option.
+fn encode_categories(categories: &[Category]) -> String {
+    categories
+        .iter()
+        .map(|category| {
+            let selector = match category.1 {
+                ItemSelector::Selected => '1',
+                ItemSelector::Discarded => '0',
+            };
+            format!("{}{}", selector, escape(&category.0))
+        })
+        .collect::<Vec<String>>()
+        .join(&ITEM.to_string())
+}
+
+/// Decodes the categories of an enabled option.
+fn decode_categories(text: &str) -> Option<CategoryFilter> {
+    if text.is_empty() {
+        return Some(CategoryFilter::Enabled(Vec::new()));
+    }
+
+    text.split(ITEM)
+        .map(|item| {
+            let selector = match item.chars().next()? {
+                '1' => ItemSelector::Selected,
+                '0' => ItemSelector::Discarded,
+                _ => return None,
+            };
+            Some(Category(unescape(&item[1..])?, selector))
+        })
+        .collect::<Option<Vec<Category>>>()
+        .map(CategoryFilter::Enabled)
+}
+
+/// Decodes an optional date.
+fn decode_date(text: &str) -> Option<Option<NaiveDate>> {
+    if text.is_empty() {
+        Some(None)
+    } else {
+        NaiveDate::parse_from_str(text, DATE_FORMAT).ok().map(Some)
+    }
+}
+
+/// `wasm_bindgen` compatible functions.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+impl Filter {
+    /// Encodes all options as a short URL-safe string.
+    pub fn encode_compact(&self) -> String {
+        let default = Filter::default();
+        let mut segments = Vec::new();
+
+        match self.visibility {
+            VisibilityFilter::VisibleOnly => {}
+            VisibilityFilter::VisibilityIgnored => segments.push("v0".to_string()),
+            VisibilityFilter::HiddenOnly => segments.push("v2".to_string()),
+        }
+        if self.show_scheduled {
+            segments.push("x".into());
+        }
+        match self.sign {
+            SignFilter::SignIgnored => {}
+            SignFilter::PositiveOnly => segments.push("a1".into()),
+            SignFilter::NegativeOnly => segments.push("a2".into()),
+        }
+        if self.untagged_only {
+            segments.push("u".into());
+        }
+        if self.unassigned_only {
+            segments.push("l".into());
+        }
+        if let NaiveDateFilter::Relative(period) = self.date_option {
+            segments.push(format!("c{}", period as u8));
+        }
+        let (start, end) = match self.date_option {
+            NaiveDateFilter::DateIgnored | NaiveDateFilter::Relative(_) => (None, None),
+            NaiveDateFilter::Since(start) => (Some(start), None),
+            NaiveDateFilter::Until(end) => (None, Some(end)),
+            NaiveDateFilter::Between(start, end) => (Some(start), Some(end)),
+        };
+        if start.is_some() || end.is_some() {
+            let date = |date: Option<NaiveDate>| {
+                date.map_or(String::new(), |date| date.format(DATE_FORMAT).to_string())
+            };
+            segments.push(format!("d{}{}{}", date(start), PART, date(end)));
+        }
+        if self.state_option != default.state_option {
+            segments.push(format!("s{}", self.state_option.mask()));
+        }
+        [
+            ('r', &self.resource_option),
+            ('t', &self.tag_option),
+            ('p', &self.project_option),
+            ('y', &self.payee_option),
+            ('k', &self.currency_option),
+        ]
+        .iter()
+        .for_each(|(key, option)| {
+            if let CategoryFilter::Enabled(categories) = option {
+                segments.push(format!("{}{}", key, encode_categories(categories)));
+            }
+        });
+        match &self.metadata_option {
+            MetadataFilter::MetadataIgnored => {}
+            MetadataFilter::WithKey(key) => segments.push(format!("m{}", escape(key))),
+            MetadataFilter::WithValue(key, value) => {
+                segments.push(format!("m{}{}{}", escape(key), PART, escape(value)))
+            }
+        }
+        match &self.field_option {
+            FieldFilter::FieldIgnored => {}
+            FieldFilter::WithField(name) => segments.push(format!("f{}", escape(name))),
+            FieldFilter::WithFieldValue(name, value) => segments.push(format!(
+                "f{}{}{}{}{}",
+                escape(name),
+                PART,
+                field_code(value.kind()),
+                PART,
+                escape(&field_text(value))
+            )),
+        }
+        if let Some(code) = &self.base_currency {
+            segments.push(format!("b{}", code));
+        }
+        if let Some(text) = &self.reference_option {
+            segments.push(format!("n{}", escape(text)));
+        }
+        if let Some(text) = &self.text_option {
+            segments.push(format!("w{}", escape(text)));
+        }
+        if let Some(expression) = &self.expression {
+            segments.push(format!("g{}", escape(&expression.to_string())));
+        }
+        #[cfg(feature = "regex")]
+        if let Some(pattern) = self.text_pattern() {
+            segments.push(format!("e{}", escape(pattern)));
+        }
+        if self.ordering as u8 != default.ordering as u8
+            || self.direction != default.direction
+            || !self.then_orderings.is_empty()
+        {
+            segments.push(format!(
+                "o{}",
+                self.orderings()
+                    .iter()
+                    .map(|(ordering, direction)| format!("{}{}", *ordering as u8, *direction as u8))
+                    .collect::<String>()
+            ));
+        }
+
+        segments.join(&SEGMENT.to_string())
+    }
+
+    /// Decodes options written by `encode_compact`; missing ones keep their
+    /// default value. Returns `None` for malformed input.
+    pub fn decode_compact(text: &str) -> Option<Filter> {
+        let mut filter = Filter::default();
+
+        for segment in text.split(SEGMENT).filter(|segment| !segment.is_empty()) {
+            let key = segment.chars().next()?;
+            let value = &segment[key.len_utf8()..];
+            let parts = value.split(PART).collect::<Vec<&str>>();
+
+            match key {
+                'v' => {
+                    filter.visibility = match value {
+                        "0" => VisibilityFilter::VisibilityIgnored,
+                        "2" => VisibilityFilter::HiddenOnly,
+                        _ => return None,
+                    }
+                }
+                'x' => filter.show_scheduled = true,
+                'a' => {
+                    filter.sign = match value {
+                        "1" => SignFilter::PositiveOnly,
+                        "2" => SignFilter::NegativeOnly,
+                        _ => return None,
+                    }
+                }
+                'u' => filter.untagged_only = true,
+                'l' => filter.unassigned_only = true,
+                'd' => match parts.as_slice() {
+                    [start, end] => filter.date_option.set_range(super::OptionNaiveDateRange(
+                        decode_date(start)?,
+                        decode_date(end)?,
+                    )),
+                    _ => return None,
+                },
+                's' => {
+                    filter.state_option = StateFilter::from_mask(value.parse::<u8>().ok()?)?;
+                }
+                'c' => {
+                    let period = value.parse::<usize>().ok()?;
+                    filter.date_option = NaiveDateFilter::Relative(*RELATIVE_PERIODS.get(period)?);
+                }
+                'r' => filter.resource_option = decode_categories(value)?,
+                't' => filter.tag_option = decode_categories(value)?,
+                'p' => filter.project_option = decode_categories(value)?,
+                'y' => filter.payee_option = decode_categories(value)?,
+                'k' => filter.currency_option = decode_categories(value)?,
+                'b' => {
+                    if value.is_empty() || !filter.set_base_currency(value) {
+                        return None;
+                    }
+                }
+                'm' => {
+                    filter.metadata_option = match parts.as_slice() {
+                        [key] => MetadataFilter::WithKey(unescape(key)?),
+                        [key, value] => MetadataFilter::WithValue(unescape(key)?, unescape(value)?),
+                        _ => return None,
+                    }
+                }
+                'f' => {
+                    filter.field_option = match parts.as_slice() {
+                        [name] => FieldFilter::WithField(unescape(name)?),
+                        [name, kind, value] => {
+                            let kind = match *kind {
+                                "T" => FieldType::Text,
+                                "N" => FieldType::Number,
+                                "B" => FieldType::Bool,
+                                _ => return None,
+                            };
+                            FieldFilter::WithFieldValue(
+                                unescape(name)?,
+                                kind.parse(&unescape(value)?)?,
+                            )
+                        }
+                        _ => return None,
+                    }
+                }
+                'n' => filter.reference_option = Some(unescape(value)?),
+                'w' => filter.text_option = Some(unescape(value)?),
+                'g' => filter.expression = Some(FilterExpr::parse(&unescape(value)?)?),
+                #[cfg(feature = "regex")]
+                'e' => {
+                    if !filter.set_text_pattern(&unescape(value)?) {
+                        return None;
+                    }
+                }
+                'o' => {
+                    let digits = value
+                        .chars()
+                        .map(|digit| digit.to_digit(10).map(|digit| digit as usize))
+                        .collect::<Option<Vec<usize>>>()?;
+                    if digits.is_empty() || digits.len() % 2 != 0 {
+                        return None;
+                    }
+                    let mut orderings = digits
+                        .chunks(2)
+                        .map(|pair| {
+                            let direction = match pair[1] {
+                                0 => OrderingDirection::Ascending,
+                                1 => OrderingDirection::Descending,
+                                _ => return None,
+                            };
+                            Some((*ORDERINGS.get(pair[0])?, direction))
+                        })
+                        .collect::<Option<Vec<(OrderingPreference, OrderingDirection)>>>()?;
+                    let (ordering, direction) = orderings.remove(0);
+                    filter.ordering = ordering;
+                    filter.direction = direction;
+                    filter.then_orderings = orderings;
+                }
+                _ => return None,
+            }
+        }
+
+        Some(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext::CategoryType;
+
+    #[test]
+    fn escape_names() {
+        assert_eq!(escape("Café & co"), "Caf_c3_a9_20_26_20co");
+        assert_eq!(unescape(&escape("Café & co")), Some("Café & co".into()));
+        assert_eq!(unescape("a_2"), None);
+        assert_eq!(unescape("a-b"), None);
+    }
+
+    #[test]
+    fn encode_default_filter() {
+        assert_eq!(Filter::default().encode_compact(), "");
+        assert_eq!(
+            Filter::decode_compact("").map(|filter| filter.encode_compact()),
+            Some("".into())
+        );
+    }
+
+    #[test]
+    fn restore_encoded_filter() {
+        let mut filter = Filter {
+            visibility: VisibilityFilter::HiddenOnly,
+            untagged_only: true,
+            sign: SignFilter::NegativeOnly,
+            ordering: OrderingPreference::ByAmount,
+            direction: OrderingDirection::Descending,
+            ..Filter::default()
+        };
+        filter.set_date_option("2021-01-01", "");
+        filter.toggle_state(crate::order::TransactionState::Done);
+        filter.get_category_option_mut(CategoryType::Tag).set(
+            vec![
+                Category("Food & Drinks".into(), ItemSelector::Selected),
+                Category("Travel".into(), ItemSelector::Discarded),
+            ]
+            .into_iter(),
+        );
+        filter.set_metadata_value("bank_id", "");
+        filter.set_field_value_option("Rate", FieldType::Number, "-0.5");
+        filter.set_reference_option("CHQ");
+        filter.set_text_option("Café");
+        filter.set_expression("NOT tag Gift");
+
+        let text = filter.encode_compact();
+
+        assert_eq!(
+            text,
+            "v2.a2.u.d20210101-.s3.t1Food_20_26_20Drinks~0Travel.mbank_5fid-.fRate-N-_2d0_2e5.nchq.wcaf_c3_a9.gNOT_20tag_20Gift.o21"
+        );
+        assert_eq!(
+            text.chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-._~".contains(c)),
+            true
+        );
+
+        let decoded = Filter::decode_compact(&text).unwrap();
+
+        assert_eq!(decoded.encode_compact(), text);
+        assert_eq!(decoded.tag_option(), filter.tag_option());
+        assert_eq!(decoded.date_option(), filter.date_option());
+        assert_eq!(decoded.field_option(), filter.field_option());
+        assert_eq!(decoded.expression(), filter.expression());
+        assert_eq!(Filter::decode_compact("gtag").is_none(), true);
+        assert_eq!(Filter::decode_compact("q1").is_none(), true);
+        assert_eq!(Filter::decode_compact("s9").is_none(), true);
+        assert_eq!(Filter::decode_compact("t2Food").is_none(), true);
+        assert_eq!(Filter::decode_compact("é1").is_none(), true);
+    }
+
+    #[test]
+    fn encode_several_orderings() {
+        let mut filter = Filter::default();
+        filter.add_then_ordering(OrderingPreference::ByAmount, OrderingDirection::Descending);
+
+        let text = filter.encode_compact();
+        assert_eq!(text, "o3021");
+        assert_eq!(
+            Filter::decode_compact(&text).unwrap().encode_compact(),
+            text
+        );
+        assert_eq!(Filter::decode_compact("o302").is_none(), true);
+        assert_eq!(Filter::decode_compact("o").is_none(), true);
+    }
+
+    #[test]
+    fn encode_currencies() {
+        let mut filter = Filter::default();
+        filter
+            .get_currency_option_mut()
+            .add(Category("USD".into(), ItemSelector::Selected));
+        filter.set_base_currency("eur");
+
+        let text = filter.encode_compact();
+        assert_eq!(text, "k1USD.bEUR");
+
+        let decoded = Filter::decode_compact(&text).unwrap();
+        assert_eq!(decoded.currency_option(), filter.currency_option());
+        assert_eq!(decoded.base_currency(), filter.base_currency());
+        assert_eq!(Filter::decode_compact("bEURO").is_none(), true);
+    }
+
+    #[test]
+    fn encode_relative_date() {
+        let mut filter = Filter::default();
+        filter.set_date_relative(RelativePeriod::YearToDate);
+
+        assert_eq!(filter.encode_compact(), "c4");
+        assert_eq!(
+            Filter::decode_compact("c4").map(|filter| filter.date_option),
+            Some(NaiveDateFilter::Relative(RelativePeriod::YearToDate))
+        );
+        assert_eq!(Filter::decode_compact("c6").is_none(), true);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn encode_text_pattern() {
+        let mut filter = Filter::default();
+        filter.set_text_pattern("^Transfert|Netflix");
+
+        let text = filter.encode_compact();
+        assert_eq!(text, "e_5eTransfert_7cNetflix");
+        assert_eq!(
+            Filter::decode_compact(&text).unwrap().text_pattern(),
+            Some("^Transfert|Netflix")
+        );
+        assert_eq!(Filter::decode_compact("e_28").is_none(), true);
+    }
+}
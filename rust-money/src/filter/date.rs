@@ -1,12 +1,52 @@
 //! Filtering option which allows or not an `Order` according to its *date*.
+use crate::stats::Period;
 pub use chrono::NaiveDate;
-use NaiveDateFilter::{Between, DateIgnored, Since, Until};
+use chrono::{Datelike, Duration, Local};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasmbind")]
+use wasm_bindgen::prelude::*;
+use NaiveDateFilter::{Between, DateIgnored, Relative, Since, Until};
 
 /// Regroups a pair of optional `NaiveDate`.
 pub struct OptionNaiveDateRange(pub Option<NaiveDate>, pub Option<NaiveDate>);
 
+/// Date range following the current date.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum RelativePeriod {
+    /// From Monday to Sunday.
+    ThisWeek,
+    ThisMonth,
+    LastMonth,
+    /// Today and the 29 days before.
+    Last30Days,
+    /// From January 1st to today.
+    YearToDate,
+    LastYear,
+}
+
+impl RelativePeriod {
+    /// Returns the first and last days of the period, for a given today.
+    pub fn bounds(self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let first_of_year = |year| NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or(today);
+        match self {
+            RelativePeriod::ThisWeek => Period::Week.bounds(today),
+            RelativePeriod::ThisMonth => Period::Month.bounds(today),
+            RelativePeriod::LastMonth => {
+                Period::Month.bounds(today.with_day(1).unwrap_or(today) - Duration::days(1))
+            }
+            RelativePeriod::Last30Days => (today - Duration::days(29), today),
+            RelativePeriod::YearToDate => (first_of_year(today.year()), today),
+            RelativePeriod::LastYear => (
+                first_of_year(today.year() - 1),
+                first_of_year(today.year()) - Duration::days(1),
+            ),
+        }
+    }
+}
+
 /// References different states for a date range.
-#[derive(PartialEq, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub enum NaiveDateFilter {
     /// No date filtering is enabled.
     DateIgnored,
@@ -16,6 +56,8 @@ pub enum NaiveDateFilter {
     Until(NaiveDate),
     /// Filtering enabled from a given date to another one.
     Between(NaiveDate, NaiveDate),
+    /// Filtering enabled on a period resolved against the current date.
+    Relative(RelativePeriod),
 }
 
 impl NaiveDateFilter {
@@ -35,13 +77,13 @@ impl NaiveDateFilter {
     pub fn set_beginning(&mut self, start_date: Option<NaiveDate>) {
         if let Some(date) = start_date {
             *self = match *self {
-                DateIgnored => Since(date),
+                DateIgnored | Relative(_) => Since(date),
                 Since(_) => Since(date),
                 Until(end) | Between(_, end) => NaiveDateFilter::check_range(date, end),
             }
         } else {
             *self = match *self {
-                DateIgnored | Since(_) => DateIgnored,
+                DateIgnored | Since(_) | Relative(_) => DateIgnored,
                 Until(end) | Between(_, end) => Until(end),
             }
         }
@@ -51,13 +93,13 @@ impl NaiveDateFilter {
     pub fn set_end(&mut self, end_date: Option<NaiveDate>) {
         if let Some(date) = end_date {
             *self = match *self {
-                DateIgnored => Until(date),
+                DateIgnored | Relative(_) => Until(date),
                 Since(begin) | Between(begin, _) => NaiveDateFilter::check_range(begin, date),
                 Until(_) => Until(date),
             }
         } else {
             *self = match *self {
-                DateIgnored | Until(_) => DateIgnored,
+                DateIgnored | Until(_) | Relative(_) => DateIgnored,
                 Since(begin) | Between(begin, _) => Since(begin),
             }
         }
@@ -72,11 +114,27 @@ impl NaiveDateFilter {
         }
     }
 
-    // Evaluates if a date is allowed or not.
+    /// Returns the fixed range of a relative period, for a given today.
+    pub fn resolve(&self, today: NaiveDate) -> NaiveDateFilter {
+        match *self {
+            Relative(period) => {
+                let (start, end) = period.bounds(today);
+                Between(start, end)
+            }
+            DateIgnored => DateIgnored,
+            Since(start) => Since(start),
+            Until(end) => Until(end),
+            Between(start, end) => Between(start, end),
+        }
+    }
+
+    /// Evaluates if a date is allowed or not; relative periods are resolved
+    /// against the current date.
     pub fn is_date_allowed(&self, date: Option<NaiveDate>) -> bool {
         match self {
             DateIgnored => true,
-            _ if date == None => false,
+            Relative(_) => self.is_date_allowed_on(date, Local::now().date_naive()),
+            _ if date.is_none() => false,
             Until(end) => end.signed_duration_since(date.unwrap()).num_days() >= 0,
             Since(start) => date.unwrap().signed_duration_since(*start).num_days() >= 0,
             Between(start, end) => {
@@ -86,6 +144,11 @@ impl NaiveDateFilter {
             }
         }
     }
+
+    /// Evaluates if a date is allowed or not, for a given today.
+    pub fn is_date_allowed_on(&self, date: Option<NaiveDate>, today: NaiveDate) -> bool {
+        self.resolve(today).is_date_allowed(date)
+    }
 }
 
 impl From<OptionNaiveDateRange> for NaiveDateFilter {
@@ -100,6 +163,60 @@ impl From<OptionNaiveDateRange> for NaiveDateFilter {
 mod tests {
     use super::*;
 
+    #[test]
+    fn resolve_relative_periods() {
+        let today = NaiveDate::from_ymd(2021, 3, 17);
+        let bounds = |period: RelativePeriod| period.bounds(today);
+
+        assert_eq!(
+            bounds(RelativePeriod::ThisWeek),
+            (
+                NaiveDate::from_ymd(2021, 3, 15),
+                NaiveDate::from_ymd(2021, 3, 21)
+            )
+        );
+        assert_eq!(
+            bounds(RelativePeriod::ThisMonth),
+            (
+                NaiveDate::from_ymd(2021, 3, 1),
+                NaiveDate::from_ymd(2021, 3, 31)
+            )
+        );
+        assert_eq!(
+            bounds(RelativePeriod::LastMonth),
+            (
+                NaiveDate::from_ymd(2021, 2, 1),
+                NaiveDate::from_ymd(2021, 2, 28)
+            )
+        );
+        assert_eq!(
+            bounds(RelativePeriod::Last30Days),
+            (NaiveDate::from_ymd(2021, 2, 16), today)
+        );
+        assert_eq!(
+            bounds(RelativePeriod::YearToDate),
+            (NaiveDate::from_ymd(2021, 1, 1), today)
+        );
+        assert_eq!(
+            bounds(RelativePeriod::LastYear),
+            (
+                NaiveDate::from_ymd(2020, 1, 1),
+                NaiveDate::from_ymd(2020, 12, 31)
+            )
+        );
+
+        // The range moves along with today
+        let filter = Relative(RelativePeriod::Last30Days);
+        let date = Some(NaiveDate::from_ymd(2021, 2, 20));
+
+        assert_eq!(filter.is_date_allowed_on(date, today), true);
+        assert_eq!(
+            filter.is_date_allowed_on(date, NaiveDate::from_ymd(2021, 4, 1)),
+            false
+        );
+        assert_eq!(filter.is_date_allowed_on(None, today), false);
+    }
+
     #[test]
     fn disable() {
         let start = NaiveDate::from_ymd(2020, 2, 3);
@@ -0,0 +1,52 @@
+//! Criteria of a `Filter` which reject an `Order`, to understand why it is
+//! missing from a view.
+use serde::Serialize;
+#[cfg(feature = "wasmbind")]
+use wasm_bindgen::prelude::*;
+
+/// Filtering criterion not satisfied by an order.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub enum RejectionReason {
+    /// The order is visible while hidden ones are expected, or conversely.
+    Visibility,
+    /// The order is scheduled after today.
+    Schedule,
+    State,
+    /// The order has no date or a date out of range.
+    Date,
+    /// The amount has not the expected sign.
+    Amount,
+    Resource,
+    Project,
+    Payee,
+    Currency,
+    Metadata,
+    Field,
+    /// The reference does not contain the searched text.
+    Reference,
+    /// Neither the description nor the notes contain the searched text.
+    Text,
+    /// The expression is not satisfied.
+    Expression,
+    /// Some selected tags are missing, or a discarded one is present.
+    Tag,
+}
+
+/// Outcome of a filter for an order.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct FilterVerdict {
+    pub allowed: bool,
+    /// Criteria rejecting the order, empty if allowed.
+    pub rejections: Vec<RejectionReason>,
+}
+
+impl FilterVerdict {
+    /// Builds the verdict from rejecting criteria.
+    pub fn new(rejections: Vec<RejectionReason>) -> FilterVerdict {
+        FilterVerdict {
+            allowed: rejections.is_empty(),
+            rejections,
+        }
+    }
+}
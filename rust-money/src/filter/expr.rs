@@ -0,0 +1,344 @@
+//! Filtering option which allows or not an `Order` according to a boolean
+//! combination of criteria, like `(tag Food OR tag Restaurant) AND NOT resource Cash`.
+use crate::order::Order;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use FilterExpr::{And, Not, Or, Payee, Project, Resource, Tag, Text};
+
+/// Tree of criteria.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum FilterExpr {
+    /// The order owns a given tag.
+    Tag(String),
+    /// The order is assigned to a given resource.
+    Resource(String),
+    Project(String),
+    Payee(String),
+    /// The description or notes contain a given text, ignoring case.
+    Text(String),
+    /// All expressions are satisfied; `true` if empty.
+    And(Vec<FilterExpr>),
+    /// Some expression is satisfied; `false` if empty.
+    Or(Vec<FilterExpr>),
+    Not(Box<FilterExpr>),
+}
+
+/// Lexical unit of a textual expression.
+#[derive(PartialEq, Debug)]
+enum Token {
+    Open,
+    Close,
+    Word(String),
+    Quoted(String),
+}
+
+/// Splits a textual expression; `None` if a quote is not closed.
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => value.push(chars.next()?),
+                        c => value.push(c),
+                    }
+                }
+                tokens.push(Token::Quoted(value));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "()\"".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Some(tokens)
+}
+
+/// Returns `true` for the words combining expressions.
+fn is_operator(word: &str) -> bool {
+    ["and", "or", "not"]
+        .iter()
+        .any(|operator| word.eq_ignore_ascii_case(operator))
+}
+
+/// Recursive descent parser; `AND` binds tighter than `OR`.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<&Token> {
+        self.position += 1;
+        self.tokens.get(self.position - 1)
+    }
+
+    fn eat(&mut self, operator: &str) -> bool {
+        let found = matches!(
+            self.tokens.get(self.position),
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(operator)
+        );
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn parse_or(&mut self) -> Option<FilterExpr> {
+        let mut items = vec![self.parse_and()?];
+        while self.eat("or") {
+            items.push(self.parse_and()?);
+        }
+        Some(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Or(items)
+        })
+    }
+
+    fn parse_and(&mut self) -> Option<FilterExpr> {
+        let mut items = vec![self.parse_unary()?];
+        while self.eat("and") {
+            items.push(self.parse_unary()?);
+        }
+        Some(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            And(items)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Option<FilterExpr> {
+        if self.eat("not") {
+            return Some(Not(Box::new(self.parse_unary()?)));
+        }
+        let criterion = match self.next()? {
+            Token::Open => {
+                let expression = self.parse_or()?;
+                return match self.next()? {
+                    Token::Close => Some(expression),
+                    _ => None,
+                };
+            }
+            Token::Word(word) => word.to_lowercase(),
+            _ => return None,
+        };
+        let value = match self.next()? {
+            Token::Word(word) if !is_operator(word) => word.clone(),
+            Token::Quoted(value) => value.clone(),
+            _ => return None,
+        };
+
+        match criterion.as_str() {
+            "tag" => Some(Tag(value)),
+            "resource" => Some(Resource(value)),
+            "project" => Some(Project(value)),
+            "payee" => Some(Payee(value)),
+            "text" => Some(Text(value)),
+            _ => None,
+        }
+    }
+}
+
+impl FilterExpr {
+    /// Reads an expression like `(tag Food OR tag Restaurant) AND NOT resource Cash`.
+    /// Values with spaces or parentheses are quoted: `tag "Food & Drinks"`.
+    /// Returns `None` if it is malformed.
+    pub fn parse(text: &str) -> Option<FilterExpr> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            position: 0,
+        };
+        let expression = parser.parse_or()?;
+
+        if parser.position == parser.tokens.len() {
+            Some(expression)
+        } else {
+            None
+        }
+    }
+
+    /// Evaluates if an order satisfies the expression or not.
+    pub fn is_order_allowed(&self, order: &Order) -> bool {
+        match self {
+            Tag(tag) => order.has_tag(tag),
+            Resource(resource) => order.resource.as_deref() == Some(resource),
+            Project(project) => order.project.as_ref() == Some(project),
+            Payee(payee) => order.payee.as_ref() == Some(payee),
+            Text(text) => {
+                let text = text.to_lowercase();
+                order.description.to_lowercase().contains(&text)
+                    || order
+                        .notes
+                        .as_ref()
+                        .is_some_and(|notes| notes.to_lowercase().contains(&text))
+            }
+            And(items) => items.iter().all(|item| item.is_order_allowed(order)),
+            Or(items) => items.iter().any(|item| item.is_order_allowed(order)),
+            Not(item) => !item.is_order_allowed(order),
+        }
+    }
+
+    /// Combines with another expression, both being required.
+    pub fn and(self, other: FilterExpr) -> FilterExpr {
+        match self {
+            And(mut items) => {
+                items.push(other);
+                And(items)
+            }
+            item => And(vec![item, other]),
+        }
+    }
+
+    /// Combines with another expression, either being enough.
+    pub fn or(self, other: FilterExpr) -> FilterExpr {
+        match self {
+            Or(mut items) => {
+                items.push(other);
+                Or(items)
+            }
+            item => Or(vec![item, other]),
+        }
+    }
+}
+
+impl std::ops::Not for FilterExpr {
+    type Output = FilterExpr;
+
+    fn not(self) -> FilterExpr {
+        Not(Box::new(self))
+    }
+}
+
+/// Writes a value, quoted unless it is a plain word.
+fn write_value(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    if !value.is_empty()
+        && !is_operator(value)
+        && !value
+            .chars()
+            .any(|c| c.is_whitespace() || "()\"\\".contains(c))
+    {
+        write!(f, "{}", value)
+    } else {
+        write!(
+            f,
+            "\"{}\"",
+            value.replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    }
+}
+
+/// Writes a combined expression, in parentheses.
+fn write_nested(f: &mut fmt::Formatter, expression: &FilterExpr) -> fmt::Result {
+    match expression {
+        And(_) | Or(_) => write!(f, "({})", expression),
+        _ => write!(f, "{}", expression),
+    }
+}
+
+/// Writes the textual form read by `FilterExpr::parse`.
+impl fmt::Display for FilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (criterion, value) = match self {
+            Tag(value) => ("tag", value),
+            Resource(value) => ("resource", value),
+            Project(value) => ("project", value),
+            Payee(value) => ("payee", value),
+            Text(value) => ("text", value),
+            And(items) | Or(items) => {
+                let operator = if let And(_) = self { " AND " } else { " OR " };
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, "{}", operator)?;
+                    }
+                    write_nested(f, item)?;
+                }
+                return Ok(());
+            }
+            Not(item) => {
+                write!(f, "NOT ")?;
+                return write_nested(f, item);
+            }
+        };
+        write!(f, "{} ", criterion)?;
+        write_value(f, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_expression() {
+        let expression = FilterExpr::parse(
+            "(tag Food or TAG \"Restaurant & Bar\") AND NOT resource Cash AND text \"\\\"\"",
+        )
+        .unwrap();
+
+        assert_eq!(
+            expression,
+            Tag("Food".into())
+                .or(Tag("Restaurant & Bar".into()))
+                .and(!Resource("Cash".into()))
+                .and(Text("\"".into()))
+        );
+        assert_eq!(
+            expression.to_string(),
+            "(tag Food OR tag \"Restaurant & Bar\") AND NOT resource Cash AND text \"\\\"\""
+        );
+        assert_eq!(FilterExpr::parse(&expression.to_string()), Some(expression));
+        assert_eq!(
+            FilterExpr::parse("tag A or tag B and tag C"),
+            Some(Tag("A".into()).or(Tag("B".into()).and(Tag("C".into()))))
+        );
+
+        [
+            "",
+            "tag",
+            "tag and",
+            "(tag A",
+            "tag A)",
+            "tag \"A",
+            "color Red",
+        ]
+        .iter()
+        .for_each(|text| assert_eq!(FilterExpr::parse(text), None, "{}", text));
+    }
+
+    #[test]
+    fn allow_order_matching_expression() {
+        let expression =
+            FilterExpr::parse("(tag Food OR tag Restaurant) AND NOT resource Cash").unwrap();
+        let order = |tag: &str, resource: &str| Order {
+            tags: vec![tag.into()],
+            resource: Some(resource.into()),
+            ..Order::default()
+        };
+
+        assert_eq!(expression.is_order_allowed(&order("Food", "Bank")), true);
+        assert_eq!(
+            expression.is_order_allowed(&order("Restaurant", "Card")),
+            true
+        );
+        assert_eq!(expression.is_order_allowed(&order("Food", "Cash")), false);
+        assert_eq!(expression.is_order_allowed(&order("Travel", "Bank")), false);
+        assert_eq!(And(Vec::new()).is_order_allowed(&Order::default()), true);
+        assert_eq!(Or(Vec::new()).is_order_allowed(&Order::default()), false);
+    }
+}
@@ -0,0 +1,53 @@
+//! Filtering option which allows or not an `Order` according to its custom *fields*.
+use crate::field::FieldValue;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use FieldFilter::{FieldIgnored, WithField, WithFieldValue};
+
+/// References different ways to match custom fields.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum FieldFilter {
+    /// No field filtering is enabled.
+    FieldIgnored,
+    /// Filtering enabled on orders owning a value for a given field.
+    WithField(String),
+    /// Filtering enabled on orders owning a given value for a given field.
+    WithFieldValue(String, FieldValue),
+}
+
+impl FieldFilter {
+    /// Evaluates if some field values are allowed or not.
+    pub fn is_field_allowed(&self, fields: &BTreeMap<String, FieldValue>) -> bool {
+        match self {
+            FieldIgnored => true,
+            WithField(name) => fields.contains_key(name),
+            WithFieldValue(name, value) => fields.get(name) == Some(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allow_field_with_value() {
+        let filter = WithFieldValue("Reimbursable".into(), FieldValue::Bool(true));
+        let mut fields = BTreeMap::new();
+
+        assert_eq!(FieldIgnored.is_field_allowed(&fields), true);
+        assert_eq!(filter.is_field_allowed(&fields), false);
+
+        fields.insert("Reimbursable".to_string(), FieldValue::Bool(false));
+
+        assert_eq!(
+            WithField("Reimbursable".into()).is_field_allowed(&fields),
+            true
+        );
+        assert_eq!(filter.is_field_allowed(&fields), false);
+
+        fields.insert("Reimbursable".to_string(), FieldValue::Bool(true));
+
+        assert_eq!(filter.is_field_allowed(&fields), true);
+    }
+}
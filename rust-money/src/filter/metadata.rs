@@ -0,0 +1,68 @@
+//! Filtering option which allows or not an `Order` according to its custom *metadata*.
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use MetadataFilter::{MetadataIgnored, WithKey, WithValue};
+
+/// References different ways to match metadata.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum MetadataFilter {
+    /// No metadata filtering is enabled.
+    MetadataIgnored,
+    /// Filtering enabled on orders owning a given key.
+    WithKey(String),
+    /// Filtering enabled on orders owning a given key with a given value.
+    WithValue(String, String),
+}
+
+impl MetadataFilter {
+    /// Evaluates if some metadata are allowed or not.
+    pub fn is_metadata_allowed(&self, metadata: &BTreeMap<String, String>) -> bool {
+        match self {
+            MetadataIgnored => true,
+            WithKey(key) => metadata.contains_key(key),
+            WithValue(key, value) => metadata.get(key) == Some(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allow_any_metadata() {
+        let metadata = BTreeMap::new();
+
+        assert_eq!(MetadataIgnored.is_metadata_allowed(&metadata), true);
+    }
+
+    #[test]
+    fn allow_metadata_with_key() {
+        let filter = WithKey("bank_id".into());
+        let mut metadata = BTreeMap::new();
+
+        assert_eq!(filter.is_metadata_allowed(&metadata), false);
+
+        metadata.insert("project".to_string(), "Kitchen".to_string());
+
+        assert_eq!(filter.is_metadata_allowed(&metadata), false);
+
+        metadata.insert("bank_id".to_string(), "".to_string());
+
+        assert_eq!(filter.is_metadata_allowed(&metadata), true);
+    }
+
+    #[test]
+    fn allow_metadata_with_value() {
+        let filter = WithValue("project".into(), "Kitchen".into());
+        let mut metadata = BTreeMap::new();
+
+        metadata.insert("project".to_string(), "Garden".to_string());
+
+        assert_eq!(filter.is_metadata_allowed(&metadata), false);
+
+        metadata.insert("project".to_string(), "Kitchen".to_string());
+
+        assert_eq!(filter.is_metadata_allowed(&metadata), true);
+    }
+}
@@ -1,16 +1,32 @@
 //! # Management of filtering options for an `Order` list.
+pub mod bitset;
 pub mod category;
+pub mod compact;
 pub mod date;
+pub mod explain;
+pub mod expr;
+pub mod field;
+pub mod metadata;
+pub mod state;
 
 use crate::ext::OrderingDirection::Ascending;
 use crate::ext::OrderingPreference::ById;
-use crate::ext::{OrderingDirection, OrderingPreference};
+use crate::ext::{CategoryType, OrderingDirection, OrderingPreference};
+use crate::field::FieldType;
 use crate::order::{Order, TransactionState};
+use crate::stats::Period;
 use category::CategoryFilter;
 use category::CategoryFilter::CategoryIgnored;
+use chrono::Local;
 pub use chrono::NaiveDate;
-use date::NaiveDateFilter::{Between, DateIgnored, Since, Until};
-pub use date::{NaiveDateFilter, OptionNaiveDateRange};
+use date::NaiveDateFilter::{Between, DateIgnored, Relative, Since, Until};
+pub use date::{NaiveDateFilter, OptionNaiveDateRange, RelativePeriod};
+use explain::{FilterVerdict, RejectionReason};
+use expr::FilterExpr;
+use field::FieldFilter;
+use metadata::MetadataFilter;
+use serde::{Deserialize, Serialize};
+use state::StateFilter;
 use std::str::FromStr;
 #[cfg(feature = "wasmbind")]
 use wasm_bindgen::prelude::*;
@@ -19,7 +35,7 @@ use VisibilityFilter::{HiddenOnly, VisibilityIgnored, VisibleOnly};
 
 /// Stores current state of a given filter parameter.
 #[cfg_attr(feature = "wasmbind", wasm_bindgen)]
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
 pub enum ItemSelector {
     /// Filter out.
     Discarded,
@@ -39,7 +55,7 @@ impl ItemSelector {
 
 /// Filtering options for visibility.
 #[cfg_attr(feature = "wasmbind", wasm_bindgen)]
-#[derive(Copy, Clone)]
+#[derive(Serialize, Deserialize, Copy, Clone)]
 pub enum VisibilityFilter {
     /// No visibility filtering is enabled.
     VisibilityIgnored,
@@ -49,36 +65,104 @@ pub enum VisibilityFilter {
     HiddenOnly,
 }
 
+/// Filtering options for the sign of amounts.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum SignFilter {
+    /// No sign filtering is enabled.
+    SignIgnored,
+    /// Filter incomes.
+    PositiveOnly,
+    /// Filter expenses.
+    NegativeOnly,
+}
+
+/// Dates of a filtering pass, read once for all orders.
+pub(crate) struct FilterDates {
+    /// Current date, for scheduled orders.
+    today: NaiveDate,
+    /// Date option with relative periods resolved.
+    range: NaiveDateFilter,
+}
+
 /// Stores all filtering options.
+///
+/// Serialized to save the state of a view; missing options keep their default
+/// value.
 #[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Filter {
-    /// Keeps visible orders if `true`.
+    /// Keeps visible or hidden orders.
     pub visibility: VisibilityFilter,
+    /// Keeps orders scheduled in the future if `true`.
+    pub show_scheduled: bool,
+    /// Keeps incomes or expenses.
+    pub sign: SignFilter,
+    /// Keeps orders without any tag only if `true`.
+    pub untagged_only: bool,
+    /// Keeps orders without resource only if `true`.
+    pub unassigned_only: bool,
     pub(crate) date_option: NaiveDateFilter,
-    pub(crate) state_option: [ItemSelector; 3],
+    pub(crate) state_option: StateFilter,
     pub(crate) resource_option: CategoryFilter,
     pub(crate) tag_option: CategoryFilter,
+    pub(crate) project_option: CategoryFilter,
+    pub(crate) payee_option: CategoryFilter,
+    /// Currency codes of allowed orders.
+    pub(crate) currency_option: CategoryFilter,
+    /// Currency of orders without their own one, usually the account one.
+    pub(crate) base_currency: Option<String>,
+    pub(crate) metadata_option: MetadataFilter,
+    pub(crate) field_option: FieldFilter,
+    /// Part of the reference of allowed orders, ignoring case.
+    pub(crate) reference_option: Option<String>,
+    /// Part of the description or notes of allowed orders, ignoring case.
+    pub(crate) text_option: Option<String>,
+    /// Pattern of the description or notes of allowed orders, ignoring case.
+    #[cfg(feature = "regex")]
+    #[serde(with = "pattern")]
+    pub(crate) text_pattern: Option<regex_lite::Regex>,
+    /// Combination of criteria, required along with other options.
+    pub(crate) expression: Option<FilterExpr>,
     pub ordering: OrderingPreference,
     pub direction: OrderingDirection,
+    /// Sorting criteria applied in turn to orders equal by `ordering`.
+    pub(crate) then_orderings: Vec<(OrderingPreference, OrderingDirection)>,
 }
 
 impl Default for Filter {
     fn default() -> Self {
         Filter {
             visibility: VisibleOnly,
+            show_scheduled: false,
+            sign: SignFilter::SignIgnored,
+            untagged_only: false,
+            unassigned_only: false,
             date_option: DateIgnored,
-            state_option: [Selected, Selected, Selected],
+            state_option: StateFilter::default(),
             resource_option: CategoryIgnored,
             tag_option: CategoryIgnored,
+            project_option: CategoryIgnored,
+            payee_option: CategoryIgnored,
+            currency_option: CategoryIgnored,
+            base_currency: None,
+            metadata_option: MetadataFilter::MetadataIgnored,
+            field_option: FieldFilter::FieldIgnored,
+            reference_option: None,
+            text_option: None,
+            #[cfg(feature = "regex")]
+            text_pattern: None,
+            expression: None,
             ordering: ById,
             direction: Ascending,
+            then_orderings: Vec::new(),
         }
     }
 }
 
 /// Functions exclusive to `wasm-bindgen`
 #[cfg_attr(feature = "wasmbind", wasm_bindgen)]
-#[cfg(not(default))]
 impl Filter {
     /// Traits are not supported by `wasm-bindgen`.
     #[cfg_attr(feature = "wasmbind", wasm_bindgen(constructor))]
@@ -88,7 +172,7 @@ impl Filter {
 
     /// Returns selected *state*
     pub fn get_state(&self, state: TransactionState) -> ItemSelector {
-        self.state_option[state as usize]
+        self.state_option.get(state)
     }
 }
 
@@ -119,7 +203,7 @@ impl Filter {
             .set_beginning(NaiveDate::from_str(start).ok());
 
         match self.date_option {
-            DateIgnored | Until(_) => false,
+            DateIgnored | Until(_) | Relative(_) => false,
             Since(_) | Between(_, _) => true,
         }
     }
@@ -133,11 +217,82 @@ impl Filter {
         self.date_option.set_end(NaiveDate::from_str(end).ok());
 
         match self.date_option {
-            DateIgnored | Since(_) => false,
+            DateIgnored | Since(_) | Relative(_) => false,
             Until(_) | Between(_, _) => true,
         }
     }
 
+    /// Appends a sorting criterion for orders equal by the previous ones.
+    pub fn add_then_ordering(
+        &mut self,
+        ordering: OrderingPreference,
+        direction: OrderingDirection,
+    ) {
+        self.then_orderings.push((ordering, direction));
+    }
+
+    /// Keeps the main sorting criterion only.
+    pub fn clear_then_orderings(&mut self) {
+        self.then_orderings.clear();
+    }
+
+    /// Allows orders of a calendar month only.
+    ///
+    /// # Output
+    /// * `true` if the operation succeeded
+    /// * `false` if the month is not within `1..=12`.
+    pub fn set_month(&mut self, year: i32, month: u32) -> bool {
+        if let Some(first) = NaiveDate::from_ymd_opt(year, month, 1) {
+            let (start, end) = Period::Month.bounds(first);
+            self.date_option = Between(start, end);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Allows orders of a calendar year only.
+    ///
+    /// # Output
+    /// * `true` if the operation succeeded
+    /// * `false` if the year is out of the supported range.
+    pub fn set_year(&mut self, year: i32) -> bool {
+        match (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year, 12, 31),
+        ) {
+            (Some(start), Some(end)) => {
+                self.date_option = Between(start, end);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Allows orders of a period following the current date, like the last
+    /// 30 days, only.
+    pub fn set_date_relative(&mut self, period: RelativePeriod) {
+        self.date_option = Relative(period);
+    }
+
+    /// Sets the currency of orders without their own one, from an *ISO 4217*
+    /// code; an empty one clears it.
+    ///
+    /// # Output
+    /// * `true` if the operation succeeded
+    /// * `false` if the code is invalid.
+    pub fn set_base_currency(&mut self, code: &str) -> bool {
+        if code.trim().is_empty() {
+            self.base_currency = None;
+            true
+        } else if let Some(code) = crate::currency::normalize_code(code) {
+            self.base_currency = Some(code);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Disable *date* filter.
     pub fn disable_date_option(&mut self) {
         self.date_option = DateIgnored;
@@ -145,16 +300,280 @@ impl Filter {
 
     /// Toggles the selection of a given state.
     pub fn toggle_state(&mut self, state: TransactionState) {
-        self.state_option[state as usize].toggle();
+        self.state_option.get_mut(state).toggle();
+    }
+
+    /// Allows orders owning a given metadata key only.
+    ///
+    /// # Output
+    /// * `true` if the operation succeeded
+    /// * `false` otherwise.
+    pub fn set_metadata_key(&mut self, key: &str) -> bool {
+        if !key.is_empty() {
+            self.metadata_option = MetadataFilter::WithKey(key.into());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Allows orders owning a given metadata key with a given value only.
+    ///
+    /// # Output
+    /// * `true` if the operation succeeded
+    /// * `false` otherwise.
+    pub fn set_metadata_value(&mut self, key: &str, value: &str) -> bool {
+        if !key.is_empty() {
+            self.metadata_option = MetadataFilter::WithValue(key.into(), value.into());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Disable *metadata* filter.
+    pub fn disable_metadata_option(&mut self) {
+        self.metadata_option = MetadataFilter::MetadataIgnored;
+    }
+
+    /// Allows orders owning a value for a given custom field only.
+    ///
+    /// # Output
+    /// * `true` if the operation succeeded
+    /// * `false` otherwise.
+    pub fn set_field_option(&mut self, name: &str) -> bool {
+        if !name.is_empty() {
+            self.field_option = FieldFilter::WithField(name.into());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Allows orders owning a given value for a given custom field only.
+    /// The value is parsed according to the field type.
+    ///
+    /// # Output
+    /// * `true` if the operation succeeded
+    /// * `false` otherwise.
+    pub fn set_field_value_option(&mut self, name: &str, kind: FieldType, value: &str) -> bool {
+        match kind.parse(value) {
+            Some(value) if !name.is_empty() => {
+                self.field_option = FieldFilter::WithFieldValue(name.into(), value);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Disable *field* filter.
+    pub fn disable_field_option(&mut self) {
+        self.field_option = FieldFilter::FieldIgnored;
+    }
+
+    /// Allows orders whose reference contains a given text only.
+    ///
+    /// # Output
+    /// * `true` if the operation succeeded
+    /// * `false` otherwise.
+    pub fn set_reference_option(&mut self, text: &str) -> bool {
+        if !text.trim().is_empty() {
+            self.reference_option = Some(text.trim().to_lowercase());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Disable *reference* filter.
+    pub fn disable_reference_option(&mut self) {
+        self.reference_option = None;
+    }
+
+    /// Allows orders whose description or notes contain a given text only.
+    ///
+    /// # Output
+    /// * `true` if the operation succeeded
+    /// * `false` otherwise.
+    pub fn set_text_option(&mut self, text: &str) -> bool {
+        if !text.trim().is_empty() {
+            self.disable_text_option();
+            self.text_option = Some(text.trim().to_lowercase());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Allows orders satisfying an expression like
+    /// `(tag Food OR tag Restaurant) AND NOT resource Cash` only.
+    ///
+    /// # Output
+    /// * `true` if the operation succeeded
+    /// * `false` if the expression is malformed.
+    pub fn set_expression(&mut self, text: &str) -> bool {
+        if let Some(expression) = FilterExpr::parse(text) {
+            self.expression = Some(expression);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Disable *expression* filter.
+    pub fn disable_expression(&mut self) {
+        self.expression = None;
+    }
+
+    /// Disable *text* filter.
+    pub fn disable_text_option(&mut self) {
+        self.text_option = None;
+        #[cfg(feature = "regex")]
+        {
+            self.text_pattern = None;
+        }
     }
 }
 
+/// Functions exclusive to `wasm-bindgen`
+#[cfg(feature = "regex")]
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
 impl Filter {
+    /// Allows orders whose description or notes match a regular expression
+    /// only, like `^Transfert` or `Gamepass|Netflix`. Replaces the searched text.
+    ///
+    /// # Output
+    /// * `true` if the operation succeeded
+    /// * `false` if the pattern is blank or invalid.
+    pub fn set_text_pattern(&mut self, pattern: &str) -> bool {
+        let pattern = pattern.trim();
+
+        if pattern.is_empty() {
+            return false;
+        }
+        match pattern::build(pattern) {
+            Some(regex) => {
+                self.text_option = None;
+                self.text_pattern = Some(regex);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Text pattern of a filter, stored as its source.
+#[cfg(feature = "regex")]
+mod pattern {
+    use regex_lite::{Regex, RegexBuilder};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    /// Compiles a pattern ignoring case; `None` if invalid.
+    pub fn build(pattern: &str) -> Option<Regex> {
+        RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .ok()
+    }
+
+    pub fn serialize<S: Serializer>(
+        regex: &Option<Regex>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match regex {
+            Some(regex) => serializer.serialize_some(regex.as_str()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Regex>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|pattern| {
+                build(&pattern)
+                    .ok_or_else(|| D::Error::custom(format!("invalid pattern: {}", pattern)))
+            })
+            .transpose()
+    }
+}
+
+/// Default options restricted by an expression.
+impl From<FilterExpr> for Filter {
+    fn from(expression: FilterExpr) -> Self {
+        Filter {
+            expression: Some(expression),
+            ..Filter::default()
+        }
+    }
+}
+
+impl Filter {
+    /// Returns all sorting criteria, main one first.
+    pub fn orderings(&self) -> Vec<(OrderingPreference, OrderingDirection)> {
+        std::iter::once((self.ordering, self.direction))
+            .chain(self.then_orderings.iter().copied())
+            .collect()
+    }
+
+    /// Getter of attribute *expression*.
+    pub fn expression(&self) -> Option<&FilterExpr> {
+        self.expression.as_ref()
+    }
+
     /// Getter of attribute *date_option*.
     pub fn date_option(&self) -> &NaiveDateFilter {
         &self.date_option
     }
 
+    /// Getter of attribute *metadata_option*.
+    pub fn metadata_option(&self) -> &MetadataFilter {
+        &self.metadata_option
+    }
+
+    /// Getter of attribute *field_option*.
+    pub fn field_option(&self) -> &FieldFilter {
+        &self.field_option
+    }
+
+    /// Getter of attribute *reference_option*.
+    pub fn reference_option(&self) -> Option<&String> {
+        self.reference_option.as_ref()
+    }
+
+    /// Getter of attribute *text_option*.
+    pub fn text_option(&self) -> Option<&String> {
+        self.text_option.as_ref()
+    }
+
+    /// Getter of attribute *text_pattern*.
+    #[cfg(feature = "regex")]
+    pub fn text_pattern(&self) -> Option<&str> {
+        self.text_pattern.as_ref().map(|regex| regex.as_str())
+    }
+
+    /// Returns `true` if the description or notes match the pattern, if any.
+    #[cfg(feature = "regex")]
+    fn is_pattern_matched(&self, order: &Order) -> bool {
+        self.text_pattern.as_ref().is_none_or(|regex| {
+            regex.is_match(&order.description)
+                || order
+                    .notes
+                    .as_ref()
+                    .is_some_and(|notes| regex.is_match(notes))
+        })
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn is_pattern_matched(&self, _order: &Order) -> bool {
+        true
+    }
+
+    /// Getter of attribute *state_option*.
+    pub fn state_option(&self) -> &StateFilter {
+        &self.state_option
+    }
+
     /// Getter of attribute *tag_option*.
     pub fn tag_option(&self) -> &CategoryFilter {
         &self.tag_option
@@ -175,35 +594,266 @@ impl Filter {
         &mut self.resource_option
     }
 
+    /// Getter of attribute *project_option*.
+    pub fn project_option(&self) -> &CategoryFilter {
+        &self.project_option
+    }
+
+    /// Required to make the structure compatible with `wasm-bindgen`.
+    pub fn get_project_option_mut(&mut self) -> &mut CategoryFilter {
+        &mut self.project_option
+    }
+
+    /// Getter of attribute *payee_option*.
+    pub fn payee_option(&self) -> &CategoryFilter {
+        &self.payee_option
+    }
+
+    /// Required to make the structure compatible with `wasm-bindgen`.
+    pub fn get_payee_option_mut(&mut self) -> &mut CategoryFilter {
+        &mut self.payee_option
+    }
+
+    /// Getter of attribute *currency_option*.
+    pub fn currency_option(&self) -> &CategoryFilter {
+        &self.currency_option
+    }
+
+    /// Required to make the structure compatible with `wasm-bindgen`.
+    pub fn get_currency_option_mut(&mut self) -> &mut CategoryFilter {
+        &mut self.currency_option
+    }
+
+    /// Getter of attribute *base_currency*.
+    pub fn base_currency(&self) -> Option<&String> {
+        self.base_currency.as_ref()
+    }
+
+    /// Returns the filtering option of a given *category* type.
+    pub fn category_option(&self, kind: CategoryType) -> &CategoryFilter {
+        match kind {
+            CategoryType::Resource => &self.resource_option,
+            CategoryType::Tag => &self.tag_option,
+            CategoryType::Project => &self.project_option,
+            CategoryType::Payee => &self.payee_option,
+        }
+    }
+
+    /// Returns the mutable filtering option of a given *category* type.
+    pub fn get_category_option_mut(&mut self, kind: CategoryType) -> &mut CategoryFilter {
+        match kind {
+            CategoryType::Resource => &mut self.resource_option,
+            CategoryType::Tag => &mut self.tag_option,
+            CategoryType::Project => &mut self.project_option,
+            CategoryType::Payee => &mut self.payee_option,
+        }
+    }
+
+    /// Resolves dates of the filter against the current date, once for all
+    /// orders of a filtering pass.
+    pub(crate) fn dates(&self) -> FilterDates {
+        self.dates_on(Local::now().date_naive())
+    }
+
+    /// Resolves dates of the filter against a given today.
+    pub(crate) fn dates_on(&self, today: NaiveDate) -> FilterDates {
+        FilterDates {
+            today,
+            range: self.date_option.resolve(today),
+        }
+    }
+
     /// Returns `true` if the *order* satisifies all filtering options; `false` otherwise.
     pub fn is_order_allowed(&self, order: &Order) -> bool {
+        self.is_order_allowed_on(order, &self.dates())
+    }
+
+    /// Same as `is_order_allowed`, with dates resolved by the caller.
+    pub(crate) fn is_order_allowed_on(&self, order: &Order, dates: &FilterDates) -> bool {
+        // If some tags are selected, allowed orders are the ones which own them
+        // at least.
+        self.is_order_allowed_with_tags(order, dates, || {
+            self.tag_option.with_each_selected(&order.tags)
+        })
+    }
+
+    /// Same as `is_order_allowed_on`, with the tag option evaluated by the
+    /// caller.
+    pub(crate) fn is_order_allowed_with_tags(
+        &self,
+        order: &Order,
+        dates: &FilterDates,
+        tag_match: impl FnOnce() -> bool,
+    ) -> bool {
+        self.criteria(order, dates)
+            .iter()
+            .all(|(_, is_matching)| *is_matching)
+            && tag_match()
+    }
+
+    /// Tells whether the *order* is allowed, with the criteria rejecting it.
+    pub fn explain(&self, order: &Order) -> FilterVerdict {
+        let tag_match = self.tag_option.with_each_selected(&order.tags);
+        let mut reasons = self
+            .criteria(order, &self.dates())
+            .iter()
+            .chain(std::iter::once(&(RejectionReason::Tag, tag_match)))
+            .filter(|(_, is_matching)| !is_matching)
+            .map(|(reason, _)| *reason)
+            .collect::<Vec<RejectionReason>>();
+
+        // The tag option and the untagged one share their reason
+        reasons.dedup();
+        FilterVerdict::new(reasons)
+    }
+
+    /// Evaluates each criterion except the tag option, which is the slowest.
+    fn criteria(&self, order: &Order, dates: &FilterDates) -> [(RejectionReason, bool); 15] {
         // Discard incompatible orders
         let visibility_match = match self.visibility {
             VisibilityIgnored => true,
-            VisibleOnly => order.visible,
-            HiddenOnly => !order.visible,
+            VisibleOnly => order.is_visible(),
+            HiddenOnly => !order.is_visible(),
         };
 
+        // Future orders are listed from their date only
+        let schedule_match =
+            self.show_scheduled || !order.is_scheduled() || !order.is_scheduled_after(dates.today);
+
         // Make sure the current state is among allowed ones
-        let state_match = self.state_option[order.state() as usize] == Selected;
+        let state_match = self.state_option.is_state_allowed(order.state());
 
         // If the date does not satisfy the range, the order will be rejected.
-        let date_match = self.date_option.is_date_allowed(order.date);
-
-        // If some tags are selected, allowed orders are the ones which own them
-        // at least.
-        let tag_match = self.tag_option.with_each_selected(&order.tags);
+        let date_match = dates.range.is_date_allowed(order.date);
 
         // Make sure the resource is part of allowed ones
-        let resource_match = self.resource_option.among_any_selected(&order.resource);
+        let resource_match = self.resource_option.among_any_selected(&order.resource)
+            && (!self.unassigned_only || order.resource.is_none());
+
+        // Make sure the project is part of allowed ones
+        let project_match = self.project_option.among_any_selected(&order.project);
+
+        // Make sure the payee is part of allowed ones
+        let payee_match = self.payee_option.among_any_selected(&order.payee);
 
-        visibility_match && state_match && date_match && tag_match && resource_match
+        let amount_match = match self.sign {
+            SignFilter::SignIgnored => true,
+            SignFilter::PositiveOnly => order.amount.is_positive(),
+            SignFilter::NegativeOnly => order.amount.is_negative(),
+        };
+
+        // Make sure the currency is part of allowed ones
+        let currency_match = match &self.currency_option {
+            CategoryIgnored => true,
+            option => option.among_any_selected(
+                &order
+                    .currency
+                    .clone()
+                    .or_else(|| self.base_currency.clone()),
+            ),
+        };
+
+        // Make sure custom metadata satisfy the expected key or value
+        let metadata_match = self.metadata_option.is_metadata_allowed(&order.metadata);
+
+        // Make sure custom fields hold the expected value
+        let field_match = self.field_option.is_field_allowed(&order.fields);
+
+        // Make sure the reference contains the searched text
+        let reference_match = self.reference_option.as_ref().is_none_or(|text| {
+            order
+                .reference
+                .as_ref()
+                .is_some_and(|reference| reference.to_lowercase().contains(text))
+        });
+
+        // Make sure the description or notes contain the searched text
+        let text_match = self.text_option.as_ref().is_none_or(|text| {
+            order.description.to_lowercase().contains(text)
+                || order
+                    .notes
+                    .as_ref()
+                    .is_some_and(|notes| notes.to_lowercase().contains(text))
+        }) && self.is_pattern_matched(order);
+
+        [
+            (RejectionReason::Visibility, visibility_match),
+            (RejectionReason::Schedule, schedule_match),
+            (RejectionReason::State, state_match),
+            (RejectionReason::Date, date_match),
+            (RejectionReason::Amount, amount_match),
+            (RejectionReason::Resource, resource_match),
+            (RejectionReason::Project, project_match),
+            (RejectionReason::Payee, payee_match),
+            (RejectionReason::Currency, currency_match),
+            (RejectionReason::Metadata, metadata_match),
+            (RejectionReason::Field, field_match),
+            (RejectionReason::Reference, reference_match),
+            (RejectionReason::Text, text_match),
+            (
+                RejectionReason::Expression,
+                self.expression
+                    .as_ref()
+                    .is_none_or(|expression| expression.is_order_allowed(order)),
+            ),
+            // Kept last, next to the tag option in explanations
+            (
+                RejectionReason::Tag,
+                !self.untagged_only || order.tags.is_empty(),
+            ),
+        ]
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::field::{CustomField, FieldValue};
+    use crate::order::HiddenReason;
+    use category::Category;
+
+    #[test]
+    fn restore_serialized_filter() {
+        let mut filter = Filter {
+            visibility: VisibilityIgnored,
+            ordering: OrderingPreference::ByAmount,
+            ..Filter::default()
+        };
+        filter.set_date_option("2021-01-01", "2021-12-31");
+        filter
+            .get_category_option_mut(CategoryType::Tag)
+            .add(Category("Food".into(), ItemSelector::Discarded));
+        filter.set_metadata_value("bank_id", "42");
+
+        let data = serde_yaml::to_string(&filter).unwrap();
+        let restored = serde_yaml::from_str::<Filter>(&data).unwrap();
+
+        assert_eq!(serde_yaml::to_string(&restored).unwrap(), data);
+        assert_eq!(restored.date_option(), filter.date_option());
+        assert_eq!(restored.tag_option(), filter.tag_option());
+        assert_eq!(restored.encode_compact(), filter.encode_compact());
+
+        // Missing options keep their default value
+        let partial = serde_yaml::from_str::<Filter>("show_scheduled: true").unwrap();
+        assert_eq!(partial.show_scheduled, true);
+        assert_eq!(partial.encode_compact(), "x");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn restore_serialized_pattern() {
+        let mut filter = Filter::default();
+        filter.set_text_pattern("^Transfert");
+
+        let data = serde_yaml::to_string(&filter).unwrap();
+        let restored = serde_yaml::from_str::<Filter>(&data).unwrap();
+
+        assert_eq!(restored.text_pattern(), Some("^Transfert"));
+        assert_eq!(
+            serde_yaml::from_str::<Filter>("text_pattern: (Netflix").is_err(),
+            true
+        );
+    }
 
     #[test]
     fn allow_order_with_any_visibility() {
@@ -212,11 +862,11 @@ mod tests {
             ..Filter::default()
         };
         let allowed_order_1 = Order {
-            visible: true,
+            hidden: None,
             ..Order::default()
         };
         let allowed_order_2 = Order {
-            visible: false,
+            hidden: Some(HiddenReason::Manual),
             ..Order::default()
         };
 
@@ -231,11 +881,11 @@ mod tests {
             ..Filter::default()
         };
         let allowed_order = Order {
-            visible: true,
+            hidden: None,
             ..Order::default()
         };
         let rejected_order = Order {
-            visible: false,
+            hidden: Some(HiddenReason::Manual),
             ..Order::default()
         };
 
@@ -250,11 +900,11 @@ mod tests {
             ..Filter::default()
         };
         let allowed_order = Order {
-            visible: false,
+            hidden: Some(HiddenReason::Manual),
             ..Order::default()
         };
         let rejected_order = Order {
-            visible: true,
+            hidden: None,
             ..Order::default()
         };
 
@@ -262,6 +912,52 @@ mod tests {
         assert_eq!(filter.is_order_allowed(&rejected_order), false);
     }
 
+    #[test]
+    fn allow_scheduled_order_from_its_date() {
+        let mut filter = Filter::default();
+        let today = Local::now().date_naive();
+        let mut future_order = Order {
+            date: Some(today + chrono::Duration::days(1)),
+            ..Order::default()
+        };
+        let mut due_order = Order {
+            date: Some(today),
+            ..Order::default()
+        };
+        future_order.set_scheduled(true);
+        due_order.set_scheduled(true);
+
+        assert_eq!(filter.is_order_allowed(&future_order), false);
+        assert_eq!(filter.is_order_allowed(&due_order), true);
+
+        filter.show_scheduled = true;
+
+        assert_eq!(filter.is_order_allowed(&future_order), true);
+    }
+
+    #[test]
+    fn resolve_dates_once_per_pass() {
+        let mut filter = Filter::default();
+        filter.set_date_relative(RelativePeriod::LastMonth);
+        let dates = filter.dates_on(NaiveDate::from_ymd(2021, 4, 15));
+        let order = |day| Order {
+            date: Some(NaiveDate::from_ymd(2021, 3, 1) + chrono::Duration::days(day)),
+            ..Order::default()
+        };
+        let mut scheduled_order = order(30);
+        scheduled_order.set_scheduled(true);
+
+        assert_eq!(filter.is_order_allowed_on(&order(0), &dates), true);
+        assert_eq!(filter.is_order_allowed_on(&order(31), &dates), false);
+        assert_eq!(filter.is_order_allowed_on(&scheduled_order, &dates), true);
+
+        // Scheduled orders are compared with the same today
+        let filter = Filter::default();
+        let dates = filter.dates_on(NaiveDate::from_ymd(2021, 3, 30));
+        assert_eq!(filter.is_order_allowed_on(&order(29), &dates), true);
+        assert_eq!(filter.is_order_allowed_on(&scheduled_order, &dates), false);
+    }
+
     #[test]
     fn allow_order_with_any_date() {
         let filter = Filter {
@@ -365,4 +1061,353 @@ mod tests {
         assert_eq!(filter.is_order_allowed(&rejected_order_1), false);
         assert_eq!(filter.is_order_allowed(&rejected_order_2), false);
     }
+
+    #[test]
+    fn allow_order_with_selected_project() {
+        let mut filter = Filter::default();
+        filter.get_category_option_mut(CategoryType::Project).set(
+            vec![
+                Category("Japan Trip".into(), Selected),
+                Category("Kitchen".into(), Discarded),
+            ]
+            .into_iter(),
+        );
+        let allowed_order = Order {
+            project: Some("Japan Trip".into()),
+            ..Order::default()
+        };
+        let rejected_order_1 = Order {
+            project: Some("Kitchen".into()),
+            ..Order::default()
+        };
+        let rejected_order_2 = Order::default();
+
+        assert_eq!(filter.is_order_allowed(&allowed_order), true);
+        assert_eq!(filter.is_order_allowed(&rejected_order_1), false);
+        assert_eq!(filter.is_order_allowed(&rejected_order_2), false);
+    }
+
+    #[test]
+    fn allow_order_with_selected_payee() {
+        let mut filter = Filter::default();
+        filter.get_category_option_mut(CategoryType::Payee).set(
+            vec![
+                Category("Bakery".into(), Selected),
+                Category("Grocery".into(), Discarded),
+            ]
+            .into_iter(),
+        );
+        let allowed_order = Order {
+            payee: Some("Bakery".into()),
+            ..Order::default()
+        };
+        let rejected_order = Order {
+            payee: Some("Grocery".into()),
+            ..Order::default()
+        };
+
+        assert_eq!(filter.is_order_allowed(&allowed_order), true);
+        assert_eq!(filter.is_order_allowed(&rejected_order), false);
+        assert_eq!(Order::from(&filter).payee(), Some(&"Bakery".to_string()));
+    }
+
+    #[test]
+    fn allow_order_with_metadata_value() {
+        let mut filter = Filter::default();
+        let mut allowed_order = Order::default();
+        let rejected_order = Order::default();
+
+        assert_eq!(filter.set_metadata_value("", "Kitchen"), false);
+        assert_eq!(filter.set_metadata_value("project", "Kitchen"), true);
+
+        allowed_order.set_metadata("project", "Kitchen");
+
+        assert_eq!(filter.is_order_allowed(&allowed_order), true);
+        assert_eq!(filter.is_order_allowed(&rejected_order), false);
+    }
+
+    #[test]
+    fn allow_order_with_reference() {
+        let mut filter = Filter::default();
+        let allowed_order = Order {
+            reference: Some("CHQ-0042".into()),
+            ..Order::default()
+        };
+        let rejected_order = Order::default();
+
+        assert_eq!(filter.set_reference_option(" "), false);
+        assert_eq!(filter.set_reference_option("chq-00"), true);
+        assert_eq!(filter.is_order_allowed(&allowed_order), true);
+        assert_eq!(filter.is_order_allowed(&rejected_order), false);
+    }
+
+    #[test]
+    fn set_calendar_periods() {
+        let mut filter = Filter::default();
+        let date = |year, month, day| NaiveDate::from_ymd(year, month, day);
+
+        assert_eq!(filter.set_month(2024, 2), true);
+        assert_eq!(
+            filter.date_option,
+            Between(date(2024, 2, 1), date(2024, 2, 29))
+        );
+        assert_eq!(filter.set_month(2023, 2), true);
+        assert_eq!(
+            filter.date_option,
+            Between(date(2023, 2, 1), date(2023, 2, 28))
+        );
+        assert_eq!(filter.set_month(2023, 12), true);
+        assert_eq!(
+            filter.date_option,
+            Between(date(2023, 12, 1), date(2023, 12, 31))
+        );
+        assert_eq!(filter.set_month(2023, 13), false);
+        assert_eq!(filter.set_month(2023, 0), false);
+        assert_eq!(filter.set_year(2021), true);
+        assert_eq!(
+            filter.date_option,
+            Between(date(2021, 1, 1), date(2021, 12, 31))
+        );
+        assert_eq!(filter.set_year(i32::MAX), false);
+    }
+
+    #[test]
+    fn allow_order_with_expression() {
+        let mut filter = Filter::from(
+            expr::FilterExpr::Tag("Food".into()).or(expr::FilterExpr::Tag("Restaurant".into())),
+        );
+        let allowed_order = Order {
+            tags: vec!["Restaurant".into()],
+            ..Order::default()
+        };
+        let rejected_order = Order {
+            tags: vec!["Travel".into()],
+            ..Order::default()
+        };
+
+        assert_eq!(filter.is_order_allowed(&allowed_order), true);
+        assert_eq!(
+            filter.explain(&rejected_order).rejections,
+            vec![RejectionReason::Expression]
+        );
+        assert_eq!(filter.set_expression("tag Food OR"), false);
+        assert_eq!(filter.set_expression("NOT tag Restaurant"), true);
+        assert_eq!(filter.is_order_allowed(&allowed_order), false);
+
+        filter.disable_expression();
+        assert_eq!(filter.is_order_allowed(&rejected_order), true);
+    }
+
+    #[test]
+    fn allow_order_with_sign() {
+        let mut filter = Filter::default();
+        let order = |cents| Order {
+            amount: crate::amount::Amount::from_cents(cents),
+            ..Order::default()
+        };
+
+        filter.sign = SignFilter::PositiveOnly;
+        assert_eq!(filter.is_order_allowed(&order(1500)), true);
+        assert_eq!(
+            filter.explain(&order(-1500)).rejections,
+            vec![RejectionReason::Amount]
+        );
+        assert_eq!(filter.is_order_allowed(&order(0)), false);
+
+        filter.sign = SignFilter::NegativeOnly;
+        assert_eq!(filter.is_order_allowed(&order(-1500)), true);
+        assert_eq!(filter.is_order_allowed(&order(1500)), false);
+    }
+
+    #[test]
+    fn allow_order_with_currency() {
+        let mut filter = Filter::default();
+        let order = |currency: Option<&str>| Order {
+            currency: currency.map(String::from),
+            ..Order::default()
+        };
+
+        filter
+            .get_currency_option_mut()
+            .add(Category("USD".into(), ItemSelector::Selected));
+        assert_eq!(filter.is_order_allowed(&order(Some("USD"))), true);
+        assert_eq!(
+            filter.explain(&order(Some("EUR"))).rejections,
+            vec![RejectionReason::Currency]
+        );
+        assert_eq!(filter.is_order_allowed(&order(None)), false);
+
+        // Orders without currency use the base one
+        assert_eq!(filter.set_base_currency("usd"), true);
+        assert_eq!(filter.is_order_allowed(&order(None)), true);
+        assert_eq!(filter.set_base_currency("dollar"), false);
+        assert_eq!(filter.base_currency(), Some(&"USD".to_string()));
+    }
+
+    #[test]
+    fn allow_incomplete_orders() {
+        let mut filter = Filter::default();
+        let categorized_order = Order {
+            tags: vec!["Food".into()],
+            resource: Some("Bank".into()),
+            ..Order::default()
+        };
+        let untagged_order = Order {
+            resource: Some("Bank".into()),
+            ..Order::default()
+        };
+        let bare_order = Order::default();
+
+        filter.untagged_only = true;
+        assert_eq!(filter.is_order_allowed(&untagged_order), true);
+        assert_eq!(filter.is_order_allowed(&bare_order), true);
+        assert_eq!(
+            filter.explain(&categorized_order).rejections,
+            vec![RejectionReason::Tag]
+        );
+
+        filter.unassigned_only = true;
+        assert_eq!(
+            filter.explain(&untagged_order).rejections,
+            vec![RejectionReason::Resource]
+        );
+        assert_eq!(filter.is_order_allowed(&bare_order), true);
+
+        filter.untagged_only = false;
+        assert_eq!(
+            filter.explain(&categorized_order).rejections,
+            vec![RejectionReason::Resource]
+        );
+    }
+
+    #[test]
+    fn allow_order_with_text() {
+        let mut filter = Filter::default();
+        let described_order = Order {
+            description: "Pizza at Luigi's".into(),
+            ..Order::default()
+        };
+        let noted_order = Order {
+            notes: Some("Birthday of LUIGI".into()),
+            ..Order::default()
+        };
+        let rejected_order = Order::default();
+
+        assert_eq!(filter.set_text_option(""), false);
+        assert_eq!(filter.set_text_option(" luigi "), true);
+        assert_eq!(filter.is_order_allowed(&described_order), true);
+        assert_eq!(filter.is_order_allowed(&noted_order), true);
+        assert_eq!(
+            filter.explain(&rejected_order).rejections,
+            vec![RejectionReason::Text]
+        );
+
+        filter.disable_text_option();
+        assert_eq!(filter.is_order_allowed(&rejected_order), true);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn allow_order_matching_pattern() {
+        let mut filter = Filter::default();
+        let order = |description: &str| Order {
+            description: description.into(),
+            ..Order::default()
+        };
+
+        assert_eq!(filter.set_text_pattern("(Gamepass"), false);
+        assert_eq!(filter.set_text_pattern(" "), false);
+        assert_eq!(filter.set_text_pattern("^Transfert"), true);
+        assert_eq!(
+            filter.is_order_allowed(&order("TRANSFERT to savings")),
+            true
+        );
+        assert_eq!(
+            filter.explain(&order("Card transfert")).rejections,
+            vec![RejectionReason::Text]
+        );
+
+        assert_eq!(filter.set_text_pattern("Gamepass|Netflix"), true);
+        assert_eq!(filter.text_pattern(), Some("Gamepass|Netflix"));
+        assert_eq!(filter.is_order_allowed(&order("Netflix March")), true);
+        assert_eq!(filter.is_order_allowed(&order("Xbox gamepass")), true);
+        assert_eq!(filter.is_order_allowed(&order("Spotify")), false);
+
+        // Searching a text replaces the pattern
+        filter.set_text_option("spot");
+        assert_eq!(filter.text_pattern(), None);
+        assert_eq!(filter.is_order_allowed(&order("Spotify")), true);
+    }
+
+    #[test]
+    fn allow_order_with_field_value() {
+        let mut filter = Filter::default();
+        let list = [CustomField {
+            name: "Reimbursable".into(),
+            kind: FieldType::Bool,
+        }];
+        let mut allowed_order = Order::default();
+        let rejected_order = Order::default();
+
+        assert_eq!(
+            filter.set_field_value_option("Reimbursable", FieldType::Bool, "maybe"),
+            false
+        );
+        assert_eq!(
+            filter.set_field_value_option("Reimbursable", FieldType::Bool, "true"),
+            true
+        );
+
+        allowed_order.set_field("Reimbursable", FieldValue::Bool(true), &list);
+
+        assert_eq!(filter.is_order_allowed(&allowed_order), true);
+        assert_eq!(filter.is_order_allowed(&rejected_order), false);
+        assert_eq!(Order::from(&filter).fields(), allowed_order.fields());
+    }
+
+    #[test]
+    fn explain_rejected_order() {
+        let mut filter = Filter::default();
+        let mut order = Order {
+            tags: vec!["Food".into()],
+            ..Order::default()
+        };
+
+        assert_eq!(filter.explain(&order), FilterVerdict::new(Vec::new()));
+        assert_eq!(filter.explain(&order).allowed, true);
+
+        filter.set_date_option("2021-01-01", "2021-01-31");
+        filter.toggle_state(TransactionState::Pending);
+        filter
+            .get_tag_option_mut()
+            .add(Category("Travel".into(), Selected));
+
+        assert_eq!(
+            filter.explain(&order),
+            FilterVerdict {
+                allowed: false,
+                rejections: vec![
+                    RejectionReason::State,
+                    RejectionReason::Date,
+                    RejectionReason::Tag
+                ],
+            }
+        );
+        assert_eq!(
+            filter.explain(&order).rejections,
+            vec![
+                RejectionReason::State,
+                RejectionReason::Date,
+                RejectionReason::Tag
+            ]
+        );
+
+        order.date = NaiveDate::from_ymd_opt(2021, 1, 15);
+        order.tags.push("Travel".into());
+        filter.toggle_state(TransactionState::Pending);
+
+        assert_eq!(filter.explain(&order).rejections, Vec::new());
+        assert_eq!(filter.explain(&order).allowed, true);
+        assert_eq!(filter.is_order_allowed(&order), true);
+    }
 }
@@ -0,0 +1,124 @@
+//! Filtering option which allows or not an `Order` according to its *state*.
+use super::ItemSelector::{self, Discarded, Selected};
+use crate::order::TransactionState::{self, Done, InProgress, Pending};
+use serde::{Deserialize, Serialize};
+
+/// Selection of each transaction state.
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct StateFilter {
+    pending: ItemSelector,
+    in_progress: ItemSelector,
+    done: ItemSelector,
+}
+
+impl Default for StateFilter {
+    fn default() -> Self {
+        StateFilter {
+            pending: Selected,
+            in_progress: Selected,
+            done: Selected,
+        }
+    }
+}
+
+impl StateFilter {
+    /// All states, in the order of their mask bits.
+    pub const STATES: [TransactionState; 3] = [Pending, InProgress, Done];
+
+    pub fn pending(&self) -> ItemSelector {
+        self.pending
+    }
+
+    pub fn in_progress(&self) -> ItemSelector {
+        self.in_progress
+    }
+
+    pub fn done(&self) -> ItemSelector {
+        self.done
+    }
+
+    /// Returns the selection of a given state.
+    pub fn get(&self, state: TransactionState) -> ItemSelector {
+        match state {
+            Pending => self.pending,
+            InProgress => self.in_progress,
+            Done => self.done,
+        }
+    }
+
+    /// Returns the mutable selection of a given state.
+    pub fn get_mut(&mut self, state: TransactionState) -> &mut ItemSelector {
+        match state {
+            Pending => &mut self.pending,
+            InProgress => &mut self.in_progress,
+            Done => &mut self.done,
+        }
+    }
+
+    /// Evaluates if a state is allowed or not.
+    pub fn is_state_allowed(&self, state: TransactionState) -> bool {
+        self.get(state) == Selected
+    }
+
+    /// Returns the first selected state, if any.
+    pub fn first_selected(&self) -> Option<TransactionState> {
+        Self::STATES
+            .iter()
+            .copied()
+            .find(|&state| self.is_state_allowed(state))
+    }
+
+    /// Packs selected states as bits, `Pending` being the lowest one.
+    pub fn mask(&self) -> u8 {
+        Self::STATES
+            .iter()
+            .enumerate()
+            .filter(|(_, &state)| self.is_state_allowed(state))
+            .map(|(index, _)| 1 << index)
+            .sum()
+    }
+
+    /// Unpacks selected states; `None` if unknown bits are set.
+    pub fn from_mask(mask: u8) -> Option<StateFilter> {
+        if mask >> Self::STATES.len() != 0 {
+            return None;
+        }
+        let mut filter = StateFilter::default();
+        Self::STATES.iter().enumerate().for_each(|(index, &state)| {
+            *filter.get_mut(state) = if mask & (1 << index) != 0 {
+                Selected
+            } else {
+                Discarded
+            }
+        });
+        Some(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_states() {
+        let mut filter = StateFilter::default();
+        filter.get_mut(InProgress).toggle();
+
+        assert_eq!(filter.pending(), Selected);
+        assert_eq!(filter.in_progress(), Discarded);
+        assert_eq!(filter.is_state_allowed(Done), true);
+        assert_eq!(filter.mask(), 0b101);
+        assert_eq!(StateFilter::from_mask(0b101), Some(filter));
+        assert_eq!(StateFilter::from_mask(8), None);
+        assert_eq!(
+            StateFilter::from_mask(0b110).unwrap().first_selected(),
+            Some(InProgress)
+        );
+        assert_eq!(StateFilter::from_mask(0).unwrap().first_selected(), None);
+        assert_eq!(
+            serde_yaml::from_str::<StateFilter>("done: Discarded").unwrap(),
+            StateFilter::from_mask(0b011).unwrap()
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! # Balances of resources projected from recurring orders.
+use crate::amount::Amount;
+use crate::ext::RequestFailure;
+use crate::recurring::RecurringRule;
+use crate::stats::{MonthlyBalance, Period};
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// Projected balance at the end of a month.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub struct ForecastPoint {
+    /// First day of the month.
+    pub month: NaiveDate,
+    /// Done orders, along with recurring expenses.
+    pub pessimistic: Amount,
+    /// All orders, along with all recurring orders.
+    pub optimistic: Amount,
+}
+
+/// Projected balances of a resource.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct ResourceForecast {
+    pub resource: String,
+    pub points: Vec<ForecastPoint>,
+}
+
+/// Adds to month-end balances the occurrences of recurring rules after a
+/// date which do not exist as orders yet. Amounts are negated for a
+/// liability, whose balance is the amount owed.
+///
+/// Fails with `RequestFailure::Overflow` if a sum overflows.
+pub fn project(
+    balances: &[MonthlyBalance],
+    rules: &[&RecurringRule],
+    after: NaiveDate,
+    liability: bool,
+) -> Result<Vec<ForecastPoint>, RequestFailure> {
+    balances
+        .iter()
+        .map(|balance| {
+            let (_, last_day) = Period::Month.bounds(balance.month);
+            let mut point = ForecastPoint {
+                month: balance.month,
+                pessimistic: balance.current,
+                optimistic: balance.expected,
+            };
+
+            for rule in rules {
+                let first = rule
+                    .materialized_until
+                    .map_or(after, |date| date.max(after));
+                let count = rule
+                    .occurrences_until(last_day)
+                    .into_iter()
+                    .filter(|&occurrence| occurrence > first)
+                    .count();
+                let amount = if liability {
+                    -rule.template.amount
+                } else {
+                    rule.template.amount
+                };
+                let total = Amount::checked_sum(std::iter::repeat_n(amount, count))
+                    .ok_or(RequestFailure::Overflow)?;
+
+                point.optimistic = point
+                    .optimistic
+                    .checked_add(total)
+                    .ok_or(RequestFailure::Overflow)?;
+                // Income may not come, expenses will
+                if rule.template.amount.is_negative() {
+                    point.pessimistic = point
+                        .pessimistic
+                        .checked_add(total)
+                        .ok_or(RequestFailure::Overflow)?;
+                }
+            }
+            Ok(point)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::Order;
+
+    #[test]
+    fn project_recurring_orders() {
+        let rule = |day: u32, cents: i64| {
+            RecurringRule::new(
+                "Rule",
+                NaiveDate::from_ymd(2021, 1, day),
+                Order {
+                    amount: Amount::from_cents(cents),
+                    ..Order::default()
+                },
+            )
+        };
+        let (salary, mut rent) = (rule(25, 250000), rule(5, -90000));
+        rent.materialized_until = Some(NaiveDate::from_ymd(2021, 4, 30));
+        let balances = (3..=5)
+            .map(|month| MonthlyBalance {
+                month: NaiveDate::from_ymd(2021, month, 1),
+                current: Amount::from_cents(100000),
+                expected: Amount::from_cents(120000),
+            })
+            .collect::<Vec<MonthlyBalance>>();
+
+        let points = project(
+            &balances,
+            &[&salary, &rent],
+            NaiveDate::from_ymd(2021, 3, 10),
+            false,
+        )
+        .unwrap();
+        assert_eq!(
+            points
+                .iter()
+                .map(|point| (point.pessimistic.cents(), point.optimistic.cents()))
+                .collect::<Vec<(i64, i64)>>(),
+            [(100000, 370000), (100000, 620000), (10000, 780000)]
+        );
+        assert_eq!(
+            project(
+                &balances[..1],
+                &[&salary],
+                NaiveDate::from_ymd(2021, 3, 10),
+                true
+            )
+            .unwrap()[0]
+                .optimistic,
+            Amount::from_cents(-130000)
+        );
+    }
+}
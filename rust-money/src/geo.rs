@@ -0,0 +1,208 @@
+//! # Geolocation of orders.
+use crate::amount::Amount;
+use crate::ext::RequestFailure;
+use crate::filter::{NaiveDateFilter, OptionNaiveDateRange};
+use crate::order::Order;
+use serde::{Deserialize, Serialize};
+
+/// Mean radius of the Earth in meters.
+const EARTH_RADIUS: f64 = 6_371_000.0;
+
+/// Coordinates expressed in decimal degrees.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// User-defined area identified by a name.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Place {
+    pub name: String,
+    pub center: Location,
+    /// Radius in meters.
+    pub radius: f64,
+}
+
+/// Amount spent within a given place.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct PlaceSpend {
+    pub place: String,
+    pub amount: Amount,
+    pub count: usize,
+}
+
+impl Location {
+    /// Returns a location if coordinates are valid.
+    pub fn new(latitude: f64, longitude: f64) -> Option<Location> {
+        if (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude) {
+            Some(Location {
+                latitude,
+                longitude,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Computes the great-circle distance in meters (*haversine* formula).
+    pub fn distance(&self, other: &Location) -> f64 {
+        let delta_latitude = (other.latitude - self.latitude).to_radians();
+        let delta_longitude = (other.longitude - self.longitude).to_radians();
+        let a = (delta_latitude / 2.0).sin().powi(2)
+            + self.latitude.to_radians().cos()
+                * other.latitude.to_radians().cos()
+                * (delta_longitude / 2.0).sin().powi(2);
+
+        2.0 * EARTH_RADIUS * a.sqrt().asin()
+    }
+}
+
+impl Place {
+    /// Returns `true` if the location is within the area.
+    pub fn contains(&self, location: &Location) -> bool {
+        self.center.distance(location) <= self.radius
+    }
+}
+
+/// Sums visible orders of each place within a date range.
+///
+/// An order located in overlapping places is assigned to the nearest one.
+/// Places without any order are omitted.
+///
+/// Fails with `RequestFailure::Overflow` if a sum overflows.
+pub fn spend_by_place(
+    places: &[Place],
+    orders: &[Order],
+    date_range: OptionNaiveDateRange,
+) -> Result<Vec<PlaceSpend>, RequestFailure> {
+    let date_filter = NaiveDateFilter::from(date_range);
+    let mut result = places
+        .iter()
+        .map(|place| PlaceSpend {
+            place: place.name.clone(),
+            amount: Amount::ZERO,
+            count: 0,
+        })
+        .collect::<Vec<PlaceSpend>>();
+
+    for (order, location) in orders
+        .iter()
+        .filter(|order| order.is_visible() && date_filter.is_date_allowed(order.date))
+        .filter_map(|order| order.location().map(|location| (order, location)))
+    {
+        if let Some((index, _)) = places
+            .iter()
+            .enumerate()
+            .filter(|(_, place)| place.contains(location))
+            .min_by(|a, b| {
+                a.1.center
+                    .distance(location)
+                    .partial_cmp(&b.1.center.distance(location))
+                    .expect("Something goes wrong..")
+            })
+        {
+            result[index].amount = result[index]
+                .amount
+                .checked_add(order.amount)
+                .ok_or(RequestFailure::Overflow)?;
+            result[index].count += 1;
+        }
+    }
+
+    result.retain(|spend| spend.count > 0);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::HiddenReason;
+    use chrono::NaiveDate;
+
+    fn located_order(latitude: f64, longitude: f64, cents: i64) -> Order {
+        let mut order = Order {
+            amount: Amount::from_cents(cents),
+            date: Some(NaiveDate::from_ymd(2020, 5, 12)),
+            ..Order::default()
+        };
+        order.set_location(latitude, longitude);
+        order
+    }
+
+    #[test]
+    fn reject_invalid_coordinates() {
+        assert_eq!(Location::new(91.0, 0.0), None);
+        assert_eq!(Location::new(0.0, -180.5), None);
+        assert!(Location::new(48.8584, 2.2945).is_some());
+    }
+
+    #[test]
+    fn compute_distance() {
+        let eiffel_tower = Location::new(48.8584, 2.2945).unwrap();
+        let louvre = Location::new(48.8606, 2.3376).unwrap();
+        let distance = eiffel_tower.distance(&louvre);
+
+        assert!((distance - 3160.0).abs() < 20.0);
+        assert_eq!(louvre.distance(&louvre), 0.0);
+    }
+
+    #[test]
+    fn sum_spend_by_place() {
+        let places = [
+            Place {
+                name: "Home".into(),
+                center: Location::new(48.8584, 2.2945).unwrap(),
+                radius: 1000.0,
+            },
+            Place {
+                name: "Office".into(),
+                center: Location::new(48.8606, 2.3376).unwrap(),
+                radius: 500.0,
+            },
+            Place {
+                name: "Holidays".into(),
+                center: Location::new(43.2965, 5.3698).unwrap(),
+                radius: 10000.0,
+            },
+        ];
+        let mut orders = vec![
+            located_order(48.8590, 2.2950, -1250),
+            located_order(48.8600, 2.3370, -800),
+            located_order(48.8580, 2.2940, -750),
+            located_order(45.7640, 4.8357, -10000),
+            Order {
+                amount: Amount::from_cents(-3000),
+                ..Order::default()
+            },
+        ];
+        orders.push(Order {
+            hidden: Some(HiddenReason::Manual),
+            ..located_order(48.8584, 2.2945, -5000)
+        });
+
+        assert_eq!(
+            spend_by_place(&places, &orders, OptionNaiveDateRange(None, None)),
+            Ok(vec![
+                PlaceSpend {
+                    place: "Home".into(),
+                    amount: Amount::from_cents(-2000),
+                    count: 2,
+                },
+                PlaceSpend {
+                    place: "Office".into(),
+                    amount: Amount::from_cents(-800),
+                    count: 1,
+                },
+            ])
+        );
+        assert_eq!(
+            spend_by_place(
+                &places,
+                &orders,
+                OptionNaiveDateRange(Some(NaiveDate::from_ymd(2021, 1, 1)), None)
+            ),
+            Ok(vec![])
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! # Direct connection to banks.
+use super::preview::{self, ImportPreview, ImportedTransaction};
+use crate::Account;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Account held by a bank.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct BankAccount {
+    /// Identifier given by the connector.
+    pub id: String,
+    pub name: String,
+    pub iban: Option<String>,
+    /// Currency code, if known.
+    pub currency: Option<String>,
+}
+
+/// Defines connector error types.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ConnectorError {
+    /// Credentials are missing, invalid or expired.
+    Authentication,
+    /// The service could not be reached or refused the request.
+    Network(String),
+    /// The service answered with unexpected data.
+    InvalidResponse(String),
+}
+
+impl fmt::Display for ConnectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConnectorError::Authentication => write!(f, "authentication failed"),
+            ConnectorError::Network(error) => write!(f, "network error: {}", error),
+            ConnectorError::InvalidResponse(error) => write!(f, "invalid response: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ConnectorError {}
+
+/// Source of bank transactions (PSD2 aggregator, bank API, ..).
+pub trait BankConnector {
+    /// Lists accounts reachable with current credentials.
+    fn list_accounts(&mut self) -> Result<Vec<BankAccount>, ConnectorError>;
+
+    /// Fetches transactions of an account since a given date.
+    fn fetch_transactions(
+        &mut self,
+        account_id: &str,
+        since: NaiveDate,
+    ) -> Result<Vec<ImportedTransaction>, ConnectorError>;
+}
+
+/// Fetches transactions of a bank account and sorts out already known ones.
+pub fn fetch_preview<C: BankConnector + ?Sized>(
+    connector: &mut C,
+    account: &Account,
+    account_id: &str,
+    since: NaiveDate,
+) -> Result<ImportPreview, ConnectorError> {
+    let transactions = connector.fetch_transactions(account_id, since)?;
+
+    Ok(preview::preview(account.orders(), transactions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::Amount;
+    use crate::order::TransactionState;
+
+    /// Serves transactions from memory.
+    struct MemoryConnector(Vec<ImportedTransaction>);
+
+    impl BankConnector for MemoryConnector {
+        fn list_accounts(&mut self) -> Result<Vec<BankAccount>, ConnectorError> {
+            Ok(vec![BankAccount {
+                id: "main".into(),
+                name: "Checking".into(),
+                iban: None,
+                currency: Some("EUR".into()),
+            }])
+        }
+
+        fn fetch_transactions(
+            &mut self,
+            account_id: &str,
+            since: NaiveDate,
+        ) -> Result<Vec<ImportedTransaction>, ConnectorError> {
+            if account_id == "main" {
+                Ok(self
+                    .0
+                    .iter()
+                    .filter(|transaction| transaction.date >= Some(since))
+                    .cloned()
+                    .collect())
+            } else {
+                Err(ConnectorError::InvalidResponse("unknown account".into()))
+            }
+        }
+    }
+
+    #[test]
+    fn fetch_new_transactions() {
+        let transactions = (1..=3)
+            .map(|day| ImportedTransaction {
+                external_id: Some(format!("TX-{}", day)),
+                date: Some(NaiveDate::from_ymd(2021, 3, day)),
+                description: "Bakery".into(),
+                amount: Amount::from_cents(-420),
+                currency: None,
+                state: TransactionState::Done,
+            })
+            .collect::<Vec<ImportedTransaction>>();
+        let mut connector = MemoryConnector(transactions.clone());
+        let mut account = Account::create();
+        account.apply_import(
+            &preview::preview(account.orders(), transactions[1..2].to_vec()),
+            None,
+        );
+
+        let since = NaiveDate::from_ymd(2021, 3, 2);
+        let result = fetch_preview(&mut connector, &account, "main", since).unwrap();
+
+        assert_eq!(connector.list_accounts().unwrap().len(), 1);
+        assert_eq!(result.new, transactions[2..]);
+        assert_eq!(result.duplicates.len(), 1);
+        assert_eq!(
+            fetch_preview(&mut connector, &account, "savings", since),
+            Err(ConnectorError::InvalidResponse("unknown account".into()))
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! # Transactions read from CSV exports of banks.
+use crate::amount::Amount;
+use crate::import::ImportedTransaction;
+use crate::order::TransactionState;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Columns holding transaction fields, counted from `0`, and their format.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct CsvMapping {
+    pub date: usize,
+    pub description: usize,
+    pub amount: usize,
+    /// Column holding the resource name, if any.
+    pub resource: Option<usize>,
+    /// Format of dates, like `%d/%m/%Y`.
+    pub date_format: String,
+    /// Amounts are written like `-1 234,56`.
+    pub decimal_comma: bool,
+    pub delimiter: char,
+    /// The first line names columns.
+    pub header: bool,
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        CsvMapping {
+            date: 0,
+            description: 1,
+            amount: 2,
+            resource: None,
+            date_format: "%Y-%m-%d".into(),
+            decimal_comma: false,
+            delimiter: ',',
+            header: true,
+        }
+    }
+}
+
+/// Reason why a row is not imported.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub enum CsvRowFailure {
+    MissingColumn,
+    IncorrectDate,
+    IncorrectAmount,
+    UnknownResource,
+}
+
+/// Row which is not imported, along with its line number starting at `1`.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct CsvRowError {
+    pub line: usize,
+    pub failure: CsvRowFailure,
+}
+
+/// Transaction read from a row.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CsvRecord {
+    pub line: usize,
+    pub transaction: ImportedTransaction,
+    pub resource: Option<String>,
+}
+
+/// Outcome of a CSV import.
+#[derive(Serialize, Clone, PartialEq, Debug, Default)]
+pub struct CsvImportReport {
+    /// Identifiers of created orders.
+    pub created: Vec<usize>,
+    /// Rows matching an existing order.
+    pub duplicates: usize,
+    pub errors: Vec<CsvRowError>,
+}
+
+/// Splits content into rows of fields, each with its starting line number.
+/// Quoted fields may hold delimiters, line breaks and doubled quotes.
+fn rows(content: &str, delimiter: char) -> Vec<(usize, Vec<String>)> {
+    let mut rows = Vec::new();
+    let (mut fields, mut field) = (Vec::new(), String::new());
+    let (mut line, mut start, mut quoted) = (1, 1, false);
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            '\n' if quoted => {
+                field.push(c);
+                line += 1;
+            }
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                rows.push((start, std::mem::take(&mut fields)));
+                line += 1;
+                start = line;
+            }
+            '\r' if !quoted => {}
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        rows.push((start, fields));
+    }
+
+    rows.retain(|(_, fields)| fields.iter().any(|field| !field.trim().is_empty()));
+    rows
+}
+
+/// Reads an amount, ignoring spaces between thousands.
+fn parse_amount(text: &str, decimal_comma: bool) -> Option<Amount> {
+    let text = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+    let text = if decimal_comma {
+        text.replace('.', "").replace(',', ".")
+    } else {
+        text.replace(',', "")
+    };
+
+    text.parse().ok()
+}
+
+/// Reads transactions from CSV content; rows which cannot be read are reported.
+pub fn parse(content: &str, mapping: &CsvMapping) -> (Vec<CsvRecord>, Vec<CsvRowError>) {
+    let (mut records, mut errors) = (Vec::new(), Vec::new());
+
+    rows(content, mapping.delimiter)
+        .into_iter()
+        .skip(usize::from(mapping.header))
+        .for_each(|(line, fields)| {
+            let column = |index: usize| {
+                fields
+                    .get(index)
+                    .map(|field| field.trim())
+                    .ok_or(CsvRowFailure::MissingColumn)
+            };
+            let record = (|| {
+                let (date, description, amount) = (
+                    column(mapping.date)?,
+                    column(mapping.description)?,
+                    column(mapping.amount)?,
+                );
+                let resource = match mapping.resource {
+                    Some(index) => Some(column(index)?.to_string()).filter(|name| !name.is_empty()),
+                    None => None,
+                };
+
+                Ok(CsvRecord {
+                    line,
+                    transaction: ImportedTransaction {
+                        external_id: None,
+                        date: Some(
+                            NaiveDate::parse_from_str(date, &mapping.date_format)
+                                .map_err(|_| CsvRowFailure::IncorrectDate)?,
+                        ),
+                        description: description.into(),
+                        amount: parse_amount(amount, mapping.decimal_comma)
+                            .ok_or(CsvRowFailure::IncorrectAmount)?,
+                        currency: None,
+                        state: TransactionState::Done,
+                    },
+                    resource,
+                })
+            })();
+
+            match record {
+                Ok(record) => records.push(record),
+                Err(failure) => errors.push(CsvRowError { line, failure }),
+            }
+        });
+
+    (records, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mapped_columns() {
+        let content = "Account;Date;Label;Amount\r\n\
+                       Bank;02/03/2021;\"Bakery; \"\"Chez Paul\"\"\";-4,20\r\n\
+                       \r\n\
+                       Bank;03/03/2021;\"Salary\nMarch\";1 234,56\r\n\
+                       Bank;2021-03-04;Rent;-600,00\r\n\
+                       ;05/03/2021;Refund;12,3,4\r\n\
+                       Bank;06/03/2021\r\n";
+        let mapping = CsvMapping {
+            date: 1,
+            description: 2,
+            amount: 3,
+            resource: Some(0),
+            date_format: "%d/%m/%Y".into(),
+            decimal_comma: true,
+            delimiter: ';',
+            header: true,
+        };
+        let (records, errors) = parse(content, &mapping);
+
+        assert_eq!(
+            records
+                .iter()
+                .map(|record| (
+                    record.line,
+                    record.transaction.description.as_str(),
+                    record.transaction.amount
+                ))
+                .collect::<Vec<(usize, &str, Amount)>>(),
+            vec![
+                (2, "Bakery; \"Chez Paul\"", Amount::from_cents(-420)),
+                (4, "Salary\nMarch", Amount::from_cents(123456)),
+            ]
+        );
+        assert_eq!(
+            records[0].transaction.date,
+            Some(NaiveDate::from_ymd(2021, 3, 2))
+        );
+        assert_eq!(records[0].resource, Some("Bank".into()));
+        assert_eq!(
+            errors,
+            vec![
+                CsvRowError {
+                    line: 6,
+                    failure: CsvRowFailure::IncorrectDate
+                },
+                CsvRowError {
+                    line: 7,
+                    failure: CsvRowFailure::IncorrectAmount
+                },
+                CsvRowError {
+                    line: 8,
+                    failure: CsvRowFailure::MissingColumn
+                },
+            ]
+        );
+    }
+}
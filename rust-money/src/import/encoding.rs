@@ -0,0 +1,151 @@
+//! Detection and conversion of the text encoding of imported data.
+use encoding_rs::{Encoding, ISO_8859_15, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasmbind")]
+use wasm_bindgen::prelude::*;
+
+/// Text encodings supported on import.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum TextEncoding {
+    /// Default encoding of most modern tools.
+    Utf8,
+    /// Little-endian UTF-16, mostly produced by spreadsheet tools.
+    Utf16Le,
+    /// Big-endian UTF-16.
+    Utf16Be,
+    /// Western European encoding used by most bank exports on Windows.
+    Windows1252,
+    /// Latin-9, which adds the `€` sign to Latin-1.
+    Iso8859_15,
+}
+
+/// Text resulting from the decoding of imported bytes.
+#[derive(PartialEq, Debug)]
+pub struct DecodedText {
+    pub content: String,
+    /// Encoding used to decode the bytes.
+    pub encoding: TextEncoding,
+    /// `true` if some bytes could not be mapped and were replaced.
+    pub lossy: bool,
+}
+
+impl TextEncoding {
+    fn as_encoding(self) -> &'static Encoding {
+        match self {
+            TextEncoding::Utf8 => UTF_8,
+            TextEncoding::Utf16Le => UTF_16LE,
+            TextEncoding::Utf16Be => UTF_16BE,
+            TextEncoding::Windows1252 => WINDOWS_1252,
+            TextEncoding::Iso8859_15 => ISO_8859_15,
+        }
+    }
+
+    fn from_encoding(encoding: &'static Encoding) -> Option<TextEncoding> {
+        [
+            TextEncoding::Utf8,
+            TextEncoding::Utf16Le,
+            TextEncoding::Utf16Be,
+            TextEncoding::Windows1252,
+            TextEncoding::Iso8859_15,
+        ]
+        .iter()
+        .copied()
+        .find(|candidate| candidate.as_encoding() == encoding)
+    }
+}
+
+/// Guesses the encoding of some bytes.
+///
+/// A *byte order mark* always wins, then valid UTF-8 is kept as is.
+/// Anything else is considered as **Windows-1252** which is a superset of
+/// Latin-1 and the usual encoding of bank exports.
+pub fn detect(bytes: &[u8]) -> TextEncoding {
+    if let Some(encoding) =
+        Encoding::for_bom(bytes).and_then(|(encoding, _)| TextEncoding::from_encoding(encoding))
+    {
+        encoding
+    } else if std::str::from_utf8(bytes).is_ok() {
+        TextEncoding::Utf8
+    } else {
+        TextEncoding::Windows1252
+    }
+}
+
+/// Converts bytes into UTF-8 text.
+///
+/// The encoding is detected unless `forced` is provided. A *byte order mark*
+/// matching the selected encoding is stripped.
+pub fn decode(bytes: &[u8], forced: Option<TextEncoding>) -> DecodedText {
+    let encoding = forced.unwrap_or_else(|| detect(bytes));
+    let (content, lossy) = encoding.as_encoding().decode_with_bom_removal(bytes);
+
+    DecodedText {
+        content: content.into_owned(),
+        encoding,
+        lossy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_utf8() {
+        let bytes = "Date;Libellé;Montant\n2020-03-04;Café ☕;-3,50".as_bytes();
+
+        assert_eq!(detect(bytes), TextEncoding::Utf8);
+        assert_eq!(
+            decode(bytes, None),
+            DecodedText {
+                content: "Date;Libellé;Montant\n2020-03-04;Café ☕;-3,50".into(),
+                encoding: TextEncoding::Utf8,
+                lossy: false,
+            }
+        );
+    }
+
+    #[test]
+    fn detect_windows_1252() {
+        // "Libellé;-12,99 €" encoded as Windows-1252
+        let bytes = b"Libell\xe9;-12,99 \x80";
+
+        assert_eq!(detect(bytes), TextEncoding::Windows1252);
+        assert_eq!(decode(bytes, None).content, "Libellé;-12,99 €");
+    }
+
+    #[test]
+    fn detect_and_strip_byte_order_mark() {
+        let bytes = b"\xff\xfeO\x00K\x00";
+        let result = decode(bytes, None);
+
+        assert_eq!(result.encoding, TextEncoding::Utf16Le);
+        assert_eq!(result.content, "OK");
+
+        let bytes = b"\xef\xbb\xbfOK";
+
+        assert_eq!(decode(bytes, None).content, "OK");
+    }
+
+    #[test]
+    fn force_encoding() {
+        // "€" is 0xA4 with Latin-9 but "¤" with Windows-1252
+        let bytes = b"-12,99 \xa4";
+
+        assert_eq!(decode(bytes, None).content, "-12,99 ¤");
+        assert_eq!(
+            decode(bytes, Some(TextEncoding::Iso8859_15)).content,
+            "-12,99 €"
+        );
+    }
+
+    #[test]
+    fn report_lossy_decoding() {
+        let bytes = b"Caf\xe9";
+        let result = decode(bytes, Some(TextEncoding::Utf8));
+
+        assert_eq!(result.lossy, true);
+        assert_eq!(result.content, "Caf\u{fffd}");
+    }
+}
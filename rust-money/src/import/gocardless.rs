@@ -0,0 +1,297 @@
+//! # Connector for the *GoCardless Bank Account Data* API (PSD2 aggregator).
+use super::connector::{BankAccount, BankConnector, ConnectorError};
+use super::preview::ImportedTransaction;
+use crate::amount::Amount;
+use crate::order::TransactionState;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// Production endpoint.
+pub const DEFAULT_BASE_URL: &str = "https://bankaccountdata.gocardless.com";
+
+/// Accesses accounts linked by a given *requisition* (end-user agreement).
+pub struct GoCardlessConnector {
+    base_url: String,
+    secret_id: String,
+    secret_key: String,
+    requisition_id: String,
+    access_token: Option<String>,
+    agent: ureq::Agent,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access: String,
+}
+
+#[derive(Deserialize)]
+struct RequisitionResponse {
+    accounts: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct DetailsResponse {
+    account: AccountDetails,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountDetails {
+    iban: Option<String>,
+    currency: Option<String>,
+    name: Option<String>,
+    owner_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TransactionsResponse {
+    transactions: TransactionLists,
+}
+
+#[derive(Deserialize)]
+struct TransactionLists {
+    #[serde(default)]
+    booked: Vec<RawTransaction>,
+    #[serde(default)]
+    pending: Vec<RawTransaction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTransaction {
+    transaction_id: Option<String>,
+    internal_transaction_id: Option<String>,
+    booking_date: Option<NaiveDate>,
+    value_date: Option<NaiveDate>,
+    transaction_amount: RawAmount,
+    remittance_information_unstructured: Option<String>,
+    creditor_name: Option<String>,
+    debtor_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawAmount {
+    amount: String,
+    currency: Option<String>,
+}
+
+impl RawTransaction {
+    /// Converts into a transaction, with a given state.
+    fn convert(self, state: TransactionState) -> Result<ImportedTransaction, ConnectorError> {
+        let amount = Amount::from_str(&self.transaction_amount.amount).map_err(|_| {
+            ConnectorError::InvalidResponse(format!(
+                "invalid amount {}",
+                self.transaction_amount.amount
+            ))
+        })?;
+
+        Ok(ImportedTransaction {
+            external_id: self.transaction_id.or(self.internal_transaction_id),
+            date: self.booking_date.or(self.value_date),
+            description: self
+                .remittance_information_unstructured
+                .or(self.creditor_name)
+                .or(self.debtor_name)
+                .unwrap_or_default(),
+            amount,
+            currency: self.transaction_amount.currency,
+            state,
+        })
+    }
+}
+
+/// Converts a raw transaction list.
+fn parse_transactions(content: &str) -> Result<Vec<ImportedTransaction>, ConnectorError> {
+    let response: TransactionsResponse = serde_json::from_str(content)
+        .map_err(|error| ConnectorError::InvalidResponse(error.to_string()))?;
+    let lists = response.transactions;
+
+    lists
+        .booked
+        .into_iter()
+        .map(|transaction| transaction.convert(TransactionState::Done))
+        .chain(
+            lists
+                .pending
+                .into_iter()
+                .map(|transaction| transaction.convert(TransactionState::Pending)),
+        )
+        .collect()
+}
+
+/// Converts a transport or status error.
+fn request_error(error: ureq::Error) -> ConnectorError {
+    match error {
+        ureq::Error::Status(401, _) | ureq::Error::Status(403, _) => ConnectorError::Authentication,
+        error => ConnectorError::Network(error.to_string()),
+    }
+}
+
+impl GoCardlessConnector {
+    /// Creates a connector for the production endpoint.
+    pub fn new(secret_id: &str, secret_key: &str, requisition_id: &str) -> GoCardlessConnector {
+        GoCardlessConnector::with_base_url(DEFAULT_BASE_URL, secret_id, secret_key, requisition_id)
+    }
+
+    /// Creates a connector for a given endpoint (sandbox, proxy, ..).
+    pub fn with_base_url(
+        base_url: &str,
+        secret_id: &str,
+        secret_key: &str,
+        requisition_id: &str,
+    ) -> GoCardlessConnector {
+        GoCardlessConnector {
+            base_url: base_url.trim_end_matches('/').into(),
+            secret_id: secret_id.into(),
+            secret_key: secret_key.into(),
+            requisition_id: requisition_id.into(),
+            access_token: None,
+            agent: ureq::AgentBuilder::new().build(),
+        }
+    }
+
+    /// Returns an access token, requesting a new one if needed.
+    fn access_token(&mut self) -> Result<String, ConnectorError> {
+        if let Some(token) = &self.access_token {
+            return Ok(token.clone());
+        }
+
+        let response: TokenResponse = self
+            .agent
+            .post(&format!("{}/api/v2/token/new/", self.base_url))
+            .send_json(ureq::json!({
+                "secret_id": self.secret_id,
+                "secret_key": self.secret_key,
+            }))
+            .map_err(request_error)?
+            .into_json()
+            .map_err(|error| ConnectorError::InvalidResponse(error.to_string()))?;
+
+        self.access_token = Some(response.access.clone());
+        Ok(response.access)
+    }
+
+    /// Sends an authenticated `GET` request and returns the body.
+    fn get(&mut self, path: &str) -> Result<String, ConnectorError> {
+        let token = self.access_token()?;
+        let result = self
+            .agent
+            .get(&format!("{}{}", self.base_url, path))
+            .set("Authorization", &format!("Bearer {}", token))
+            .call();
+
+        if let Err(ureq::Error::Status(401, _)) = result {
+            // The token expired
+            self.access_token = None;
+        }
+
+        result
+            .map_err(request_error)?
+            .into_string()
+            .map_err(|error| ConnectorError::InvalidResponse(error.to_string()))
+    }
+}
+
+impl BankConnector for GoCardlessConnector {
+    fn list_accounts(&mut self) -> Result<Vec<BankAccount>, ConnectorError> {
+        let content = self.get(&format!("/api/v2/requisitions/{}/", self.requisition_id))?;
+        let requisition: RequisitionResponse = serde_json::from_str(&content)
+            .map_err(|error| ConnectorError::InvalidResponse(error.to_string()))?;
+
+        requisition
+            .accounts
+            .into_iter()
+            .map(|id| {
+                let content = self.get(&format!("/api/v2/accounts/{}/details/", id))?;
+                let details: DetailsResponse = serde_json::from_str(&content)
+                    .map_err(|error| ConnectorError::InvalidResponse(error.to_string()))?;
+                let details = details.account;
+
+                Ok(BankAccount {
+                    name: details
+                        .name
+                        .or(details.owner_name)
+                        .unwrap_or_else(|| id.clone()),
+                    id,
+                    iban: details.iban,
+                    currency: details.currency,
+                })
+            })
+            .collect()
+    }
+
+    fn fetch_transactions(
+        &mut self,
+        account_id: &str,
+        since: NaiveDate,
+    ) -> Result<Vec<ImportedTransaction>, ConnectorError> {
+        let content = self.get(&format!(
+            "/api/v2/accounts/{}/transactions/?date_from={}",
+            account_id,
+            since.format("%Y-%m-%d")
+        ))?;
+
+        parse_transactions(&content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_booked_and_pending_transactions() {
+        let transactions = parse_transactions(
+            r#"{"transactions": {
+                "booked": [{
+                    "transactionId": "2021030201",
+                    "bookingDate": "2021-03-02",
+                    "valueDate": "2021-03-01",
+                    "transactionAmount": {"amount": "-4.20", "currency": "EUR"},
+                    "remittanceInformationUnstructured": "BAKERY PARIS"
+                }],
+                "pending": [{
+                    "valueDate": "2021-03-03",
+                    "transactionAmount": {"amount": "2500.00", "currency": "EUR"},
+                    "debtorName": "ACME"
+                }]
+            }}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            transactions,
+            [
+                ImportedTransaction {
+                    external_id: Some("2021030201".into()),
+                    date: Some(NaiveDate::from_ymd(2021, 3, 2)),
+                    description: "BAKERY PARIS".into(),
+                    amount: Amount::from_cents(-420),
+                    currency: Some("EUR".into()),
+                    state: TransactionState::Done,
+                },
+                ImportedTransaction {
+                    external_id: None,
+                    date: Some(NaiveDate::from_ymd(2021, 3, 3)),
+                    description: "ACME".into(),
+                    amount: Amount::from_cents(250000),
+                    currency: Some("EUR".into()),
+                    state: TransactionState::Pending,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_amount() {
+        let result = parse_transactions(
+            r#"{"transactions": {"booked": [{"transactionAmount": {"amount": "N/A"}}]}}"#,
+        );
+
+        assert_eq!(
+            result,
+            Err(ConnectorError::InvalidResponse("invalid amount N/A".into()))
+        );
+    }
+}
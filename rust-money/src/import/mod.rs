@@ -0,0 +1,58 @@
+//! # Import of external data into an `Account`.
+pub mod connector;
+pub mod csv;
+pub mod encoding;
+#[cfg(feature = "network")]
+pub mod gocardless;
+pub mod ofx;
+pub mod preview;
+
+pub use connector::{BankAccount, BankConnector, ConnectorError};
+pub use csv::{CsvImportReport, CsvMapping};
+pub use encoding::{DecodedText, TextEncoding};
+pub use preview::{ImportPreview, ImportedTransaction};
+use serde::{Deserialize, Serialize};
+
+/// Settings dedicated to a given data source (a bank, an application, ..).
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ImportProfile {
+    pub label: String,
+    /// Overrides the detected encoding when set.
+    pub encoding: Option<TextEncoding>,
+}
+
+impl Default for ImportProfile {
+    fn default() -> Self {
+        ImportProfile {
+            label: "".to_string(),
+            encoding: None,
+        }
+    }
+}
+
+impl ImportProfile {
+    /// Converts raw data into text according to the profile.
+    pub fn decode(&self, bytes: &[u8]) -> DecodedText {
+        encoding::decode(bytes, self.encoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_with_profile_override() {
+        let bytes = b"Loyer;-600,00 \xa4";
+        let mut profile = ImportProfile {
+            label: "My bank".into(),
+            ..ImportProfile::default()
+        };
+
+        assert_eq!(profile.decode(bytes).encoding, TextEncoding::Windows1252);
+
+        profile.encoding = Some(TextEncoding::Iso8859_15);
+
+        assert_eq!(profile.decode(bytes).content, "Loyer;-600,00 €");
+    }
+}
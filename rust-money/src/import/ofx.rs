@@ -0,0 +1,122 @@
+//! # Transactions read from OFX/QFX bank statements.
+//!
+//! Both the SGML form (OFX 1.x, without closing tags) and the XML form
+//! (OFX 2.x) are read.
+use crate::amount::Amount;
+use crate::import::ImportedTransaction;
+use crate::order::TransactionState;
+use chrono::NaiveDate;
+
+/// Fields of a `STMTTRN` element.
+#[derive(Default)]
+struct StatementEntry {
+    fitid: Option<String>,
+    posted: Option<String>,
+    amount: Option<String>,
+    name: Option<String>,
+    memo: Option<String>,
+}
+
+/// Replaces the entities of XML and SGML text.
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Reads a date like `20210302` or `20210302120000.000[-5:EST]`.
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(text.get(..8)?, "%Y%m%d").ok()
+}
+
+impl StatementEntry {
+    /// Converts into a pending transaction; `None` if a field is malformed.
+    fn into_transaction(self, currency: Option<&String>) -> Option<ImportedTransaction> {
+        let description = self.name.or(self.memo).unwrap_or_default();
+
+        Some(ImportedTransaction {
+            external_id: self.fitid,
+            date: Some(parse_date(self.posted.as_ref()?)?),
+            description,
+            amount: self.amount?.replace(',', ".").parse::<Amount>().ok()?,
+            currency: currency.cloned(),
+            state: TransactionState::Pending,
+        })
+    }
+}
+
+/// Reads transactions of a statement, identified by their `FITID`.
+/// Returns `None` if the content is not OFX or a transaction is malformed.
+pub fn parse(content: &str) -> Option<Vec<ImportedTransaction>> {
+    let mut entries = Vec::new();
+    let (mut entry, mut currency, mut is_ofx) = (None::<StatementEntry>, None, false);
+
+    for element in content.split('<').skip(1) {
+        let (tag, value) = element.split_once('>')?;
+        let value = Some(unescape(value.trim())).filter(|value| !value.is_empty());
+
+        match (tag.trim().to_uppercase().as_str(), entry.as_mut()) {
+            ("OFX", _) => is_ofx = true,
+            ("CURDEF", _) => currency = value,
+            ("STMTTRN", _) => entry = Some(StatementEntry::default()),
+            ("/STMTTRN", Some(_)) => entries.push(entry.take()?),
+            ("FITID", Some(entry)) => entry.fitid = value,
+            ("DTPOSTED", Some(entry)) => entry.posted = value,
+            ("TRNAMT", Some(entry)) => entry.amount = value,
+            ("NAME", Some(entry)) => entry.name = value,
+            ("MEMO", Some(entry)) => entry.memo = value,
+            _ => {}
+        }
+    }
+
+    if !is_ofx {
+        return None;
+    }
+    entries
+        .into_iter()
+        .map(|entry| entry.into_transaction(currency.as_ref()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sgml_and_xml_statements() {
+        let sgml = "OFXHEADER:100\nDATA:OFXSGML\n\n<OFX><BANKMSGSRSV1><STMTTRNRS><STMTRS>\n\
+                    <CURDEF>EUR\n<BANKTRANLIST>\n\
+                    <STMTTRN><TRNTYPE>DEBIT<DTPOSTED>20210302120000.000[+1:CET]\n\
+                    <TRNAMT>-4,20<FITID>TX-1<NAME>Bakery &amp; Co<MEMO>Card payment\n</STMTTRN>\n\
+                    <STMTTRN><TRNTYPE>CREDIT<DTPOSTED>20210303<TRNAMT>1234.56\n\
+                    <FITID>TX-2<MEMO>Salary\n</STMTTRN>\n\
+                    </BANKTRANLIST></STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>";
+        let transactions = parse(sgml).unwrap();
+
+        assert_eq!(
+            transactions[0],
+            ImportedTransaction {
+                external_id: Some("TX-1".into()),
+                date: Some(NaiveDate::from_ymd(2021, 3, 2)),
+                description: "Bakery & Co".into(),
+                amount: Amount::from_cents(-420),
+                currency: Some("EUR".into()),
+                state: TransactionState::Pending,
+            }
+        );
+        assert_eq!(transactions[1].description, "Salary");
+        assert_eq!(transactions[1].amount, Amount::from_cents(123456));
+
+        let xml = "<?xml version=\"1.0\"?><OFX><STMTTRN><DTPOSTED>20210304</DTPOSTED>\
+                   <TRNAMT>-600.00</TRNAMT><FITID>TX-3</FITID><NAME>Rent</NAME></STMTTRN></OFX>";
+        let transactions = parse(xml).unwrap();
+
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].external_id, Some("TX-3".into()));
+        assert_eq!(transactions[0].currency, None);
+        assert_eq!(parse("<OFX><STMTTRN><TRNAMT>1.00</STMTTRN></OFX>"), None);
+        assert_eq!(parse("date,amount\n2021-03-02,-4.20"), None);
+    }
+}
@@ -0,0 +1,160 @@
+//! # Review of transactions before their import.
+use crate::amount::Amount;
+use crate::order::{Order, TransactionState};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Metadata key holding the identifier given by the source of an order.
+pub const IMPORT_ID_KEY: &str = "import_id";
+
+/// Transaction read from an external source.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ImportedTransaction {
+    /// Identifier given by the source, if any.
+    pub external_id: Option<String>,
+    pub date: Option<NaiveDate>,
+    pub description: String,
+    pub amount: Amount,
+    /// Currency code, if known.
+    pub currency: Option<String>,
+    pub state: TransactionState,
+}
+
+impl ImportedTransaction {
+    /// Converts the transaction into a new order.
+    pub fn to_order(&self) -> Order {
+        let mut order = Order {
+            date: self.date,
+            description: self.description.as_str().into(),
+            amount: self.amount,
+            state: self.state,
+            ..Order::default()
+        };
+
+        if let Some(currency) = &self.currency {
+            order.set_currency(currency);
+        }
+        if let Some(external_id) = &self.external_id {
+            order.set_metadata(IMPORT_ID_KEY, external_id);
+        }
+
+        order
+    }
+
+    /// Returns `true` if the order most likely records the same transaction.
+    pub(crate) fn matches(&self, order: &Order) -> bool {
+        match (&self.external_id, order.metadata().get(IMPORT_ID_KEY)) {
+            (Some(external_id), Some(import_id)) => external_id == import_id,
+            _ => {
+                self.date == order.date
+                    && self.amount == order.amount
+                    && self.description.trim().to_lowercase()
+                        == order.description.trim().to_lowercase()
+            }
+        }
+    }
+}
+
+/// Transaction which has already been imported.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct ImportDuplicate {
+    pub transaction: ImportedTransaction,
+    /// Matching order, if already recorded in the account.
+    pub order_id: Option<usize>,
+}
+
+/// Splits transactions between new and already known ones.
+#[derive(Serialize, Clone, PartialEq, Debug, Default)]
+pub struct ImportPreview {
+    pub new: Vec<ImportedTransaction>,
+    pub duplicates: Vec<ImportDuplicate>,
+}
+
+/// Compares transactions with existing orders and between themselves.
+pub fn preview(orders: &[Order], transactions: Vec<ImportedTransaction>) -> ImportPreview {
+    let mut result = ImportPreview::default();
+    let mut known_ids = HashSet::new();
+
+    transactions.into_iter().for_each(|transaction| {
+        if let Some(order) = orders.iter().find(|order| transaction.matches(order)) {
+            result.duplicates.push(ImportDuplicate {
+                transaction,
+                order_id: Some(order.id()),
+            });
+        } else if transaction
+            .external_id
+            .as_ref()
+            .is_some_and(|id| !known_ids.insert(id.clone()))
+        {
+            result.duplicates.push(ImportDuplicate {
+                transaction,
+                order_id: None,
+            });
+        } else {
+            result.new.push(transaction);
+        }
+    });
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction(
+        external_id: Option<&str>,
+        description: &str,
+        cents: i64,
+    ) -> ImportedTransaction {
+        ImportedTransaction {
+            external_id: external_id.map(String::from),
+            date: Some(NaiveDate::from_ymd(2021, 3, 2)),
+            description: description.into(),
+            amount: Amount::from_cents(cents),
+            currency: None,
+            state: TransactionState::Done,
+        }
+    }
+
+    #[test]
+    fn convert_to_order() {
+        let order = transaction(Some("TX-1"), "Bakery", -420).to_order();
+
+        assert_eq!(order.description, "Bakery");
+        assert_eq!(order.amount, Amount::from_cents(-420));
+        assert_eq!(order.state(), TransactionState::Done);
+        assert_eq!(order.metadata()[IMPORT_ID_KEY], "TX-1");
+    }
+
+    #[test]
+    fn detect_duplicates() {
+        let orders = vec![
+            transaction(Some("TX-1"), "Bakery", -420).to_order(),
+            Order {
+                id: 1,
+                ..transaction(None, "Rent", -60000).to_order()
+            },
+        ];
+        let result = preview(
+            &orders,
+            vec![
+                transaction(Some("TX-1"), "BAKERY PARIS", -420),
+                transaction(Some("TX-2"), " rent", -60000),
+                transaction(Some("TX-3"), "Salary", 250000),
+                transaction(Some("TX-3"), "Salary", 250000),
+            ],
+        );
+
+        assert_eq!(result.new, [transaction(Some("TX-3"), "Salary", 250000)]);
+        assert_eq!(
+            result
+                .duplicates
+                .iter()
+                .map(|duplicate| duplicate.order_id)
+                .collect::<Vec<Option<usize>>>(),
+            [Some(0), Some(1), None]
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! # Strings shared between orders.
+//!
+//! Big accounts repeat the same descriptions, tags and resources over
+//! thousands of orders. While the orders list is loaded, each distinct value
+//! is allocated once and shared by every order holding it.
+use crate::order::Order;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+thread_local! {
+    /// Distinct strings met while loading orders, if any.
+    static TABLE: RefCell<Option<HashSet<Arc<str>>>> = const { RefCell::new(None) };
+}
+
+/// Immutable string, shared by orders holding the same value once loaded.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Interned(Arc<str>);
+
+impl Interned {
+    /// Returns the shared copy of a string while orders are loaded, a new
+    /// one otherwise.
+    fn intern(value: &str) -> Interned {
+        TABLE.with(|table| match table.borrow_mut().as_mut() {
+            Some(table) => match table.get(value) {
+                Some(shared) => Interned(shared.clone()),
+                None => {
+                    let shared = Arc::<str>::from(value);
+                    table.insert(shared.clone());
+                    Interned(shared)
+                }
+            },
+            None => Interned(value.into()),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns `true` if both strings share the same allocation.
+    pub fn is_shared_with(&self, other: &Interned) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Loads orders, sharing their repeated descriptions, tags and resources.
+pub(crate) fn deserialize_orders<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Order>, D::Error> {
+    let previous = TABLE.with(|table| table.replace(Some(HashSet::new())));
+    let orders = Vec::<Order>::deserialize(deserializer);
+    TABLE.with(|table| table.replace(previous));
+    orders
+}
+
+impl Deref for Interned {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Interned {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Interned {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Interned {
+    fn from(value: &str) -> Self {
+        Interned(value.into())
+    }
+}
+
+impl From<String> for Interned {
+    fn from(value: String) -> Self {
+        Interned(value.into())
+    }
+}
+
+impl From<&String> for Interned {
+    fn from(value: &String) -> Self {
+        Interned(value.as_str().into())
+    }
+}
+
+impl From<Interned> for String {
+    fn from(value: Interned) -> Self {
+        value.0.as_ref().into()
+    }
+}
+
+impl PartialEq<str> for Interned {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Interned {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Interned {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl PartialEq<Interned> for str {
+    fn eq(&self, other: &Interned) -> bool {
+        self == &*other.0
+    }
+}
+
+impl PartialEq<Interned> for &str {
+    fn eq(&self, other: &Interned) -> bool {
+        *self == &*other.0
+    }
+}
+
+impl PartialEq<Interned> for String {
+    fn eq(&self, other: &Interned) -> bool {
+        **self == *other.0
+    }
+}
+
+impl fmt::Display for Interned {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Debug for Interned {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for Interned {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Interned {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct InternedVisitor;
+
+        impl Visitor<'_> for InternedVisitor {
+            type Value = Interned;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Interned, E> {
+                Ok(Interned::intern(value))
+            }
+        }
+
+        deserializer.deserialize_str(InternedVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Account;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn share_strings_of_loaded_orders() {
+        let data = "- description: Bakery\n  amount: -4.2\n  resource: Bank\n  tags: [Food]\n  state: Done\n  date: ~\n\
+                    - description: Bakery\n  amount: -3.5\n  resource: Bank\n  tags: [Food]\n  state: Done\n  date: ~\n";
+        let orders = deserialize_orders(serde_yaml::Deserializer::from_str(data)).unwrap();
+        let alone = serde_yaml::from_str::<Vec<Order>>(data).unwrap();
+
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[0].description, "Bakery");
+        assert_eq!(
+            orders[0].description.is_shared_with(&orders[1].description),
+            true
+        );
+        assert_eq!(orders[0].tags[0].is_shared_with(&orders[1].tags[0]), true);
+        assert_eq!(
+            orders[0]
+                .resource
+                .as_ref()
+                .unwrap()
+                .is_shared_with(orders[1].resource.as_ref().unwrap()),
+            true
+        );
+        // Outside of a list of orders, strings are not shared
+        assert_eq!(
+            alone[0].description.is_shared_with(&alone[1].description),
+            false
+        );
+
+        let mut account = Account::create();
+        (0..2).for_each(|_| {
+            let id = account.add_order();
+            account.get_order_mut(id).unwrap().description = "Rent".into();
+        });
+        let loaded = Account::try_from(serde_yaml::to_string(&account).unwrap().as_str()).unwrap();
+        assert_eq!(
+            loaded.orders()[0]
+                .description
+                .is_shared_with(&loaded.orders()[1].description),
+            true
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! # Sub-ledgers scoped to a tag (a child, a roommate, ..).
+use crate::amount::Amount;
+use crate::ext::CategoryAmount;
+use serde::{Deserialize, Serialize};
+
+/// View over the orders owning a given tag.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SubLedger {
+    /// Unique name.
+    pub name: String,
+    /// Tag of all orders of the sub-ledger.
+    pub tag: String,
+    /// Label of the recurring rule crediting the allowance, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowance: Option<String>,
+}
+
+impl SubLedger {
+    /// Returns the label of the allowance recurring rule.
+    #[cfg(feature = "rules")]
+    pub(crate) fn allowance_label(&self) -> String {
+        format!("{} allowance", self.name)
+    }
+}
+
+/// Overview of a sub-ledger within a date range.
+#[derive(Serialize, PartialEq, Debug)]
+pub struct SubLedgerReport {
+    pub name: String,
+    pub tag: String,
+    /// Amounts of visible orders, if any.
+    pub balance: Option<CategoryAmount>,
+    /// Sum of received amounts (allowance, gifts, ..).
+    pub credits: Amount,
+    /// Sum of spent amounts, as a positive value.
+    pub spent: Amount,
+}
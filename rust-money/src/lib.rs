@@ -1,30 +1,187 @@
 //! # Money - Core Rust API
 //!
 //! `money` is a collection of utilities to make tracking money expenses.
+#![cfg_attr(test, allow(deprecated, clippy::bool_assert_comparison))]
 
+pub mod allocation;
+pub mod amount;
+pub mod attachment;
+#[cfg(feature = "reporting")]
+pub mod audit;
+pub mod backup;
+pub mod budget;
+#[cfg(feature = "import")]
+pub mod bundle;
+pub mod category;
+pub mod columnar;
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod currency;
+pub mod diff;
+#[cfg(feature = "encryption")]
+pub mod encryption;
+#[cfg(feature = "reporting")]
+pub mod export;
 pub mod ext;
+pub mod field;
 pub mod filter;
+pub mod forecast;
+pub mod geo;
+#[cfg(feature = "import")]
+pub mod import;
+pub mod intern;
+pub mod ledger;
+#[cfg(feature = "reporting")]
+pub mod lint;
+pub mod merge;
+pub mod migration;
+pub mod milestone;
 pub mod order;
+pub mod project;
+pub mod recurring;
+pub mod source;
+pub mod stats;
+pub mod storage;
+#[cfg(all(feature = "import", feature = "rules"))]
+pub mod sync;
+pub mod trash;
 
-use ext::{ExclusiveItemExt, RequestFailure};
-use filter::Filter;
-use order::Order;
+use allocation::{Allocation, AllocationSummary, IncomeAllocation};
+use amount::Amount;
+use backup::BackupPolicy;
+use budget::{BudgetReport, TagBudget};
+use category::{
+    BalanceLimit, CategoryEntry, OpeningBalance, ResourceKind, TagCatalog, TagGroup, TagResolver,
+};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime};
+use columnar::OrderColumns;
+use diff::{AccountDiff, CategoryDiff, ModifiedOrder};
+#[cfg(feature = "reporting")]
+use export::{AmountFormat, DeltaExport};
+use ext::{
+    CategorizationProgress, CategoryAmount, CategoryCollision, CategoryType, CategoryUsage,
+    CurrencyAmount, ExclusiveItemExt, FilteredPage, IncomeExpense, KeyMatching, LoadWarning,
+    NetWorth, OrderListExt, PeriodCount, Reconciliation, RequestFailure, ResourceBalance,
+    StateCount,
+};
+use field::{CustomField, FieldType};
+use filter::category::{Category, CategoryFilter};
+use filter::{Filter, ItemSelector, OptionNaiveDateRange};
+use forecast::ResourceForecast;
+use geo::{Location, Place, PlaceSpend};
+#[cfg(feature = "import")]
+use import::csv::{CsvImportReport, CsvMapping, CsvRowError, CsvRowFailure};
+#[cfg(feature = "import")]
+use import::preview::{ImportPreview, ImportedTransaction};
+use intern::Interned;
+use ledger::{SubLedger, SubLedgerReport};
+#[cfg(feature = "reporting")]
+use lint::LintIssue;
+use merge::{MergeConflict, MergeReport, MergeStrategy};
+use migration::AppliedMigration;
+use milestone::{CrossedMilestone, Milestone, ResourceAlert, ThresholdDirection};
+use order::{
+    ComponentKind, HiddenReason, Order, OrderCache, OrderKind, OrderMut, OrderPatch, OrderTemplate,
+    TransactionState,
+};
+use project::{Project, ProjectSummary};
+#[cfg(feature = "rules")]
+use recurring::Frequency;
+use recurring::RecurringRule;
 use serde::{Deserialize, Serialize};
+use source::{ConflictResolution, FileStamp, SaveError, SourceStamp};
+use stats::{
+    AmountOutlier, CashFlow, MonthlyBalance, PayeeStats, Period, PeriodComparison, Sparkline,
+    SpendingTrend, TagSpending,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fs::File;
-use std::io::prelude::*;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use storage::{Storage, StorageError};
+use trash::TrashedOrder;
 #[cfg(feature = "wasmbind")]
 use wasm_bindgen::prelude::*;
 
+/// Version of the data format written by this library.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Tag of orders fixing a cash discrepancy.
+pub const CASH_ADJUSTMENT_TAG: &str = "Adjustment";
+
+/// Longest span of sparklines, about a century.
+pub const MAX_SPARKLINE_DAYS: u32 = 36_525;
+
 /// Manages account data.
 #[cfg_attr(feature = "wasmbind", wasm_bindgen)]
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 pub struct Account {
+    /// Version of the data format, upgraded while loading.
+    #[serde(default)]
+    version: u32,
     label: String,
-    tags: Vec<String>,
-    resources: Vec<String>,
+    /// Currency code of orders without their own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    currency: Option<String>,
+    tags: Vec<CategoryEntry>,
+    /// Canonical tag of each alias.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    tag_aliases: BTreeMap<String, String>,
+    resources: Vec<CategoryEntry>,
+    /// How names of tags, resources and payees are told apart.
+    #[serde(default, skip_serializing_if = "KeyMatching::is_exact")]
+    category_matching: KeyMatching,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    payees: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    custom_fields: Vec<CustomField>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    projects: Vec<Project>,
+    /// Income assigned to budget envelopes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    allocations: Vec<IncomeAllocation>,
+    /// Balance thresholds of resources.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    milestones: Vec<Milestone>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tag_groups: Vec<TagGroup>,
+    /// Spending limits of tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    budgets: Vec<TagBudget>,
+    #[serde(deserialize_with = "intern::deserialize_orders")]
     orders: Vec<Order>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    places: Vec<Place>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    recurring: Vec<RecurringRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    sub_ledgers: Vec<SubLedger>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    templates: Vec<OrderTemplate>,
+    /// Deleted orders, until purged.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    trash: Vec<TrashedOrder>,
+    /// Marker of the last delta export.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_export: Option<NaiveDateTime>,
+    /// Copies kept when saving over a file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    backup: Option<BackupPolicy>,
+    /// Identifier of the next created order.
+    #[serde(default)]
+    next_id: usize,
+    /// Migrations applied while loading.
+    #[serde(skip)]
+    migrations: Vec<AppliedMigration>,
+    /// Issues found while loading.
+    #[serde(skip)]
+    load_warnings: Vec<LoadWarning>,
+    #[serde(skip)]
+    order_cache: OrderCache,
+    /// File the account was loaded from or saved to.
+    #[serde(skip)]
+    source: SourceStamp,
 }
 
 /// `wasm_bindgen` compatible functions.
@@ -37,10 +194,33 @@ impl Account {
         console_error_panic_hook::set_once();
 
         Account {
+            version: SCHEMA_VERSION,
             label: "MONEY".into(),
+            currency: None,
             tags: Vec::new(),
+            tag_aliases: BTreeMap::new(),
             resources: Vec::new(),
+            category_matching: KeyMatching::Exact,
+            payees: Vec::new(),
+            custom_fields: Vec::new(),
+            projects: Vec::new(),
+            allocations: Vec::new(),
+            milestones: Vec::new(),
+            tag_groups: Vec::new(),
+            budgets: Vec::new(),
             orders: Vec::new(),
+            places: Vec::new(),
+            recurring: Vec::new(),
+            sub_ledgers: Vec::new(),
+            templates: Vec::new(),
+            trash: Vec::new(),
+            last_export: None,
+            backup: None,
+            next_id: 0,
+            migrations: Vec::new(),
+            load_warnings: Vec::new(),
+            order_cache: OrderCache::default(),
+            source: SourceStamp::default(),
         }
     }
 
@@ -49,212 +229,5178 @@ impl Account {
         self.label = label.into();
     }
 
-    /// Adds a valid tag if it doesn't exist yet.
+    /// Sets the base currency from an *ISO 4217* code.
+    pub fn set_currency(&mut self, code: &str) -> Option<RequestFailure> {
+        if code.trim().is_empty() {
+            Some(RequestFailure::EmptyArgument)
+        } else if let Some(code) = currency::normalize_code(code) {
+            self.currency = Some(code);
+            None
+        } else {
+            Some(RequestFailure::IncorrectArgument)
+        }
+    }
+
+    /// Removes the base currency.
+    pub fn clear_currency(&mut self) {
+        self.currency = None;
+    }
+
+    /// Sets how names of new tags, resources and payees are compared to
+    /// existing ones.
+    pub fn set_category_matching(&mut self, matching: KeyMatching) {
+        self.category_matching = matching;
+    }
+
+    /// Adds a valid tag if it doesn't exist yet, as a tag or an alias.
     pub fn add_tag(&mut self, tag: &str) -> Option<RequestFailure> {
-        self.tags.add_exclusive(tag)
+        let identity = self.category_matching.identity(tag);
+        if self
+            .tag_aliases
+            .keys()
+            .any(|alias| self.category_matching.identity(alias) == identity)
+        {
+            return Some(RequestFailure::ExistingItem);
+        }
+
+        self.order_cache.invalidate();
+        self.tags
+            .add_exclusive_matching(tag, self.category_matching)
     }
 
     /// Removes a tag everywhere.
+    /// Archive it instead to keep it on existing orders.
     pub fn remove_tag(&mut self, tag: &str) -> Option<RequestFailure> {
+        self.order_cache.invalidate();
         if self.tags.remove_exclusive(tag).is_none() {
             // Remove related tag from orders
             self.orders.iter_mut().for_each(|x| {
-                x.remove_tag(tag);
+                if x.remove_tag(tag) {
+                    x.touch();
+                }
+            });
+            // Funds of the envelope become unallocated
+            self.allocations.iter_mut().for_each(|item| {
+                item.allocations
+                    .retain(|allocation| allocation.envelope != tag)
             });
+            self.allocations.retain(|item| !item.allocations.is_empty());
+            self.tag_groups
+                .iter_mut()
+                .for_each(|group| group.tags.retain(|item| item != tag));
+            self.tag_aliases.retain(|_, item| item != tag);
+            self.budgets.retain(|budget| budget.tag != tag);
             None
         } else {
             Some(RequestFailure::UnknownItem)
         }
     }
 
+    /// Lets an alias, like "Restos", stand for an existing tag.
+    pub fn add_tag_alias(&mut self, alias: &str, tag: &str) -> Option<RequestFailure> {
+        let alias = ext::normalize_key(alias);
+        let identity = self.category_matching.identity(&alias);
+        let exists = self
+            .tags
+            .iter()
+            .map(|entry| &entry.name)
+            .chain(self.tag_aliases.keys())
+            .any(|name| self.category_matching.identity(name) == identity);
+
+        if !self.tags.iter().any(|entry| entry == tag) {
+            Some(RequestFailure::UnknownItem)
+        } else {
+            let failure = ext::check_new_key(&alias, exists);
+            if failure.is_none() {
+                self.tag_aliases.insert(alias, tag.into());
+            }
+            failure
+        }
+    }
+
+    /// Removes an alias; orders keep their canonical tag.
+    pub fn remove_tag_alias(&mut self, alias: &str) -> Option<RequestFailure> {
+        match self.tag_aliases.remove(alias) {
+            Some(_) => None,
+            None => Some(RequestFailure::UnknownItem),
+        }
+    }
+
+    /// Adds an empty group of tags with a unique name.
+    pub fn add_tag_group(&mut self, name: &str) -> Option<RequestFailure> {
+        let failure =
+            ext::check_new_key(name, self.tag_groups.iter().any(|group| group.name == name));
+        if failure.is_none() {
+            self.tag_groups.push(TagGroup {
+                name: name.into(),
+                tags: Vec::new(),
+            });
+        }
+        failure
+    }
+
+    /// Removes a group; its tags are kept.
+    pub fn remove_tag_group(&mut self, name: &str) -> Option<RequestFailure> {
+        match self.tag_groups.iter().position(|group| group.name == name) {
+            Some(index) => {
+                self.tag_groups.remove(index);
+                None
+            }
+            None => Some(RequestFailure::UnknownItem),
+        }
+    }
+
+    /// Moves a tag into a group, or out of any group if `group` is empty.
+    pub fn set_tag_group(&mut self, tag: &str, group: &str) -> Option<RequestFailure> {
+        if !self.tags.iter().any(|item| item == tag)
+            || !(group.is_empty() || self.tag_groups.iter().any(|item| item.name == group))
+        {
+            return Some(RequestFailure::UnknownItem);
+        }
+
+        self.tag_groups.iter_mut().for_each(|item| {
+            item.tags.retain(|name| name != tag);
+            if item.name == group {
+                item.tags.push(tag.into());
+            }
+        });
+        None
+    }
+
     /// Adds a valid resource if it doesn't exist yet.
     pub fn add_resource(&mut self, resource: &str) -> Option<RequestFailure> {
-        self.resources.add_exclusive(resource)
+        self.resources
+            .add_exclusive_matching(resource, self.category_matching)
     }
 
     /// Removes a resource evrywhere.
+    /// Archive it instead to keep it on existing orders.
     pub fn remove_resource(&mut self, resource: &str) -> Option<RequestFailure> {
         if self.resources.remove_exclusive(resource).is_none() {
             // Remove related resource from orders
+            self.order_cache.invalidate();
             self.orders.iter_mut().for_each(|x| {
-                if x.resource == Some(resource.to_string()) {
+                if x.resource.as_deref() == Some(resource) {
                     x.resource = None;
+                    x.touch();
+                }
+            });
+            self.milestones
+                .retain(|milestone| milestone.resource != resource);
+            None
+        } else {
+            Some(RequestFailure::UnknownItem)
+        }
+    }
+
+    /// Sets the color, icon and description of a tag or resource; empty ones
+    /// are cleared.
+    pub fn set_category_details(
+        &mut self,
+        kind: CategoryType,
+        name: &str,
+        color: &str,
+        icon: &str,
+        description: &str,
+    ) -> Option<RequestFailure> {
+        let entries = match kind {
+            CategoryType::Tag => &mut self.tags,
+            CategoryType::Resource => &mut self.resources,
+            CategoryType::Project | CategoryType::Payee => {
+                return Some(RequestFailure::IncorrectArgument)
+            }
+        };
+
+        match entries.iter_mut().find(|entry| entry.name == name) {
+            Some(entry) => {
+                if entry.set_details(color, icon, description) {
+                    None
+                } else {
+                    Some(RequestFailure::IncorrectArgument)
+                }
+            }
+            None => Some(RequestFailure::UnknownItem),
+        }
+    }
+
+    /// Archives a tag or resource, or makes it available again.
+    pub fn set_category_archived(
+        &mut self,
+        kind: CategoryType,
+        name: &str,
+        archived: bool,
+    ) -> Option<RequestFailure> {
+        let entries = match kind {
+            CategoryType::Tag => &mut self.tags,
+            CategoryType::Resource => &mut self.resources,
+            CategoryType::Project | CategoryType::Payee => {
+                return Some(RequestFailure::IncorrectArgument)
+            }
+        };
+
+        match entries.iter_mut().find(|entry| entry.name == name) {
+            Some(entry) => {
+                entry.archived = archived;
+                None
+            }
+            None => Some(RequestFailure::UnknownItem),
+        }
+    }
+
+    /// Sets the kind of a resource, or clears it.
+    pub fn set_resource_kind(
+        &mut self,
+        resource: &str,
+        kind: Option<ResourceKind>,
+    ) -> Option<RequestFailure> {
+        match self
+            .resources
+            .iter_mut()
+            .find(|entry| entry.name == resource)
+        {
+            Some(entry) => {
+                entry.kind = kind;
+                None
+            }
+            None => Some(RequestFailure::UnknownItem),
+        }
+    }
+
+    /// Sets the currency of a resource from an *ISO 4217* code.
+    pub fn set_resource_currency(&mut self, resource: &str, code: &str) -> Option<RequestFailure> {
+        let code = match currency::normalize_code(code) {
+            Some(code) => code,
+            None if code.is_empty() => return Some(RequestFailure::EmptyArgument),
+            None => return Some(RequestFailure::IncorrectArgument),
+        };
+
+        match self
+            .resources
+            .iter_mut()
+            .find(|entry| entry.name == resource)
+        {
+            Some(entry) => {
+                entry.currency = Some(code);
+                None
+            }
+            None => Some(RequestFailure::UnknownItem),
+        }
+    }
+
+    /// Makes a resource use the currency of the account.
+    pub fn clear_resource_currency(&mut self, resource: &str) -> Option<RequestFailure> {
+        match self
+            .resources
+            .iter_mut()
+            .find(|entry| entry.name == resource)
+        {
+            Some(entry) => {
+                entry.currency = None;
+                None
+            }
+            None => Some(RequestFailure::UnknownItem),
+        }
+    }
+
+    /// Sets the largest amount which can be owed on a resource.
+    pub fn set_resource_credit_limit(
+        &mut self,
+        resource: &str,
+        amount: f64,
+    ) -> Option<RequestFailure> {
+        match Amount::from_f64(amount) {
+            Some(amount) if !amount.is_negative() => {
+                self.set_resource_limit(resource, Some(BalanceLimit::Credit(amount)))
+            }
+            _ => Some(RequestFailure::IncorrectArgument),
+        }
+    }
+
+    /// Sets the lowest balance to keep on a resource.
+    pub fn set_resource_minimum_balance(
+        &mut self,
+        resource: &str,
+        amount: f64,
+    ) -> Option<RequestFailure> {
+        match Amount::from_f64(amount) {
+            Some(amount) => self.set_resource_limit(resource, Some(BalanceLimit::Minimum(amount))),
+            None => Some(RequestFailure::IncorrectArgument),
+        }
+    }
+
+    /// Removes the balance limit of a resource.
+    pub fn clear_resource_limit(&mut self, resource: &str) -> Option<RequestFailure> {
+        self.set_resource_limit(resource, None)
+    }
+
+    /// Sets the balance of a resource at a given date, counted as a done
+    /// amount by balances which include this date.
+    pub fn set_resource_opening_balance(
+        &mut self,
+        resource: &str,
+        amount: f64,
+        date: &str,
+    ) -> Option<RequestFailure> {
+        let (amount, date) = match (Amount::from_f64(amount), date.parse()) {
+            (Some(amount), Ok(date)) => (amount, date),
+            _ => return Some(RequestFailure::IncorrectArgument),
+        };
+
+        match self
+            .resources
+            .iter_mut()
+            .find(|entry| entry.name == resource)
+        {
+            Some(entry) => {
+                entry.opening = Some(OpeningBalance { amount, date });
+                None
+            }
+            None => Some(RequestFailure::UnknownItem),
+        }
+    }
+
+    /// Removes the opening balance of a resource.
+    pub fn clear_resource_opening_balance(&mut self, resource: &str) -> Option<RequestFailure> {
+        match self
+            .resources
+            .iter_mut()
+            .find(|entry| entry.name == resource)
+        {
+            Some(entry) => {
+                entry.opening = None;
+                None
+            }
+            None => Some(RequestFailure::UnknownItem),
+        }
+    }
+
+    /// Returns the kind of a resource, if set.
+    pub fn resource_kind(&self, resource: &str) -> Option<ResourceKind> {
+        self.resources
+            .iter()
+            .find(|entry| entry.name == resource)?
+            .kind
+    }
+
+    /// Adds a valid payee if it doesn't exist yet.
+    pub fn add_payee(&mut self, payee: &str) -> Option<RequestFailure> {
+        self.payees
+            .add_exclusive_matching(payee, self.category_matching)
+    }
+
+    /// Removes a payee everywhere.
+    pub fn remove_payee(&mut self, payee: &str) -> Option<RequestFailure> {
+        if self.payees.remove_exclusive(payee).is_none() {
+            // Remove related payee from orders
+            self.order_cache.invalidate();
+            self.orders
+                .iter_mut()
+                .filter(|order| order.payee.as_deref() == Some(payee))
+                .for_each(|order| {
+                    order.clear_payee();
+                    order.touch();
+                });
+            None
+        } else {
+            Some(RequestFailure::UnknownItem)
+        }
+    }
+
+    /// Declares a custom field of orders with a unique name.
+    pub fn add_custom_field(&mut self, name: &str, kind: FieldType) -> Option<RequestFailure> {
+        if name.is_empty() {
+            Some(RequestFailure::EmptyArgument)
+        } else if name.chars().all(char::is_whitespace) {
+            Some(RequestFailure::IncorrectArgument)
+        } else if self.custom_fields.iter().any(|field| field.name == name) {
+            Some(RequestFailure::ExistingItem)
+        } else {
+            self.custom_fields.push(CustomField {
+                name: name.into(),
+                kind,
+            });
+            None
+        }
+    }
+
+    /// Removes a custom field and its values everywhere.
+    pub fn remove_custom_field(&mut self, name: &str) -> Option<RequestFailure> {
+        if let Some(index) = self
+            .custom_fields
+            .iter()
+            .position(|field| field.name == name)
+        {
+            self.custom_fields.remove(index);
+            self.orders.iter_mut().for_each(|order| {
+                if order.remove_field(name) {
+                    order.touch();
+                }
+            });
+            None
+        } else {
+            Some(RequestFailure::UnknownItem)
+        }
+    }
+
+    /// Adds a valid project if it doesn't exist yet.
+    pub fn add_project(&mut self, project: &str) -> Option<RequestFailure> {
+        if project.is_empty() {
+            Some(RequestFailure::EmptyArgument)
+        } else if project.chars().all(char::is_whitespace) {
+            Some(RequestFailure::IncorrectArgument)
+        } else if self.projects.iter().any(|item| item.name == project) {
+            Some(RequestFailure::ExistingItem)
+        } else {
+            self.projects.push(Project::new(project));
+            None
+        }
+    }
+
+    /// Removes a project everywhere.
+    pub fn remove_project(&mut self, project: &str) -> Option<RequestFailure> {
+        if let Some(index) = self.projects.iter().position(|item| item.name == project) {
+            self.projects.remove(index);
+            // Remove related project from orders
+            self.order_cache.invalidate();
+            self.orders.iter_mut().for_each(|x| {
+                if x.project == Some(project.to_string()) {
+                    x.project = None;
+                    x.touch();
+                }
+            });
+            None
+        } else {
+            Some(RequestFailure::UnknownItem)
+        }
+    }
+
+    /// Sets the budget of a project as a positive amount.
+    pub fn set_project_budget(&mut self, project: &str, budget: f64) -> Option<RequestFailure> {
+        if let Some(item) = self.projects.iter_mut().find(|item| item.name == project) {
+            match Amount::from_f64(budget) {
+                Some(budget) if !budget.is_negative() => {
+                    item.budget = Some(budget);
+                    None
                 }
+                _ => Some(RequestFailure::IncorrectArgument),
+            }
+        } else {
+            Some(RequestFailure::UnknownItem)
+        }
+    }
+
+    /// Removes the budget of a project.
+    pub fn clear_project_budget(&mut self, project: &str) -> Option<RequestFailure> {
+        if let Some(item) = self.projects.iter_mut().find(|item| item.name == project) {
+            item.budget = None;
+            None
+        } else {
+            Some(RequestFailure::UnknownItem)
+        }
+    }
+
+    /// Sets the spending limit of a tag for each period, as a positive
+    /// amount, replacing the one of the same period.
+    pub fn set_tag_budget(
+        &mut self,
+        tag: &str,
+        period: Period,
+        amount: f64,
+    ) -> Option<RequestFailure> {
+        if !self.tags.iter().any(|entry| entry == tag) {
+            return Some(RequestFailure::UnknownItem);
+        }
+        match Amount::from_f64(amount) {
+            Some(amount) if !amount.is_negative() => {
+                self.budgets
+                    .retain(|budget| budget.tag != tag || budget.period != period);
+                self.budgets.push(TagBudget {
+                    tag: tag.into(),
+                    period,
+                    amount,
+                });
+                None
+            }
+            _ => Some(RequestFailure::IncorrectArgument),
+        }
+    }
+
+    /// Removes the spending limit of a tag for a period.
+    pub fn clear_tag_budget(&mut self, tag: &str, period: Period) -> Option<RequestFailure> {
+        let count = self.budgets.len();
+        self.budgets
+            .retain(|budget| budget.tag != tag || budget.period != period);
+
+        if self.budgets.len() < count {
+            None
+        } else {
+            Some(RequestFailure::UnknownItem)
+        }
+    }
+
+    /// Adds a balance threshold to a resource.
+    pub fn add_milestone(
+        &mut self,
+        resource: &str,
+        direction: ThresholdDirection,
+        threshold: f64,
+    ) -> Option<RequestFailure> {
+        let threshold = match Amount::from_f64(threshold) {
+            Some(threshold) => threshold,
+            None => return Some(RequestFailure::IncorrectArgument),
+        };
+
+        if !self.resources.iter().any(|item| item == resource) {
+            Some(RequestFailure::UnknownItem)
+        } else if self.milestones.iter().any(|item| {
+            item.resource == resource && item.direction == direction && item.threshold == threshold
+        }) {
+            Some(RequestFailure::ExistingItem)
+        } else {
+            self.milestones
+                .push(Milestone::new(resource, direction, threshold));
+            None
+        }
+    }
+
+    /// Removes a balance threshold by its position.
+    pub fn remove_milestone(&mut self, index: usize) -> bool {
+        if index < self.milestones.len() {
+            self.milestones.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Adds a named place if it doesn't exist yet.
+    /// `radius` is expressed in meters.
+    pub fn add_place(
+        &mut self,
+        name: &str,
+        latitude: f64,
+        longitude: f64,
+        radius: f64,
+    ) -> Option<RequestFailure> {
+        if name.is_empty() {
+            Some(RequestFailure::EmptyArgument)
+        } else if self.places.iter().any(|place| place.name == name) {
+            Some(RequestFailure::ExistingItem)
+        } else if let (Some(center), true) = (Location::new(latitude, longitude), radius > 0.0) {
+            self.places.push(Place {
+                name: name.into(),
+                center,
+                radius,
+            });
+            None
+        } else {
+            Some(RequestFailure::IncorrectArgument)
+        }
+    }
+
+    /// Removes a named place.
+    pub fn remove_place(&mut self, name: &str) -> Option<RequestFailure> {
+        if let Some(index) = self.places.iter().position(|place| place.name == name) {
+            self.places.remove(index);
+            None
+        } else {
+            Some(RequestFailure::UnknownItem)
+        }
+    }
+
+    /// Adds a sub-ledger over the orders owning an existing tag.
+    pub fn add_sub_ledger(&mut self, name: &str, tag: &str) -> Option<RequestFailure> {
+        if name.trim().is_empty() {
+            Some(RequestFailure::EmptyArgument)
+        } else if !self.tags.iter().any(|item| item == tag) {
+            Some(RequestFailure::UnknownItem)
+        } else if self.sub_ledgers.iter().any(|ledger| ledger.name == name) {
+            Some(RequestFailure::ExistingItem)
+        } else {
+            self.sub_ledgers.push(SubLedger {
+                name: name.into(),
+                tag: tag.into(),
+                allowance: None,
             });
             None
+        }
+    }
+
+    /// Removes a sub-ledger along with its allowance; orders are kept.
+    pub fn remove_sub_ledger(&mut self, name: &str) -> Option<RequestFailure> {
+        if let Some(index) = self.sub_ledgers.iter().position(|item| item.name == name) {
+            let ledger = self.sub_ledgers.remove(index);
+            if let Some(label) = ledger.allowance {
+                self.remove_recurring_rule(&label);
+            }
+            None
         } else {
             Some(RequestFailure::UnknownItem)
         }
     }
 
-    /// Creates a default order.
-    pub fn add_order(&mut self) {
-        self.orders.push(Order::default());
+    /// Credits a sub-ledger periodically from a given date.
+    /// Replaces the previous allowance; already created orders are kept.
+    #[cfg(feature = "rules")]
+    pub fn set_sub_ledger_allowance(
+        &mut self,
+        name: &str,
+        amount: f64,
+        frequency: Frequency,
+        start: &str,
+    ) -> Option<RequestFailure> {
+        let index = match self.sub_ledgers.iter().position(|item| item.name == name) {
+            Some(index) => index,
+            None => return Some(RequestFailure::UnknownItem),
+        };
+        let (amount, start) = match (Amount::from_f64(amount), start.parse::<NaiveDate>()) {
+            (Some(amount), Ok(start)) if amount.is_positive() => (amount, start),
+            _ => return Some(RequestFailure::IncorrectArgument),
+        };
+        let label = self.sub_ledgers[index].allowance_label();
+        let template = Order {
+            description: label.as_str().into(),
+            amount,
+            tags: vec![self.sub_ledgers[index].tag.as_str().into()],
+            ..Order::default()
+        };
+
+        self.remove_recurring_rule(&label);
+        self.recurring.push(RecurringRule {
+            frequency,
+            ..RecurringRule::new(&label, start, template)
+        });
+        self.sub_ledgers[index].allowance = Some(label);
+        None
+    }
+
+    /// Returns a filter listing the orders of a sub-ledger.
+    pub fn sub_ledger_filter(&self, name: &str) -> Option<Filter> {
+        let ledger = self.sub_ledgers.iter().find(|item| item.name == name)?;
+
+        Some(Filter {
+            tag_option: CategoryFilter::Enabled(vec![Category(
+                ledger.tag.clone(),
+                ItemSelector::Selected,
+            )]),
+            ..Filter::default()
+        })
+    }
+
+    /// Compares the counted cash of a resource with its balance at a given
+    /// date, then creates a done order of the discrepancy, if any.
+    pub fn reconcile_cash(
+        &mut self,
+        resource: &str,
+        counted_amount: f64,
+        date: &str,
+    ) -> Option<RequestFailure> {
+        if !self.resources.iter().any(|item| item == resource) {
+            return Some(RequestFailure::UnknownItem);
+        }
+        let (counted_amount, date) = match (Amount::from_f64(counted_amount), date.parse()) {
+            (Some(amount), Ok(date)) => (amount, date),
+            _ => return Some(RequestFailure::IncorrectArgument),
+        };
+        let balance = match self.resource_balance(resource, date) {
+            Ok(balance) => balance,
+            Err(failure) => return Some(failure),
+        };
+        if counted_amount == balance {
+            return None;
+        }
+        // Balances of liabilities are amounts owed
+        let discrepancy = match self.resource_kind(resource) {
+            Some(kind) if kind.is_liability() => balance.checked_sub(counted_amount),
+            _ => counted_amount.checked_sub(balance),
+        };
+        let adjustment = match discrepancy {
+            Some(amount) => Order {
+                date: Some(date),
+                description: "Cash adjustment".into(),
+                amount,
+                resource: Some(resource.into()),
+                tags: vec![CASH_ADJUSTMENT_TAG.into()],
+                state: TransactionState::Done,
+                ..Order::default()
+            },
+            None => return Some(RequestFailure::Overflow),
+        };
+
+        // The account only changes once every check passed
+        self.add_tag(CASH_ADJUSTMENT_TAG);
+        self.push_order(adjustment);
+        None
+    }
+
+    /// Creates a default order and returns its id.
+    pub fn add_order(&mut self) -> usize {
+        self.push_order(Order::default())
     }
 
-    /// Creates a filtered order.
+    /// Creates a filtered order and returns its id.
     ///
     /// > Can not be merged with `add_default_order` as optional with reference
     /// > is not supported by **wasmbindgen**
-    pub fn add_filtered_order(&mut self, filter: &Filter) {
-        self.orders.push(Order::from(filter));
+    pub fn add_filtered_order(&mut self, filter: &Filter) -> usize {
+        self.push_order(Order::from(filter))
+    }
+
+    /// Counts orders allowed by the filter, without sorting them.
+    pub fn count_filtered_orders(&self, filter: &Filter) -> usize {
+        let mut count = 0;
+        self.visit_filtered_orders(filter, |_| count += 1);
+        count
     }
 
-    /// duplicates an existing order and returns its id.
-    pub fn duplicate_order(&mut self, index: usize) -> bool {
+    /// Duplicates an existing order and returns its id.
+    pub fn duplicate_order(&mut self, id: usize) -> Option<usize> {
         // Copy the order if it exists
-        if let Some(order) = self.orders.get(index) {
+        if let Some(order) = self.get_order(id) {
             let copy = order.clone();
-            self.orders.push(copy);
-            true
+            Some(self.push_order(copy))
         } else {
-            false
+            None
         }
     }
 
-    /// Deletes one order permanently.
-    pub fn delete_order(&mut self, index: usize) -> bool {
-        if self.orders.get(index).is_some() {
-            self.orders.remove(index);
+    /// Locks or unlocks an order matched against a bank statement.
+    pub fn set_order_reconciled(&mut self, id: usize, reconciled: bool) -> bool {
+        if let Some(index) = self.order_index(id) {
+            self.orders[index].reconciled = reconciled;
+            self.orders[index].touch();
             true
         } else {
             false
         }
     }
-}
 
-impl Account {
-    /// Returns the label.
-    pub fn label(&self) -> &String {
-        &self.label
+    /// Sets the cheque number or bank reference of an order, unique within
+    /// its resource. An empty reference removes it.
+    pub fn set_order_reference(&mut self, id: usize, reference: &str) -> Option<RequestFailure> {
+        let reference = reference.trim();
+        let resource = match self.get_order(id) {
+            Some(order) => order.resource.clone(),
+            None => return Some(RequestFailure::UnknownItem),
+        };
+
+        if !reference.is_empty()
+            && self.orders.iter().any(|order| {
+                order.id != id
+                    && order.is_visible()
+                    && order.resource == resource
+                    && order.reference.as_deref() == Some(reference)
+            })
+        {
+            Some(RequestFailure::ExistingItem)
+        } else if let Some(mut order) = self.get_order_mut(id) {
+            order.reference = if reference.is_empty() {
+                None
+            } else {
+                Some(reference.into())
+            };
+            None
+        } else {
+            // Reconciled orders are locked
+            Some(RequestFailure::IncorrectArgument)
+        }
     }
 
-    /// Returns available tags.
-    pub fn tags(&self) -> &Vec<String> {
-        &self.tags
+    /// Moves an order to the position of another one, such as undated orders
+    /// rearranged by drag and drop.
+    pub fn move_order(&mut self, id: usize, target: usize) -> bool {
+        match (self.order_index(id), self.order_index(target)) {
+            (Some(from), Some(to)) => {
+                let order = self.orders.remove(from);
+                self.orders.insert(to, order);
+                self.order_cache.invalidate();
+                true
+            }
+            _ => false,
+        }
     }
 
-    /// Returns available resources.
-    pub fn resources(&self) -> &Vec<String> {
-        &self.resources
+    /// Links a refund or chargeback to its original order, which can not
+    /// be a refund itself.
+    pub fn link_order(&mut self, id: usize, original: usize) -> Option<RequestFailure> {
+        match self.get_order(original) {
+            None => Some(RequestFailure::UnknownItem),
+            Some(order) if id == original || order.related_order.is_some() => {
+                Some(RequestFailure::IncorrectArgument)
+            }
+            Some(_) => match self.get_order_mut(id) {
+                Some(mut order) => {
+                    order.related_order = Some(original);
+                    None
+                }
+                None => Some(RequestFailure::UnknownItem),
+            },
+        }
     }
 
-    /// Offers access to a given order
-    pub fn get_order_mut(&mut self, index: usize) -> Option<&mut Order> {
-        self.orders.get_mut(index)
+    /// Removes the link of an order to its original one.
+    pub fn unlink_order(&mut self, id: usize) -> bool {
+        match self.get_order_mut(id) {
+            Some(mut order) => order.related_order.take().is_some(),
+            None => false,
+        }
     }
 
-    /// Returns all orders
-    pub fn orders(&self) -> &Vec<Order> {
-        &self.orders
+    /// Saves a copy of an order as a named template.
+    pub fn save_order_as_template(&mut self, id: usize, name: &str) -> Option<RequestFailure> {
+        if name.trim().is_empty() {
+            Some(RequestFailure::EmptyArgument)
+        } else if self.templates.iter().any(|template| template.name == name) {
+            Some(RequestFailure::ExistingItem)
+        } else if let Some(order) = self.get_order(id) {
+            let order = Order {
+                date: None,
+                attachments: Vec::new(),
+                reference: None,
+                related_order: None,
+                ..order.clone()
+            };
+            self.templates.push(OrderTemplate {
+                name: name.into(),
+                order,
+            });
+            None
+        } else {
+            Some(RequestFailure::UnknownItem)
+        }
     }
 
-    /// Stores data as YAML file.
-    pub fn save_file(&self, path: &Path) -> std::io::Result<()> {
-        let mut file = File::create(path)?;
-        file.write_all(serde_yaml::to_string(self).unwrap().as_bytes())?;
-        Ok(())
+    /// Removes a template.
+    pub fn remove_template(&mut self, name: &str) -> Option<RequestFailure> {
+        if let Some(index) = self.templates.iter().position(|item| item.name == name) {
+            self.templates.remove(index);
+            None
+        } else {
+            Some(RequestFailure::UnknownItem)
+        }
     }
 
-    /// Returns an initialized account from YAML file.
-    pub fn load_file(path: &Path) -> std::io::Result<Account> {
-        let file = File::open(path)?;
+    /// Creates an undated order from a template and returns its id.
+    pub fn add_order_from_template(&mut self, name: &str) -> Option<usize> {
+        let order = self
+            .templates
+            .iter()
+            .find(|template| template.name == name)?
+            .order
+            .clone();
 
-        match Account::try_from(file) {
-            Ok(data) => Ok(data),
-            Err(error) => Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("{}", error),
-            )),
+        Some(self.push_order(order))
+    }
+
+    /// Moves one order to the trash, unless reconciled.
+    pub fn delete_order(&mut self, id: usize) -> bool {
+        if let Some(index) = self
+            .order_index(id)
+            .filter(|&index| !self.orders[index].reconciled)
+        {
+            self.order_cache.invalidate();
+            self.trash.push(TrashedOrder {
+                order: self.orders.remove(index),
+                deleted_at: Local::now().naive_local(),
+            });
+            true
+        } else {
+            false
         }
     }
-}
 
-impl TryFrom<File> for Account {
-    type Error = serde_yaml::Error;
+    /// Moves an order back from the trash, at its former position.
+    pub fn restore_order(&mut self, id: usize) -> bool {
+        if let Some(index) = self.trash.iter().position(|item| item.order.id == id) {
+            let order = self.trash.remove(index).order;
+            self.order_cache.invalidate();
+            let position = self
+                .orders
+                .iter()
+                .position(|item| item.id > id)
+                .unwrap_or(self.orders.len());
 
-    fn try_from(file: File) -> Result<Self, Self::Error> {
-        serde_yaml::from_reader(file)
+            self.orders.insert(position, order);
+            true
+        } else {
+            false
+        }
     }
-}
 
-impl TryFrom<&str> for Account {
-    type Error = serde_yaml::Error;
+    /// Deletes all orders of the trash permanently.
+    /// Returns the number of purged orders.
+    pub fn purge_trash(&mut self) -> usize {
+        let count = self.trash.len();
+        self.trash.clear();
+        count
+    }
 
-    fn try_from(content: &str) -> Result<Self, Self::Error> {
-        serde_yaml::from_str(content)
+    /// Hides visible orders allowed by the filter for a given reason;
+    /// reconciled orders are left as they are.
+    /// Returns the number of hidden orders.
+    pub fn hide_matching(&mut self, filter: &Filter, reason: HiddenReason) -> usize {
+        let dates = filter.dates();
+        let hidden = self
+            .orders
+            .iter_mut()
+            .filter(|order| {
+                order.is_visible() && !order.reconciled && filter.is_order_allowed_on(order, &dates)
+            })
+            .map(|order| {
+                order.hide(reason);
+                order.touch();
+            })
+            .count();
+
+        if hidden > 0 {
+            self.order_cache.invalidate();
+        }
+        hidden
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::NaiveDate;
+impl Account {
+    /// Returns unpaid visible orders due within a number of days from today,
+    /// sorted by date.
+    pub fn upcoming_orders(&self, days: u32) -> Vec<&Order> {
+        self.upcoming_orders_from(Local::now().date_naive(), days)
+    }
+
+    /// Same as `upcoming_orders`, from a given date.
+    pub fn upcoming_orders_from(&self, today: NaiveDate, days: u32) -> Vec<&Order> {
+        let last = today + Duration::days(i64::from(days));
+        let mut orders = self
+            .orders
+            .iter()
+            .filter(|order| order.is_visible() && order.state != TransactionState::Done)
+            .filter(|order| order.date.is_some_and(|date| today <= date && date <= last))
+            .collect::<Vec<&Order>>();
+
+        orders.sort_by_key(|order| order.date);
+        orders
+    }
+
+    /// Returns the label.
+    pub fn label(&self) -> &String {
+        &self.label
+    }
+
+    /// Returns the base currency, if any.
+    pub fn currency(&self) -> Option<&String> {
+        self.currency.as_ref()
+    }
+
+    /// Returns the currency of a resource, the account one if not set.
+    pub fn resource_currency(&self, resource: &str) -> Option<&String> {
+        self.resources
+            .iter()
+            .find(|entry| entry.name == resource)
+            .and_then(|entry| entry.currency.as_ref())
+            .or(self.currency.as_ref())
+    }
+
+    /// Returns ids of counted orders whose currency differs from the one of
+    /// their resource, without conversion to it.
+    pub fn currency_mismatches(&self) -> Vec<usize> {
+        self.orders
+            .iter()
+            .filter(|order| order.is_counted(&[]))
+            .filter(|order| match &order.resource {
+                Some(resource) => {
+                    let expected = self.resource_currency(resource);
+                    order.currency.as_ref().or(self.currency.as_ref()) != expected
+                        && order
+                            .conversion
+                            .as_ref()
+                            .is_none_or(|conversion| Some(&conversion.currency) != expected)
+                }
+                None => false,
+            })
+            .map(|order| order.id)
+            .collect()
+    }
+
+    /// Sets or clears the balance limit of a resource.
+    pub fn set_resource_limit(
+        &mut self,
+        resource: &str,
+        limit: Option<BalanceLimit>,
+    ) -> Option<RequestFailure> {
+        match self
+            .resources
+            .iter_mut()
+            .find(|entry| entry.name == resource)
+        {
+            Some(entry) => {
+                entry.limit = limit;
+                None
+            }
+            None => Some(RequestFailure::UnknownItem),
+        }
+    }
+
+    /// Returns resources whose done or expected balance within a date range
+    /// breaks their limit. Resources mixing currencies are skipped.
+    pub fn resource_alerts(&self, date_range: OptionNaiveDateRange) -> Vec<ResourceAlert> {
+        let OptionNaiveDateRange(start, end) = date_range;
+
+        self.resources
+            .iter()
+            .filter_map(|entry| {
+                let limit = entry.limit?;
+                let mut groups = self
+                    .category_amount_by_currency(
+                        CategoryType::Resource,
+                        &entry.name,
+                        OptionNaiveDateRange(start, end),
+                    )
+                    .ok()?;
+                let (current, expected) = match groups.len() {
+                    0 => (Amount::ZERO, Amount::ZERO),
+                    1 => {
+                        let amount = groups.remove(0).amount;
+                        (amount.current, amount.expected)
+                    }
+                    _ => return None,
+                };
+                let liability = entry.kind.is_some_and(ResourceKind::is_liability);
+
+                if limit.is_violated(current, liability) || limit.is_violated(expected, liability) {
+                    Some(ResourceAlert {
+                        resource: entry.name.clone(),
+                        limit,
+                        current,
+                        expected,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the opening balance of a resource, if set.
+    pub fn resource_opening_balance(&self, resource: &str) -> Option<OpeningBalance> {
+        self.resources
+            .iter()
+            .find(|entry| entry.name == resource)?
+            .opening
+    }
+
+    /// Computes the amounts of a *category* between a given range.
+    ///
+    /// Returns `None` if there is no order, or if orders use different
+    /// currencies once the base currency is applied.
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    pub fn category_amount(
+        &self,
+        kind: CategoryType,
+        category: &str,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<Option<CategoryAmount>, RequestFailure> {
+        let mut groups = self.category_amount_by_currency(kind, category, date_range)?;
+
+        Ok(if groups.len() == 1 {
+            groups.pop().map(|group| group.amount)
+        } else {
+            None
+        })
+    }
+
+    /// Computes the amounts of every *category* between a given range, as
+    /// `category_amount` would for each one.
+    ///
+    /// Categories whose orders use different currencies are left out.
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    pub fn all_category_amounts(
+        &self,
+        kind: CategoryType,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<HashMap<String, CategoryAmount>, RequestFailure> {
+        let OptionNaiveDateRange(start, end) = date_range;
+        let mut amounts = self
+            .columns()
+            .all_category_stats(kind, (start, end))?
+            .into_iter()
+            .map(|(category, stats)| (category, stats.amount))
+            .collect::<HashMap<String, CategoryAmount>>();
+
+        if kind == CategoryType::Resource {
+            // Liabilities and opening balances need adjustments
+            for entry in self.resources.iter().filter(|entry| {
+                entry.opening.is_some() || entry.kind.is_some_and(ResourceKind::is_liability)
+            }) {
+                match self.category_amount(kind, &entry.name, OptionNaiveDateRange(start, end))? {
+                    Some(amount) => amounts.insert(entry.name.clone(), amount),
+                    None => amounts.remove(&entry.name),
+                };
+            }
+        }
+        Ok(amounts)
+    }
+
+    /// Returns groups of tags.
+    pub fn tag_groups(&self) -> &Vec<TagGroup> {
+        &self.tag_groups
+    }
+
+    /// Computes the amounts of orders owning tags of a group between a given
+    /// range.
+    ///
+    /// Returns `None` if there is no order, or if orders use different
+    /// currencies. Fails with `RequestFailure::UnknownItem` if the group is
+    /// unknown, or `RequestFailure::Overflow` if a sum overflows.
+    pub fn tag_group_amount(
+        &self,
+        group: &str,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<Option<CategoryAmount>, RequestFailure> {
+        let group = self
+            .tag_groups
+            .iter()
+            .find(|item| item.name == group)
+            .ok_or(RequestFailure::UnknownItem)?;
+        let OptionNaiveDateRange(start, end) = date_range;
+        self.columns().tags_amount(&group.tags, (start, end))
+    }
+
+    /// Computes the amounts of a *category* for each currency.
+    /// Amounts of liability resources are inverted, to be the amount owed.
+    /// Resources hold converted amounts of orders, and their opening balance
+    /// in their currency if the range contains its date.
+    ///
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    pub fn category_amount_by_currency(
+        &self,
+        kind: CategoryType,
+        category: &str,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<Vec<CurrencyAmount>, RequestFailure> {
+        let OptionNaiveDateRange(start, end) = date_range;
+        let mut groups = self.columns().category_amount_by_currency(
+            kind,
+            category,
+            (start, end),
+            self.currency.as_deref(),
+        )?;
+
+        if kind != CategoryType::Resource {
+            return Ok(groups);
+        }
+        if self
+            .resource_kind(category)
+            .is_some_and(ResourceKind::is_liability)
+        {
+            groups = groups
+                .into_iter()
+                .map(|group| CurrencyAmount {
+                    currency: group.currency,
+                    amount: group.amount.negated(),
+                })
+                .collect();
+        }
+
+        if let Some(opening) = self.resource_opening_balance(category).filter(|opening| {
+            start.is_none_or(|start| opening.date >= start)
+                && end.is_none_or(|end| opening.date <= end)
+        }) {
+            let currency = self.resource_currency(category).cloned();
+            // Groups are sorted by currency
+            let index = match groups.binary_search_by(|group| group.currency.cmp(&currency)) {
+                Ok(index) => index,
+                Err(index) => {
+                    groups.insert(
+                        index,
+                        CurrencyAmount {
+                            currency,
+                            amount: CategoryAmount {
+                                current: Amount::ZERO,
+                                pending: Amount::ZERO,
+                                in_progress: Amount::ZERO,
+                                expected: Amount::ZERO,
+                            },
+                        },
+                    );
+                    index
+                }
+            };
+            let amount = &mut groups[index].amount;
+            amount.current = amount
+                .current
+                .checked_add(opening.amount)
+                .ok_or(RequestFailure::Overflow)?;
+            amount.expected = amount
+                .expected
+                .checked_add(opening.amount)
+                .ok_or(RequestFailure::Overflow)?;
+        }
+
+        Ok(groups)
+    }
+
+    /// Sums balances of done orders of all resources until a date, assets
+    /// minus liabilities, along with the balance of each one. Resources
+    /// without kind are assets.
+    ///
+    /// Returns `None` if a resource mixes currencies or holds another one
+    /// than the account, or if a sum overflows.
+    pub fn net_worth(&self, date: NaiveDate) -> Option<NetWorth> {
+        let (mut assets, mut liabilities) = (Amount::ZERO, Amount::ZERO);
+        let mut resources = Vec::new();
+
+        for entry in &self.resources {
+            if entry.currency.is_some() && entry.currency != self.currency {
+                return None;
+            }
+            let balance = self.resource_balance(&entry.name, date).ok()?;
+            let liability = entry.kind.is_some_and(ResourceKind::is_liability);
+            if liability {
+                liabilities = liabilities.checked_add(balance)?;
+            } else {
+                assets = assets.checked_add(balance)?;
+            }
+            resources.push(ResourceBalance {
+                resource: entry.name.clone(),
+                balance,
+                liability,
+            });
+        }
+
+        Some(NetWorth {
+            assets,
+            liabilities,
+            net: assets.checked_sub(liabilities)?,
+            resources,
+        })
+    }
+
+    /// Computes the balance at the end of each month overlapping a date range,
+    /// for a resource or, if `None`, for all resources: assets minus
+    /// liabilities.
+    ///
+    /// Fails with `RequestFailure::UnknownItem` if the resource does not
+    /// exist, with `IncorrectArgument` if balances hold different currencies,
+    /// or with `Overflow` if a sum overflows.
+    pub fn monthly_balances(
+        &self,
+        resource: Option<&str>,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<MonthlyBalance>, RequestFailure> {
+        let entries = match resource {
+            Some(name) => vec![self
+                .resources
+                .iter()
+                .find(|entry| *entry == name)
+                .ok_or(RequestFailure::UnknownItem)?],
+            None if self
+                .resources
+                .iter()
+                .any(|entry| entry.currency.is_some() && entry.currency != self.currency) =>
+            {
+                return Err(RequestFailure::IncorrectArgument)
+            }
+            None => self.resources.iter().collect(),
+        };
+
+        Period::Month
+            .split(start, end)
+            .into_iter()
+            .map(|(month, last_day)| {
+                let mut balance = MonthlyBalance {
+                    month,
+                    current: Amount::ZERO,
+                    expected: Amount::ZERO,
+                };
+
+                for entry in &entries {
+                    let mut groups = self.category_amount_by_currency(
+                        CategoryType::Resource,
+                        &entry.name,
+                        OptionNaiveDateRange(None, Some(last_day)),
+                    )?;
+                    let mut amount = match groups.len() {
+                        0 => continue,
+                        1 => groups.remove(0).amount,
+                        _ => return Err(RequestFailure::IncorrectArgument),
+                    };
+                    // Debts lower the balance of the account
+                    if resource.is_none() && entry.kind.is_some_and(ResourceKind::is_liability) {
+                        amount = amount.negated();
+                    }
+                    balance.current = balance
+                        .current
+                        .checked_add(amount.current)
+                        .ok_or(RequestFailure::Overflow)?;
+                    balance.expected = balance
+                        .expected
+                        .checked_add(amount.expected)
+                        .ok_or(RequestFailure::Overflow)?;
+                }
+                Ok(balance)
+            })
+            .collect()
+    }
+
+    /// Projects the balance of each resource at the end of the current month
+    /// and of the `months` following ones, from recurring orders.
+    pub fn balance_forecast(&self, months: u32) -> Result<Vec<ResourceForecast>, RequestFailure> {
+        self.balance_forecast_at(Local::now().date_naive(), months)
+    }
+
+    /// Same as `balance_forecast`, from a given date.
+    ///
+    /// Fails with `RequestFailure::IncorrectArgument` if a resource mixes
+    /// currencies, or with `Overflow` if a sum overflows.
+    pub fn balance_forecast_at(
+        &self,
+        date: NaiveDate,
+        months: u32,
+    ) -> Result<Vec<ResourceForecast>, RequestFailure> {
+        let end = date
+            .checked_add_months(Months::new(months))
+            .ok_or(RequestFailure::IncorrectArgument)?;
+
+        self.resources
+            .iter()
+            .map(|entry| {
+                let balances = self.monthly_balances(Some(&entry.name), date, end)?;
+                let rules = self
+                    .recurring
+                    .iter()
+                    .filter(|rule| rule.template.resource.as_deref() == Some(&entry.name))
+                    .collect::<Vec<&RecurringRule>>();
+
+                Ok(ResourceForecast {
+                    resource: entry.name.clone(),
+                    points: forecast::project(
+                        &balances,
+                        &rules,
+                        date,
+                        entry.kind.is_some_and(ResourceKind::is_liability),
+                    )?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns balances of each resource not archived over the last `days`
+    /// days, one per day or per week.
+    ///
+    /// Fails with `RequestFailure::IncorrectArgument` if `days` exceeds
+    /// `MAX_SPARKLINE_DAYS` or reaches before supported dates.
+    pub fn sparklines(&self, days: u32, weekly: bool) -> Result<Vec<Sparkline>, RequestFailure> {
+        self.sparklines_at(Local::now().date_naive(), days, weekly)
+    }
+
+    /// Same as `sparklines`, until a given date. Resources mixing currencies
+    /// are skipped.
+    pub fn sparklines_at(
+        &self,
+        date: NaiveDate,
+        days: u32,
+        weekly: bool,
+    ) -> Result<Vec<Sparkline>, RequestFailure> {
+        if days > MAX_SPARKLINE_DAYS {
+            return Err(RequestFailure::IncorrectArgument);
+        }
+        let step = if weekly { 7 } else { 1 };
+        let count = days.div_ceil(step).max(1);
+        let start = date
+            .checked_sub_signed(Duration::days(i64::from((count - 1) * step)))
+            .ok_or(RequestFailure::IncorrectArgument)?;
+
+        let sparklines = self
+            .resources
+            .iter()
+            .filter(|entry| !entry.archived)
+            .filter_map(|entry| {
+                let liability = entry.kind.is_some_and(ResourceKind::is_liability);
+                // Changes after the first date, by step
+                let mut changes = vec![Amount::ZERO; count as usize];
+                let index = |day: NaiveDate| ((day - start).num_days() as u32).div_ceil(step);
+
+                for order in self.orders.iter().filter(|order| {
+                    order.is_counted(&[])
+                        && order.state() == TransactionState::Done
+                        && order.resource.as_deref() == Some(&entry.name)
+                }) {
+                    match order.date {
+                        Some(day) if day > start && day <= date => {
+                            let amount = ext::category_money(order, CategoryType::Resource).1;
+                            let change = &mut changes[index(day) as usize];
+                            *change =
+                                change.checked_add(if liability { -amount } else { amount })?;
+                        }
+                        _ => {}
+                    }
+                }
+                if let Some(opening) = entry
+                    .opening
+                    .filter(|opening| opening.date > start && opening.date <= date)
+                {
+                    let change = &mut changes[index(opening.date) as usize];
+                    *change = change.checked_add(opening.amount)?;
+                }
+
+                let mut balance = self.resource_balance(&entry.name, start).ok()?;
+                let balances = changes
+                    .into_iter()
+                    .map(|change| {
+                        balance = balance.checked_add(change)?;
+                        Some(balance)
+                    })
+                    .collect::<Option<Vec<Amount>>>()?;
+
+                Some(Sparkline {
+                    resource: entry.name.clone(),
+                    start,
+                    step,
+                    balances,
+                })
+            })
+            .collect::<Vec<Sparkline>>();
+        Ok(sparklines)
+    }
+
+    /// Adds a recurring rule with a unique label.
+    #[cfg(feature = "rules")]
+    pub fn add_recurring_rule(&mut self, rule: RecurringRule) -> Option<RequestFailure> {
+        if rule.label.trim().is_empty() {
+            Some(RequestFailure::EmptyArgument)
+        } else if rule.interval == 0 {
+            Some(RequestFailure::IncorrectArgument)
+        } else if self.recurring.iter().any(|item| item.label == rule.label) {
+            Some(RequestFailure::ExistingItem)
+        } else {
+            self.recurring.push(rule);
+            None
+        }
+    }
+
+    /// Removes a recurring rule; already created orders are kept.
+    pub fn remove_recurring_rule(&mut self, label: &str) -> Option<RequestFailure> {
+        if let Some(index) = self.recurring.iter().position(|rule| rule.label == label) {
+            self.recurring.remove(index);
+            None
+        } else {
+            Some(RequestFailure::UnknownItem)
+        }
+    }
+
+    /// Returns recurring rules.
+    pub fn recurring_rules(&self) -> &Vec<RecurringRule> {
+        &self.recurring
+    }
+
+    /// Creates pending orders for all occurrences of recurring rules until a
+    /// given date, included, and returns their ids.
+    ///
+    /// Occurrences are created only once.
+    #[cfg(feature = "rules")]
+    pub fn materialize_recurring_until(&mut self, date: NaiveDate) -> Vec<usize> {
+        let orders = self
+            .recurring
+            .iter_mut()
+            .flat_map(|rule| rule.materialize_until(date))
+            .collect::<Vec<Order>>();
+
+        orders
+            .into_iter()
+            .map(|order| self.push_order(order))
+            .collect()
+    }
+
+    /// Sorts out transactions already recorded in the account.
+    #[cfg(feature = "import")]
+    pub fn preview_import(&self, transactions: Vec<ImportedTransaction>) -> ImportPreview {
+        import::preview::preview(&self.orders, transactions)
+    }
+
+    /// Creates orders from new transactions of a preview and returns their ids.
+    /// An unknown resource is ignored.
+    #[cfg(feature = "import")]
+    pub fn apply_import(&mut self, preview: &ImportPreview, resource: Option<&str>) -> Vec<usize> {
+        preview
+            .new
+            .iter()
+            .map(|transaction| {
+                let mut order = transaction.to_order();
+                if let Some(resource) = resource {
+                    order.set_resource(resource, &self.resources());
+                }
+                self.push_order(order)
+            })
+            .collect()
+    }
+
+    /// Creates done orders from rows of a CSV export, skipping rows matching
+    /// an order recorded before the import.
+    #[cfg(feature = "import")]
+    pub fn import_csv(&mut self, content: &str, mapping: &CsvMapping) -> CsvImportReport {
+        let (records, errors) = import::csv::parse(content, mapping);
+        let (known, resources) = (self.orders.len(), self.resources());
+        let mut report = CsvImportReport {
+            errors,
+            ..CsvImportReport::default()
+        };
+
+        records.into_iter().for_each(|record| {
+            let mut order = record.transaction.to_order();
+            if let Some(resource) = &record.resource {
+                if !order.set_resource(resource, &resources) {
+                    report.errors.push(CsvRowError {
+                        line: record.line,
+                        failure: CsvRowFailure::UnknownResource,
+                    });
+                    return;
+                }
+            }
+
+            if self.orders[..known]
+                .iter()
+                .any(|order| record.transaction.matches(order))
+            {
+                report.duplicates += 1;
+            } else {
+                report.created.push(self.push_order(order));
+            }
+        });
+        report.errors.sort_by_key(|error| error.line);
+
+        report
+    }
+
+    /// Creates pending orders on a resource from an OFX/QFX statement and
+    /// returns their ids; transactions already imported are skipped.
+    #[cfg(feature = "import")]
+    pub fn import_ofx(
+        &mut self,
+        content: &str,
+        resource: &str,
+    ) -> Result<Vec<usize>, RequestFailure> {
+        if !self.resources.iter().any(|entry| entry == resource) {
+            return Err(RequestFailure::UnknownItem);
+        }
+        let mut transactions =
+            import::ofx::parse(content).ok_or(RequestFailure::IncorrectArgument)?;
+        // The currency of the statement is only kept if foreign to the resource
+        let currency = self.resource_currency(resource).cloned();
+        transactions
+            .iter_mut()
+            .filter(|transaction| transaction.currency == currency)
+            .for_each(|transaction| transaction.currency = None);
+
+        let preview = self.preview_import(transactions);
+        Ok(self.apply_import(&preview, Some(resource)))
+    }
+
+    /// Adds categories and orders of another account, such as one tracked by
+    /// someone else. Identical orders are skipped; different orders sharing
+    /// a reference on a resource are reported and handled by `strategy`.
+    pub fn merge(&mut self, other: Account, strategy: MergeStrategy) -> MergeReport {
+        let mut report = MergeReport::default();
+        let known = self.orders.len();
+        // Ids of the other account, and their orders here
+        let mut ids = HashMap::new();
+        let mut replaced = Vec::new();
+        self.merge_categories(&other);
+
+        for order in other.orders {
+            let id = order.id;
+            if let Some(ours) = self.orders[..known]
+                .iter()
+                .find(|ours| merge::is_duplicate(ours, &order))
+            {
+                ids.insert(id, ours.id);
+                report.duplicates += 1;
+                continue;
+            }
+
+            if let Some(index) = self.orders[..known]
+                .iter()
+                .position(|ours| merge::is_conflicting(ours, &order))
+            {
+                let ours = &mut self.orders[index];
+                report.conflicts.push(MergeConflict {
+                    id: ours.id,
+                    ours: ours.clone(),
+                    theirs: order.clone(),
+                });
+                match strategy {
+                    MergeStrategy::KeepOurs => {
+                        ids.insert(id, ours.id);
+                        continue;
+                    }
+                    MergeStrategy::KeepTheirs if ours.reconciled => {
+                        ids.insert(id, ours.id);
+                        continue;
+                    }
+                    MergeStrategy::KeepTheirs => {
+                        ids.insert(id, ours.id);
+                        replaced.push(ours.id);
+                        *ours = Order {
+                            id: ours.id,
+                            created_at: ours.created_at,
+                            updated_at: Some(Local::now().naive_local()),
+                            ..order
+                        };
+                        self.order_cache.invalidate();
+                        continue;
+                    }
+                    MergeStrategy::KeepBoth => {}
+                }
+            }
+
+            let order = if self.orders[..known]
+                .iter()
+                .any(|ours| ours.reference.is_some() && ours.reference == order.reference)
+            {
+                Order {
+                    reference: None,
+                    ..order
+                }
+            } else {
+                order
+            };
+            let added = self.push_order(order);
+            ids.insert(id, added);
+            report.added.push(added);
+        }
+
+        // Relations still point to orders of the other account
+        self.orders
+            .iter_mut()
+            .filter(|order| report.added.contains(&order.id) || replaced.contains(&order.id))
+            .for_each(|order| {
+                order.related_order = order.related_order.and_then(|id| ids.get(&id).copied())
+            });
+
+        report
+    }
+
+    /// Returns changes turning this account into `other`, such as a backup
+    /// into the current account.
+    pub fn diff(&self, other: &Account) -> AccountDiff {
+        let mut diff = AccountDiff {
+            tags: CategoryDiff::new(&self.tags, &other.tags, |entry| &entry.name),
+            resources: CategoryDiff::new(&self.resources, &other.resources, |entry| &entry.name),
+            payees: CategoryDiff::new(&self.payees, &other.payees, |payee| payee),
+            projects: CategoryDiff::new(&self.projects, &other.projects, |entry| &entry.name),
+            ..AccountDiff::default()
+        };
+        diff.orders(&self.orders, &other.orders);
+        diff
+    }
+
+    /// Returns sub-ledgers.
+    pub fn sub_ledgers(&self) -> &Vec<SubLedger> {
+        &self.sub_ledgers
+    }
+
+    /// Returns the overview of a sub-ledger within a date range.
+    ///
+    /// Fails with `RequestFailure::UnknownItem` if the sub-ledger doesn't
+    /// exist, or `RequestFailure::Overflow` if a sum overflows.
+    pub fn sub_ledger_report(
+        &self,
+        name: &str,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<SubLedgerReport, RequestFailure> {
+        let ledger = self
+            .sub_ledgers
+            .iter()
+            .find(|item| item.name == name)
+            .ok_or(RequestFailure::UnknownItem)?;
+        let date_filter =
+            filter::NaiveDateFilter::from(OptionNaiveDateRange(date_range.0, date_range.1));
+        let amounts = self
+            .orders
+            .iter()
+            .filter(|order| order.is_visible() && order.has_tag(&ledger.tag))
+            .filter(|order| date_filter.is_date_allowed(order.date))
+            .map(|order| order.amount)
+            .collect::<Vec<Amount>>();
+
+        let sum = |keep: fn(Amount) -> bool| {
+            Amount::checked_sum(amounts.iter().copied().filter(|amount| keep(*amount)))
+                .ok_or(RequestFailure::Overflow)
+        };
+
+        Ok(SubLedgerReport {
+            name: ledger.name.clone(),
+            tag: ledger.tag.clone(),
+            balance: self.category_amount(CategoryType::Tag, &ledger.tag, date_range)?,
+            credits: sum(Amount::is_positive)?,
+            spent: -sum(Amount::is_negative)?,
+        })
+    }
+
+    /// Returns the migrations applied to upgrade the loaded file.
+    pub fn applied_migrations(&self) -> &Vec<AppliedMigration> {
+        &self.migrations
+    }
+
+    /// Returns the issues found in the loaded file.
+    pub fn load_warnings(&self) -> &Vec<LoadWarning> {
+        &self.load_warnings
+    }
+
+    /// Returns tags, resources and payees whose names are identical once
+    /// compared according to the matching mode.
+    pub fn category_collisions(&self) -> Vec<CategoryCollision> {
+        [
+            (CategoryType::Tag, self.tags()),
+            (CategoryType::Resource, self.resources()),
+            (CategoryType::Payee, self.payees.clone()),
+        ]
+        .iter()
+        .flat_map(|(kind, names)| {
+            ext::find_collisions(names, self.category_matching)
+                .into_iter()
+                .map(move |names| CategoryCollision { kind: *kind, names })
+        })
+        .collect()
+    }
+
+    /// Returns names of available tags.
+    pub fn tags(&self) -> Vec<String> {
+        category::names(&self.tags)
+    }
+
+    /// Removes a tag, moving it to another one on orders, envelopes and
+    /// aliases if `new` is given, and returns the number of rewired orders.
+    pub fn remove_tag_with_reassign(
+        &mut self,
+        old: &str,
+        new: Option<&str>,
+    ) -> Result<usize, RequestFailure> {
+        if !self.tags.iter().any(|entry| entry == old) {
+            return Err(RequestFailure::UnknownItem);
+        }
+        let mut rewired = 0;
+
+        if let Some(new) = new {
+            if new == old {
+                return Err(RequestFailure::IncorrectArgument);
+            } else if !self.tags.iter().any(|entry| entry == new) {
+                return Err(RequestFailure::UnknownItem);
+            }
+
+            self.orders
+                .iter_mut()
+                .filter(|order| order.tags.iter().any(|tag| tag == old))
+                .for_each(|order| {
+                    order.remove_tag(old);
+                    order.tags.add_exclusive(new);
+                    order.touch();
+                    rewired += 1;
+                });
+            self.allocations
+                .iter_mut()
+                .flat_map(|item| item.allocations.iter_mut())
+                .filter(|allocation| allocation.envelope == old)
+                .for_each(|allocation| allocation.envelope = new.into());
+            self.tag_aliases
+                .values_mut()
+                .filter(|tag| *tag == old)
+                .for_each(|tag| *tag = new.into());
+        } else {
+            rewired = self
+                .orders
+                .iter()
+                .filter(|order| order.tags.iter().any(|tag| tag == old))
+                .count();
+        }
+
+        self.remove_tag(old);
+        Ok(rewired)
+    }
+
+    /// Removes a resource, moving its orders to another one if `new` is
+    /// given, and returns the number of rewired orders.
+    pub fn remove_resource_with_reassign(
+        &mut self,
+        old: &str,
+        new: Option<&str>,
+    ) -> Result<usize, RequestFailure> {
+        if !self.resources.iter().any(|entry| entry == old) {
+            return Err(RequestFailure::UnknownItem);
+        } else if new == Some(old) {
+            return Err(RequestFailure::IncorrectArgument);
+        } else if new.is_some_and(|new| !self.resources.iter().any(|entry| entry == new)) {
+            return Err(RequestFailure::UnknownItem);
+        }
+
+        let mut rewired = 0;
+        self.orders
+            .iter_mut()
+            .filter(|order| order.resource.as_deref() == Some(old))
+            .for_each(|order| {
+                order.resource = new.map(Interned::from);
+                order.touch();
+                rewired += 1;
+            });
+
+        self.remove_resource(old);
+        Ok(rewired)
+    }
+
+    /// Returns the canonical tag of each alias.
+    pub fn tag_aliases(&self) -> &BTreeMap<String, String> {
+        &self.tag_aliases
+    }
+
+    /// Returns names of available tags along with their aliases, to resolve
+    /// tags given by users.
+    pub fn tag_catalog(&self) -> TagCatalog {
+        TagCatalog {
+            tags: self.tags(),
+            aliases: self.tag_aliases.clone(),
+        }
+    }
+
+    /// Returns names of available resources.
+    pub fn resources(&self) -> Vec<String> {
+        category::names(&self.resources)
+    }
+
+    /// Returns available tags with their details.
+    pub fn tag_entries(&self) -> &Vec<CategoryEntry> {
+        &self.tags
+    }
+
+    /// Returns available resources with their details.
+    pub fn resource_entries(&self) -> &Vec<CategoryEntry> {
+        &self.resources
+    }
+
+    /// Returns available payees.
+    pub fn payees(&self) -> &Vec<String> {
+        &self.payees
+    }
+
+    /// Returns declared custom fields.
+    pub fn custom_fields(&self) -> &Vec<CustomField> {
+        &self.custom_fields
+    }
+
+    /// Returns available projects.
+    pub fn projects(&self) -> &Vec<Project> {
+        &self.projects
+    }
+
+    /// Returns the overview of a project.
+    ///
+    /// Fails with `RequestFailure::UnknownItem` if the project doesn't exist,
+    /// or `RequestFailure::Overflow` if a sum overflows.
+    pub fn project_summary(&self, project: &str) -> Result<ProjectSummary, RequestFailure> {
+        let budget = self
+            .projects
+            .iter()
+            .find(|item| item.name == project)
+            .ok_or(RequestFailure::UnknownItem)?
+            .budget;
+        let amount = self.category_amount(
+            CategoryType::Project,
+            project,
+            OptionNaiveDateRange(None, None),
+        )?;
+        let dates = self
+            .orders
+            .iter()
+            .filter(|order| order.is_visible() && order.project.as_deref() == Some(project))
+            .filter_map(|order| order.date);
+
+        let remaining = match budget {
+            Some(budget) => Some(
+                budget
+                    .checked_add(
+                        amount
+                            .as_ref()
+                            .map(|amount| amount.expected)
+                            .unwrap_or_default(),
+                    )
+                    .ok_or(RequestFailure::Overflow)?,
+            ),
+            None => None,
+        };
+
+        Ok(ProjectSummary {
+            name: project.into(),
+            budget,
+            remaining,
+            amount,
+            start: dates.clone().min(),
+            end: dates.max(),
+        })
+    }
+
+    /// Offers access to a given order
+    pub fn get_order(&self, id: usize) -> Option<&Order> {
+        self.orders.iter().find(|order| order.id == id)
+    }
+
+    /// Applies a change to several orders, returning the outcome of each one:
+    /// `None` on success, `UnknownItem` for unknown orders, tags or resources,
+    /// `IncorrectArgument` for reconciled orders and `ExistingItem` for tags
+    /// already owned.
+    pub fn bulk_update(
+        &mut self,
+        ids: &[usize],
+        patch: &OrderPatch,
+    ) -> Vec<Option<RequestFailure>> {
+        let (tags, resources) = (self.tag_catalog(), self.resources());
+
+        ids.iter()
+            .map(|&id| {
+                if self.get_order(id).is_none() {
+                    return Some(RequestFailure::UnknownItem);
+                }
+                let mut order = match self.get_order_mut(id) {
+                    Some(order) => order,
+                    None => return Some(RequestFailure::IncorrectArgument),
+                };
+
+                match patch {
+                    OrderPatch::SetState(state) => order.set_state(*state),
+                    OrderPatch::SetResource(resource) => {
+                        if !order.set_resource(resource, &resources) {
+                            return Some(RequestFailure::UnknownItem);
+                        }
+                    }
+                    OrderPatch::AddTag(tag) => {
+                        if tags.resolve_tag(tag).is_none() {
+                            return Some(RequestFailure::UnknownItem);
+                        } else if !order.add_tag(tag, &tags) {
+                            return Some(RequestFailure::ExistingItem);
+                        }
+                    }
+                    OrderPatch::RemoveTag(tag) => {
+                        if !order.remove_tag(tag) {
+                            return Some(RequestFailure::UnknownItem);
+                        }
+                    }
+                    OrderPatch::ToggleVisibility => {
+                        if order.is_visible() {
+                            order.hide(HiddenReason::Manual);
+                        } else {
+                            order.show();
+                        }
+                    }
+                }
+                None
+            })
+            .collect()
+    }
+
+    /// Returns visible orders linked to a given one, such as its refunds.
+    pub fn related_orders(&self, id: usize) -> Vec<&Order> {
+        self.orders
+            .iter()
+            .filter(|order| order.related_order == Some(id) && order.is_visible())
+            .collect()
+    }
+
+    /// Computes the amount of an order once its refunds are deducted.
+    /// Returns `None` for unknown orders or if the sum overflows.
+    pub fn net_amount(&self, id: usize) -> Option<Amount> {
+        let order = self.get_order(id)?;
+
+        Amount::checked_sum(
+            std::iter::once(order.amount).chain(
+                self.related_orders(id)
+                    .into_iter()
+                    .map(|related| related.amount),
+            ),
+        )
+    }
+
+    /// Offers mutable access to a given order, unless reconciled.
+    /// The order is considered as modified if its data changed.
+    pub fn get_order_mut(&mut self, id: usize) -> Option<OrderMut<'_>> {
+        let order = self
+            .orders
+            .iter_mut()
+            .find(|order| order.id == id && !order.reconciled)?;
+
+        Some(OrderMut::new(order, &mut self.order_cache))
+    }
+
+    /// Returns the balance of done orders of a resource until a date.
+    ///
+    /// Fails with `RequestFailure::IncorrectArgument` if they hold different
+    /// currencies, or `RequestFailure::Overflow` if a sum overflows.
+    fn resource_balance(&self, resource: &str, date: NaiveDate) -> Result<Amount, RequestFailure> {
+        let mut groups = self.category_amount_by_currency(
+            CategoryType::Resource,
+            resource,
+            OptionNaiveDateRange(None, Some(date)),
+        )?;
+
+        match groups.len() {
+            0 => Ok(Amount::ZERO),
+            1 => Ok(groups.remove(0).amount.current),
+            _ => Err(RequestFailure::IncorrectArgument),
+        }
+    }
+
+    /// Assigns an income order to budget envelopes, replacing previous
+    /// allocations; an empty list clears them.
+    ///
+    /// Envelopes must be tags and amounts positive; fails with
+    /// `InsufficientFunds` if they exceed the income.
+    pub fn allocate(&mut self, id: usize, allocations: Vec<Allocation>) -> Option<RequestFailure> {
+        let income = match self.get_order(id) {
+            // Transfers are not income
+            Some(order) if order.effective_kind() != OrderKind::Income => Amount::ZERO,
+            Some(order) => order.amount,
+            None => return Some(RequestFailure::UnknownItem),
+        };
+
+        if let Some(failure) = allocation::validate(income, &allocations, &self.tags()) {
+            return Some(failure);
+        }
+        self.allocations.retain(|item| item.order != id);
+        if !allocations.is_empty() {
+            self.allocations.push(IncomeAllocation {
+                order: id,
+                allocations,
+            });
+        }
+        None
+    }
+
+    /// Returns allocations of an income order.
+    pub fn allocations(&self, id: usize) -> &[Allocation] {
+        self.allocations
+            .iter()
+            .find(|item| item.order == id)
+            .map_or(&[], |item| item.allocations.as_slice())
+    }
+
+    /// Computes funds of budget envelopes and income left to allocate.
+    pub fn allocation_summary(&self) -> Result<AllocationSummary, RequestFailure> {
+        allocation::summarize(&self.orders, &self.allocations)
+    }
+
+    /// Returns spending limits of tags.
+    pub fn tag_budgets(&self) -> &Vec<TagBudget> {
+        &self.budgets
+    }
+
+    /// Compares spending limits of tags with their expenses within the
+    /// periods holding a date.
+    pub fn budget_report(&self, date: NaiveDate) -> Result<BudgetReport, RequestFailure> {
+        budget::report(&self.orders, &self.budgets, date)
+    }
+
+    /// Returns balance thresholds of resources.
+    pub fn milestones(&self) -> &Vec<Milestone> {
+        &self.milestones
+    }
+
+    /// Returns thresholds crossed by balances until today since the last check.
+    pub fn check_milestones(&mut self) -> Vec<CrossedMilestone> {
+        self.check_milestones_at(Local::now().date_naive())
+    }
+
+    /// Returns thresholds crossed by balances until a date since the last
+    /// check. Resources mixing currencies are skipped.
+    pub fn check_milestones_at(&mut self, date: NaiveDate) -> Vec<CrossedMilestone> {
+        let balances = self
+            .milestones
+            .iter()
+            .map(|milestone| self.resource_balance(&milestone.resource, date).ok())
+            .collect::<Vec<Option<Amount>>>();
+
+        self.milestones
+            .iter_mut()
+            .zip(balances)
+            .filter_map(|(milestone, balance)| milestone.check(balance?))
+            .collect()
+    }
+
+    /// Compares the balance of a resource with a bank statement, then
+    /// reconciles its done orders until that date once they match.
+    ///
+    /// Fails with `RequestFailure::UnknownItem` for unknown resources,
+    /// `RequestFailure::IncorrectArgument` for mixed currencies, or
+    /// `RequestFailure::Overflow` if a sum overflows.
+    pub fn reconcile(
+        &mut self,
+        resource: &str,
+        statement_balance: Amount,
+        date: NaiveDate,
+    ) -> Result<Reconciliation, RequestFailure> {
+        if !self.resources.iter().any(|item| item == resource) {
+            return Err(RequestFailure::UnknownItem);
+        }
+        let balance = self.resource_balance(resource, date)?;
+        let discrepancy = statement_balance
+            .checked_sub(balance)
+            .ok_or(RequestFailure::Overflow)?;
+        let reconciled = if discrepancy == Amount::ZERO {
+            self.orders
+                .iter_mut()
+                .filter(|order| {
+                    order.is_visible()
+                        && !order.reconciled
+                        && order.state == TransactionState::Done
+                        && order.resource.as_deref() == Some(resource)
+                        && order.date.is_some_and(|order_date| order_date <= date)
+                })
+                .map(|order| {
+                    order.reconciled = true;
+                    order.touch();
+                })
+                .count()
+        } else {
+            0
+        };
+
+        Ok(Reconciliation {
+            balance,
+            statement_balance,
+            discrepancy,
+            reconciled,
+        })
+    }
+
+    /// Returns all orders
+    pub fn orders(&self) -> &Vec<Order> {
+        &self.orders
+    }
+
+    /// Calls a function with each order allowed by the filter, in list order.
+    /// Tags are matched against cached bitsets.
+    fn visit_filtered_orders<'a>(&'a self, filter: &Filter, mut visit: impl FnMut(&'a Order)) {
+        let dates = filter.dates();
+        self.order_cache
+            .tag_bitsets
+            .with(&self.tags(), &self.orders, |bitsets| {
+                match bitsets.selected_mask(filter.tag_option()) {
+                    Some(mask) => self
+                        .orders
+                        .iter()
+                        .enumerate()
+                        .filter(|(row, order)| {
+                            filter.is_order_allowed_with_tags(order, &dates, || {
+                                bitsets.with_each(*row, &mask)
+                            })
+                        })
+                        .for_each(|(_, order)| visit(order)),
+                    // Orders may own tags removed from the account
+                    None => self
+                        .orders
+                        .iter()
+                        .filter(|order| filter.is_order_allowed_on(order, &dates))
+                        .for_each(visit),
+                }
+            });
+    }
+
+    /// Returns orders allowed by the filter, sorted by its preference.
+    ///
+    /// Same as `OrderListExt::apply_filter`, but tags are matched against
+    /// cached bitsets.
+    pub fn filtered_orders(&self, filter: &Filter) -> Vec<(usize, &Order)> {
+        let mut filtered_vector = Vec::new();
+        self.visit_filtered_orders(filter, |order| filtered_vector.push((order.id, order)));
+
+        ext::sort_filtered_orders(filter, &mut filtered_vector);
+        filtered_vector
+    }
+
+    /// Counts orders allowed by the filter for each state.
+    pub fn count_filtered_orders_by_state(&self, filter: &Filter) -> StateCount {
+        let mut count = StateCount::default();
+        self.visit_filtered_orders(filter, |order| match order.state() {
+            TransactionState::Pending => count.pending += 1,
+            TransactionState::InProgress => count.in_progress += 1,
+            TransactionState::Done => count.done += 1,
+        });
+        count
+    }
+
+    /// Same as `filtered_orders`, keeping up to `limit` orders from `offset`.
+    pub fn filtered_orders_paged(
+        &self,
+        filter: &Filter,
+        offset: usize,
+        limit: usize,
+    ) -> FilteredPage<'_> {
+        FilteredPage::new(self.filtered_orders(filter), offset, limit)
+    }
+
+    /// Same as `filtered_orders`, along with the running balance after each
+    /// order, starting from opening balances of allowed resources.
+    ///
+    /// Fails with `RequestFailure::Overflow` if a sum overflows.
+    pub fn filtered_orders_with_running_balance(
+        &self,
+        filter: &Filter,
+    ) -> Result<Vec<(usize, &Order, Amount)>, RequestFailure> {
+        let opening = if filter.unassigned_only {
+            Amount::ZERO
+        } else {
+            Amount::checked_sum(
+                self.resources
+                    .iter()
+                    .filter(|entry| {
+                        filter
+                            .resource_option()
+                            .among_any_selected(&Some(entry.name.clone()))
+                    })
+                    .filter_map(|entry| {
+                        // Opening balances of liabilities are amounts owed
+                        entry.opening.map(|opening| {
+                            if entry.kind.is_some_and(ResourceKind::is_liability) {
+                                -opening.amount
+                            } else {
+                                opening.amount
+                            }
+                        })
+                    }),
+            )
+            .ok_or(RequestFailure::Overflow)?
+        };
+
+        ext::with_running_balance(self.filtered_orders(filter), opening)
+    }
+
+    /// Returns order templates.
+    pub fn templates(&self) -> &Vec<OrderTemplate> {
+        &self.templates
+    }
+
+    /// Returns deleted orders, oldest deletion first.
+    pub fn trash(&self) -> &Vec<TrashedOrder> {
+        &self.trash
+    }
+
+    /// Exports visible orders as CSV.
+    #[cfg(feature = "reporting")]
+    pub fn export_csv(&self, format: AmountFormat) -> String {
+        export::orders_to_csv(
+            self.orders.iter().filter(|order| order.is_visible()),
+            format,
+        )
+    }
+
+    /// Returns orders added or changed since a marker, along with the
+    /// marker of this export.
+    #[cfg(feature = "reporting")]
+    pub fn export_since(&self, marker: Option<NaiveDateTime>) -> DeltaExport {
+        export::export_since(
+            &self.orders,
+            &self.trash,
+            marker,
+            Local::now().naive_local(),
+        )
+    }
+
+    /// Same as `export_since` from the stored marker, which is then updated.
+    #[cfg(feature = "reporting")]
+    pub fn export_changes(&mut self) -> DeltaExport {
+        let export = self.export_since(self.last_export);
+        self.last_export = Some(export.marker);
+        export
+    }
+
+    /// Returns the marker of the last delta export, if any.
+    pub fn last_export(&self) -> Option<NaiveDateTime> {
+        self.last_export
+    }
+
+    /// Returns available places.
+    pub fn places(&self) -> &Vec<Place> {
+        &self.places
+    }
+
+    /// Sums visible located orders per place within a date range.
+    pub fn spend_by_place(
+        &self,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<Vec<PlaceSpend>, RequestFailure> {
+        geo::spend_by_place(&self.places, &self.orders, date_range)
+    }
+
+    /// Sums a component of visible orders within a date range.
+    ///
+    /// Fails with `RequestFailure::Overflow` if the sum overflows.
+    pub fn component_total(
+        &self,
+        kind: ComponentKind,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<Amount, RequestFailure> {
+        let date_filter = filter::NaiveDateFilter::from(date_range);
+
+        Amount::checked_sum(
+            self.orders
+                .iter()
+                .filter(|order| order.is_visible() && date_filter.is_date_allowed(order.date))
+                .filter_map(|order| order.component(kind)),
+        )
+        .ok_or(RequestFailure::Overflow)
+    }
+
+    /// Returns a column-oriented copy of visible orders, for heavy
+    /// analytics. It is built once, until orders change.
+    pub fn columns(&self) -> Arc<OrderColumns> {
+        self.order_cache.columns.get(&self.orders)
+    }
+
+    /// Returns suggestions to tidy the account up.
+    #[cfg(feature = "reporting")]
+    pub fn lint(&self) -> Vec<LintIssue> {
+        lint::lint(self, Local::now().date_naive())
+    }
+
+    /// Picks up to `count` filtered orders reproducibly from a seed,
+    /// optionally weighted by amount; see `audit::sample`.
+    #[cfg(feature = "reporting")]
+    pub fn audit_sample(
+        &self,
+        filter: &Filter,
+        count: usize,
+        weighted: bool,
+        seed: u64,
+    ) -> Vec<usize> {
+        let orders = self
+            .filtered_orders(filter)
+            .into_iter()
+            .map(|(_, order)| order)
+            .collect::<Vec<&Order>>();
+
+        audit::sample(&orders, count, weighted, seed)
+    }
+
+    /// Counts visible orders missing tags or resource within a date range.
+    pub fn categorization_progress(
+        &self,
+        date_range: OptionNaiveDateRange,
+    ) -> Result<CategorizationProgress, RequestFailure> {
+        self.orders.calculate_categorization_progress(date_range)
+    }
+
+    /// Computes, for each tag, resource, project or payee, how many orders
+    /// use it, when and for which total, to spot stale ones.
+    pub fn category_usage(&self, kind: CategoryType) -> Result<Vec<CategoryUsage>, RequestFailure> {
+        let names = match kind {
+            CategoryType::Tag => self.tags(),
+            CategoryType::Resource => self.resources(),
+            CategoryType::Project => self
+                .projects
+                .iter()
+                .map(|project| project.name.clone())
+                .collect(),
+            CategoryType::Payee => self.payees.clone(),
+        };
+
+        self.orders.calculate_category_usage(kind, &names)
+    }
+
+    /// Computes income and expenses of each period overlapping a date range.
+    pub fn income_expense(
+        &self,
+        period: Period,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<IncomeExpense>, RequestFailure> {
+        self.orders.calculate_income_expense(period, start, end)
+    }
+
+    /// Computes the cash flow of each period overlapping a date range, for a
+    /// resource or, if `None`, for the whole account.
+    ///
+    /// Fails with `RequestFailure::UnknownItem` if the resource does not
+    /// exist, or with `Overflow` if a sum overflows.
+    pub fn cash_flow(
+        &self,
+        period: Period,
+        start: NaiveDate,
+        end: NaiveDate,
+        resource: Option<&str>,
+    ) -> Result<Vec<CashFlow>, RequestFailure> {
+        if resource.is_some_and(|name| !self.resources.iter().any(|entry| entry == name)) {
+            return Err(RequestFailure::UnknownItem);
+        }
+        stats::cash_flow(&self.orders, period, (start, end), resource)
+    }
+
+    /// Returns up to `count` tags with the largest spending within a date
+    /// range, largest first.
+    pub fn top_spending_tags(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        count: usize,
+    ) -> Result<Vec<TagSpending>, RequestFailure> {
+        stats::top_spending_tags(&self.columns(), (start, end), count)
+    }
+
+    /// Computes the monthly spending of a *category* within a date range,
+    /// averaged over the last `window` months, and its trend.
+    pub fn spending_trend(
+        &self,
+        kind: CategoryType,
+        category: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+        window: usize,
+    ) -> Result<SpendingTrend, RequestFailure> {
+        stats::spending_trend(&self.columns(), (kind, category), (start, end), window)
+    }
+
+    /// Computes totals, counts and average amounts of orders of each payee
+    /// within a date range, the largest spending first.
+    pub fn payee_stats(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<PayeeStats>, RequestFailure> {
+        stats::payee_stats(&self.orders, (start, end))
+    }
+
+    /// Returns orders whose amount is far from usual amounts of one of their
+    /// tags, to be reviewed.
+    pub fn amount_outliers(&self) -> Vec<AmountOutlier> {
+        stats::amount_outliers(&self.orders)
+    }
+
+    /// Compares spending of the current period with the previous one.
+    pub fn period_vs_previous(&self, period: Period) -> Result<PeriodComparison, RequestFailure> {
+        stats::period_vs_previous(&self.columns(), period, Local::now().date_naive())
+    }
+
+    /// Returns each month holding at least one dated order, sorted chronologically.
+    pub fn periods_with_data(&self) -> Vec<PeriodCount> {
+        let mut periods = BTreeMap::<(i32, u32), usize>::new();
+
+        self.orders
+            .iter()
+            .filter_map(|order| order.date)
+            .for_each(|date| *periods.entry((date.year(), date.month())).or_insert(0) += 1);
+
+        periods
+            .into_iter()
+            .map(|((year, month), count)| PeriodCount { year, month, count })
+            .collect()
+    }
+
+    /// Stores data as YAML file, or TOML file if its extension is `.toml`.
+    /// Data is compressed with gzip if the extension is `.gz`, like
+    /// `money.yml.gz`, with the `compression` feature.
+    ///
+    /// The file is replaced atomically: an interrupted save keeps its previous
+    /// content. Fails with a `Conflict` error if the file changed since loaded
+    /// or last saved; see `save_file_resolving`.
+    pub fn save_file(&self, path: &Path) -> Result<(), SaveError> {
+        self.source.check(path)?;
+        self.write_file(path)
+    }
+
+    /// Stores data as YAML file, resolving a conflict with changes made by
+    /// another process; see `save_file_encrypted_resolving` for encrypted
+    /// files.
+    ///
+    /// Returns orders changed both here and in the file since it was loaded
+    /// or last saved, kept as they are here.
+    pub fn save_file_resolving(
+        &mut self,
+        path: &Path,
+        resolution: ConflictResolution,
+    ) -> Result<Vec<MergeConflict>, SaveError> {
+        let conflicts = if resolution == ConflictResolution::ReloadAndMerge && path.exists() {
+            let stored = Account::load_file(path)?;
+            self.merge_stored(path, stored)
+        } else {
+            Vec::new()
+        };
+
+        self.write_file(path)?;
+        Ok(conflicts)
+    }
+
+    /// Saves the account into a storage backend.
+    pub fn save_to(&self, storage: &mut dyn Storage) -> Result<(), StorageError> {
+        storage.save(self)
+    }
+
+    /// Returns the account saved in a storage backend.
+    pub fn load_from(storage: &dyn Storage) -> Result<Account, StorageError> {
+        storage.load()
+    }
+
+    /// Returns the copies kept when saving over a file, if any.
+    pub fn backup_policy(&self) -> Option<&BackupPolicy> {
+        self.backup.as_ref()
+    }
+
+    /// Keeps copies of files before saving over them, or stops if `None`.
+    /// Fails with `IncorrectArgument` if no copy would be kept.
+    pub fn set_backup_policy(&mut self, policy: Option<BackupPolicy>) -> Option<RequestFailure> {
+        if policy.as_ref().is_some_and(|policy| policy.keep == 0) {
+            Some(RequestFailure::IncorrectArgument)
+        } else {
+            self.backup = policy;
+            None
+        }
+    }
+
+    /// Returns the copies kept of an account file, from the oldest.
+    pub fn backups(&self, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+        match &self.backup {
+            Some(policy) => policy.list(path),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Replaces a file with saved content, after copying it if required.
+    fn replace_file(&self, path: &Path, content: &[u8]) -> Result<(), SaveError> {
+        if let Some(policy) = &self.backup {
+            policy.rotate(path, Local::now().naive_local())?;
+        }
+        source::write_atomic(path, content)?;
+        self.source.set(FileStamp::new(path, content));
+        Ok(())
+    }
+
+    /// Writes data as YAML or TOML file, whatever its current content.
+    fn write_file(&self, path: &Path) -> Result<(), SaveError> {
+        #[cfg(feature = "toml")]
+        let content = if is_toml_file(path) {
+            self.to_toml()
+                .map_err(|error| SaveError::Encoding(format!("{}", error)))?
+        } else {
+            serde_yaml::to_string(self).unwrap()
+        };
+        #[cfg(not(feature = "toml"))]
+        let content = serde_yaml::to_string(self).unwrap();
+        #[cfg(feature = "compression")]
+        let content = if compression::is_compressed_file(path) {
+            compression::compress(content.as_bytes())
+        } else {
+            content.into_bytes()
+        };
+        #[cfg(not(feature = "compression"))]
+        let content = content.into_bytes();
+        self.replace_file(path, &content)
+    }
+
+    /// Returns an initialized account from YAML file, or TOML file if its
+    /// extension is `.toml`. Gzip-compressed files are detected with the
+    /// `compression` feature.
+    pub fn load_file(path: &Path) -> std::io::Result<Account> {
+        let content = std::fs::read(path)?;
+        #[cfg(feature = "compression")]
+        let data: &[u8] = &compression::decompress(&content)?;
+        #[cfg(not(feature = "compression"))]
+        let data: &[u8] = &content;
+        #[cfg(feature = "encryption")]
+        if encryption::is_encrypted(data) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "encrypted file: a passphrase is required",
+            ));
+        }
+        let text = std::str::from_utf8(data)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+        #[cfg(feature = "toml")]
+        let account = if is_toml_file(path) {
+            Account::from_toml(text).map_err(|error| format!("{}", error))
+        } else {
+            Account::try_from(text).map_err(|error| format!("{}", error))
+        };
+        #[cfg(not(feature = "toml"))]
+        let account = Account::try_from(text).map_err(|error| format!("{}", error));
+
+        match account {
+            Ok(data) => {
+                data.source.set(FileStamp::new(path, &content));
+                Ok(data)
+            }
+            Err(error) => Err(std::io::Error::new(std::io::ErrorKind::InvalidData, error)),
+        }
+    }
+
+    /// Returns an initialized account from YAML data, gzip-compressed or not.
+    pub fn from_bytes(data: &[u8]) -> std::io::Result<Account> {
+        #[cfg(feature = "compression")]
+        let data: &[u8] = &compression::decompress(data)?;
+        std::str::from_utf8(data)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+            .and_then(|text| {
+                Account::try_from(text).map_err(|error| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{}", error))
+                })
+            })
+    }
+
+    /// Converts account data into gzip-compressed YAML.
+    #[cfg(feature = "compression")]
+    pub fn to_compressed(&self) -> Vec<u8> {
+        compression::compress(serde_yaml::to_string(self).unwrap().as_bytes())
+    }
+
+    /// Stores data as YAML file encrypted under a passphrase.
+    ///
+    /// Replaced atomically and fails with a `Conflict` error like `save_file`.
+    #[cfg(feature = "encryption")]
+    pub fn save_file_encrypted(&self, path: &Path, passphrase: &str) -> Result<(), SaveError> {
+        self.source.check(path)?;
+        self.write_file_encrypted(path, passphrase)
+    }
+
+    /// Same as `save_file_resolving` for a file encrypted under a
+    /// passphrase, reloaded with the same passphrase.
+    #[cfg(feature = "encryption")]
+    pub fn save_file_encrypted_resolving(
+        &mut self,
+        path: &Path,
+        passphrase: &str,
+        resolution: ConflictResolution,
+    ) -> Result<Vec<MergeConflict>, SaveError> {
+        let conflicts = if resolution == ConflictResolution::ReloadAndMerge && path.exists() {
+            let stored = Account::load_file_encrypted(path, passphrase)?;
+            self.merge_stored(path, stored)
+        } else {
+            Vec::new()
+        };
+
+        self.write_file_encrypted(path, passphrase)?;
+        Ok(conflicts)
+    }
+
+    /// Writes data as encrypted YAML file, whatever its current content.
+    #[cfg(feature = "encryption")]
+    fn write_file_encrypted(&self, path: &Path, passphrase: &str) -> Result<(), SaveError> {
+        let content = self
+            .to_encrypted(passphrase)
+            .map_err(|failure| SaveError::Encoding(format!("{:?}", failure)))?;
+        self.replace_file(path, &content)
+    }
+
+    /// Returns an initialized account from a YAML file encrypted under a
+    /// passphrase.
+    #[cfg(feature = "encryption")]
+    pub fn load_file_encrypted(path: &Path, passphrase: &str) -> std::io::Result<Account> {
+        let content = std::fs::read(path)?;
+        let data = Account::from_encrypted(&content, passphrase).map_err(|failure| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{:?}", failure))
+        })?;
+        data.source.set(FileStamp::new(path, &content));
+        Ok(data)
+    }
+
+    /// Converts account data into YAML encrypted under a passphrase.
+    #[cfg(feature = "encryption")]
+    pub fn to_encrypted(&self, passphrase: &str) -> Result<Vec<u8>, RequestFailure> {
+        encryption::encrypt(serde_yaml::to_string(self).unwrap().as_bytes(), passphrase)
+    }
+
+    /// Returns an initialized account from YAML encrypted under a passphrase.
+    /// Fails with `IncorrectArgument` if the passphrase or data is wrong.
+    #[cfg(feature = "encryption")]
+    pub fn from_encrypted(data: &[u8], passphrase: &str) -> Result<Account, RequestFailure> {
+        let content = encryption::decrypt(data, passphrase)?;
+        std::str::from_utf8(&content)
+            .ok()
+            .and_then(|content| Account::try_from(content).ok())
+            .ok_or(RequestFailure::IncorrectArgument)
+    }
+
+    /// Converts account data into compact JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// Returns an initialized account from JSON data, upgraded like YAML files.
+    pub fn from_json(content: &str) -> Result<Account, serde_json::Error> {
+        Account::from_data(serde_json::from_str(content), || {
+            serde_json::from_str(content)
+        })
+    }
+
+    /// Converts account data into TOML.
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    /// Returns an initialized account from TOML data, upgraded like YAML files.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(content: &str) -> Result<Account, toml::de::Error> {
+        Account::from_data(toml::from_str(content), || toml::from_str(content))
+    }
+
+    /// Saves the account into a single zip bundle, along with its manifest.
+    #[cfg(feature = "import")]
+    pub fn export_bundle(&self, path: &Path) -> std::io::Result<()> {
+        bundle::write_bundle(self, File::create(path)?).map(|_| ())
+    }
+
+    /// Returns an initialized account from a zip bundle.
+    #[cfg(feature = "import")]
+    pub fn import_bundle(path: &Path) -> std::io::Result<Account> {
+        bundle::read_bundle(File::open(path)?).map(|(account, _)| account)
+    }
+}
+
+impl Account {
+    /// Stamps a new id and the creation time on the order before storing it.
+    /// Copies of reconciled orders are unlocked.
+    fn push_order(&mut self, mut order: Order) -> usize {
+        let id = self.next_id;
+
+        order.id = id;
+        order.created_at = Some(Local::now().naive_local());
+        order.updated_at = None;
+        order.reconciled = false;
+        self.order_cache.invalidate();
+        self.next_id += 1;
+        self.orders.push(order);
+        id
+    }
+
+    /// Merges categories and orders of the version of the account stored in
+    /// a file, comparing orders by id. Changes made on one side since the
+    /// file was loaded or last saved win; orders deleted on one side stay
+    /// deleted unless changed on the other one. Without such a point, all
+    /// orders are considered as changed.
+    ///
+    /// Returns orders changed on both sides, kept as they are here.
+    fn merge_stored(&mut self, path: &Path, mut stored: Account) -> Vec<MergeConflict> {
+        let synced_at = self
+            .source
+            .get()
+            .filter(|stamp| stamp.path == path)
+            .map(|stamp| stamp.synced_at);
+        let changed = |order: &Order| {
+            synced_at.is_none_or(|synced_at| {
+                order
+                    .updated_at()
+                    .is_some_and(|updated_at| updated_at > synced_at)
+            })
+        };
+        // Orders created on both sides may share an id
+        let (created, orders) = std::mem::take(&mut stored.orders)
+            .into_iter()
+            .partition::<Vec<Order>, _>(|order| {
+                self.orders
+                    .iter()
+                    .any(|ours| ours.id == order.id && ours.created_at != order.created_at)
+            });
+        stored.orders = orders;
+        let diff = self.diff(&stored);
+        let mut conflicts = Vec::new();
+        self.merge_categories(&stored);
+        self.order_cache.invalidate();
+
+        // Orders only known here
+        for order in diff.removed.iter().filter(|order| !changed(order)) {
+            if let Some(index) = self.order_index(order.id) {
+                self.trash.push(TrashedOrder {
+                    order: self.orders.remove(index),
+                    deleted_at: Local::now().naive_local(),
+                });
+            }
+        }
+
+        for ModifiedOrder {
+            before: ours,
+            after: theirs,
+            ..
+        } in diff.modified
+        {
+            if changed(&theirs) && !changed(&ours) {
+                if let Some(index) = self.order_index(ours.id) {
+                    self.orders[index] = theirs;
+                }
+            } else if changed(&theirs) {
+                conflicts.push(MergeConflict {
+                    id: ours.id,
+                    ours,
+                    theirs,
+                });
+            }
+        }
+
+        // Orders only known by the file
+        for order in diff.added.into_iter().filter(|order| changed(order)) {
+            self.trash.retain(|item| {
+                item.order.id != order.id || item.order.created_at != order.created_at
+            });
+            self.insert_stored(order);
+        }
+        created
+            .into_iter()
+            .for_each(|order| self.insert_stored(order));
+
+        conflicts
+    }
+
+    /// Adds an order of another version of the account, at the position of
+    /// its id unless another order here already uses it.
+    fn insert_stored(&mut self, order: Order) {
+        if self
+            .orders
+            .iter()
+            .chain(self.trash.iter().map(|item| &item.order))
+            .any(|item| item.id == order.id)
+        {
+            let (created_at, updated_at) = (order.created_at, order.updated_at);
+            self.push_order(order);
+            let merged = self.orders.last_mut().unwrap();
+            merged.created_at = created_at;
+            merged.updated_at = updated_at;
+        } else {
+            let position = self
+                .orders
+                .iter()
+                .position(|item| item.id > order.id)
+                .unwrap_or(self.orders.len());
+            self.next_id = self.next_id.max(order.id + 1);
+            self.order_cache.invalidate();
+            self.orders.insert(position, order);
+        }
+    }
+
+    /// Adds categories of another account which are missing here.
+    ///
+    /// Custom fields, places and budgets missing here are added too; recurring
+    /// rules, sub-ledgers, templates, allocations and milestones are settings
+    /// of each account, left as is.
+    fn merge_categories(&mut self, other: &Account) {
+        for (entries, others) in [
+            (&mut self.tags, &other.tags),
+            (&mut self.resources, &other.resources),
+        ] {
+            others.iter().for_each(|entry| {
+                if !entries.iter().any(|item| item.name == entry.name) {
+                    entries.push(entry.clone());
+                }
+            });
+        }
+        other.payees.iter().for_each(|payee| {
+            self.payees.add_exclusive(payee);
+        });
+        other.tag_aliases.iter().for_each(|(alias, tag)| {
+            self.tag_aliases
+                .entry(alias.clone())
+                .or_insert_with(|| tag.clone());
+        });
+        other.tag_groups.iter().for_each(|group| {
+            if !self.tag_groups.iter().any(|item| item.name == group.name) {
+                self.tag_groups.push(group.clone());
+            }
+        });
+        other.projects.iter().for_each(|project| {
+            if !self.projects.iter().any(|item| item.name == project.name) {
+                self.projects.push(project.clone());
+            }
+        });
+        other.custom_fields.iter().for_each(|field| {
+            if !self
+                .custom_fields
+                .iter()
+                .any(|item| item.name == field.name)
+            {
+                self.custom_fields.push(field.clone());
+            }
+        });
+        other.places.iter().for_each(|place| {
+            if !self.places.iter().any(|item| item.name == place.name) {
+                self.places.push(place.clone());
+            }
+        });
+        other.budgets.iter().for_each(|budget| {
+            if !self
+                .budgets
+                .iter()
+                .any(|item| item.tag == budget.tag && item.period == budget.period)
+            {
+                self.budgets.push(budget.clone());
+            }
+        });
+    }
+
+    /// Returns the position of an order in the list.
+    fn order_index(&self, id: usize) -> Option<usize> {
+        self.orders.iter().position(|order| order.id == id)
+    }
+
+    /// Builds the account straight from data in the current schema; older
+    /// data is loaded again as raw data to be upgraded first.
+    fn from_data<E: serde::de::Error>(
+        account: Result<Account, E>,
+        raw_data: impl FnOnce() -> Result<serde_yaml::Value, E>,
+    ) -> Result<Account, E> {
+        let mut account = match account {
+            Ok(account) if account.version == SCHEMA_VERSION => account,
+            account => {
+                let data = raw_data()?;
+                if migration::version(&data) == u64::from(SCHEMA_VERSION) {
+                    account?
+                } else {
+                    Account::from_value(data).map_err(E::custom)?
+                }
+            }
+        };
+        // Duplicates from sloppy input are reported, not merged
+        let collisions = account.category_collisions();
+        if !collisions.is_empty() {
+            account
+                .load_warnings
+                .push(LoadWarning::CategoryCollisions(collisions));
+        }
+        account.assign_order_ids();
+        Ok(account)
+    }
+
+    /// Upgrades raw data before building the account.
+    fn from_value(mut data: serde_yaml::Value) -> Result<Account, serde_yaml::Error> {
+        let version = migration::version(&data);
+        if version > u64::from(SCHEMA_VERSION) {
+            return Err(serde::de::Error::custom(format!(
+                "unsupported schema version {}",
+                version
+            )));
+        }
+        let migrations = migration::migrate(&mut data);
+        let mut account: Account = serde_yaml::from_value(data)?;
+        account.migrations = migrations;
+        Ok(account)
+    }
+
+    /// Makes sure each order owns a unique id.
+    ///
+    /// Files written before ids existed hold `0` everywhere: the first
+    /// occurrence keeps its id and duplicates get new ones, in order.
+    fn assign_order_ids(&mut self) {
+        let mut known_ids = HashSet::new();
+        let mut next_id = self
+            .orders
+            .iter()
+            .chain(self.trash.iter().map(|item| &item.order))
+            .map(|order| order.id + 1)
+            .max()
+            .unwrap_or(0)
+            .max(self.next_id);
+
+        self.orders
+            .iter_mut()
+            .chain(self.trash.iter_mut().map(|item| &mut item.order))
+            .for_each(|order| {
+                if !known_ids.insert(order.id) {
+                    order.id = next_id;
+                    known_ids.insert(next_id);
+                    next_id += 1;
+                }
+            });
+        self.next_id = next_id;
+    }
+}
+
+/// Returns `true` if a path has a `.toml` extension, possibly followed by `.gz`.
+#[cfg(feature = "toml")]
+fn is_toml_file(path: &Path) -> bool {
+    #[cfg(feature = "compression")]
+    let path = if compression::is_compressed_file(path) {
+        Path::new(path.file_stem().unwrap_or_default())
+    } else {
+        path
+    };
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"))
+}
+
+impl TryFrom<File> for Account {
+    type Error = serde_yaml::Error;
+
+    fn try_from(file: File) -> Result<Self, Self::Error> {
+        let content = std::io::read_to_string(file).map_err(serde::de::Error::custom)?;
+        Account::try_from(content.as_str())
+    }
+}
+
+impl TryFrom<&str> for Account {
+    type Error = serde_yaml::Error;
+
+    fn try_from(content: &str) -> Result<Self, Self::Error> {
+        Account::from_data(serde_yaml::from_str(content), || {
+            serde_yaml::from_str(content)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// Returns an empty folder for a test, unique to the process; removed by
+    /// the test once done.
+    pub(crate) fn test_directory(test: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("money-{}-{}", std::process::id(), test));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    mod account {
+        use super::*;
+        use ext::OrderListExt;
+        use filter::category::{Category, CategoryFilter};
+        use filter::date::NaiveDateFilter;
+        use filter::state::StateFilter;
+        use filter::{Filter, ItemSelector, VisibilityFilter};
+        use order::{HiddenReason, TransactionState};
+
+        #[test]
+        fn duplicate_existing_order() {
+            let mut account = Account {
+                orders: vec![
+                    Order::default(),
+                    Order {
+                        id: 1,
+                        description: "Test".into(),
+                        amount: Amount::from_cents(-299),
+                        ..Order::default()
+                    },
+                ],
+                next_id: 2,
+                ..Account::create()
+            };
+
+            assert_eq!(account.duplicate_order(2), None);
+            assert_eq!(account.duplicate_order(1), Some(2));
+            assert_eq!(account.duplicate_order(0), Some(3));
+            assert_eq!(
+                account.orders[1],
+                Order {
+                    id: 1,
+                    ..account.orders[2].clone()
+                }
+            );
+            assert_eq!(
+                account.orders[0],
+                Order {
+                    id: 0,
+                    ..account.orders[3].clone()
+                }
+            );
+        }
+
+        #[test]
+        fn hide_matching_orders() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            account.add_resource("Savings");
+            let resources = account.resources().clone();
+
+            ["Bank", "Savings", "Bank", "Savings"]
+                .iter()
+                .for_each(|resource| {
+                    let id = account.add_order();
+                    account
+                        .get_order_mut(id)
+                        .unwrap()
+                        .set_resource(resource, &resources);
+                });
+            assert_eq!(account.set_order_reconciled(3, true), true);
+
+            let mut filter = Filter::default();
+            filter.resource_option.set(
+                vec![
+                    Category("Bank".into(), ItemSelector::Discarded),
+                    Category("Savings".into(), ItemSelector::Selected),
+                ]
+                .into_iter(),
+            );
+
+            assert_eq!(
+                account.hide_matching(&filter, HiddenReason::InternalTransfer),
+                1
+            );
+            assert_eq!(
+                account
+                    .orders()
+                    .iter()
+                    .map(|order| order.hidden_reason())
+                    .collect::<Vec<Option<HiddenReason>>>(),
+                [None, Some(HiddenReason::InternalTransfer), None, None]
+            );
+            // Already hidden orders are not visible anymore
+            assert_eq!(account.hide_matching(&filter, HiddenReason::Manual), 0);
+
+            // Nor hidden again, and reconciled ones are left alone
+            let updated_at = account.get_order(1).unwrap().updated_at();
+            filter.visibility = VisibilityFilter::VisibilityIgnored;
+            assert_eq!(account.hide_matching(&filter, HiddenReason::Manual), 0);
+            assert_eq!(
+                account
+                    .orders()
+                    .iter()
+                    .map(|order| order.hidden_reason())
+                    .collect::<Vec<Option<HiddenReason>>>(),
+                [None, Some(HiddenReason::InternalTransfer), None, None]
+            );
+            assert_eq!(account.get_order(1).unwrap().updated_at(), updated_at);
+        }
+
+        #[test]
+        fn sum_orders_by_currency() {
+            let mut account = Account::create();
+            account.add_tag("Travel");
+            let tags = account.tags().clone();
+
+            assert_eq!(
+                account.set_currency(""),
+                Some(RequestFailure::EmptyArgument)
+            );
+            assert_eq!(
+                account.set_currency("Euro"),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.set_currency("eur"), None);
+            assert_eq!(account.currency(), Some(&"EUR".to_string()));
+
+            [(None, -5000), (Some("USD"), -12000), (Some("EUR"), -3000)]
+                .iter()
+                .for_each(|&(currency, amount)| {
+                    let id = account.add_order();
+                    let mut order = account.get_order_mut(id).unwrap();
+                    order.amount = Amount::from_cents(amount);
+                    order.add_tag("Travel", &tags);
+                    if let Some(currency) = currency {
+                        order.set_currency(currency);
+                    }
+                });
+
+            assert_eq!(
+                account
+                    .category_amount_by_currency(
+                        CategoryType::Tag,
+                        "Travel",
+                        OptionNaiveDateRange(None, None)
+                    )
+                    .unwrap()
+                    .iter()
+                    .map(|group| (group.currency.clone(), group.amount.expected))
+                    .collect::<Vec<(Option<String>, Amount)>>(),
+                [
+                    (Some("EUR".into()), Amount::from_cents(-8000)),
+                    (Some("USD".into()), Amount::from_cents(-12000))
+                ]
+            );
+        }
+
+        #[cfg(feature = "rules")]
+        #[test]
+        fn materialize_recurring_orders() {
+            let mut account = Account::create();
+            account.add_resource("Bank I");
+            let resources = account.resources().clone();
+            let mut salary = Order {
+                description: "Salary".into(),
+                amount: Amount::from_cents(250000),
+                ..Order::default()
+            };
+            salary.set_resource("Bank I", &resources);
+            let rule = RecurringRule::new("Salary", NaiveDate::from_ymd(2020, 1, 3), salary);
+
+            assert_eq!(account.add_recurring_rule(rule.clone()), None);
+            assert_eq!(
+                account.add_recurring_rule(rule.clone()),
+                Some(RequestFailure::ExistingItem)
+            );
+            assert_eq!(
+                account.add_recurring_rule(RecurringRule {
+                    label: " ".into(),
+                    ..rule.clone()
+                }),
+                Some(RequestFailure::EmptyArgument)
+            );
+            assert_eq!(
+                account.add_recurring_rule(RecurringRule {
+                    label: "Loan".into(),
+                    interval: 0,
+                    ..rule
+                }),
+                Some(RequestFailure::IncorrectArgument)
+            );
+
+            let ids = account.materialize_recurring_until(NaiveDate::from_ymd(2020, 12, 31));
+
+            assert_eq!(ids, (0..12).collect::<Vec<usize>>());
+            assert_eq!(
+                account.orders()[11].date,
+                Some(NaiveDate::from_ymd(2020, 12, 3))
+            );
+            assert_eq!(account.orders()[11].resource, Some("Bank I".into()));
+            assert_eq!(
+                account
+                    .materialize_recurring_until(NaiveDate::from_ymd(2020, 12, 31))
+                    .is_empty(),
+                true
+            );
+
+            // Materialized occurrences are kept after reload
+            let mut loaded_account =
+                Account::try_from(serde_yaml::to_string(&account).unwrap().as_str()).unwrap();
+
+            assert_eq!(
+                loaded_account.materialize_recurring_until(NaiveDate::from_ymd(2021, 1, 3)),
+                [12]
+            );
+            assert_eq!(loaded_account.remove_recurring_rule("Salary"), None);
+            assert_eq!(
+                loaded_account.remove_recurring_rule("Salary"),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(loaded_account.orders().len(), 13);
+        }
+
+        #[test]
+        fn list_upcoming_orders() {
+            let mut account = Account::create();
+            let today = NaiveDate::from_ymd(2021, 5, 10);
+
+            [
+                (12, TransactionState::Pending, "Electricity"),
+                (11, TransactionState::InProgress, "Water"),
+                (11, TransactionState::Done, "Paid"),
+                (9, TransactionState::Pending, "Overdue"),
+                (30, TransactionState::Pending, "Too far"),
+            ]
+            .iter()
+            .for_each(|&(day, state, description)| {
+                let id = account.add_order();
+                let mut order = account.get_order_mut(id).unwrap();
+                order.date = Some(NaiveDate::from_ymd(2021, 5, day));
+                order.description = description.into();
+                order.set_state(state);
+                order.set_scheduled(true);
+            });
+
+            assert_eq!(
+                account
+                    .upcoming_orders_from(today, 7)
+                    .iter()
+                    .map(|order| order.description.as_str())
+                    .collect::<Vec<&str>>(),
+                ["Water", "Electricity"]
+            );
+        }
+
+        #[cfg(feature = "rules")]
+        #[test]
+        fn track_sub_ledger_allowance() {
+            let mut account = Account::create();
+            account.add_tag("Emma");
+
+            assert_eq!(
+                account.add_sub_ledger("Emma", "Unknown"),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(account.add_sub_ledger("Emma", "Emma"), None);
+            assert_eq!(
+                account.add_sub_ledger("Emma", "Emma"),
+                Some(RequestFailure::ExistingItem)
+            );
+            assert_eq!(
+                account.set_sub_ledger_allowance("Emma", -5.0, Frequency::Weekly, "2021-03-01"),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(
+                account.set_sub_ledger_allowance("Emma", 5.0, Frequency::Weekly, "2021-03-01"),
+                None
+            );
+            assert_eq!(
+                account
+                    .materialize_recurring_until(NaiveDate::from_ymd(2021, 3, 31))
+                    .len(),
+                5
+            );
+
+            let id = account.add_order();
+            let mut order = account.get_order_mut(id).unwrap();
+            order.date = Some(NaiveDate::from_ymd(2021, 3, 20));
+            order.amount = Amount::from_cents(-350);
+            order.tags = vec!["Emma".into()];
+            drop(order);
+            account.add_order();
+
+            let filter = account.sub_ledger_filter("Emma").unwrap();
+            assert_eq!(
+                account
+                    .orders()
+                    .iter()
+                    .filter(|order| filter.is_order_allowed(order))
+                    .count(),
+                6
+            );
+
+            let report = account
+                .sub_ledger_report(
+                    "Emma",
+                    OptionNaiveDateRange(None, Some(NaiveDate::from_ymd(2021, 3, 31))),
+                )
+                .unwrap();
+            assert_eq!(
+                account
+                    .sub_ledger_report("Noah", OptionNaiveDateRange(None, None))
+                    .err(),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(report.credits, Amount::from_cents(2500));
+            assert_eq!(report.spent, Amount::from_cents(350));
+            assert_eq!(report.balance.unwrap().expected, Amount::from_cents(2150));
+
+            assert_eq!(account.remove_sub_ledger("Emma"), None);
+            assert_eq!(account.recurring_rules().is_empty(), true);
+            assert_eq!(account.sub_ledger_filter("Emma").is_none(), true);
+        }
+
+        #[test]
+        fn reconcile_counted_cash() {
+            let mut account = Account::create();
+            account.add_resource("Wallet");
+
+            [
+                (-1250, TransactionState::Done),
+                (-800, TransactionState::Pending),
+            ]
+            .iter()
+            .for_each(|&(cents, state)| {
+                let id = account.add_order();
+                let mut order = account.get_order_mut(id).unwrap();
+                order.date = Some(NaiveDate::from_ymd(2021, 6, 1));
+                order.amount = Amount::from_cents(cents);
+                order.resource = Some("Wallet".into());
+                order.set_state(state);
+            });
+
+            assert_eq!(
+                account.reconcile_cash("Purse", 10.0, "2021-06-02"),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(
+                account.reconcile_cash("Wallet", 10.0, "02/06/2021"),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.reconcile_cash("Wallet", 10.0, "2021-06-02"), None);
+
+            let adjustment = account.orders().last().unwrap();
+            assert_eq!(adjustment.amount, Amount::from_cents(2250));
+            assert_eq!(adjustment.tags, [CASH_ADJUSTMENT_TAG]);
+            assert_eq!(adjustment.state(), TransactionState::Done);
+            assert_eq!(account.tags().contains(&CASH_ADJUSTMENT_TAG.into()), true);
+
+            // Balanced cash needs no adjustment
+            assert_eq!(account.reconcile_cash("Wallet", 10.0, "2021-06-02"), None);
+            assert_eq!(account.orders().len(), 3);
+        }
+
+        #[test]
+        fn sum_tips_of_the_year() {
+            let mut account = Account::create();
+
+            [(2020, -300), (2021, -500), (2021, -250)]
+                .iter()
+                .for_each(|&(year, tip)| {
+                    let id = account.add_order();
+                    let mut order = account.get_order_mut(id).unwrap();
+                    order.date = Some(NaiveDate::from_ymd(year, 7, 1));
+                    order.set_component(ComponentKind::Base, Amount::from_cents(-3000));
+                    order.set_component(ComponentKind::Tip, Amount::from_cents(tip));
+                });
+
+            assert_eq!(
+                account.component_total(
+                    ComponentKind::Tip,
+                    OptionNaiveDateRange(
+                        Some(NaiveDate::from_ymd(2021, 1, 1)),
+                        Some(NaiveDate::from_ymd(2021, 12, 31))
+                    )
+                ),
+                Ok(Amount::from_cents(-750))
+            );
+            assert_eq!(
+                account.component_total(ComponentKind::Fee, OptionNaiveDateRange(None, None)),
+                Ok(Amount::ZERO)
+            );
+        }
+
+        #[test]
+        fn reconcile_with_statement() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            let resources = account.resources().clone();
+            let date = NaiveDate::from_ymd(2021, 4, 30);
+
+            let ids = [-4000, 150000]
+                .iter()
+                .map(|&cents| {
+                    let id = account.add_order();
+                    let mut order = account.get_order_mut(id).unwrap();
+                    order.date = Some(NaiveDate::from_ymd(2021, 4, 12));
+                    order.amount = Amount::from_cents(cents);
+                    order.set_resource("Bank", &resources);
+                    order.set_state(TransactionState::Done);
+                    id
+                })
+                .collect::<Vec<usize>>();
+
+            assert_eq!(
+                account.reconcile("Savings", Amount::ZERO, date),
+                Err(RequestFailure::UnknownItem)
+            );
+            assert_eq!(
+                account.reconcile("Bank", Amount::from_cents(145000), date),
+                Ok(Reconciliation {
+                    balance: Amount::from_cents(146000),
+                    statement_balance: Amount::from_cents(145000),
+                    discrepancy: Amount::from_cents(-1000),
+                    reconciled: 0,
+                })
+            );
+            assert_eq!(
+                account
+                    .reconcile("Bank", Amount::from_cents(146000), date)
+                    .map(|reconciliation| reconciliation.reconciled),
+                Ok(2)
+            );
+
+            // Reconciled orders are locked
+            assert_eq!(account.get_order(ids[0]).unwrap().is_reconciled(), true);
+            assert_eq!(account.get_order_mut(ids[0]).is_none(), true);
+            assert_eq!(account.delete_order(ids[0]), false);
+            assert_eq!(account.set_order_reconciled(ids[0], false), true);
+            assert_eq!(account.delete_order(ids[0]), true);
+        }
+
+        #[test]
+        fn reject_overflowing_reconciliation() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            account.add_project("Car");
+            account.set_project_budget("Car", 1000.0);
+            let (resources, projects) = (account.resources(), account.projects().clone());
+            let date = NaiveDate::from_ymd(2021, 4, 30);
+
+            let id = account.add_order();
+            let mut order = account.get_order_mut(id).unwrap();
+            order.date = Some(date);
+            order.amount = Amount::MAX;
+            order.set_resource("Bank", &resources);
+            order.set_project("Car", &projects);
+            order.set_state(TransactionState::Done);
+            drop(order);
+
+            assert_eq!(
+                account.reconcile("Bank", Amount::MIN, date),
+                Err(RequestFailure::Overflow)
+            );
+            assert_eq!(
+                account.reconcile_cash("Bank", -90_000_000_000_000.0, "2021-04-30"),
+                Some(RequestFailure::Overflow)
+            );
+            assert_eq!(account.orders().len(), 1);
+            assert_eq!(account.tags().contains(&CASH_ADJUSTMENT_TAG.into()), false);
+            assert_eq!(
+                account.project_summary("Car"),
+                Err(RequestFailure::Overflow)
+            );
+        }
+
+        #[test]
+        fn allocate_income_to_envelopes() {
+            let mut account = Account::create();
+            account.add_tag("Food");
+            account.add_tag("Rent");
+            let tags = account.tags();
+            let ids = [200000, -80000, -3000]
+                .iter()
+                .map(|&cents| {
+                    let id = account.add_order();
+                    let mut order = account.get_order_mut(id).unwrap();
+                    order.amount = Amount::from_cents(cents);
+                    order.add_tag(if cents == -3000 { "Food" } else { "Rent" }, &tags);
+                    id
+                })
+                .collect::<Vec<usize>>();
+            let envelope = |envelope: &str, cents| Allocation {
+                envelope: envelope.into(),
+                amount: Amount::from_cents(cents),
+            };
+
+            assert_eq!(
+                account.allocate(ids[0], vec![envelope("Travel", 1000)]),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(
+                account.allocate(ids[1], vec![envelope("Rent", 1000)]),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(
+                account.allocate(
+                    ids[0],
+                    vec![envelope("Rent", 150000), envelope("Food", 60000)]
+                ),
+                Some(RequestFailure::InsufficientFunds)
+            );
+            assert_eq!(
+                account.allocate(
+                    ids[0],
+                    vec![envelope("Rent", 80000), envelope("Food", 50000)]
+                ),
+                None
+            );
+
+            let summary = account.allocation_summary().unwrap();
+
+            assert_eq!(summary.unallocated, Amount::from_cents(70000));
+            assert_eq!(summary.envelopes[0].envelope, "Food");
+            assert_eq!(summary.envelopes[0].available, Amount::from_cents(47000));
+            assert_eq!(summary.envelopes[1].available, Amount::ZERO);
+
+            account.remove_tag("Food");
+
+            assert_eq!(account.allocations(ids[0]), &[envelope("Rent", 80000)]);
+            assert_eq!(
+                account.allocation_summary().unwrap().unallocated,
+                Amount::from_cents(120000)
+            );
+        }
+
+        #[test]
+        fn invert_credit_card_balances() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            account.add_resource("Card");
+            let resources = account.resources();
+            let date = NaiveDate::from_ymd(2021, 4, 30);
+
+            [("Bank", 250000), ("Card", -12000), ("Card", -3000)]
+                .iter()
+                .for_each(|&(resource, cents)| {
+                    let id = account.add_order();
+                    let mut order = account.get_order_mut(id).unwrap();
+                    order.date = Some(NaiveDate::from_ymd(2021, 4, 12));
+                    order.amount = Amount::from_cents(cents);
+                    order.set_resource(resource, &resources);
+                    order.set_state(TransactionState::Done);
+                });
+
+            assert_eq!(
+                account.set_resource_kind("Wallet", Some(ResourceKind::Cash)),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(
+                account.net_worth(date).map(|worth| worth.net),
+                Some(Amount::from_cents(235000))
+            );
+            assert_eq!(
+                account.set_resource_kind("Card", Some(ResourceKind::CreditCard)),
+                None
+            );
+            assert_eq!(
+                account
+                    .category_amount(
+                        CategoryType::Resource,
+                        "Card",
+                        OptionNaiveDateRange(None, None)
+                    )
+                    .unwrap()
+                    .map(|amount| amount.current),
+                Some(Amount::from_cents(15000))
+            );
+            assert_eq!(
+                account.net_worth(date),
+                Some(NetWorth {
+                    assets: Amount::from_cents(250000),
+                    liabilities: Amount::from_cents(15000),
+                    net: Amount::from_cents(235000),
+                    resources: vec![
+                        ResourceBalance {
+                            resource: "Bank".into(),
+                            balance: Amount::from_cents(250000),
+                            liability: false,
+                        },
+                        ResourceBalance {
+                            resource: "Card".into(),
+                            balance: Amount::from_cents(15000),
+                            liability: true,
+                        },
+                    ],
+                })
+            );
+        }
+
+        #[test]
+        fn compute_monthly_balances() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            account.add_resource("Card");
+            account.set_resource_kind("Card", Some(ResourceKind::CreditCard));
+            let resources = account.resources();
+
+            [
+                ("Bank", 1, 250000, TransactionState::Done),
+                ("Card", 1, -12000, TransactionState::Done),
+                ("Bank", 3, -3000, TransactionState::Pending),
+            ]
+            .iter()
+            .for_each(|&(resource, month, cents, state)| {
+                let id = account.add_order();
+                let mut order = account.get_order_mut(id).unwrap();
+                order.date = Some(NaiveDate::from_ymd(2021, month, 12));
+                order.amount = Amount::from_cents(cents);
+                order.set_resource(resource, &resources);
+                order.set_state(state);
+            });
+            let (start, end) = (
+                NaiveDate::from_ymd(2021, 1, 1),
+                NaiveDate::from_ymd(2021, 3, 31),
+            );
+
+            let balances = account.monthly_balances(None, start, end).unwrap();
+            assert_eq!(balances.len(), 3);
+            assert_eq!(balances[1].month, NaiveDate::from_ymd(2021, 2, 1));
+            assert_eq!(balances[1].current, Amount::from_cents(238000));
+            assert_eq!(balances[2].current, Amount::from_cents(238000));
+            assert_eq!(balances[2].expected, Amount::from_cents(235000));
+            assert_eq!(
+                account
+                    .monthly_balances(Some("Card"), start, end)
+                    .unwrap()
+                    .iter()
+                    .map(|balance| balance.current)
+                    .collect::<Vec<Amount>>(),
+                [Amount::from_cents(12000); 3]
+            );
+            assert_eq!(
+                account.monthly_balances(Some("Wallet"), start, end),
+                Err(RequestFailure::UnknownItem)
+            );
+        }
+
+        #[test]
+        fn draw_resource_sparklines() {
+            let mut account = Account::create();
+            ["Bank", "Card", "Old"]
+                .iter()
+                .for_each(|resource| assert_eq!(account.add_resource(resource), None));
+            account.set_resource_kind("Card", Some(ResourceKind::CreditCard));
+            account.set_resource_opening_balance("Bank", 100.0, "2021-03-04");
+            account.set_category_archived(CategoryType::Resource, "Old", true);
+            let resources = account.resources();
+
+            [
+                ("Bank", 1, -1000),
+                ("Bank", 9, -2000),
+                ("Card", 5, -3000),
+                ("Card", 12, -500),
+                ("Bank", 20, 9999),
+            ]
+            .iter()
+            .for_each(|&(resource, day, cents)| {
+                let id = account.add_order();
+                let mut order = account.get_order_mut(id).unwrap();
+                order.date = Some(NaiveDate::from_ymd(2021, 3, day));
+                order.amount = Amount::from_cents(cents);
+                order.set_resource(resource, &resources);
+                order.set_state(TransactionState::Done);
+            });
+            let date = NaiveDate::from_ymd(2021, 3, 15);
+
+            [(14, false), (15, true)]
+                .iter()
+                .for_each(|&(days, weekly)| {
+                    let sparklines = account.sparklines_at(date, days, weekly).unwrap();
+                    assert_eq!(sparklines.len(), 2);
+                    sparklines.iter().for_each(|sparkline| {
+                        assert_eq!(sparkline.balances.len(), if weekly { 3 } else { 14 });
+                        sparkline
+                            .balances
+                            .iter()
+                            .enumerate()
+                            .for_each(|(index, &balance)| {
+                                let day = sparkline.start
+                                    + Duration::days(index as i64 * i64::from(sparkline.step));
+                                assert_eq!(
+                                    Some(balance),
+                                    account.resource_balance(&sparkline.resource, day).ok(),
+                                    "{} {}",
+                                    sparkline.resource,
+                                    day
+                                );
+                            });
+                    });
+                });
+            assert_eq!(
+                account.sparklines_at(date, MAX_SPARKLINE_DAYS + 1, false),
+                Err(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(
+                account.sparklines_at(NaiveDate::MIN, 15, true),
+                Err(RequestFailure::IncorrectArgument)
+            );
+        }
+
+        #[test]
+        fn include_opening_balances() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            account.add_resource("Card");
+            account.set_resource_kind("Card", Some(ResourceKind::CreditCard));
+            let resources = account.resources();
+            let id = account.add_order();
+            let mut order = account.get_order_mut(id).unwrap();
+            order.date = Some(NaiveDate::from_ymd(2021, 2, 8));
+            order.amount = Amount::from_cents(-2500);
+            order.set_resource("Bank", &resources);
+            order.set_state(TransactionState::Done);
+            drop(order);
+
+            assert_eq!(
+                account.set_resource_opening_balance("Bank", 1000.0, "2021-01-31"),
+                None
+            );
+            assert_eq!(
+                account.set_resource_opening_balance("Card", 150.0, "2021-01-31"),
+                None
+            );
+            assert_eq!(
+                account.set_resource_opening_balance("Bank", 1000.0, "31/01/2021"),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(
+                account.set_resource_opening_balance("Wallet", 1000.0, "2021-01-31"),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(
+                account.category_amount(
+                    CategoryType::Resource,
+                    "Bank",
+                    OptionNaiveDateRange(None, None)
+                ),
+                Ok(Some(CategoryAmount {
+                    current: Amount::from_cents(97500),
+                    pending: Amount::ZERO,
+                    in_progress: Amount::ZERO,
+                    expected: Amount::from_cents(97500),
+                }))
+            );
+            // Only changes within the range
+            assert_eq!(
+                account
+                    .category_amount(
+                        CategoryType::Resource,
+                        "Bank",
+                        OptionNaiveDateRange(Some(NaiveDate::from_ymd(2021, 2, 1)), None)
+                    )
+                    .unwrap()
+                    .map(|amount| amount.current),
+                Some(Amount::from_cents(-2500))
+            );
+            assert_eq!(
+                account
+                    .net_worth(NaiveDate::from_ymd(2021, 1, 31))
+                    .map(|worth| (worth.assets, worth.liabilities, worth.net)),
+                Some((
+                    Amount::from_cents(100000),
+                    Amount::from_cents(15000),
+                    Amount::from_cents(85000)
+                ))
+            );
+            let mut filter = Filter::default();
+            assert_eq!(
+                account
+                    .filtered_orders_with_running_balance(&filter)
+                    .map(|orders| orders[0].2),
+                Ok(Amount::from_cents(82500))
+            );
+            filter
+                .get_category_option_mut(CategoryType::Resource)
+                .add(Category("Bank".into(), ItemSelector::Selected));
+            assert_eq!(
+                account
+                    .filtered_orders_with_running_balance(&filter)
+                    .map(|orders| orders[0].2),
+                Ok(Amount::from_cents(97500))
+            );
+            assert_eq!(account.clear_resource_opening_balance("Card"), None);
+            assert_eq!(
+                account
+                    .net_worth(NaiveDate::from_ymd(2021, 2, 28))
+                    .map(|worth| worth.net),
+                Some(Amount::from_cents(97500))
+            );
+        }
+
+        #[test]
+        fn alert_on_broken_limits() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            account.add_resource("Card");
+            account.add_resource("Cash");
+            account.set_resource_kind("Card", Some(ResourceKind::CreditCard));
+            let resources = account.resources();
+
+            [
+                ("Bank", -20000, TransactionState::Done),
+                ("Card", -40000, TransactionState::Done),
+                ("Card", -15000, TransactionState::Pending),
+            ]
+            .iter()
+            .for_each(|&(resource, cents, state)| {
+                let id = account.add_order();
+                let mut order = account.get_order_mut(id).unwrap();
+                order.date = Some(NaiveDate::from_ymd(2021, 6, 1));
+                order.amount = Amount::from_cents(cents);
+                order.set_resource(resource, &resources);
+                order.set_state(state);
+            });
+
+            assert_eq!(
+                account.set_resource_credit_limit("Card", -1.0),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.set_resource_credit_limit("Card", 500.0), None);
+            assert_eq!(account.set_resource_minimum_balance("Bank", -100.0), None);
+            assert_eq!(account.set_resource_minimum_balance("Cash", 0.0), None);
+            assert_eq!(
+                account.set_resource_minimum_balance("Wallet", 0.0),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(
+                account.resource_alerts(OptionNaiveDateRange(None, None)),
+                vec![
+                    ResourceAlert {
+                        resource: "Bank".into(),
+                        limit: BalanceLimit::Minimum(Amount::from_cents(-10000)),
+                        current: Amount::from_cents(-20000),
+                        expected: Amount::from_cents(-20000),
+                    },
+                    // Only once pending orders are done
+                    ResourceAlert {
+                        resource: "Card".into(),
+                        limit: BalanceLimit::Credit(Amount::from_cents(50000)),
+                        current: Amount::from_cents(40000),
+                        expected: Amount::from_cents(55000),
+                    },
+                ]
+            );
+            assert_eq!(account.clear_resource_limit("Bank"), None);
+            assert_eq!(
+                account
+                    .resource_alerts(OptionNaiveDateRange(
+                        None,
+                        Some(NaiveDate::from_ymd(2021, 5, 31))
+                    ))
+                    .len(),
+                0
+            );
+        }
+
+        #[cfg(feature = "fx")]
+        #[test]
+        fn hold_resource_currencies() {
+            let mut account = Account::create();
+            account.set_currency("EUR");
+            account.add_resource("Bank");
+            account.add_resource("US Card");
+            let resources = account.resources();
+
+            assert_eq!(
+                account.set_resource_currency("US Card", "dollar"),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.set_resource_currency("US Card", "usd"), None);
+            assert_eq!(
+                account.resource_currency("US Card"),
+                Some(&"USD".to_string())
+            );
+            assert_eq!(account.resource_currency("Bank"), Some(&"EUR".to_string()));
+
+            let ids = [("Bank", None), ("US Card", None), ("Bank", Some("USD"))]
+                .iter()
+                .map(|&(resource, currency)| {
+                    let id = account.add_order();
+                    let mut order = account.get_order_mut(id).unwrap();
+                    order.amount = Amount::from_cents(-1000);
+                    order.set_resource(resource, &resources);
+                    if let Some(currency) = currency {
+                        order.set_currency(currency);
+                    }
+                    id
+                })
+                .collect::<Vec<usize>>();
+
+            assert_eq!(account.currency_mismatches(), vec![ids[1], ids[2]]);
+            assert_eq!(
+                account
+                    .get_order_mut(ids[2])
+                    .unwrap()
+                    .set_conversion("eur", Amount::from_cents(-920)),
+                true
+            );
+            assert_eq!(account.currency_mismatches(), vec![ids[1]]);
+            // Resources hold converted amounts
+            assert_eq!(
+                account
+                    .category_amount(
+                        CategoryType::Resource,
+                        "Bank",
+                        OptionNaiveDateRange(None, None)
+                    )
+                    .unwrap()
+                    .map(|amount| amount.expected),
+                Some(Amount::from_cents(-1920))
+            );
+            assert_eq!(
+                account
+                    .category_amount_by_currency(
+                        CategoryType::Resource,
+                        "US Card",
+                        OptionNaiveDateRange(None, None)
+                    )
+                    .unwrap()
+                    .iter()
+                    .map(|group| group.currency.clone())
+                    .collect::<Vec<Option<String>>>(),
+                vec![Some("EUR".to_string())]
+            );
+            assert_eq!(account.net_worth(NaiveDate::from_ymd(2021, 1, 1)), None);
+        }
+
+        #[test]
+        fn check_balance_milestones() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            let resources = account.resources().clone();
+
+            assert_eq!(
+                account.add_milestone("Savings", ThresholdDirection::Below, 200.0),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(
+                account.add_milestone("Bank", ThresholdDirection::Below, 200.0),
+                None
+            );
+            assert_eq!(
+                account.add_milestone("Bank", ThresholdDirection::Above, 10000.0),
+                None
+            );
+            assert_eq!(
+                account.add_milestone("Bank", ThresholdDirection::Above, 10000.0),
+                Some(RequestFailure::ExistingItem)
+            );
+
+            [(12, 50000), (20, -35000), (25, 1000000)]
+                .iter()
+                .for_each(|&(day, cents)| {
+                    let id = account.add_order();
+                    let mut order = account.get_order_mut(id).unwrap();
+                    order.date = Some(NaiveDate::from_ymd(2021, 4, day));
+                    order.amount = Amount::from_cents(cents);
+                    order.set_resource("Bank", &resources);
+                    order.set_state(TransactionState::Done);
+                });
+
+            assert_eq!(
+                account.check_milestones_at(NaiveDate::from_ymd(2021, 4, 15)),
+                Vec::new()
+            );
+            assert_eq!(
+                account.check_milestones_at(NaiveDate::from_ymd(2021, 4, 20)),
+                vec![CrossedMilestone {
+                    resource: "Bank".into(),
+                    direction: ThresholdDirection::Below,
+                    threshold: Amount::from_cents(20000),
+                    balance: Amount::from_cents(15000),
+                }]
+            );
+            assert_eq!(
+                account
+                    .check_milestones_at(NaiveDate::from_ymd(2021, 4, 30))
+                    .iter()
+                    .map(|crossed| crossed.direction)
+                    .collect::<Vec<ThresholdDirection>>(),
+                vec![ThresholdDirection::Above]
+            );
+
+            account.remove_resource("Bank");
+
+            assert_eq!(account.milestones().is_empty(), true);
+        }
+
+        #[test]
+        fn track_order_changes() {
+            let mut account = Account::create();
+            let first = account.add_order();
+            let second = account.add_order();
+            let created_at = account.get_order(first).unwrap().created_at();
+
+            assert_eq!(created_at.is_some(), true);
+            assert_eq!(account.get_order(first).unwrap().updated_at(), created_at);
+
+            account.get_order_mut(first).unwrap().description = "Edited".into();
+            let updated_at = account.get_order(first).unwrap().updated_at();
+
+            assert_eq!(updated_at > created_at, true);
+            assert_eq!(account.get_order(first).unwrap().created_at(), created_at);
+
+            let filter = Filter {
+                ordering: ext::OrderingPreference::ByUpdate,
+                direction: ext::OrderingDirection::Descending,
+                ..Filter::default()
+            };
+
+            assert_eq!(
+                account
+                    .orders()
+                    .apply_filter(&filter)
+                    .iter()
+                    .map(|(id, _)| *id)
+                    .collect::<Vec<usize>>(),
+                [first, second]
+            );
+
+            // A copy is a new order
+            let copy = account.duplicate_order(first).unwrap();
+            assert_eq!(
+                account.get_order(copy).unwrap().updated_at() > updated_at,
+                true
+            );
+
+            // Rejected or empty edits leave the order untouched
+            let untouched = account.get_order(second).unwrap().updated_at();
+            assert_eq!(
+                account.get_order_mut(second).unwrap().set_currency("Euro"),
+                false
+            );
+            assert_eq!(account.get_order(second).unwrap().updated_at(), untouched);
+
+            assert_eq!(account.add_project("Trip"), None);
+            let projects = account.projects().clone();
+            account
+                .get_order_mut(second)
+                .unwrap()
+                .set_project("Trip", &projects);
+            let untouched = account.get_order(second).unwrap().updated_at();
+            assert_eq!(account.remove_project("Trip"), None);
+            assert_eq!(
+                account.get_order(second).unwrap().updated_at() > untouched,
+                true
+            );
+        }
+
+        #[test]
+        fn restore_deleted_order() {
+            let mut account = Account::create();
+            let ids = (0..3).map(|_| account.add_order()).collect::<Vec<usize>>();
+            account.get_order_mut(ids[1]).unwrap().description = "Misclick".into();
+
+            assert_eq!(account.delete_order(ids[1]), true);
+            assert_eq!(account.delete_order(ids[2]), true);
+            assert_eq!(account.trash().len(), 2);
+
+            // The trash is saved with the account
+            let mut account =
+                Account::try_from(serde_yaml::to_string(&account).unwrap().as_str()).unwrap();
+
+            assert_eq!(account.restore_order(ids[1]), true);
+            assert_eq!(account.restore_order(ids[1]), false);
+            assert_eq!(
+                account
+                    .orders()
+                    .iter()
+                    .map(|order| (order.id(), order.description.as_str()))
+                    .collect::<Vec<(usize, &str)>>(),
+                [(ids[0], ""), (ids[1], "Misclick")]
+            );
+            assert_eq!(account.add_order(), 3);
+            assert_eq!(account.purge_trash(), 1);
+            assert_eq!(account.restore_order(ids[2]), false);
+        }
+
+        /// Fills an account with orders owning 3 tags among 40.
+        fn tagged_account(count: usize) -> Account {
+            let mut account = Account::create();
+            (0..40).for_each(|index| {
+                account.add_tag(&format!("Tag {}", index));
+            });
+            let tags = account.tags().clone();
+
+            (0..count).for_each(|index| {
+                let mut order = Order::default();
+                [index % 40, index % 7, index % 13].iter().for_each(|&tag| {
+                    order.add_tag(&tags[tag], &tags);
+                });
+                account.push_order(order);
+            });
+
+            account
+        }
+
+        /// Filter selecting 2 tags.
+        fn tag_filter() -> Filter {
+            Filter {
+                tag_option: CategoryFilter::Enabled(vec![
+                    Category("Tag 3".into(), ItemSelector::Selected),
+                    Category("Tag 5".into(), ItemSelector::Selected),
+                    Category("Tag 8".into(), ItemSelector::Discarded),
+                ]),
+                ..Filter::default()
+            }
+        }
+
+        #[test]
+        fn filter_tags_with_bitsets() {
+            let mut account = tagged_account(500);
+            let filter = tag_filter();
+
+            assert_eq!(
+                account.filtered_orders(&filter),
+                account.orders().apply_filter(&filter)
+            );
+
+            // Bitsets follow changes
+            let tags = account.tags().clone();
+            account.get_order_mut(1).unwrap().add_tag("Tag 3", &tags);
+            account.get_order_mut(1).unwrap().add_tag("Tag 5", &tags);
+            account.delete_order(3);
+
+            assert_eq!(
+                account
+                    .filtered_orders(&filter)
+                    .iter()
+                    .any(|(id, _)| *id == 1),
+                true
+            );
+            assert_eq!(
+                account.filtered_orders(&filter),
+                account.orders().apply_filter(&filter)
+            );
+        }
+
+        #[test]
+        fn count_filtered_tags() {
+            let mut account = tagged_account(500);
+            let filter = tag_filter();
+            let matching = account
+                .filtered_orders(&filter)
+                .iter()
+                .map(|(id, _)| *id)
+                .collect::<Vec<usize>>();
+
+            assert_eq!(account.count_filtered_orders(&filter), matching.len());
+
+            account
+                .get_order_mut(matching[0])
+                .unwrap()
+                .set_state(TransactionState::Done);
+            let count = account.count_filtered_orders_by_state(&filter);
+
+            assert_eq!(count.done, 1);
+            assert_eq!(
+                count.pending + count.in_progress + count.done,
+                matching.len()
+            );
+        }
+
+        /// Run with `cargo test --release -- --ignored --nocapture`.
+        #[test]
+        #[ignore]
+        fn benchmark_tag_filter() {
+            let account = tagged_account(100_000);
+            let filter = tag_filter();
+            let measure = |name: &str, function: &dyn Fn() -> usize| {
+                let start = std::time::Instant::now();
+                let count = (0..10).map(|_| function()).sum::<usize>();
+                println!(
+                    "{}: {:?} ({} orders)",
+                    name,
+                    start.elapsed() / 10,
+                    count / 10
+                );
+            };
+
+            account.filtered_orders(&filter);
+            measure("strings", &|| account.orders().apply_filter(&filter).len());
+            measure("bitsets", &|| account.filtered_orders(&filter).len());
+        }
+
+        #[test]
+        fn cache_columns_until_orders_change() {
+            let mut account = tagged_account(500);
+            let amount = |account: &Account| {
+                account
+                    .category_amount(CategoryType::Tag, "Tag 3", OptionNaiveDateRange(None, None))
+                    .unwrap()
+                    .map(|amount| amount.expected)
+            };
+            let columns = account.columns();
+
+            assert_eq!(Arc::ptr_eq(&columns, &account.columns()), true);
+            assert_eq!(amount(&account), Some(Amount::ZERO));
+
+            // Columns follow changes
+            account.get_order_mut(3).unwrap().amount = Amount::from_cents(-500);
+            account.get_order_mut(43).unwrap().amount = Amount::from_cents(-200);
+
+            assert_eq!(Arc::ptr_eq(&columns, &account.columns()), false);
+            assert_eq!(amount(&account), Some(Amount::from_cents(-700)));
+            assert_eq!(
+                account.category_amount(
+                    CategoryType::Tag,
+                    "Tag 3",
+                    OptionNaiveDateRange(None, None)
+                ),
+                account.orders().calculate_category_amount(
+                    CategoryType::Tag,
+                    "Tag 3",
+                    OptionNaiveDateRange(None, None)
+                )
+            );
+
+            account.delete_order(43);
+            assert_eq!(amount(&account), Some(Amount::from_cents(-500)));
+
+            let filter = Filter {
+                tag_option: CategoryFilter::Enabled(vec![Category(
+                    "Tag 3".into(),
+                    ItemSelector::Selected,
+                )]),
+                ..Filter::default()
+            };
+            account.hide_matching(&filter, HiddenReason::Duplicate);
+            assert_eq!(amount(&account), None);
+        }
+
+        /// Run with `cargo test --release -- --ignored --nocapture`.
+        #[test]
+        #[ignore]
+        fn benchmark_category_amounts() {
+            let account = tagged_account(100_000);
+            let range = || OptionNaiveDateRange(None, None);
+            let measure = |name: &str, function: &dyn Fn() -> usize| {
+                let start = std::time::Instant::now();
+                let count = (0..10).map(|_| function()).sum::<usize>();
+                println!(
+                    "{}: {:?} ({} categories)",
+                    name,
+                    start.elapsed() / 10,
+                    count / 10
+                );
+            };
+
+            account.columns();
+            measure("orders", &|| {
+                account
+                    .tags()
+                    .iter()
+                    .filter(|tag| {
+                        account
+                            .orders()
+                            .calculate_category_amount(CategoryType::Tag, tag, range())
+                            .unwrap()
+                            .is_some()
+                    })
+                    .count()
+            });
+            measure("cached columns", &|| {
+                account
+                    .tags()
+                    .iter()
+                    .filter(|tag| {
+                        account
+                            .category_amount(CategoryType::Tag, tag, range())
+                            .unwrap()
+                            .is_some()
+                    })
+                    .count()
+            });
+        }
+
+        #[test]
+        fn keep_references_unique_per_resource() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            let resources = account.resources().clone();
+            let ids = (0..3).map(|_| account.add_order()).collect::<Vec<usize>>();
+            account
+                .get_order_mut(ids[2])
+                .unwrap()
+                .set_resource("Bank", &resources);
+
+            assert_eq!(account.set_order_reference(ids[0], "CHQ-0042"), None);
+            assert_eq!(
+                account.set_order_reference(ids[1], " CHQ-0042 "),
+                Some(RequestFailure::ExistingItem)
+            );
+            // Another resource may use the same reference
+            assert_eq!(account.set_order_reference(ids[2], "CHQ-0042"), None);
+            assert_eq!(
+                account.get_order(ids[2]).unwrap().reference(),
+                Some(&"CHQ-0042".to_string())
+            );
+            assert_eq!(account.set_order_reference(ids[0], ""), None);
+            assert_eq!(account.get_order(ids[0]).unwrap().reference(), None);
+            assert_eq!(account.set_order_reference(ids[1], "CHQ-0042"), None);
+            assert_eq!(
+                account.set_order_reference(9, "CHQ-0043"),
+                Some(RequestFailure::UnknownItem)
+            );
+        }
+
+        #[test]
+        fn bulk_update_orders() {
+            let mut account = Account::create();
+            account.add_tag("Food");
+            let ids = (0..3).map(|_| account.add_order()).collect::<Vec<usize>>();
+            account.set_order_reconciled(ids[2], true);
+
+            assert_eq!(
+                account.bulk_update(
+                    &[ids[0], ids[1], ids[2], 9],
+                    &OrderPatch::AddTag("Food".into())
+                ),
+                [
+                    None,
+                    None,
+                    Some(RequestFailure::IncorrectArgument),
+                    Some(RequestFailure::UnknownItem)
+                ]
+            );
+            assert_eq!(
+                account.bulk_update(&ids[..2], &OrderPatch::AddTag("Food".into())),
+                [
+                    Some(RequestFailure::ExistingItem),
+                    Some(RequestFailure::ExistingItem)
+                ]
+            );
+            assert_eq!(
+                account.bulk_update(&ids[..2], &OrderPatch::SetResource("Bank".into())),
+                [
+                    Some(RequestFailure::UnknownItem),
+                    Some(RequestFailure::UnknownItem)
+                ]
+            );
+            assert_eq!(
+                account.bulk_update(&ids[..1], &OrderPatch::ToggleVisibility),
+                [None]
+            );
+            assert_eq!(
+                account.bulk_update(&ids[..2], &OrderPatch::SetState(TransactionState::Done)),
+                [None, None]
+            );
+            assert_eq!(account.get_order(ids[0]).unwrap().is_visible(), false);
+            assert_eq!(
+                account.get_order(ids[1]).unwrap().state(),
+                TransactionState::Done
+            );
+        }
+
+        #[test]
+        fn move_orders_by_position() {
+            let mut account = Account::create();
+            (0..4).for_each(|_| {
+                account.add_order();
+            });
+            let mut filter = Filter {
+                ordering: ext::OrderingPreference::ByPosition,
+                ..Filter::default()
+            };
+            let ids = |filter: &Filter, account: &Account| {
+                account
+                    .filtered_orders(filter)
+                    .iter()
+                    .map(|(id, _)| *id)
+                    .collect::<Vec<usize>>()
+            };
+
+            assert_eq!(account.move_order(3, 1), true);
+            assert_eq!(ids(&filter, &account), [0, 3, 1, 2]);
+            assert_eq!(account.move_order(0, 2), true);
+            assert_eq!(ids(&filter, &account), [3, 1, 2, 0]);
+            assert_eq!(account.move_order(0, 7), false);
+
+            filter.direction = ext::OrderingDirection::Descending;
+
+            assert_eq!(ids(&filter, &account), [0, 2, 1, 3]);
+        }
+
+        #[test]
+        fn link_refund_to_original_order() {
+            let mut account = Account::create();
+            let amounts = [-12000, 4500, 3000];
+            amounts.iter().for_each(|&amount| {
+                let id = account.add_order();
+                account.get_order_mut(id).unwrap().amount = Amount::from_cents(amount);
+            });
+
+            assert_eq!(account.link_order(1, 5), Some(RequestFailure::UnknownItem));
+            assert_eq!(
+                account.link_order(0, 0),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.link_order(1, 0), None);
+            assert_eq!(account.link_order(2, 0), None);
+            assert_eq!(
+                account.link_order(0, 1),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.get_order(1).unwrap().related_order(), Some(0));
+            assert_eq!(account.net_amount(0), Some(Amount::from_cents(-4500)));
+
+            account.get_order_mut(2).unwrap().hide(HiddenReason::Manual);
+
+            assert_eq!(account.net_amount(0), Some(Amount::from_cents(-7500)));
+            assert_eq!(account.unlink_order(1), true);
+            assert_eq!(account.unlink_order(1), false);
+            assert_eq!(account.net_amount(0), Some(Amount::from_cents(-12000)));
+            assert_eq!(account.net_amount(5), None);
+        }
+
+        #[test]
+        fn create_order_from_template() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            let resources = account.resources().clone();
+            let id = account.add_order();
+            let mut order = account.get_order_mut(id).unwrap();
+            order.date = Some(NaiveDate::from_ymd(2021, 2, 5));
+            order.description = "Monthly loan".into();
+            order.amount = Amount::from_cents(-60000);
+            order.set_resource("Bank", &resources);
+            drop(order);
+
+            assert_eq!(
+                account.save_order_as_template(id, ""),
+                Some(RequestFailure::EmptyArgument)
+            );
+            assert_eq!(
+                account.save_order_as_template(7, "Loan"),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(account.save_order_as_template(id, "Loan"), None);
+            assert_eq!(
+                account.save_order_as_template(id, "Loan"),
+                Some(RequestFailure::ExistingItem)
+            );
+
+            let copy = account.add_order_from_template("Loan").unwrap();
+
+            assert_eq!(
+                account.get_order(copy),
+                Some(&Order {
+                    id: copy,
+                    date: None,
+                    ..account.get_order(id).unwrap().clone()
+                })
+            );
+            assert_eq!(account.remove_template("Loan"), None);
+            assert_eq!(account.add_order_from_template("Loan"), None);
+        }
+
+        #[test]
+        fn keep_ids_after_deletion() {
+            let mut account = Account::create();
+            let ids = (0..3).map(|_| account.add_order()).collect::<Vec<usize>>();
+
+            assert_eq!(ids, vec![0, 1, 2]);
+            assert_eq!(account.delete_order(1), true);
+            assert_eq!(account.delete_order(1), false);
+            assert_eq!(account.get_order(2).map(|order| order.id()), Some(2));
+            assert_eq!(account.add_order(), 3);
+        }
+
+        #[test]
+        fn assign_ids_to_legacy_orders() {
+            let mut account = Account::try_from(
+                "label: Legacy\n\
+                 tags: []\n\
+                 resources: []\n\
+                 orders:\n  \
+                 - {date: ~, description: A, amount: 1.0, resource: ~, tags: [], state: Done, visible: true}\n  \
+                 - {date: ~, description: B, amount: 2.0, resource: ~, tags: [], state: Done, visible: true}\n  \
+                 - {date: ~, description: C, amount: 3.0, resource: ~, tags: [], state: Done, visible: true}\n",
+            )
+            .unwrap();
+
+            assert_eq!(
+                account
+                    .orders()
+                    .iter()
+                    .map(|order| (order.id(), order.description.as_str()))
+                    .collect::<Vec<(usize, &str)>>(),
+                vec![(0, "A"), (1, "B"), (2, "C")]
+            );
+            assert_eq!(account.add_order(), 3);
+            assert_eq!(
+                account
+                    .applied_migrations()
+                    .iter()
+                    .map(|migration| migration.id)
+                    .collect::<Vec<&str>>(),
+                ["order-ids", "hidden-reason"]
+            );
+            assert_eq!(
+                serde_yaml::to_string(&account)
+                    .unwrap()
+                    .starts_with(&format!("---\nversion: {}\n", SCHEMA_VERSION)),
+                true
+            );
+            assert_eq!(
+                Account::try_from(format!("version: {}\n", SCHEMA_VERSION + 1).as_str())
+                    .unwrap_err()
+                    .to_string(),
+                format!("unsupported schema version {}", SCHEMA_VERSION + 1)
+            );
+        }
+
+        #[test]
+        fn report_category_collisions() {
+            let mut account = Account::try_from(
+                "label: Sloppy\n\
+                 tags: [Food, \"Food \", food]\n\
+                 resources: [Bank]\n\
+                 category_matching: CaseInsensitive\n\
+                 orders: []\n",
+            )
+            .unwrap();
+
+            let collisions = vec![CategoryCollision {
+                kind: CategoryType::Tag,
+                names: vec!["Food".into(), "Food ".into(), "food".into()],
+            }];
+
+            assert_eq!(account.category_collisions(), collisions);
+            assert_eq!(
+                account.load_warnings(),
+                &vec![LoadWarning::CategoryCollisions(collisions)]
+            );
+            assert_eq!(account.applied_migrations().is_empty(), true);
+            assert_eq!(
+                account.add_resource(" BANK"),
+                Some(RequestFailure::ExistingItem)
+            );
+            account.set_category_matching(KeyMatching::Exact);
+            assert_eq!(account.add_resource(" BANK"), None);
+            assert_eq!(account.resources(), vec!["Bank", "BANK"]);
+        }
+
+        #[test]
+        fn list_periods_with_data() {
+            let dates = [
+                Some(NaiveDate::from_ymd(2020, 11, 10)),
+                None,
+                Some(NaiveDate::from_ymd(2020, 3, 4)),
+                Some(NaiveDate::from_ymd(2021, 1, 3)),
+                Some(NaiveDate::from_ymd(2020, 3, 25)),
+            ];
+            let account = Account {
+                orders: dates
+                    .iter()
+                    .map(|&date| Order {
+                        date,
+                        ..Order::default()
+                    })
+                    .collect(),
+                ..Account::create()
+            };
+
+            assert_eq!(
+                account.periods_with_data(),
+                vec![
+                    PeriodCount {
+                        year: 2020,
+                        month: 3,
+                        count: 2
+                    },
+                    PeriodCount {
+                        year: 2020,
+                        month: 11,
+                        count: 1
+                    },
+                    PeriodCount {
+                        year: 2021,
+                        month: 1,
+                        count: 1
+                    },
+                ]
+            );
+            assert_eq!(Account::create().periods_with_data(), vec![]);
+        }
+
+        #[test]
+        fn add_remove_places() {
+            let mut account = Account::create();
+
+            assert_eq!(account.add_place("Home", 48.8584, 2.2945, 500.0), None);
+            assert_eq!(
+                account.add_place("Home", 43.2965, 5.3698, 500.0),
+                Some(RequestFailure::ExistingItem)
+            );
+            assert_eq!(
+                account.add_place("", 43.2965, 5.3698, 500.0),
+                Some(RequestFailure::EmptyArgument)
+            );
+            assert_eq!(
+                account.add_place("Nowhere", 143.2965, 5.3698, 500.0),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(
+                account.add_place("Point", 43.2965, 5.3698, 0.0),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.places().len(), 1);
+            assert_eq!(
+                account.remove_place("Office"),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(account.remove_place("Home"), None);
+            assert_eq!(account.places().is_empty(), true);
+        }
+
+        #[test]
+        fn manage_tag_budgets() {
+            let mut account = Account::create();
+            account.add_tag("Food");
+            let tags = account.tags();
+            let id = account.add_order();
+            let mut order = account.get_order_mut(id).unwrap();
+            order.date = Some(NaiveDate::from_ymd(2021, 3, 2));
+            order.amount = Amount::from_cents(-12000);
+            order.add_tag("Food", &tags);
+            drop(order);
+
+            assert_eq!(
+                account.set_tag_budget("Travel", Period::Month, 100.0),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(
+                account.set_tag_budget("Food", Period::Month, -100.0),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.set_tag_budget("Food", Period::Month, 100.0), None);
+            assert_eq!(account.set_tag_budget("Food", Period::Month, 150.0), None);
+            assert_eq!(account.tag_budgets().len(), 1);
+
+            let report = account
+                .budget_report(NaiveDate::from_ymd(2021, 3, 31))
+                .unwrap();
+            assert_eq!(report.lines[0].remaining, Amount::from_cents(3000));
+            assert_eq!(report.overspent.len(), 0);
+            assert_eq!(
+                account.clear_tag_budget("Food", Period::Week),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(account.remove_tag("Food"), None);
+            assert_eq!(account.tag_budgets().len(), 0);
+        }
+
+        #[test]
+        fn summarize_project() {
+            let mut account = Account::create();
+
+            assert_eq!(account.add_project("Japan Trip"), None);
+            assert_eq!(
+                account.add_project("Japan Trip"),
+                Some(RequestFailure::ExistingItem)
+            );
+            assert_eq!(
+                account.set_project_budget("Japan Trip", -10.0),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.set_project_budget("Japan Trip", 3000.0), None);
+
+            [
+                (Some(NaiveDate::from_ymd(2025, 4, 2)), -120000, true),
+                (Some(NaiveDate::from_ymd(2025, 3, 12)), -80000, true),
+                (None, -15000, true),
+                (Some(NaiveDate::from_ymd(2025, 1, 1)), -40000, false),
+            ]
+            .iter()
+            .for_each(|&(date, amount, visible)| {
+                let projects = account.projects().clone();
+                let id = account.add_order();
+                let mut order = account.get_order_mut(id).unwrap();
+                order.date = date;
+                order.amount = Amount::from_cents(amount);
+                if !visible {
+                    order.hide(HiddenReason::Manual);
+                }
+                order.set_project("Japan Trip", &projects);
+            });
+
+            let summary = account.project_summary("Japan Trip").unwrap();
+
+            assert_eq!(summary.budget, Some(Amount::from_cents(300000)));
+            assert_eq!(summary.remaining, Some(Amount::from_cents(85000)));
+            assert_eq!(
+                summary.amount.unwrap().expected,
+                Amount::from_cents(-215000)
+            );
+            assert_eq!(summary.start, Some(NaiveDate::from_ymd(2025, 3, 12)));
+            assert_eq!(summary.end, Some(NaiveDate::from_ymd(2025, 4, 2)));
+            assert_eq!(
+                account.project_summary("Kitchen"),
+                Err(RequestFailure::UnknownItem)
+            );
+
+            assert_eq!(account.remove_project("Japan Trip"), None);
+            assert_eq!(
+                account
+                    .orders()
+                    .iter()
+                    .all(|order| order.project().is_none()),
+                true
+            );
+        }
+
+        #[test]
+        fn remove_payee_used_by_orders() {
+            let mut account = Account::create();
+            account.add_payee("Bakery");
+            let payees = account.payees().clone();
+            let id = account.add_order();
+
+            assert_eq!(
+                account
+                    .get_order_mut(id)
+                    .unwrap()
+                    .set_payee("Grocery", &payees),
+                false
+            );
+            assert_eq!(
+                account
+                    .get_order_mut(id)
+                    .unwrap()
+                    .set_payee("Bakery", &payees),
+                true
+            );
+            assert_eq!(
+                account.add_payee("Bakery"),
+                Some(RequestFailure::ExistingItem)
+            );
+            assert_eq!(account.remove_payee("Bakery"), None);
+            assert_eq!(account.get_order(id).unwrap().payee(), None);
+            assert_eq!(
+                account.remove_payee("Bakery"),
+                Some(RequestFailure::UnknownItem)
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "reporting")]
+        fn lint_account() {
+            let mut account = Account::create();
+            account.add_tag("Food");
+            account.add_tag("Restaurant");
+            account.add_tag("restaurants");
+            let tags = account.tags().clone();
+            let today = NaiveDate::from_ymd(2021, 6, 15);
+            [
+                (NaiveDate::from_ymd(2021, 6, 1), TransactionState::Pending),
+                (NaiveDate::from_ymd(2021, 4, 1), TransactionState::Pending),
+                (NaiveDate::from_ymd(2021, 7, 1), TransactionState::Done),
+            ]
+            .iter()
+            .for_each(|&(date, state)| {
+                let id = account.add_order();
+                let mut order = account.get_order_mut(id).unwrap();
+                order.date = Some(date);
+                order.set_state(state);
+                order.add_tag("Food", &tags);
+            });
+            account.add_order();
+
+            assert_eq!(
+                lint::lint(&account, today),
+                [
+                    LintIssue::UnusedTag {
+                        tag: "Restaurant".into()
+                    },
+                    LintIssue::UnusedTag {
+                        tag: "restaurants".into()
+                    },
+                    LintIssue::UntaggedOrder { id: 3 },
+                    LintIssue::StalePendingOrder {
+                        id: 1,
+                        date: NaiveDate::from_ymd(2021, 4, 1)
+                    },
+                    LintIssue::SimilarCategories {
+                        category: CategoryType::Tag,
+                        first: "Restaurant".into(),
+                        second: "restaurants".into()
+                    },
+                    LintIssue::FutureDoneOrder {
+                        id: 2,
+                        date: NaiveDate::from_ymd(2021, 7, 1)
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn remove_custom_field_used_by_orders() {
+            let mut account = Account::create();
+            account.add_custom_field("Reimbursable by", FieldType::Text);
+            let fields = account.custom_fields().clone();
+            let id = account.add_order();
+            account.get_order_mut(id).unwrap().set_field(
+                "Reimbursable by",
+                field::FieldValue::Text("Employer".into()),
+                &fields,
+            );
+
+            assert_eq!(
+                account.add_custom_field("Reimbursable by", FieldType::Bool),
+                Some(RequestFailure::ExistingItem)
+            );
+            assert_eq!(
+                account.add_custom_field(" ", FieldType::Bool),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.remove_custom_field("Reimbursable by"), None);
+            assert_eq!(account.get_order(id).unwrap().fields().is_empty(), true);
+            assert_eq!(
+                account.remove_custom_field("Reimbursable by"),
+                Some(RequestFailure::UnknownItem)
+            );
+        }
+
+        #[test]
+        fn remove_resource_used_by_orders() {
+            let resources = [
+                String::from("Bank"),
+                String::from("Cash"),
+                String::from("Gift Check"),
+            ];
+            let mut orders = [
+                Order {
+                    resource: Some(resources[0].clone().into()),
+                    ..Order::default()
+                },
+                Order {
+                    resource: Some(resources[1].clone().into()),
+                    ..Order::default()
+                },
+                Order {
+                    resource: Some(resources[1].clone().into()),
+                    ..Order::default()
+                },
+                Order {
+                    resource: Some(resources[2].clone().into()),
+                    ..Order::default()
+                },
+            ];
+            let mut account = Account {
+                resources: resources.iter().map(|x| CategoryEntry::new(x)).collect(),
+                orders: orders.to_vec(),
+                ..Account::create()
+            };
+
+            orders[1].resource = None;
+            orders[2].resource = None;
+            assert_eq!(account.remove_resource(resources[1].as_str()), None);
+            assert_eq!(account.orders, orders);
+        }
+
+        #[test]
+        fn set_category_details() {
+            let mut account = Account::create();
+            account.add_tag("Food");
+            account.add_resource("Bank");
+
+            assert_eq!(
+                account.set_category_details(CategoryType::Tag, "Food", "#f80", "🍔", ""),
+                None
+            );
+            assert_eq!(
+                account.set_category_details(CategoryType::Resource, "Bank", "blue", "", ""),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(
+                account.set_category_details(CategoryType::Tag, "Travel", "", "", ""),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(
+                account.set_category_details(CategoryType::Payee, "Food", "", "", ""),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.tags(), vec!["Food"]);
+            assert_eq!(account.tag_entries()[0].icon, Some("🍔".into()));
+
+            let loaded =
+                Account::try_from(serde_yaml::to_string(&account).unwrap().as_str()).unwrap();
+
+            assert_eq!(loaded.tag_entries(), account.tag_entries());
+            assert_eq!(loaded.resource_entries(), &vec![CategoryEntry::new("Bank")]);
+        }
+
+        #[test]
+        fn reassign_removed_categories() {
+            let mut account = Account::create();
+            ["Restos", "Restaurant", "Bar"].iter().for_each(|tag| {
+                account.add_tag(tag);
+            });
+            account.add_resource("Old Bank");
+            account.add_resource("Bank");
+            let (tags, resources) = (account.tags(), account.resources());
+
+            let ids = [vec!["Restos"], vec!["Restos", "Restaurant"], vec!["Bar"]]
+                .iter()
+                .map(|order_tags| {
+                    let id = account.add_order();
+                    let mut order = account.get_order_mut(id).unwrap();
+                    order_tags.iter().for_each(|tag| {
+                        order.add_tag(tag, &tags);
+                    });
+                    order.set_resource("Old Bank", &resources);
+                    id
+                })
+                .collect::<Vec<usize>>();
+
+            assert_eq!(
+                account.remove_tag_with_reassign("Restos", Some("Food")),
+                Err(RequestFailure::UnknownItem)
+            );
+            assert_eq!(
+                account.remove_tag_with_reassign("Restos", Some("Restos")),
+                Err(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(
+                account.remove_tag_with_reassign("Restos", Some("Restaurant")),
+                Ok(2)
+            );
+            assert_eq!(account.tags(), vec!["Restaurant", "Bar"]);
+            assert_eq!(
+                account.get_order(ids[1]).unwrap().tags,
+                vec!["Restaurant".to_string()]
+            );
+            assert_eq!(account.remove_tag_with_reassign("Bar", None), Ok(1));
+            assert_eq!(account.get_order(ids[2]).unwrap().tags.len(), 0);
+            assert_eq!(
+                account.remove_resource_with_reassign("Old Bank", Some("Bank")),
+                Ok(3)
+            );
+            assert_eq!(account.resources(), vec!["Bank"]);
+            assert_eq!(
+                account.get_order(ids[0]).unwrap().resource,
+                Some("Bank".into())
+            );
+            assert_eq!(account.remove_resource_with_reassign("Bank", None), Ok(3));
+            assert_eq!(account.get_order(ids[0]).unwrap().resource, None);
+        }
+
+        #[test]
+        fn resolve_tag_aliases() {
+            let mut account = Account::create();
+            account.add_tag("Restaurant");
+
+            assert_eq!(
+                account.add_tag_alias("Restos", "Food"),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(account.add_tag_alias(" Restos ", "Restaurant"), None);
+            assert_eq!(
+                account.add_tag_alias("Restos", "Restaurant"),
+                Some(RequestFailure::ExistingItem)
+            );
+            assert_eq!(
+                account.add_tag_alias("Restaurant", "Restaurant"),
+                Some(RequestFailure::ExistingItem)
+            );
+            assert_eq!(
+                account.add_tag("Restos"),
+                Some(RequestFailure::ExistingItem)
+            );
+
+            let id = account.add_order();
+            let tags = account.tag_catalog();
+            assert_eq!(
+                account.get_order_mut(id).unwrap().add_tag("Restos", &tags),
+                true
+            );
+            assert_eq!(
+                account.get_order(id).unwrap().tags,
+                vec!["Restaurant".to_string()]
+            );
+            assert_eq!(account.remove_tag_alias("Restos"), None);
+            assert_eq!(
+                account.bulk_update(&[id], &OrderPatch::AddTag("Restos".into())),
+                vec![Some(RequestFailure::UnknownItem)]
+            );
+
+            account.add_tag_alias("Restos", "Restaurant");
+            assert_eq!(account.remove_tag("Restaurant"), None);
+            assert_eq!(account.tag_aliases().len(), 0);
+        }
+
+        #[test]
+        fn sum_tag_groups() {
+            let mut account = Account::create();
+            ["Rent", "Food", "Games"].iter().for_each(|tag| {
+                account.add_tag(tag);
+            });
+            let tags = account.tags();
+
+            assert_eq!(account.add_tag_group("Essentials"), None);
+            assert_eq!(
+                account.add_tag_group("Essentials"),
+                Some(RequestFailure::ExistingItem)
+            );
+            assert_eq!(account.add_tag_group("Fun"), None);
+            assert_eq!(
+                account.set_tag_group("Rent", "Savings"),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(account.set_tag_group("Rent", "Essentials"), None);
+            assert_eq!(account.set_tag_group("Food", "Fun"), None);
+            assert_eq!(account.set_tag_group("Food", "Essentials"), None);
+            assert_eq!(account.set_tag_group("Games", "Fun"), None);
+
+            [
+                (vec!["Rent"], -90000),
+                (vec!["Food"], -4500),
+                (vec!["Food", "Rent"], -1000),
+                (vec!["Games"], -6000),
+            ]
+            .iter()
+            .for_each(|(order_tags, cents)| {
+                let id = account.add_order();
+                let mut order = account.get_order_mut(id).unwrap();
+                order.date = Some(NaiveDate::from_ymd(2021, 5, 2));
+                order.amount = Amount::from_cents(*cents);
+                order_tags.iter().for_each(|tag| {
+                    order.add_tag(tag, &tags);
+                });
+            });
+
+            let range = || OptionNaiveDateRange(None, Some(NaiveDate::from_ymd(2021, 5, 31)));
+            assert_eq!(
+                account
+                    .tag_group_amount("Essentials", range())
+                    .unwrap()
+                    .map(|amount| amount.expected),
+                Some(Amount::from_cents(-95500))
+            );
+            assert_eq!(
+                account
+                    .tag_group_amount("Fun", range())
+                    .unwrap()
+                    .map(|amount| amount.expected),
+                Some(Amount::from_cents(-6000))
+            );
+            assert_eq!(
+                account.tag_group_amount("Savings", range()),
+                Err(RequestFailure::UnknownItem)
+            );
 
-    mod account {
-        use super::*;
-        use ext::OrderListExt;
-        use filter::category::{Category, CategoryFilter};
-        use filter::date::NaiveDateFilter;
-        use filter::{Filter, ItemSelector, VisibilityFilter};
-        use order::TransactionState;
+            assert_eq!(account.remove_tag("Games"), None);
+            assert_eq!(account.tag_groups()[1].tags, Vec::<String>::new());
+            assert_eq!(account.remove_tag_group("Fun"), None);
+            assert_eq!(account.tag_groups().len(), 1);
+        }
 
         #[test]
-        fn duplicate_existing_order() {
-            let mut account = Account {
-                orders: vec![
-                    Order::default(),
-                    Order {
-                        description: "Test".into(),
-                        amount: -2.99,
-                        ..Order::default()
-                    },
-                ],
-                ..Account::create()
-            };
+        fn compute_category_usage() {
+            let mut account = Account::create();
+            account.add_tag("Food");
+            account.add_tag("Gym");
+            let tags = account.tags();
+
+            [(2021, 3, -1200), (2020, 11, -800), (2021, 1, -500)]
+                .iter()
+                .for_each(|&(year, month, cents)| {
+                    let id = account.add_order();
+                    let mut order = account.get_order_mut(id).unwrap();
+                    order.date = Some(NaiveDate::from_ymd(year, month, 1));
+                    order.amount = Amount::from_cents(cents);
+                    order.add_tag("Food", &tags);
+                });
 
-            assert_eq!(account.duplicate_order(2), false);
-            assert_eq!(account.duplicate_order(1), true);
-            assert_eq!(account.duplicate_order(0), true);
-            assert_eq!(account.orders[1], account.orders[2]);
-            assert_eq!(account.orders[0], account.orders[3]);
+            assert_eq!(
+                account.category_usage(CategoryType::Tag),
+                Ok(vec![
+                    CategoryUsage {
+                        name: "Food".into(),
+                        count: 3,
+                        first_used: Some(NaiveDate::from_ymd(2020, 11, 1)),
+                        last_used: Some(NaiveDate::from_ymd(2021, 3, 1)),
+                        total: Amount::from_cents(-2500),
+                    },
+                    CategoryUsage {
+                        name: "Gym".into(),
+                        count: 0,
+                        first_used: None,
+                        last_used: None,
+                        total: Amount::ZERO,
+                    },
+                ])
+            );
+            assert_eq!(account.category_usage(CategoryType::Payee), Ok(Vec::new()));
         }
 
         #[test]
-        fn remove_resource_used_by_orders() {
-            let resources = [
-                String::from("Bank"),
-                String::from("Cash"),
-                String::from("Gift Check"),
-            ];
-            let mut orders = [
-                Order {
-                    resource: Some(resources[0].clone()),
-                    ..Order::default()
-                },
-                Order {
-                    resource: Some(resources[1].clone()),
-                    ..Order::default()
-                },
-                Order {
-                    resource: Some(resources[1].clone()),
-                    ..Order::default()
-                },
-                Order {
-                    resource: Some(resources[2].clone()),
-                    ..Order::default()
-                },
-            ];
-            let mut account = Account {
-                resources: resources.to_vec(),
-                orders: orders.to_vec(),
-                ..Account::create()
-            };
+        fn archive_categories() {
+            let mut account = Account::create();
+            account.add_tag("Rent");
+            account.add_resource("Old Bank");
+            let (tags, resources) = (account.tags(), account.resources());
+            let id = account.add_order();
+            let mut order = account.get_order_mut(id).unwrap();
+            order.add_tag("Rent", &tags);
+            order.set_resource("Old Bank", &resources);
+            order.set_state(TransactionState::Done);
+            drop(order);
 
-            orders[1].resource = None;
-            orders[2].resource = None;
-            assert_eq!(account.remove_resource(resources[1].as_str()), None);
-            assert_eq!(account.orders, orders);
+            assert_eq!(
+                account.set_category_archived(CategoryType::Resource, "Old Bank", true),
+                None
+            );
+            assert_eq!(
+                account.set_category_archived(CategoryType::Tag, "Food", true),
+                Some(RequestFailure::UnknownItem)
+            );
+            assert_eq!(account.resource_entries()[0].archived, true);
+            // Still valid on orders and in reports
+            assert_eq!(account.resources(), vec!["Old Bank"]);
+            assert_eq!(
+                account.get_order(id).unwrap().resource.as_ref(),
+                Some(&"Old Bank".into())
+            );
+            assert_eq!(
+                account
+                    .category_amount(
+                        CategoryType::Resource,
+                        "Old Bank",
+                        OptionNaiveDateRange(None, None)
+                    )
+                    .unwrap()
+                    .is_some(),
+                true
+            );
+
+            let loaded =
+                Account::try_from(serde_yaml::to_string(&account).unwrap().as_str()).unwrap();
+            assert_eq!(loaded.resource_entries(), account.resource_entries());
+            assert_eq!(
+                account.set_category_archived(CategoryType::Resource, "Old Bank", false),
+                None
+            );
+            assert_eq!(
+                account.resource_entries()[0],
+                CategoryEntry::new("Old Bank")
+            );
         }
 
         #[test]
@@ -267,16 +5413,16 @@ mod tests {
             ];
             let mut orders = [
                 Order {
-                    tags: tags[..2].to_vec(),
+                    tags: tags[..2].iter().map(Interned::from).collect(),
                     ..Order::default()
                 },
                 Order {
-                    tags: tags[1..3].to_vec(),
+                    tags: tags[1..3].iter().map(Interned::from).collect(),
                     ..Order::default()
                 },
             ];
             let mut account = Account {
-                tags: tags.to_vec(),
+                tags: tags.iter().map(|x| CategoryEntry::new(x)).collect(),
                 orders: orders.to_vec(),
                 ..Account::create()
             };
@@ -287,6 +5433,40 @@ mod tests {
             assert_eq!(account.orders, orders);
         }
 
+        #[test]
+        #[cfg(feature = "reporting")]
+        fn export_changes_since_marker() {
+            let mut account = Account::create();
+            let ids = (0..3).map(|_| account.add_order()).collect::<Vec<usize>>();
+            account.orders[0].created_at = None;
+
+            let first = account.export_changes();
+
+            assert_eq!(first.since, None);
+            assert_eq!(first.orders.len(), 3);
+            assert_eq!(account.last_export(), Some(first.marker));
+
+            // Pretend changes happen after the export
+            let later = first.marker + Duration::seconds(1);
+            account.orders[1].updated_at = Some(later);
+            account.delete_order(ids[2]);
+            account.trash[0].deleted_at = later;
+
+            let second = account.export_changes();
+
+            assert_eq!(second.since, Some(first.marker));
+            assert_eq!(
+                second
+                    .orders
+                    .iter()
+                    .map(|order| order.id())
+                    .collect::<Vec<usize>>(),
+                [ids[1]]
+            );
+            assert_eq!(second.deleted, [ids[2]]);
+            assert_eq!(account.export_since(Some(later)).orders.is_empty(), true);
+        }
+
         #[test]
         fn export_orders() {
             let expected_orders = [Order::default(), Order::default(), Order::default()];
@@ -314,61 +5494,60 @@ mod tests {
                 (
                     0,
                     &Order {
+                        id: 0,
                         date: Some(NaiveDate::from_ymd(2020, 4, 15)),
                         resource: None,
-                        tags: tags.iter().map(|x| x.0.clone()).collect::<Vec<String>>(),
+                        tags: tags.iter().map(|x| x.0.as_str().into()).collect(),
                         state: TransactionState::Pending,
-                        visible: true,
+                        hidden: None,
                         ..Order::default()
                     },
                 ),
                 (
                     1,
                     &Order {
+                        id: 1,
                         date: None,
-                        resource: Some(resources[0].0.clone()),
-                        tags: tags[..2]
-                            .iter()
-                            .map(|x| x.0.clone())
-                            .collect::<Vec<String>>(),
+                        resource: Some(resources[0].0.clone().into()),
+                        tags: tags[..2].iter().map(|x| x.0.as_str().into()).collect(),
                         state: TransactionState::InProgress,
-                        visible: true,
+                        hidden: None,
                         ..Order::default()
                     },
                 ),
                 (
                     2,
                     &Order {
+                        id: 2,
                         date: Some(NaiveDate::from_ymd(2019, 3, 3)),
-                        resource: Some(resources[1].0.clone()),
-                        tags: tags[1..]
-                            .iter()
-                            .map(|x| x.0.clone())
-                            .collect::<Vec<String>>(),
+                        resource: Some(resources[1].0.clone().into()),
+                        tags: tags[1..].iter().map(|x| x.0.as_str().into()).collect(),
                         state: TransactionState::Done,
-                        visible: false,
+                        hidden: Some(HiddenReason::Manual),
                         ..Order::default()
                     },
                 ),
                 (
                     3,
                     &Order {
+                        id: 3,
                         date: Some(NaiveDate::from_ymd(2020, 5, 20)),
-                        resource: Some(resources[0].0.clone()),
-                        tags: vec![tags[3].0.clone()],
+                        resource: Some(resources[0].0.clone().into()),
+                        tags: vec![tags[3].0.clone().into()],
                         state: TransactionState::Done,
-                        visible: true,
+                        hidden: None,
                         ..Order::default()
                     },
                 ),
                 (
                     4,
                     &Order {
+                        id: 4,
                         date: Some(NaiveDate::from_ymd(2021, 5, 30)),
-                        resource: Some(resources[1].0.clone()),
+                        resource: Some(resources[1].0.clone().into()),
                         tags: Vec::new(),
                         state: TransactionState::Pending,
-                        visible: true,
+                        hidden: None,
                         ..Order::default()
                     },
                 ),
@@ -376,8 +5555,8 @@ mod tests {
             let account = Account {
                 resources: resources
                     .iter()
-                    .map(|x| x.0.clone())
-                    .collect::<Vec<String>>(),
+                    .map(|x| CategoryEntry::new(&x.0))
+                    .collect::<Vec<CategoryEntry>>(),
                 orders: orders.iter().map(|x| x.1.clone()).collect::<Vec<Order>>(),
                 ..Account::create()
             };
@@ -392,11 +5571,7 @@ mod tests {
             let filter_2 = Filter {
                 tag_option: CategoryFilter::Enabled(vec![tags[1].clone()]),
                 resource_option: CategoryFilter::Enabled(resources.to_vec()),
-                state_option: [
-                    ItemSelector::Selected,
-                    ItemSelector::Discarded,
-                    ItemSelector::Selected,
-                ],
+                state_option: StateFilter::from_mask(0b101).unwrap(),
                 ..Filter::default()
             };
 
@@ -429,91 +5604,101 @@ mod tests {
             ];
             let mut saved_account = Account {
                 label: "A year of wonderful things! 🙏".into(),
-                resources: resources.to_vec(),
-                tags: tags.to_vec(),
+                resources: resources.iter().map(|x| CategoryEntry::new(x)).collect(),
+                tags: tags.iter().map(|x| CategoryEntry::new(x)).collect(),
                 orders: vec![
                     Order {
                         description: "Initial amount".into(),
                         date: Some(NaiveDate::from_ymd(2020, 1, 1)),
-                        resource: Some(resources[0].clone()),
+                        resource: Some(resources[0].clone().into()),
                         tags: Vec::new(),
-                        amount: 1000.0,
+                        amount: Amount::from_cents(100000),
                         state: TransactionState::Done,
-                        visible: true,
+                        hidden: None,
+                        ..Order::default()
                     },
                     Order {
                         description: "Initial amount".into(),
                         date: Some(NaiveDate::from_ymd(2020, 1, 1)),
-                        resource: Some(resources[1].clone()),
+                        resource: Some(resources[1].clone().into()),
                         tags: Vec::new(),
-                        amount: 53.5,
+                        amount: Amount::from_cents(5350),
                         state: TransactionState::Done,
-                        visible: true,
+                        hidden: None,
+                        ..Order::default()
                     },
                     Order {
                         description: "Initial amount".into(),
                         date: Some(NaiveDate::from_ymd(2020, 1, 1)),
-                        resource: Some(resources[2].clone()),
+                        resource: Some(resources[2].clone().into()),
                         tags: Vec::new(),
-                        amount: 250.0,
+                        amount: Amount::from_cents(25000),
                         state: TransactionState::Done,
-                        visible: true,
+                        hidden: None,
+                        ..Order::default()
                     },
                     Order {
                         description: "My Anniversary 🎂".into(),
                         date: Some(NaiveDate::from_ymd(2020, 11, 10)),
-                        resource: Some(resources[1].clone()),
-                        tags: vec![tags[7].clone()],
-                        amount: 50.0,
+                        resource: Some(resources[1].clone().into()),
+                        tags: vec![tags[7].clone().into()],
+                        amount: Amount::from_cents(5000),
                         state: TransactionState::Pending,
-                        visible: true,
+                        hidden: None,
+                        ..Order::default()
                     },
                     Order {
                         description: "Gift".into(),
                         date: Some(NaiveDate::from_ymd(2020, 6, 20)),
-                        resource: Some(resources[4].clone()),
-                        tags: vec![tags[7].clone()],
-                        amount: 50.0,
+                        resource: Some(resources[4].clone().into()),
+                        tags: vec![tags[7].clone().into()],
+                        amount: Amount::from_cents(5000),
                         state: TransactionState::Pending,
-                        visible: true,
+                        hidden: None,
+                        ..Order::default()
                     },
                     Order {
                         description: "Restaurant".into(),
                         date: Some(NaiveDate::from_ymd(2020, 3, 4)),
-                        resource: Some(resources[1].clone()),
-                        tags: vec![tags[0].clone()],
-                        amount: -44.7,
+                        resource: Some(resources[1].clone().into()),
+                        tags: vec![tags[0].clone().into()],
+                        amount: Amount::from_cents(-4470),
                         state: TransactionState::InProgress,
-                        visible: true,
+                        hidden: None,
+                        ..Order::default()
                     },
                     Order {
                         description: "Metro".into(),
                         date: Some(NaiveDate::from_ymd(2020, 3, 4)),
-                        resource: Some(resources[1].clone()),
-                        tags: vec![tags[3].clone()],
-                        amount: -12.99,
+                        resource: Some(resources[1].clone().into()),
+                        tags: vec![tags[3].clone().into()],
+                        amount: Amount::from_cents(-1299),
                         state: TransactionState::Done,
-                        visible: true,
+                        hidden: None,
+                        ..Order::default()
                     },
                     Order {
                         description: "Music".into(),
                         date: Some(NaiveDate::from_ymd(2020, 3, 10)),
-                        resource: Some(resources[0].clone()),
-                        tags: vec![tags[1].clone()],
-                        amount: -13.99,
+                        resource: Some(resources[0].clone().into()),
+                        tags: vec![tags[1].clone().into()],
+                        amount: Amount::from_cents(-1399),
                         state: TransactionState::InProgress,
-                        visible: true,
+                        hidden: None,
+                        ..Order::default()
                     },
                     Order {
                         description: "Music II".into(),
                         date: Some(NaiveDate::from_ymd(2020, 3, 10)),
-                        resource: Some(resources[3].clone()),
-                        tags: vec![tags[1].clone(), tags[7].clone()],
-                        amount: -13.99,
+                        resource: Some(resources[3].clone().into()),
+                        tags: vec![tags[1].clone().into(), tags[7].clone().into()],
+                        amount: Amount::from_cents(-1399),
                         state: TransactionState::InProgress,
-                        visible: true,
+                        hidden: None,
+                        ..Order::default()
                     },
                 ],
+                ..Account::create()
             };
 
             (1..=12).for_each(|month| {
@@ -526,59 +5711,68 @@ mod tests {
                 saved_account.orders.push(Order {
                     description: "Salary".into(),
                     date: Some(NaiveDate::from_ymd(2020, month, 3)),
-                    resource: Some(resources[0].clone()),
-                    tags: vec![tags[8].clone()],
-                    amount: 2500.0,
+                    resource: Some(resources[0].clone().into()),
+                    tags: vec![tags[8].clone().into()],
+                    amount: Amount::from_cents(250000),
                     state: order_state,
-                    visible: true,
+                    hidden: None,
+                    ..Order::default()
                 });
                 saved_account.orders.push(Order {
                     description: "Loan".into(),
                     date: Some(NaiveDate::from_ymd(2020, month, 6)),
-                    resource: Some(resources[0].clone()),
-                    tags: tags[5..=6].to_vec(),
-                    amount: -600.0,
+                    resource: Some(resources[0].clone().into()),
+                    tags: tags[5..=6].iter().map(Interned::from).collect(),
+                    amount: Amount::from_cents(-60000),
                     state: order_state,
-                    visible: true,
+                    hidden: None,
+                    ..Order::default()
                 });
                 saved_account.orders.push(Order {
                     description: "GamePass Ultimate".into(),
                     date: Some(NaiveDate::from_ymd(2020, month, 15)),
-                    resource: Some(resources[2].clone()),
-                    tags: tags[1..=2].to_vec(),
-                    amount: -14.99,
+                    resource: Some(resources[2].clone().into()),
+                    tags: tags[1..=2].iter().map(Interned::from).collect(),
+                    amount: Amount::from_cents(-1499),
                     state: order_state,
-                    visible: true,
+                    hidden: None,
+                    ..Order::default()
                 });
                 saved_account.orders.push(Order {
                     description: "Transfert".into(),
                     date: Some(NaiveDate::from_ymd(2020, month, 25)),
-                    resource: Some(resources[0].clone()),
+                    resource: Some(resources[0].clone().into()),
                     tags: Vec::new(),
-                    amount: -20.0,
+                    amount: Amount::from_cents(-2000),
                     state: order_state,
-                    visible: true,
+                    hidden: None,
+                    ..Order::default()
                 });
                 saved_account.orders.push(Order {
                     description: "Transfert".into(),
                     date: Some(NaiveDate::from_ymd(2020, month, 25)),
-                    resource: Some(resources[2].clone()),
+                    resource: Some(resources[2].clone().into()),
                     tags: Vec::new(),
-                    amount: 20.0,
+                    amount: Amount::from_cents(2000),
                     state: order_state,
-                    visible: true,
+                    hidden: None,
+                    ..Order::default()
                 });
                 saved_account.orders.push(Order {
                     description: "Gazoline".into(),
                     date: Some(NaiveDate::from_ymd(2020, month, 23)),
-                    resource: Some(resources[0].clone()),
-                    tags: tags[3..=5].to_vec(),
-                    amount: -62.5,
+                    resource: Some(resources[0].clone().into()),
+                    tags: tags[3..=5].iter().map(Interned::from).collect(),
+                    amount: Amount::from_cents(-6250),
                     state: order_state,
-                    visible: true,
+                    hidden: None,
+                    ..Order::default()
                 });
             });
 
+            // Stamp ids as done when orders are created
+            saved_account.assign_order_ids();
+
             // Serialize over a file
             if let Err(error) = saved_account.save_file(Path::new("data.yml")) {
                 println!("{}", error);
@@ -590,6 +5784,552 @@ mod tests {
             loaded_account = Account::try_from(File::open("data.yml").unwrap()).unwrap();
 
             assert_eq!(loaded_account, saved_account);
+            assert_eq!(
+                Account::from_json(&saved_account.to_json()).unwrap(),
+                saved_account
+            );
+            assert_eq!(Account::from_json("{\"label\": 3}").is_err(), true);
+        }
+
+        #[cfg(feature = "toml")]
+        #[test]
+        fn save_load_toml_file() {
+            let mut account = Account::create();
+            account.set_label("Household");
+            assert_eq!(account.add_tag("Food"), None);
+            account.add_resource("Card");
+            account.set_resource_kind("Card", Some(ResourceKind::CreditCard));
+            account.set_resource_opening_balance("Card", 120.5, "2021-01-01");
+            account.set_resource_limit(
+                "Card",
+                Some(BalanceLimit::Credit(Amount::from_cents(50000))),
+            );
+            assert_eq!(account.set_tag_budget("Food", Period::Month, 300.0), None);
+            let (tags, resources) = (account.tags(), account.resources());
+            let id = account.add_order();
+            let mut order = account.get_order_mut(id).unwrap();
+            order.description = "Groceries".into();
+            order.date = Some(NaiveDate::from_ymd(2021, 3, 2));
+            order.amount = Amount::from_cents(-4250);
+            order.add_tag("Food", &tags);
+            order.set_resource("Card", &resources);
+            drop(order);
+            account.add_order();
+
+            let directory = test_directory("save_load_toml_file");
+            let path = directory.join("money.TOML");
+            account.save_file(&path).unwrap();
+            let content = std::fs::read_to_string(&path).unwrap();
+            let loaded = Account::load_file(&path).unwrap();
+            std::fs::remove_dir_all(&directory).unwrap();
+
+            assert_eq!(
+                content.starts_with("version = 1\nlabel = \"Household\""),
+                true
+            );
+            assert_eq!(loaded, account);
+            assert_eq!(Account::from_toml("label = 3").is_err(), true);
+        }
+
+        #[test]
+        fn rotate_backups_on_save() {
+            let directory = test_directory("rotate_backups_on_save");
+            let path = directory.join("money.yml");
+            let mut account = Account::create();
+
+            assert_eq!(
+                account.set_backup_policy(Some(BackupPolicy {
+                    keep: 0,
+                    folder: false
+                })),
+                Some(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(
+                account.set_backup_policy(Some(BackupPolicy {
+                    keep: 2,
+                    folder: false
+                })),
+                None
+            );
+            (1..=4).for_each(|index| {
+                account.set_label(&index.to_string());
+                account.save_file(&path).unwrap();
+            });
+            let backups = account.backups(&path).unwrap();
+            let labels = backups
+                .iter()
+                .map(|backup| Account::load_file(backup).unwrap().label().clone())
+                .collect::<Vec<String>>();
+            let loaded = Account::load_file(&path).unwrap();
+            std::fs::remove_dir_all(&directory).unwrap();
+
+            assert_eq!(labels, vec!["2", "3"]);
+            assert_eq!(loaded.backup_policy(), account.backup_policy());
+            account.set_backup_policy(None);
+            assert_eq!(account.backups(&path).unwrap().is_empty(), true);
+        }
+
+        #[cfg(feature = "compression")]
+        #[test]
+        fn save_load_compressed_file() {
+            let mut account = Account::create();
+            account.set_label("Household");
+            (0..100).for_each(|_| {
+                account.add_order();
+            });
+            let directory = test_directory("save_load_compressed_file");
+            let path = directory.join("money.yml.gz");
+
+            account.save_file(&path).unwrap();
+            let content = std::fs::read(&path).unwrap();
+            let loaded = Account::load_file(&path).unwrap();
+            std::fs::remove_dir_all(&directory).unwrap();
+
+            assert_eq!(compression::is_compressed(&content), true);
+            assert_eq!(
+                content.len() * 10 < serde_yaml::to_string(&account).unwrap().len(),
+                true
+            );
+            assert_eq!(loaded, account);
+            assert_eq!(
+                Account::from_bytes(&account.to_compressed()).unwrap(),
+                account
+            );
+            assert_eq!(
+                Account::from_bytes(serde_yaml::to_string(&account).unwrap().as_bytes()).unwrap(),
+                account
+            );
+            assert_eq!(Account::from_bytes(&[0x1f, 0x8b, 0]).is_err(), true);
+        }
+
+        #[cfg(feature = "encryption")]
+        #[test]
+        fn save_load_encrypted_file() {
+            let mut account = Account::create();
+            account.set_label("Household");
+            account.add_order();
+            let directory = test_directory("save_load_encrypted_file");
+            let path = directory.join("money.yml");
+
+            account.save_file_encrypted(&path, "correct horse").unwrap();
+            let content = std::fs::read(&path).unwrap();
+            let loaded = Account::load_file_encrypted(&path, "correct horse").unwrap();
+            let wrong = Account::load_file_encrypted(&path, "wrong horse");
+            let plain = Account::load_file(&path);
+
+            // Changed by another device since loaded
+            std::fs::write(&path, b"label: Shared").unwrap();
+            File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(10))
+                .unwrap();
+            let conflict = loaded.save_file_encrypted(&path, "correct horse");
+            std::fs::remove_dir_all(&directory).unwrap();
+
+            assert_eq!(
+                conflict.map_err(|error| matches!(error, SaveError::Conflict(_))),
+                Err(true)
+            );
+
+            assert_eq!(content.windows(9).any(|bytes| bytes == b"Household"), false);
+            assert_eq!(loaded.label(), "Household");
+            assert_eq!(loaded.orders(), account.orders());
+            assert_eq!(wrong.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+            assert_eq!(plain.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+            assert_eq!(
+                account.to_encrypted("").err(),
+                Some(RequestFailure::EmptyArgument)
+            );
+        }
+
+        #[cfg(feature = "import")]
+        #[test]
+        fn import_ofx_statement() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            assert_eq!(account.set_currency("EUR"), None);
+            let statement = |fitid: &str| {
+                format!(
+                    "<OFX><CURDEF>EUR<STMTTRN><DTPOSTED>20210302<TRNAMT>-4.20\
+                     <FITID>{}<NAME>Bakery</STMTTRN></OFX>",
+                    fitid
+                )
+            };
+
+            assert_eq!(
+                account.import_ofx(&statement("TX-1"), "Cash"),
+                Err(RequestFailure::UnknownItem)
+            );
+            assert_eq!(
+                account.import_ofx("Bakery;-4.20", "Bank"),
+                Err(RequestFailure::IncorrectArgument)
+            );
+            assert_eq!(account.import_ofx(&statement("TX-1"), "Bank"), Ok(vec![0]));
+
+            let order = &account.orders()[0];
+            assert_eq!(order.state(), TransactionState::Pending);
+            assert_eq!(order.resource, Some("Bank".into()));
+            assert_eq!(order.currency, None);
+            assert_eq!(order.metadata()[import::preview::IMPORT_ID_KEY], "TX-1");
+
+            // Same statement downloaded twice, then an identical transaction
+            assert_eq!(account.import_ofx(&statement("TX-1"), "Bank"), Ok(vec![]));
+            assert_eq!(account.import_ofx(&statement("TX-2"), "Bank"), Ok(vec![1]));
+        }
+
+        #[test]
+        fn merge_accounts() {
+            let add = |account: &mut Account, description: &str, cents: i64, resource: &str| {
+                let resources = account.resources();
+                let id = account.add_order();
+                let mut order = account.get_order_mut(id).unwrap();
+                order.description = description.into();
+                order.amount = Amount::from_cents(cents);
+                order.set_resource(resource, &resources);
+                id
+            };
+            let accounts = || {
+                let mut ours = Account::create();
+                ours.add_resource("Bank");
+                ours.add_tag("Food");
+                add(&mut ours, "Bakery", -420, "Bank");
+                let rent = add(&mut ours, "Rent", -60000, "Bank");
+                assert_eq!(ours.set_order_reference(rent, "CHQ-1"), None);
+                let plumber = add(&mut ours, "Plumber", -1500, "Bank");
+                assert_eq!(ours.set_order_reference(plumber, "CHQ-2"), None);
+
+                let mut theirs = Account::create();
+                theirs.add_resource("Bank");
+                theirs.add_resource("Cash");
+                theirs.add_tag("Leisure");
+                assert_eq!(theirs.add_custom_field("Receipt", FieldType::Bool), None);
+                assert_eq!(theirs.add_place("Cinema", 48.8606, 2.3376, 200.0), None);
+                assert_eq!(theirs.set_tag_budget("Leisure", Period::Month, 50.0), None);
+                add(&mut theirs, "Bakery", -420, "Bank");
+                let rent = add(&mut theirs, "Rent", -65000, "Bank");
+                assert_eq!(theirs.set_order_reference(rent, "CHQ-1"), None);
+                let cinema = add(&mut theirs, "Cinema", -1200, "Cash");
+                let refund = add(&mut theirs, "Refund", 1200, "Cash");
+                assert_eq!(theirs.link_order(refund, cinema), None);
+                // Same amount, different description
+                let plumber = add(&mut theirs, "Plumber (kitchen)", -1500, "Bank");
+                assert_eq!(theirs.set_order_reference(plumber, "CHQ-2"), None);
+                (ours, theirs)
+            };
+
+            let (mut merged, theirs) = accounts();
+            let report = merged.merge(theirs, MergeStrategy::KeepBoth);
+            assert_eq!(report.added, vec![3, 4, 5, 6]);
+            assert_eq!(report.duplicates, 1);
+            assert_eq!(
+                report
+                    .conflicts
+                    .iter()
+                    .map(|conflict| conflict.id)
+                    .collect::<Vec<usize>>(),
+                vec![1, 2]
+            );
+            assert_eq!(report.conflicts[0].ours.amount, Amount::from_cents(-60000));
+            assert_eq!(
+                report.conflicts[0].theirs.amount,
+                Amount::from_cents(-65000)
+            );
+            // References stay unique within a resource
+            assert_eq!(merged.get_order(3).unwrap().reference(), None);
+            assert_eq!(merged.get_order(6).unwrap().reference(), None);
+            assert_eq!(
+                merged.get_order(1).unwrap().reference(),
+                Some(&"CHQ-1".to_string())
+            );
+            assert_eq!(merged.resources(), vec!["Bank", "Cash"]);
+            assert_eq!(merged.tags(), vec!["Food", "Leisure"]);
+            assert_eq!(merged.get_order(5).unwrap().related_order(), Some(4));
+            assert_eq!(merged.custom_fields().len(), 1);
+            assert_eq!(merged.places().len(), 1);
+            assert_eq!(merged.tag_budgets().len(), 1);
+
+            let (mut merged, theirs) = accounts();
+            let report = merged.merge(theirs, MergeStrategy::KeepTheirs);
+            assert_eq!(report.added, vec![3, 4]);
+            assert_eq!(
+                merged.get_order(1).unwrap().amount,
+                Amount::from_cents(-65000)
+            );
+            assert_eq!(
+                merged.get_order(2).unwrap().description,
+                "Plumber (kitchen)"
+            );
+
+            // Reconciled orders match a bank statement
+            let (mut merged, theirs) = accounts();
+            merged.get_order_mut(1).unwrap().reconciled = true;
+            let report = merged.merge(theirs, MergeStrategy::KeepTheirs);
+            assert_eq!(report.conflicts.len(), 2);
+            assert_eq!(
+                merged.get_order(1).unwrap().amount,
+                Amount::from_cents(-60000)
+            );
+
+            let (mut merged, theirs) = accounts();
+            let report = merged.merge(theirs, MergeStrategy::KeepOurs);
+            assert_eq!(report.added, vec![3, 4]);
+            assert_eq!(report.conflicts.len(), 2);
+            assert_eq!(
+                merged.get_order(1).unwrap().amount,
+                Amount::from_cents(-60000)
+            );
+        }
+
+        #[test]
+        fn diff_accounts() {
+            let mut before = Account::create();
+            before.add_resource("Bank");
+            before.add_tag("Food");
+            before.add_project("Kitchen");
+            (0..3).for_each(|_| {
+                before.add_order();
+            });
+            let mut after = Account::from_json(&before.to_json()).unwrap();
+            after.add_tag("Leisure");
+            assert_eq!(after.set_project_budget("Kitchen", 5000.0), None);
+            after.add_project("Trip");
+            assert_eq!(after.remove_tag("Food"), None);
+            assert_eq!(after.delete_order(0), true);
+            after.get_order_mut(1).unwrap().amount = Amount::from_cents(-420);
+            after.add_order();
+
+            assert_eq!(before.diff(&before).is_empty(), true);
+            let diff = before.diff(&after);
+            assert_eq!(
+                diff.added.iter().map(Order::id).collect::<Vec<usize>>(),
+                vec![3]
+            );
+            assert_eq!(
+                diff.removed.iter().map(Order::id).collect::<Vec<usize>>(),
+                vec![0]
+            );
+            assert_eq!(diff.modified.len(), 1);
+            assert_eq!(diff.modified[0].before.id(), 1);
+            assert_eq!(diff.modified[0].fields, vec!["amount"]);
+            assert_eq!(diff.tags.added, vec!["Leisure"]);
+            assert_eq!(diff.tags.removed, vec!["Food"]);
+            assert_eq!(diff.resources.is_empty(), true);
+            assert_eq!(diff.projects.added, vec!["Trip"]);
+            assert_eq!(diff.projects.modified, vec!["Kitchen"]);
+
+            let json = serde_json::json!(diff);
+            assert_eq!(json["modified"][0]["fields"], serde_json::json!(["amount"]));
+            assert_eq!(json["tags"]["added"], serde_json::json!(["Leisure"]));
+        }
+
+        #[cfg(feature = "import")]
+        #[test]
+        fn import_csv_rows() {
+            let mut account = Account::create();
+            account.add_resource("Bank");
+            let content = "date,label,amount,account\n\
+                           2021-03-02,Bakery,-4.20,Bank\n\
+                           2021-03-02,Bakery,-4.20,Bank\n\
+                           2021-03-03,Salary,\"1,234.56\",\n\
+                           2021-03-04,Rent,-600,Savings\n\
+                           2021-03-05,Refund,abc,Bank\n";
+            let mapping = CsvMapping {
+                resource: Some(3),
+                ..CsvMapping::default()
+            };
+            let report = account.import_csv(content, &mapping);
+
+            assert_eq!(report.created, vec![0, 1, 2]);
+            assert_eq!(report.duplicates, 0);
+            assert_eq!(
+                report
+                    .errors
+                    .iter()
+                    .map(|error| (error.line, error.failure))
+                    .collect::<Vec<(usize, CsvRowFailure)>>(),
+                vec![
+                    (5, CsvRowFailure::UnknownResource),
+                    (6, CsvRowFailure::IncorrectAmount)
+                ]
+            );
+            assert_eq!(account.orders()[0].resource, Some("Bank".into()));
+            assert_eq!(account.orders()[2].resource, None);
+            assert_eq!(account.orders()[2].amount, Amount::from_cents(123456));
+            assert_eq!(account.orders()[2].state(), TransactionState::Done);
+
+            // Importing the same export again
+            let report = account.import_csv(content, &mapping);
+
+            assert_eq!(report.created.is_empty(), true);
+            assert_eq!(report.duplicates, 3);
+            assert_eq!(report.errors.len(), 2);
+        }
+
+        #[test]
+        fn detect_file_changed_since_loaded() {
+            let directory = test_directory("detect_file_changed_since_loaded");
+            let path = directory.join("money.yml");
+            let mut account = Account::create();
+            account.add_tag("Food");
+            account.save_file(&path).unwrap();
+
+            // Another process adds an order
+            let mut other = Account::load_file(&path).unwrap();
+            other.add_tag("Travel");
+            other.add_order();
+            other.save_file(&path).unwrap();
+            File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(10))
+                .unwrap();
+
+            account.add_order();
+            let error = account.save_file(&path).unwrap_err();
+
+            assert_eq!(
+                match &error {
+                    SaveError::Conflict(conflict) => Some(&conflict.path),
+                    _ => None,
+                },
+                Some(&path)
+            );
+
+            account
+                .save_file_resolving(&path, ConflictResolution::ReloadAndMerge)
+                .unwrap();
+            let merged = Account::load_file(&path).unwrap();
+
+            assert_eq!(merged.tags(), &["Food", "Travel"]);
+            assert_eq!(merged.orders().len(), 2);
+            // Saved files are not in conflict anymore
+            assert_eq!(account.save_file(&path).is_ok(), true);
+
+            std::fs::remove_dir_all(&directory).unwrap();
+        }
+
+        /// Account of 3 orders saved into a file, then loaded by another process.
+        fn shared_account(path: &Path) -> (Account, Account) {
+            let mut account = Account::create();
+            (0..3).for_each(|_| {
+                account.add_order();
+            });
+            account.save_file(path).unwrap();
+            let other = Account::load_file(path).unwrap();
+            (account, other)
+        }
+
+        #[test]
+        fn merge_concurrent_edits_on_save() {
+            let directory = test_directory("merge_concurrent_edits_on_save");
+            let path = directory.join("money.yml");
+            let (mut account, mut other) = shared_account(&path);
+
+            other.get_order_mut(0).unwrap().description = "Theirs".into();
+            other.get_order_mut(2).unwrap().description = "Theirs".into();
+            other.save_file(&path).unwrap();
+            account.get_order_mut(1).unwrap().description = "Ours".into();
+            account.get_order_mut(2).unwrap().description = "Ours".into();
+
+            let conflicts = account
+                .save_file_resolving(&path, ConflictResolution::ReloadAndMerge)
+                .unwrap();
+            let merged = Account::load_file(&path).unwrap();
+            std::fs::remove_dir_all(&directory).unwrap();
+
+            assert_eq!(
+                conflicts
+                    .iter()
+                    .map(|conflict| (conflict.id, conflict.theirs.description.as_str()))
+                    .collect::<Vec<(usize, &str)>>(),
+                [(2, "Theirs")]
+            );
+            assert_eq!(
+                merged
+                    .orders()
+                    .iter()
+                    .map(|order| (order.id(), order.description.as_str()))
+                    .collect::<Vec<(usize, &str)>>(),
+                [(0, "Theirs"), (1, "Ours"), (2, "Ours")]
+            );
+        }
+
+        #[test]
+        fn merge_concurrent_deletions_on_save() {
+            let directory = test_directory("merge_concurrent_deletions_on_save");
+            let path = directory.join("money.yml");
+            let (mut account, mut other) = shared_account(&path);
+
+            assert_eq!(other.delete_order(0), true);
+            other.get_order_mut(1).unwrap().description = "Theirs".into();
+            other.add_order();
+            other.save_file(&path).unwrap();
+            assert_eq!(account.delete_order(1), true);
+            assert_eq!(account.delete_order(2), true);
+
+            assert_eq!(
+                account
+                    .save_file_resolving(&path, ConflictResolution::ReloadAndMerge)
+                    .unwrap(),
+                Vec::new()
+            );
+            let merged = Account::load_file(&path).unwrap();
+            std::fs::remove_dir_all(&directory).unwrap();
+
+            // Deleted orders are not resurrected, unless changed meanwhile
+            assert_eq!(
+                merged
+                    .orders()
+                    .iter()
+                    .map(|order| (order.id(), order.description.as_str()))
+                    .collect::<Vec<(usize, &str)>>(),
+                [(1, "Theirs"), (3, "")]
+            );
+            assert_eq!(
+                merged
+                    .trash()
+                    .iter()
+                    .map(|item| item.order.id())
+                    .collect::<Vec<usize>>(),
+                [2, 0]
+            );
+        }
+
+        #[cfg(feature = "encryption")]
+        #[test]
+        fn merge_encrypted_file_on_save() {
+            let directory = test_directory("merge_encrypted_file_on_save");
+            let path = directory.join("money.yml");
+            let mut account = Account::create();
+            account.add_order();
+            account.save_file_encrypted(&path, "correct horse").unwrap();
+
+            let mut other = Account::load_file_encrypted(&path, "correct horse").unwrap();
+            other.get_order_mut(0).unwrap().description = "Theirs".into();
+            other.save_file_encrypted(&path, "correct horse").unwrap();
+            account.add_order();
+
+            let wrong = account.save_file_encrypted_resolving(
+                &path,
+                "wrong horse",
+                ConflictResolution::ReloadAndMerge,
+            );
+            account
+                .save_file_encrypted_resolving(
+                    &path,
+                    "correct horse",
+                    ConflictResolution::ReloadAndMerge,
+                )
+                .unwrap();
+            let merged = Account::load_file_encrypted(&path, "correct horse").unwrap();
+            std::fs::remove_dir_all(&directory).unwrap();
+
+            assert_eq!(wrong.is_err(), true);
+            assert_eq!(merged.orders().len(), 2);
+            assert_eq!(merged.orders()[0].description, "Theirs");
         }
     }
 }
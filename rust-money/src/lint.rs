@@ -0,0 +1,173 @@
+//! # Non-fatal suggestions to keep an `Account` tidy.
+use crate::ext::CategoryType;
+use crate::order::{OrderKind, TransactionState};
+use crate::Account;
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use std::fmt;
+
+/// Age from which a pending order is considered forgotten.
+pub const STALE_PENDING_DAYS: i64 = 30;
+
+/// Suggestion about account data.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type")]
+pub enum LintIssue {
+    /// Tag used by no order.
+    UnusedTag { tag: String },
+    /// Visible order without any tag.
+    UntaggedOrder { id: usize },
+    /// Pending order dated long ago.
+    StalePendingOrder { id: usize, date: NaiveDate },
+    /// Categories of the same type which only differ by their case,
+    /// punctuation or plural.
+    SimilarCategories {
+        category: CategoryType,
+        first: String,
+        second: String,
+    },
+    /// Done order dated in the future.
+    FutureDoneOrder { id: usize, date: NaiveDate },
+    /// Order whose explicit nature disagrees with its amount sign.
+    KindMismatch { id: usize, kind: OrderKind },
+    /// Order in another currency than its resource, without conversion.
+    CurrencyMismatch { id: usize, resource: String },
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LintIssue::UnusedTag { tag } => write!(f, "tag \"{}\" is not used", tag),
+            LintIssue::UntaggedOrder { id } => write!(f, "order #{} has no tag", id),
+            LintIssue::StalePendingOrder { id, date } => {
+                write!(f, "order #{} is still pending since {}", id, date)
+            }
+            LintIssue::SimilarCategories {
+                category,
+                first,
+                second,
+            } => write!(f, "{:?} \"{}\" looks like \"{}\"", category, first, second),
+            LintIssue::FutureDoneOrder { id, date } => {
+                write!(f, "order #{} is done but dated {}", id, date)
+            }
+            LintIssue::KindMismatch { id, kind } => {
+                write!(f, "order #{} is an {:?} of the wrong sign", id, kind)
+            }
+            LintIssue::CurrencyMismatch { id, resource } => write!(
+                f,
+                "order #{} is not in the currency of \"{}\"",
+                id, resource
+            ),
+        }
+    }
+}
+
+/// Reduces a category name to what identifies it.
+fn category_key(name: &str) -> String {
+    let key = name
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect::<String>();
+
+    match key.strip_suffix('s') {
+        Some(singular) if !singular.is_empty() => singular.into(),
+        _ => key,
+    }
+}
+
+/// Lists pairs of similar categories.
+fn similar_categories(category: CategoryType, names: &[String]) -> Vec<LintIssue> {
+    let keys = names
+        .iter()
+        .map(|name| category_key(name))
+        .collect::<Vec<String>>();
+
+    (0..names.len())
+        .flat_map(|i| (i + 1..names.len()).map(move |j| (i, j)))
+        .filter(|&(i, j)| keys[i] == keys[j])
+        .map(|(i, j)| LintIssue::SimilarCategories {
+            category,
+            first: names[i].clone(),
+            second: names[j].clone(),
+        })
+        .collect()
+}
+
+/// Checks an account as of a given date.
+pub fn lint(account: &Account, today: NaiveDate) -> Vec<LintIssue> {
+    let orders = account
+        .orders()
+        .iter()
+        .filter(|order| order.is_visible())
+        .collect::<Vec<_>>();
+    let mut issues = account
+        .tags()
+        .iter()
+        .filter(|tag| !account.orders().iter().any(|order| order.has_tag(tag)))
+        .map(|tag| LintIssue::UnusedTag { tag: tag.clone() })
+        .collect::<Vec<LintIssue>>();
+
+    issues.extend(
+        orders
+            .iter()
+            .filter(|order| order.tags.is_empty())
+            .map(|order| LintIssue::UntaggedOrder { id: order.id }),
+    );
+    issues.extend(
+        orders
+            .iter()
+            .filter_map(|order| match (order.state, order.date) {
+                (TransactionState::Pending, Some(date))
+                    if date < today - Duration::days(STALE_PENDING_DAYS) =>
+                {
+                    Some(LintIssue::StalePendingOrder { id: order.id, date })
+                }
+                _ => None,
+            }),
+    );
+    issues.extend(similar_categories(CategoryType::Tag, &account.tags()));
+    issues.extend(similar_categories(
+        CategoryType::Resource,
+        &account.resources(),
+    ));
+    issues.extend(similar_categories(CategoryType::Payee, account.payees()));
+    issues.extend(
+        orders
+            .iter()
+            .filter_map(|order| match (order.state, order.date) {
+                (TransactionState::Done, Some(date)) if date > today => {
+                    Some(LintIssue::FutureDoneOrder { id: order.id, date })
+                }
+                _ => None,
+            }),
+    );
+    issues.extend(
+        orders
+            .iter()
+            .filter(|order| order.is_kind_mismatched())
+            .map(|order| LintIssue::KindMismatch {
+                id: order.id,
+                kind: order.effective_kind(),
+            }),
+    );
+    issues.extend(account.currency_mismatches().into_iter().filter_map(|id| {
+        let resource = account.get_order(id)?.resource.as_deref()?.into();
+        Some(LintIssue::CurrencyMismatch { id, resource })
+    }));
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_category_keys() {
+        assert_eq!(category_key("Restaurants"), category_key("restaurant"));
+        assert_eq!(category_key("E-Shop"), category_key("eshop"));
+        assert_ne!(category_key("Food"), category_key("Fuel"));
+        assert_eq!(category_key("s"), "s");
+    }
+}
@@ -0,0 +1,60 @@
+//! # Combination of accounts tracked separately.
+use crate::order::Order;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasmbind")]
+use wasm_bindgen::prelude::*;
+
+/// Handling of different orders sharing a reference on a resource.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum MergeStrategy {
+    /// Keeps the order of the account.
+    KeepOurs,
+    /// Replaces the order of the account by the other one, keeping its id.
+    /// Reconciled orders are kept, as they match a bank statement.
+    KeepTheirs,
+    /// Adds the other order too, without its reference which must stay
+    /// unique within a resource.
+    KeepBoth,
+}
+
+/// Different orders of both accounts sharing a reference on a resource, to
+/// be resolved manually.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct MergeConflict {
+    /// Order of the account in conflict.
+    pub id: usize,
+    /// Order of the account, before merging.
+    pub ours: Order,
+    /// Order of the other account.
+    pub theirs: Order,
+}
+
+/// Outcome of a merge.
+#[derive(Serialize, Clone, PartialEq, Default, Debug)]
+pub struct MergeReport {
+    /// Ids of orders added from the other account.
+    pub added: Vec<usize>,
+    /// Number of orders of the other account already present.
+    pub duplicates: usize,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Returns `true` if orders hold the same data, whatever their ids.
+pub(crate) fn is_duplicate(ours: &Order, theirs: &Order) -> bool {
+    *ours
+        == Order {
+            id: ours.id,
+            related_order: ours.related_order,
+            ..theirs.clone()
+        }
+}
+
+/// Returns `true` if orders share a reference on a resource but differ
+/// otherwise.
+pub(crate) fn is_conflicting(ours: &Order, theirs: &Order) -> bool {
+    ours.reference.is_some()
+        && ours.reference == theirs.reference
+        && ours.resource == theirs.resource
+        && !is_duplicate(ours, theirs)
+}
@@ -0,0 +1,162 @@
+//! # Upgrade of files written by previous versions.
+//!
+//! Data stores the version of its schema. Changing the model requires to
+//! increase `SCHEMA_VERSION` and to append a migration producing it, which
+//! upgrades raw data written by previous versions.
+use crate::SCHEMA_VERSION;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+/// Record of a migration applied while loading a file.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct AppliedMigration {
+    /// Stable identifier.
+    pub id: &'static str,
+    /// Human-readable summary.
+    pub description: &'static str,
+    /// Number of upgraded records.
+    pub affected: usize,
+}
+
+/// Upgrade step of raw data, returning the number of upgraded records.
+struct Migration {
+    id: &'static str,
+    description: &'static str,
+    /// Schema version of upgraded data.
+    version: u32,
+    apply: fn(&mut Value) -> usize,
+}
+
+/// Known migrations, in application order.
+const MIGRATIONS: [Migration; 2] = [
+    Migration {
+        id: "order-ids",
+        description: "Assigned an identifier to orders without one",
+        version: 1,
+        apply: assign_order_ids,
+    },
+    Migration {
+        id: "hidden-reason",
+        description: "Replaced the visibility flag of orders by a hidden reason",
+        version: 1,
+        apply: replace_visibility_flag,
+    },
+];
+
+/// Returns the schema version of raw account data; `0` if written before
+/// versions were stored.
+pub(crate) fn version(data: &Value) -> u64 {
+    data.get("version").and_then(Value::as_u64).unwrap_or(0)
+}
+
+/// Applies migrations newer than raw account data, then stamps it with the
+/// current schema version.
+pub(crate) fn migrate(data: &mut Value) -> Vec<AppliedMigration> {
+    let version = version(data);
+    let applied = MIGRATIONS
+        .iter()
+        .filter(|migration| u64::from(migration.version) > version)
+        .filter_map(|migration| {
+            let affected = (migration.apply)(data);
+
+            if affected > 0 {
+                Some(AppliedMigration {
+                    id: migration.id,
+                    description: migration.description,
+                    affected,
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if let Some(data) = data.as_mapping_mut() {
+        data.insert(Value::from("version"), Value::from(SCHEMA_VERSION));
+    }
+    applied
+}
+
+/// Returns the raw orders, if any.
+fn orders_mut(data: &mut Value) -> impl Iterator<Item = &mut Mapping> {
+    data.get_mut("orders")
+        .and_then(Value::as_sequence_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_mapping_mut)
+}
+
+/// Orders used to be identified by their position.
+fn assign_order_ids(data: &mut Value) -> usize {
+    let id = Value::from("id");
+
+    orders_mut(data)
+        .enumerate()
+        .filter(|(_, order)| !order.contains_key(&id))
+        .map(|(index, order)| order.insert(id.clone(), Value::from(index as u64)))
+        .count()
+}
+
+/// Hidden orders used to be flagged with `visible: false`.
+fn replace_visibility_flag(data: &mut Value) -> usize {
+    let visible = Value::from("visible");
+
+    orders_mut(data)
+        .filter_map(|order| order.remove(&visible).map(|flag| (order, flag)))
+        .map(|(order, flag)| {
+            if flag == Value::Bool(false) {
+                order.insert(Value::from("hidden"), Value::from("Manual"));
+            }
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrate_legacy_orders() {
+        let mut data: Value = serde_yaml::from_str(
+            "orders:\n  \
+             - {description: A, visible: true}\n  \
+             - {description: B, visible: false}\n  \
+             - {id: 7, description: C}\n",
+        )
+        .unwrap();
+        let expected: Value = serde_yaml::from_str(
+            "version: 1\norders:\n  \
+             - {id: 0, description: A}\n  \
+             - {id: 1, description: B, hidden: Manual}\n  \
+             - {id: 7, description: C}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            migrate(&mut data)
+                .iter()
+                .map(|migration| (migration.id, migration.affected))
+                .collect::<Vec<(&str, usize)>>(),
+            [("order-ids", 2), ("hidden-reason", 2)]
+        );
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn skip_up_to_date_data() {
+        let mut data: Value =
+            serde_yaml::from_str("orders:\n  - {id: 0, description: A}\n").unwrap();
+
+        assert_eq!(migrate(&mut data), []);
+        assert_eq!(version(&data), u64::from(SCHEMA_VERSION));
+
+        // Fields of versioned data are never upgraded again
+        let mut data: Value =
+            serde_yaml::from_str("version: 1\norders:\n  - {description: A, visible: false}\n")
+                .unwrap();
+        let expected = data.clone();
+
+        assert_eq!(migrate(&mut data), []);
+        assert_eq!(data, expected);
+    }
+}
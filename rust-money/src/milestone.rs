@@ -0,0 +1,107 @@
+//! # Notifications when the balance of a resource crosses a threshold.
+use crate::amount::Amount;
+use crate::category::BalanceLimit;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasmbind")]
+use wasm_bindgen::prelude::*;
+
+/// Side of a threshold which triggers a notification.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum ThresholdDirection {
+    /// Warns when the balance falls below the threshold.
+    Below,
+    /// Celebrates when the balance rises above the threshold.
+    Above,
+}
+
+/// Balance threshold of a resource.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Milestone {
+    pub resource: String,
+    pub direction: ThresholdDirection,
+    pub threshold: Amount,
+    /// Balance when last checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last_balance: Option<Amount>,
+}
+
+/// Milestone crossed since the last check.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct CrossedMilestone {
+    pub resource: String,
+    pub direction: ThresholdDirection,
+    pub threshold: Amount,
+    pub balance: Amount,
+}
+
+/// Resource whose balance breaks its limit.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct ResourceAlert {
+    pub resource: String,
+    pub limit: BalanceLimit,
+    /// Balance of done orders.
+    pub current: Amount,
+    /// Balance once all orders are done.
+    pub expected: Amount,
+}
+
+impl Milestone {
+    /// Creates a milestone which has never been checked.
+    pub fn new(resource: &str, direction: ThresholdDirection, threshold: Amount) -> Milestone {
+        Milestone {
+            resource: resource.into(),
+            direction,
+            threshold,
+            last_balance: None,
+        }
+    }
+
+    /// Returns `true` if a balance is beyond the threshold.
+    pub fn is_reached(&self, balance: Amount) -> bool {
+        match self.direction {
+            ThresholdDirection::Below => balance < self.threshold,
+            ThresholdDirection::Above => balance > self.threshold,
+        }
+    }
+
+    /// Records the current balance; returns the crossing if the threshold has
+    /// just been reached. A first check reports an already reached threshold.
+    pub(crate) fn check(&mut self, balance: Amount) -> Option<CrossedMilestone> {
+        let was_reached = self.last_balance.is_some_and(|last| self.is_reached(last));
+        self.last_balance = Some(balance);
+
+        if self.is_reached(balance) && !was_reached {
+            Some(CrossedMilestone {
+                resource: self.resource.clone(),
+                direction: self.direction,
+                threshold: self.threshold,
+                balance,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notify_crossing_once() {
+        let mut milestone =
+            Milestone::new("Bank", ThresholdDirection::Below, Amount::from_cents(20000));
+
+        assert_eq!(milestone.check(Amount::from_cents(50000)), None);
+        assert_eq!(
+            milestone
+                .check(Amount::from_cents(15000))
+                .map(|crossed| crossed.balance),
+            Some(Amount::from_cents(15000))
+        );
+        assert_eq!(milestone.check(Amount::from_cents(10000)), None);
+        assert_eq!(milestone.check(Amount::from_cents(20000)), None);
+        assert_eq!(milestone.check(Amount::from_cents(19999)).is_some(), true);
+    }
+}
@@ -1,24 +1,237 @@
 //! # Management of a *transaction*.
 
 use super::ext::ExclusiveItemExt;
+use crate::amount::Amount;
+use crate::attachment::Attachment;
+use crate::category::TagResolver;
+use crate::columnar::ColumnCache;
+use crate::currency;
+use crate::field::{CustomField, FieldValue};
+use crate::filter::bitset::TagBitsetCache;
 use crate::filter::category::CategoryFilter;
 use crate::filter::date::NaiveDateFilter;
+use crate::filter::field::FieldFilter;
+use crate::filter::metadata::MetadataFilter;
 use crate::filter::{Filter, ItemSelector, VisibilityFilter};
-use chrono::{Local, NaiveDate};
-use serde::{Deserialize, Serialize};
+use crate::geo::Location;
+use crate::intern::Interned;
+use crate::project::Project;
+use chrono::{Local, NaiveDate, NaiveDateTime};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
 #[cfg(feature = "wasmbind")]
 use wasm_bindgen::prelude::*;
 
 /// Data associated to a unique transaction.
-#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+///
+/// Equality ignores creation and modification times.
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Order {
+    /// Unique identifier generated by `Account`.
+    #[serde(default)]
+    pub(crate) id: usize,
     pub date: Option<NaiveDate>,
-    pub description: String,
-    pub amount: f32,
-    pub(crate) resource: Option<String>,
-    pub(crate) tags: Vec<String>,
+    pub description: Interned,
+    /// Free multi-line details (invoice number, context, ..).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) notes: Option<String>,
+    pub amount: Amount,
+    /// Currency code of the amount; the account one if `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) currency: Option<String>,
+    /// Amount charged to the resource, when it holds another currency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) conversion: Option<Conversion>,
+    /// Cheque number or bank reference, unique within a resource.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) reference: Option<String>,
+    pub(crate) resource: Option<Interned>,
+    pub(crate) tags: Vec<Interned>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) payee: Option<String>,
     pub(crate) state: TransactionState,
-    pub visible: bool,
+    /// Explicit nature; deduced from the amount sign if `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) kind: Option<OrderKind>,
+    /// Why the order is hidden; visible if `None`.
+    /// Also loads the legacy `visible` flag.
+    #[serde(
+        default,
+        alias = "visible",
+        deserialize_with = "deserialize_hidden_reason",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) hidden: Option<HiddenReason>,
+    /// Matched against a bank statement, so locked.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) reconciled: bool,
+    /// Future order not listed until its date.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) scheduled: bool,
+    /// Free key-value pairs attached by integrations.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) metadata: BTreeMap<String, String>,
+    /// Values of custom fields declared by the account.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) fields: BTreeMap<String, FieldValue>,
+    /// Where the order took place.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) location: Option<Location>,
+    /// Documents related to the order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) attachments: Vec<Attachment>,
+    /// Breakdown of the amount, which is their sum when not empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) components: Vec<AmountComponent>,
+    /// Original order of a refund or chargeback.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) related_order: Option<usize>,
+    /// When added to the account; unknown for legacy orders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) created_at: Option<NaiveDateTime>,
+    /// When last edited through the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) updated_at: Option<NaiveDateTime>,
+}
+
+impl PartialEq for Order {
+    fn eq(&self, other: &Self) -> bool {
+        // Destructured to not forget new fields
+        let Order {
+            id,
+            date,
+            description,
+            notes,
+            amount,
+            currency,
+            conversion,
+            reference,
+            resource,
+            tags,
+            project,
+            payee,
+            state,
+            kind,
+            hidden,
+            reconciled,
+            scheduled,
+            metadata,
+            fields,
+            location,
+            attachments,
+            components,
+            related_order,
+            created_at: _,
+            updated_at: _,
+        } = self;
+
+        *id == other.id
+            && *date == other.date
+            && *description == other.description
+            && *notes == other.notes
+            && *amount == other.amount
+            && *currency == other.currency
+            && *conversion == other.conversion
+            && *reference == other.reference
+            && *resource == other.resource
+            && *tags == other.tags
+            && *project == other.project
+            && *payee == other.payee
+            && *state == other.state
+            && *kind == other.kind
+            && *hidden == other.hidden
+            && *reconciled == other.reconciled
+            && *scheduled == other.scheduled
+            && *metadata == other.metadata
+            && *fields == other.fields
+            && *location == other.location
+            && *attachments == other.attachments
+            && *components == other.components
+            && *related_order == other.related_order
+    }
+}
+
+/// Change applied to many orders at once.
+#[derive(Clone, PartialEq, Debug)]
+pub enum OrderPatch {
+    SetState(TransactionState),
+    /// Selects a resource among available ones.
+    SetResource(String),
+    /// Selects a tag among available ones.
+    AddTag(String),
+    RemoveTag(String),
+    /// Hides visible orders and shows hidden ones.
+    ToggleVisibility,
+}
+
+/// Data derived from the orders of an account, dropped whenever they change.
+#[derive(Default, PartialEq, Debug)]
+pub struct OrderCache {
+    pub tag_bitsets: TagBitsetCache,
+    pub columns: ColumnCache,
+}
+
+impl OrderCache {
+    /// Drops all derived data.
+    pub fn invalidate(&mut self) {
+        self.tag_bitsets.invalidate();
+        self.columns.invalidate();
+    }
+}
+
+/// Mutable access to an order of an account.
+///
+/// The order is recorded as modified once dropped, only if its data changed.
+pub struct OrderMut<'a> {
+    order: &'a mut Order,
+    before: Order,
+    cache: &'a mut OrderCache,
+}
+
+impl<'a> OrderMut<'a> {
+    pub(crate) fn new(order: &'a mut Order, cache: &'a mut OrderCache) -> Self {
+        OrderMut {
+            before: order.clone(),
+            order,
+            cache,
+        }
+    }
+}
+
+impl Deref for OrderMut<'_> {
+    type Target = Order;
+
+    fn deref(&self) -> &Order {
+        self.order
+    }
+}
+
+impl DerefMut for OrderMut<'_> {
+    fn deref_mut(&mut self) -> &mut Order {
+        self.order
+    }
+}
+
+impl Drop for OrderMut<'_> {
+    fn drop(&mut self) {
+        if *self.order != self.before {
+            self.order.touch();
+            self.cache.invalidate();
+        }
+    }
+}
+
+/// Named model of order, such as a monthly loan.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct OrderTemplate {
+    pub name: String,
+    /// Model whose *date* and attachments are ignored.
+    pub order: Order,
 }
 
 /// Different states for a given transaction.
@@ -33,16 +246,125 @@ pub enum TransactionState {
     Done = 2,
 }
 
+/// Nature of an order, whatever the sign of its amount.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrderKind {
+    Income,
+    Expense,
+    /// Money moved between two owned resources.
+    Transfer,
+}
+
+/// Different parts of an amount.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ComponentKind {
+    /// Price of goods or services.
+    Base,
+    Tip,
+    Fee,
+    Tax,
+}
+
+/// Labeled part of an amount.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct AmountComponent {
+    pub kind: ComponentKind,
+    pub amount: Amount,
+}
+
+/// Amount of an order in another currency.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Conversion {
+    /// *ISO 4217* code.
+    pub currency: String,
+    pub amount: Amount,
+}
+
+/// Different reasons to hide a given transaction.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HiddenReason {
+    /// Hidden by the user.
+    Manual,
+    /// Money moved between two owned resources.
+    InternalTransfer,
+    /// Same transaction recorded twice.
+    Duplicate,
+    /// Not relevant for this account.
+    OutOfScope,
+}
+
+/// Accepts a hidden reason, or the legacy `visible` boolean.
+struct HiddenReasonVisitor;
+
+impl<'de> Visitor<'de> for HiddenReasonVisitor {
+    type Value = Option<HiddenReason>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a hidden reason or a visibility flag")
+    }
+
+    fn visit_bool<E: de::Error>(self, visible: bool) -> Result<Self::Value, E> {
+        Ok(if visible {
+            None
+        } else {
+            Some(HiddenReason::Manual)
+        })
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        HiddenReason::deserialize(de::value::StrDeserializer::new(value)).map(Some)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+fn deserialize_hidden_reason<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<HiddenReason>, D::Error> {
+    deserializer.deserialize_any(HiddenReasonVisitor)
+}
+
 impl Default for Order {
     fn default() -> Self {
         Order {
+            id: 0,
             date: None,
-            description: "".to_string(),
-            amount: 0.0,
+            description: "".into(),
+            notes: None,
+            amount: Amount::ZERO,
+            currency: None,
+            conversion: None,
+            reference: None,
             resource: None,
             tags: Vec::new(),
+            project: None,
+            payee: None,
             state: TransactionState::Pending,
-            visible: true,
+            kind: None,
+            hidden: None,
+            reconciled: false,
+            scheduled: false,
+            metadata: BTreeMap::new(),
+            fields: BTreeMap::new(),
+            location: None,
+            attachments: Vec::new(),
+            components: Vec::new(),
+            related_order: None,
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -50,21 +372,35 @@ impl Default for Order {
 impl From<&Filter> for Order {
     fn from(filter: &Filter) -> Self {
         Order {
-            date: match filter.date_option {
-                NaiveDateFilter::DateIgnored => None,
+            date: match filter.date_option.resolve(Local::now().date_naive()) {
+                NaiveDateFilter::DateIgnored | NaiveDateFilter::Relative(_) => None,
                 NaiveDateFilter::Since(date) | NaiveDateFilter::Between(date, _) => Some(date),
                 NaiveDateFilter::Until(date) => Some(date),
             },
             // Pick-up the first selected one
             resource: if let CategoryFilter::Enabled(items) = &filter.resource_option {
-                if let Some(first_selected) = items
+                items
                     .iter()
                     .find(|resource| resource.1 == ItemSelector::Selected)
-                {
-                    Some(first_selected.0.clone())
-                } else {
-                    None
-                }
+                    .map(|first_selected| first_selected.0.as_str().into())
+            } else {
+                None
+            },
+            // Pick-up the first selected one
+            project: if let CategoryFilter::Enabled(items) = &filter.project_option {
+                items
+                    .iter()
+                    .find(|project| project.1 == ItemSelector::Selected)
+                    .map(|first_selected| first_selected.0.clone())
+            } else {
+                None
+            },
+            // Pick-up the first selected one
+            payee: if let CategoryFilter::Enabled(items) = &filter.payee_option {
+                items
+                    .iter()
+                    .find(|payee| payee.1 == ItemSelector::Selected)
+                    .map(|first_selected| first_selected.0.clone())
             } else {
                 None
             },
@@ -74,7 +410,7 @@ impl From<&Filter> for Order {
                     .iter()
                     .filter_map(|tag| {
                         if tag.1 == ItemSelector::Selected {
-                            Some(tag.0.clone())
+                            Some(tag.0.as_str().into())
                         } else {
                             None
                         }
@@ -84,23 +420,99 @@ impl From<&Filter> for Order {
                 Vec::new()
             },
             // Pick-up the first selected one
-            state: if let Some(first_selected) = filter
+            state: filter
                 .state_option
-                .iter()
-                .enumerate()
-                .find(|(_, &state)| state == ItemSelector::Selected)
-            {
-                unsafe { std::mem::transmute(first_selected.0 as u8) }
+                .first_selected()
+                .unwrap_or(TransactionState::Pending),
+            hidden: if let VisibilityFilter::HiddenOnly = filter.visibility {
+                Some(HiddenReason::Manual)
             } else {
-                TransactionState::Pending
+                None
+            },
+            // Pick-up the expected pair
+            metadata: if let MetadataFilter::WithValue(key, value) = &filter.metadata_option {
+                let mut metadata = BTreeMap::new();
+                metadata.insert(key.clone(), value.clone());
+                metadata
+            } else {
+                BTreeMap::new()
+            },
+            fields: if let FieldFilter::WithFieldValue(name, value) = &filter.field_option {
+                let mut fields = BTreeMap::new();
+                fields.insert(name.clone(), value.clone());
+                fields
+            } else {
+                BTreeMap::new()
             },
-            visible: !matches!(filter.visibility, VisibilityFilter::HiddenOnly),
             ..Order::default()
         }
     }
 }
 
 impl Order {
+    /// Gets the unique identifier.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Sets the notes; blank ones are removed.
+    pub fn set_notes(&mut self, notes: &str) {
+        self.notes = if notes.trim().is_empty() {
+            None
+        } else {
+            Some(notes.into())
+        };
+    }
+
+    /// Gets the notes.
+    pub fn notes(&self) -> Option<&String> {
+        self.notes.as_ref()
+    }
+
+    /// Sets the currency from an *ISO 4217* code.
+    /// Invalid codes are rejected.
+    pub fn set_currency(&mut self, code: &str) -> bool {
+        if let Some(code) = currency::normalize_code(code) {
+            self.currency = Some(code);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Uses the currency of the account.
+    pub fn clear_currency(&mut self) {
+        self.currency = None;
+    }
+
+    /// Gets the currency code, if different from the account one.
+    pub fn currency(&self) -> Option<&String> {
+        self.currency.as_ref()
+    }
+
+    /// Records the amount charged to the resource in its own currency.
+    /// Invalid codes are rejected.
+    #[cfg(feature = "fx")]
+    pub fn set_conversion(&mut self, code: &str, amount: Amount) -> bool {
+        if let Some(currency) = currency::normalize_code(code) {
+            self.conversion = Some(Conversion { currency, amount });
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes the converted amount.
+    #[cfg(feature = "fx")]
+    pub fn clear_conversion(&mut self) {
+        self.conversion = None;
+    }
+
+    /// Gets the amount charged to the resource, if converted.
+    pub fn conversion(&self) -> Option<&Conversion> {
+        self.conversion.as_ref()
+    }
+
     /// Selects the resource among available ones.
     pub fn set_resource(&mut self, resource: &str, list: &[String]) -> bool {
         if list.contains(&resource.into()) {
@@ -111,15 +523,69 @@ impl Order {
         }
     }
 
-    /// Selects a tag among available ones.
-    pub fn add_tag(&mut self, tag: &str, list: &[String]) -> bool {
-        if list.contains(&tag.into()) {
-            self.tags.add_exclusive(tag).is_none()
+    /// Selects the project among available ones.
+    pub fn set_project(&mut self, project: &str, list: &[Project]) -> bool {
+        if list.iter().any(|item| item.name == project) {
+            self.project = Some(project.into());
+            true
         } else {
             false
         }
     }
 
+    /// Detaches the order from its project.
+    pub fn clear_project(&mut self) {
+        self.project = None;
+    }
+
+    /// Gets the project.
+    pub fn project(&self) -> Option<&String> {
+        self.project.as_ref()
+    }
+
+    /// Selects the payee among available ones.
+    pub fn set_payee(&mut self, payee: &str, list: &[String]) -> bool {
+        if list.contains(&payee.into()) {
+            self.payee = Some(payee.into());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes the payee.
+    pub fn clear_payee(&mut self) {
+        self.payee = None;
+    }
+
+    /// Gets the payee.
+    pub fn payee(&self) -> Option<&String> {
+        self.payee.as_ref()
+    }
+
+    /// Gets the reference, if any.
+    pub fn reference(&self) -> Option<&String> {
+        self.reference.as_ref()
+    }
+
+    /// Gets the identifier of the original order, if any.
+    pub fn related_order(&self) -> Option<usize> {
+        self.related_order
+    }
+
+    /// Selects a tag among available ones; aliases add their canonical tag.
+    pub fn add_tag<T: TagResolver + ?Sized>(&mut self, tag: &str, list: &T) -> bool {
+        match list.resolve_tag(tag) {
+            Some(tag) => self.tags.add_exclusive(&tag).is_none(),
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the order holds a tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|item| item == tag)
+    }
+
     /// Removes a tag among added ones.
     pub fn remove_tag(&mut self, tag: &str) -> bool {
         self.tags.remove_exclusive(tag).is_none()
@@ -135,7 +601,7 @@ impl Order {
     pub fn set_state(&mut self, state: TransactionState) {
         if let TransactionState::Done = state {
             if self.date.is_none() {
-                self.date = Some(Local::today().naive_local());
+                self.date = Some(Local::now().date_naive());
             }
         }
 
@@ -146,11 +612,268 @@ impl Order {
     pub fn state(&self) -> TransactionState {
         self.state
     }
+
+    /// Sets the nature explicitly, or lets the amount sign decide.
+    pub fn set_kind(&mut self, kind: Option<OrderKind>) {
+        self.kind = kind;
+    }
+
+    /// Gets the explicit nature, if any.
+    pub fn kind(&self) -> Option<OrderKind> {
+        self.kind
+    }
+
+    /// Gets the nature, deduced from the amount sign unless explicit.
+    pub fn effective_kind(&self) -> OrderKind {
+        self.kind.unwrap_or(if self.amount.is_negative() {
+            OrderKind::Expense
+        } else {
+            OrderKind::Income
+        })
+    }
+
+    /// Returns `true` if the explicit nature disagrees with the amount sign.
+    pub fn is_kind_mismatched(&self) -> bool {
+        match self.kind {
+            Some(OrderKind::Income) => self.amount.is_negative(),
+            Some(OrderKind::Expense) => self.amount.is_positive(),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the order is not hidden.
+    pub fn is_visible(&self) -> bool {
+        self.hidden.is_none()
+    }
+
+    /// Hides the order for a given reason.
+    pub fn hide(&mut self, reason: HiddenReason) {
+        self.hidden = Some(reason);
+    }
+
+    /// Makes the order visible again.
+    pub fn show(&mut self) {
+        self.hidden = None;
+    }
+
+    /// Gets the reason why the order is hidden.
+    pub fn hidden_reason(&self) -> Option<HiddenReason> {
+        self.hidden
+    }
+
+    /// Returns `true` if the order is visible or hidden for one of the
+    /// `included` reasons.
+    pub fn is_counted(&self, included: &[HiddenReason]) -> bool {
+        match self.hidden {
+            None => true,
+            Some(reason) => included.contains(&reason),
+        }
+    }
+
+    /// Gets the creation time, if known.
+    pub fn created_at(&self) -> Option<NaiveDateTime> {
+        self.created_at
+    }
+
+    /// Gets the last modification time, or the creation one.
+    pub fn updated_at(&self) -> Option<NaiveDateTime> {
+        self.updated_at.or(self.created_at)
+    }
+
+    /// Records a modification.
+    pub(crate) fn touch(&mut self) {
+        self.updated_at = Some(Local::now().naive_local());
+    }
+
+    /// Returns `true` if matched against a bank statement.
+    pub fn is_reconciled(&self) -> bool {
+        self.reconciled
+    }
+
+    /// Flags the order as a future one, listed from its date only.
+    pub fn set_scheduled(&mut self, scheduled: bool) {
+        self.scheduled = scheduled;
+    }
+
+    /// Returns `true` if flagged as a future order.
+    pub fn is_scheduled(&self) -> bool {
+        self.scheduled
+    }
+
+    /// Returns `true` if scheduled after a given date, or without date.
+    pub fn is_scheduled_after(&self, today: NaiveDate) -> bool {
+        self.scheduled && self.date.is_none_or(|date| date > today)
+    }
+
+    /// Inserts or updates a metadata value.
+    /// Empty keys are rejected.
+    pub fn set_metadata(&mut self, key: &str, value: &str) -> bool {
+        if !key.is_empty() {
+            self.metadata.insert(key.into(), value.into());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes a metadata key.
+    pub fn remove_metadata(&mut self, key: &str) -> bool {
+        self.metadata.remove(key).is_some()
+    }
+
+    /// Gets all metadata.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Sets the value of a declared custom field.
+    /// Values not matching the field type are rejected.
+    pub fn set_field(&mut self, name: &str, value: FieldValue, list: &[CustomField]) -> bool {
+        if list
+            .iter()
+            .any(|field| field.name == name && field.kind == value.kind())
+        {
+            self.fields.insert(name.into(), value);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes the value of a custom field.
+    pub fn remove_field(&mut self, name: &str) -> bool {
+        self.fields.remove(name).is_some()
+    }
+
+    /// Gets values of custom fields.
+    pub fn fields(&self) -> &BTreeMap<String, FieldValue> {
+        &self.fields
+    }
+
+    /// Sets the location from coordinates in decimal degrees.
+    /// Invalid coordinates are rejected.
+    pub fn set_location(&mut self, latitude: f64, longitude: f64) -> bool {
+        if let Some(location) = Location::new(latitude, longitude) {
+            self.location = Some(location);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes the location.
+    pub fn clear_location(&mut self) {
+        self.location = None;
+    }
+
+    /// Gets the location.
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    /// Adds an attachment.
+    /// Empty or already used file names are rejected.
+    pub fn add_attachment(&mut self, attachment: Attachment) -> bool {
+        if attachment.filename.trim().is_empty()
+            || self
+                .attachments
+                .iter()
+                .any(|item| item.filename == attachment.filename)
+        {
+            false
+        } else {
+            self.attachments.push(attachment);
+            true
+        }
+    }
+
+    /// Removes an attachment by file name.
+    pub fn remove_attachment(&mut self, filename: &str) -> bool {
+        let count = self.attachments.len();
+        self.attachments.retain(|item| item.filename != filename);
+        self.attachments.len() != count
+    }
+
+    /// Gets all attachments.
+    pub fn attachments(&self) -> &Vec<Attachment> {
+        &self.attachments
+    }
+
+    /// Inserts or updates a component, then sets the amount to the sum of
+    /// components. A zero amount removes the component. Rejected if the sum
+    /// overflows.
+    pub fn set_component(&mut self, kind: ComponentKind, amount: Amount) -> bool {
+        let mut components = self.components.clone();
+        components.retain(|item| item.kind != kind);
+        if amount != Amount::ZERO {
+            components.push(AmountComponent { kind, amount });
+        }
+
+        match Amount::checked_sum(components.iter().map(|item| item.amount)) {
+            Some(total) => {
+                self.components = components;
+                self.amount = total;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes a component; the amount is updated if others remain.
+    /// Rejected if the sum of remaining components overflows.
+    pub fn remove_component(&mut self, kind: ComponentKind) -> bool {
+        if !self.components.iter().any(|item| item.kind == kind) {
+            return false;
+        }
+        let components = self
+            .components
+            .iter()
+            .filter(|item| item.kind != kind)
+            .copied()
+            .collect::<Vec<AmountComponent>>();
+
+        match Amount::checked_sum(components.iter().map(|item| item.amount)) {
+            Some(total) => {
+                if !components.is_empty() {
+                    self.amount = total;
+                }
+                self.components = components;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Gets all components.
+    pub fn components(&self) -> &Vec<AmountComponent> {
+        &self.components
+    }
+
+    /// Gets the amount of a component, if set.
+    pub fn component(&self, kind: ComponentKind) -> Option<Amount> {
+        self.components
+            .iter()
+            .find(|item| item.kind == kind)
+            .map(|item| item.amount)
+    }
+
+    /// Sets the amount, rejected if it doesn't match existing components.
+    pub fn set_amount(&mut self, amount: Amount) -> bool {
+        if self.components.is_empty()
+            || Amount::checked_sum(self.components.iter().map(|item| item.amount)) == Some(amount)
+        {
+            self.amount = amount;
+            true
+        } else {
+            false
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::field::FieldType;
 
     #[test]
     fn add_new_with_date_filtering() {
@@ -164,6 +887,243 @@ mod tests {
         assert_eq!(order.date, Some(date));
     }
 
+    #[test]
+    fn add_new_with_metadata_filtering() {
+        let mut filter = Filter::default();
+        filter.set_metadata_value("project", "Kitchen");
+        let order = Order::from(&filter);
+
+        assert_eq!(
+            order.metadata().get("project"),
+            Some(&"Kitchen".to_string())
+        );
+    }
+
+    #[test]
+    fn detect_kind_mismatch() {
+        let mut order = Order {
+            amount: Amount::from_cents(-2500),
+            ..Order::default()
+        };
+
+        assert_eq!(order.effective_kind(), OrderKind::Expense);
+        assert_eq!(order.is_kind_mismatched(), false);
+
+        order.set_kind(Some(OrderKind::Income));
+
+        assert_eq!(order.effective_kind(), OrderKind::Income);
+        assert_eq!(order.is_kind_mismatched(), true);
+
+        order.set_kind(Some(OrderKind::Transfer));
+
+        assert_eq!(order.is_kind_mismatched(), false);
+    }
+
+    #[test]
+    fn update_custom_fields() {
+        let list = [CustomField {
+            name: "Reimbursable by".into(),
+            kind: FieldType::Text,
+        }];
+        let mut order = Order::default();
+
+        assert_eq!(
+            order.set_field("Reimbursable by", FieldValue::Number(1.0), &list),
+            false
+        );
+        assert_eq!(
+            order.set_field("Invoice", FieldValue::Text("F-12".into()), &list),
+            false
+        );
+        assert_eq!(
+            order.set_field(
+                "Reimbursable by",
+                FieldValue::Text("Employer".into()),
+                &list
+            ),
+            true
+        );
+        assert_eq!(
+            order.fields().get("Reimbursable by"),
+            Some(&FieldValue::Text("Employer".into()))
+        );
+        assert_eq!(order.remove_field("Reimbursable by"), true);
+        assert_eq!(order.remove_field("Reimbursable by"), false);
+    }
+
+    #[test]
+    fn update_metadata() {
+        let mut order = Order::default();
+
+        assert_eq!(order.set_metadata("", "Empty key"), false);
+        assert_eq!(order.set_metadata("bank_id", "TX-001"), true);
+        assert_eq!(order.set_metadata("bank_id", "TX-002"), true);
+        assert_eq!(order.metadata().len(), 1);
+        assert_eq!(order.metadata()["bank_id"], "TX-002");
+        assert_eq!(order.remove_metadata("bank_id"), true);
+        assert_eq!(order.remove_metadata("bank_id"), false);
+        assert_eq!(order, Order::default());
+    }
+
+    #[test]
+    fn hide_for_a_reason() {
+        let mut order = Order::default();
+
+        assert_eq!(order.is_visible(), true);
+        assert_eq!(order.is_counted(&[]), true);
+
+        order.hide(HiddenReason::InternalTransfer);
+
+        assert_eq!(order.is_visible(), false);
+        assert_eq!(order.hidden_reason(), Some(HiddenReason::InternalTransfer));
+        assert_eq!(order.is_counted(&[]), false);
+        assert_eq!(order.is_counted(&[HiddenReason::InternalTransfer]), true);
+
+        order.show();
+
+        assert_eq!(order, Order::default());
+    }
+
+    #[test]
+    fn load_legacy_visibility() {
+        let orders: Vec<Order> = serde_yaml::from_str(
+            "- {date: ~, description: A, amount: 1.0, resource: ~, tags: [], state: Done, visible: true}\n\
+             - {date: ~, description: B, amount: 2.0, resource: ~, tags: [], state: Done, visible: false}\n\
+             - {date: ~, description: C, amount: 3.0, resource: ~, tags: [], state: Done, hidden: Duplicate}\n\
+             - {date: ~, description: D, amount: 4.0, resource: ~, tags: [], state: Done}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            orders
+                .iter()
+                .map(|order| order.hidden_reason())
+                .collect::<Vec<Option<HiddenReason>>>(),
+            [
+                None,
+                Some(HiddenReason::Manual),
+                Some(HiddenReason::Duplicate),
+                None
+            ]
+        );
+        assert_eq!(
+            serde_yaml::to_string(&orders[2])
+                .unwrap()
+                .contains("hidden: Duplicate"),
+            true
+        );
+    }
+
+    #[test]
+    fn set_multi_line_notes() {
+        let mut order = Order::default();
+
+        order.set_notes("Invoice #2021-042\nPaid by card");
+
+        assert_eq!(
+            order.notes(),
+            Some(&"Invoice #2021-042\nPaid by card".to_string())
+        );
+
+        order.set_notes(" \n ");
+
+        assert_eq!(order, Order::default());
+    }
+
+    #[test]
+    fn manage_attachments() {
+        let mut order = Order::default();
+        let receipt = Attachment {
+            hash: Some("sha256:9f86d081".into()),
+            ..Attachment::new("receipt.jpg", "image/jpeg")
+        };
+
+        assert_eq!(
+            order.add_attachment(Attachment::new(" ", "image/jpeg")),
+            false
+        );
+        assert_eq!(order.add_attachment(receipt.clone()), true);
+        assert_eq!(
+            order.add_attachment(Attachment::new("receipt.jpg", "image/png")),
+            false
+        );
+        assert_eq!(order.attachments(), &vec![receipt]);
+        assert_eq!(order.remove_attachment("invoice.pdf"), false);
+        assert_eq!(order.remove_attachment("receipt.jpg"), true);
+        assert_eq!(order, Order::default());
+    }
+
+    #[test]
+    fn break_down_amount() {
+        let mut order = Order::default();
+
+        assert_eq!(
+            order.set_component(ComponentKind::Base, Amount::from_cents(-4000)),
+            true
+        );
+        assert_eq!(
+            order.set_component(ComponentKind::Tip, Amount::from_cents(-500)),
+            true
+        );
+
+        assert_eq!(order.amount, Amount::from_cents(-4500));
+        assert_eq!(order.set_component(ComponentKind::Fee, Amount::MIN), false);
+        assert_eq!(order.component(ComponentKind::Fee), None);
+        assert_eq!(order.set_amount(Amount::from_cents(-5000)), false);
+        assert_eq!(order.set_amount(Amount::from_cents(-4500)), true);
+
+        assert_eq!(
+            order.set_component(ComponentKind::Tip, Amount::from_cents(-600)),
+            true
+        );
+
+        assert_eq!(
+            order.component(ComponentKind::Tip),
+            Some(Amount::from_cents(-600))
+        );
+        assert_eq!(order.amount, Amount::from_cents(-4600));
+        assert_eq!(order.remove_component(ComponentKind::Fee), false);
+        assert_eq!(order.remove_component(ComponentKind::Tip), true);
+        assert_eq!(order.amount, Amount::from_cents(-4000));
+        assert_eq!(order.remove_component(ComponentKind::Base), true);
+        assert_eq!(order.amount, Amount::from_cents(-4000));
+        assert_eq!(order.set_amount(Amount::from_cents(-5000)), true);
+    }
+
+    #[test]
+    fn set_valid_currency() {
+        let mut order = Order::default();
+
+        assert_eq!(order.set_currency("Dollar"), false);
+        assert_eq!(order.currency(), None);
+        assert_eq!(order.set_currency("usd"), true);
+        assert_eq!(order.currency(), Some(&"USD".to_string()));
+
+        order.clear_currency();
+
+        assert_eq!(order, Order::default());
+    }
+
+    #[test]
+    fn schedule_future_order() {
+        let today = NaiveDate::from_ymd(2021, 5, 10);
+        let mut order = Order {
+            date: Some(NaiveDate::from_ymd(2021, 5, 12)),
+            ..Order::default()
+        };
+
+        assert_eq!(order.is_scheduled_after(today), false);
+
+        order.set_scheduled(true);
+
+        assert_eq!(order.is_scheduled(), true);
+        assert_eq!(order.is_scheduled_after(today), true);
+        assert_eq!(
+            order.is_scheduled_after(NaiveDate::from_ymd(2021, 5, 12)),
+            false
+        );
+    }
+
     #[test]
     fn set_valid_resource() {
         let resources = ["Bank".to_string(), "Cash".to_string()];
@@ -173,7 +1133,7 @@ mod tests {
         assert_eq!(
             order,
             Order {
-                resource: Some(resources[1].clone()),
+                resource: Some(resources[1].clone().into()),
                 ..Order::default()
             }
         );
@@ -188,6 +1148,20 @@ mod tests {
         assert_eq!(order, Order::default());
     }
 
+    #[test]
+    fn set_valid_project() {
+        let projects = [Project::new("Japan Trip"), Project::new("Kitchen")];
+        let mut order = Order::default();
+
+        assert_eq!(order.set_project("Wedding", &projects), false);
+        assert_eq!(order.set_project("Kitchen", &projects), true);
+        assert_eq!(order.project(), Some(&"Kitchen".to_string()));
+
+        order.clear_project();
+
+        assert_eq!(order, Order::default());
+    }
+
     #[test]
     fn add_valid_tag() {
         let tags = ["Food".to_string(), "Service".to_string()];
@@ -197,7 +1171,7 @@ mod tests {
         assert_eq!(
             order,
             Order {
-                tags: vec![tags[1].clone()],
+                tags: vec![tags[1].clone().into()],
                 ..Order::default()
             }
         );
@@ -213,7 +1187,7 @@ mod tests {
         assert_eq!(
             order,
             Order {
-                tags: vec![tags[1].clone()],
+                tags: vec![tags[1].clone().into()],
                 ..Order::default()
             }
         );
@@ -237,7 +1211,7 @@ mod tests {
             "Home".to_string(),
         ];
         let mut order = Order {
-            tags: tags.iter().map(|x| x.to_string()).collect(),
+            tags: tags.iter().map(Interned::from).collect(),
             ..Order::default()
         };
 
@@ -245,7 +1219,7 @@ mod tests {
         assert_eq!(
             order,
             Order {
-                tags: tags[1..].to_vec(),
+                tags: tags[1..].iter().map(Interned::from).collect(),
                 ..Order::default()
             }
         );
@@ -255,7 +1229,7 @@ mod tests {
     fn remove_invalid_tag() {
         let tags = ["Service".to_string(), "Video Games".to_string()];
         let mut order = Order {
-            tags: tags.iter().map(|x| x.to_string()).collect(),
+            tags: tags.iter().map(Interned::from).collect(),
             ..Order::default()
         };
 
@@ -263,7 +1237,7 @@ mod tests {
         assert_eq!(
             order,
             Order {
-                tags: tags.to_vec(),
+                tags: tags.iter().map(Interned::from).collect(),
                 ..Order::default()
             }
         );
@@ -0,0 +1,39 @@
+//! # Projects grouping orders across tags and resources (trips, renovations, ..).
+use crate::amount::Amount;
+use crate::ext::CategoryAmount;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// A named project with an optional budget.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Project {
+    pub name: String,
+    /// Maximum amount expected to be spent, as a positive value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Amount>,
+}
+
+/// Overview of the orders of a project.
+#[derive(Serialize, PartialEq, Debug)]
+pub struct ProjectSummary {
+    pub name: String,
+    pub budget: Option<Amount>,
+    /// Amounts of visible orders, if any.
+    pub amount: Option<CategoryAmount>,
+    /// Budget left once expected expenses are paid.
+    pub remaining: Option<Amount>,
+    /// Date of the first dated order.
+    pub start: Option<NaiveDate>,
+    /// Date of the last dated order.
+    pub end: Option<NaiveDate>,
+}
+
+impl Project {
+    /// Creates a project without budget.
+    pub fn new(name: &str) -> Project {
+        Project {
+            name: name.into(),
+            budget: None,
+        }
+    }
+}
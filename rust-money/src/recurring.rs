@@ -0,0 +1,191 @@
+//! # Orders repeated over time (rent, salary, subscriptions, ..).
+use crate::order::Order;
+#[cfg(feature = "rules")]
+use crate::order::TransactionState;
+use chrono::{Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasmbind")]
+use wasm_bindgen::prelude::*;
+
+/// Period between two occurrences.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// Describes an order created at regular dates.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RecurringRule {
+    /// Unique name of the rule.
+    pub label: String,
+    pub frequency: Frequency,
+    /// Number of periods between two occurrences.
+    pub interval: u32,
+    /// Date of the first occurrence.
+    pub start: NaiveDate,
+    /// Date after which no occurrence is created.
+    pub end: Option<NaiveDate>,
+    /// Model of created orders; its *date* and *state* are ignored.
+    pub template: Order,
+    /// Occurrences until this date already exist as orders.
+    #[serde(default)]
+    pub(crate) materialized_until: Option<NaiveDate>,
+}
+
+impl RecurringRule {
+    /// Creates a monthly rule starting at a given date.
+    pub fn new(label: &str, start: NaiveDate, template: Order) -> RecurringRule {
+        RecurringRule {
+            label: label.into(),
+            frequency: Frequency::Monthly,
+            interval: 1,
+            start,
+            end: None,
+            template,
+            materialized_until: None,
+        }
+    }
+
+    /// Returns the date of the `n`-th occurrence, starting from 0.
+    ///
+    /// Monthly and yearly occurrences stick to the day of `start`, or to the
+    /// last day of shorter months.
+    fn nth_occurrence(&self, n: u32) -> Option<NaiveDate> {
+        let periods = n.checked_mul(self.interval)?;
+
+        match self.frequency {
+            Frequency::Daily => self
+                .start
+                .checked_add_signed(Duration::days(i64::from(periods))),
+            Frequency::Weekly => self
+                .start
+                .checked_add_signed(Duration::weeks(i64::from(periods))),
+            Frequency::Monthly => self.start.checked_add_months(Months::new(periods)),
+            Frequency::Yearly => self
+                .start
+                .checked_add_months(Months::new(periods.checked_mul(12)?)),
+        }
+    }
+
+    /// Returns all occurrence dates until a given date, included.
+    pub fn occurrences_until(&self, date: NaiveDate) -> Vec<NaiveDate> {
+        if self.interval == 0 {
+            return Vec::new();
+        }
+
+        let last = match self.end {
+            Some(end) if end < date => end,
+            _ => date,
+        };
+
+        (0..)
+            .map_while(|n| self.nth_occurrence(n))
+            .take_while(|&occurrence| occurrence <= last)
+            .collect()
+    }
+
+    /// Creates the orders of occurrences not materialized yet, until a given
+    /// date.
+    #[cfg(feature = "rules")]
+    pub(crate) fn materialize_until(&mut self, date: NaiveDate) -> Vec<Order> {
+        let materialized_until = self.materialized_until;
+        let orders = self
+            .occurrences_until(date)
+            .into_iter()
+            .filter(|&occurrence| Some(occurrence) > materialized_until)
+            .map(|occurrence| Order {
+                date: Some(occurrence),
+                state: TransactionState::Pending,
+                ..self.template.clone()
+            })
+            .collect();
+
+        if Some(date) > materialized_until {
+            self.materialized_until = Some(date);
+        }
+
+        orders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dates(rule: &RecurringRule, until: NaiveDate) -> Vec<NaiveDate> {
+        rule.occurrences_until(until)
+    }
+
+    #[test]
+    fn stick_to_end_of_month() {
+        let rule = RecurringRule::new("Rent", NaiveDate::from_ymd(2021, 1, 31), Order::default());
+
+        assert_eq!(
+            dates(&rule, NaiveDate::from_ymd(2021, 4, 30)),
+            [
+                NaiveDate::from_ymd(2021, 1, 31),
+                NaiveDate::from_ymd(2021, 2, 28),
+                NaiveDate::from_ymd(2021, 3, 31),
+                NaiveDate::from_ymd(2021, 4, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn repeat_every_other_week_until_end() {
+        let rule = RecurringRule {
+            frequency: Frequency::Weekly,
+            interval: 2,
+            end: Some(NaiveDate::from_ymd(2021, 1, 31)),
+            ..RecurringRule::new(
+                "Groceries",
+                NaiveDate::from_ymd(2021, 1, 2),
+                Order::default(),
+            )
+        };
+
+        assert_eq!(
+            dates(&rule, NaiveDate::from_ymd(2021, 12, 31)),
+            [
+                NaiveDate::from_ymd(2021, 1, 2),
+                NaiveDate::from_ymd(2021, 1, 16),
+                NaiveDate::from_ymd(2021, 1, 30),
+            ]
+        );
+    }
+
+    #[cfg(feature = "rules")]
+    #[test]
+    fn materialize_once() {
+        let mut rule = RecurringRule::new(
+            "Salary",
+            NaiveDate::from_ymd(2020, 1, 3),
+            Order {
+                description: "Salary".into(),
+                state: TransactionState::Done,
+                ..Order::default()
+            },
+        );
+
+        let orders = rule.materialize_until(NaiveDate::from_ymd(2020, 2, 3));
+
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[1].date, Some(NaiveDate::from_ymd(2020, 2, 3)));
+        assert_eq!(orders[1].state(), TransactionState::Pending);
+        assert_eq!(orders[1].description, "Salary");
+        assert_eq!(
+            rule.materialize_until(NaiveDate::from_ymd(2020, 2, 20))
+                .is_empty(),
+            true
+        );
+        assert_eq!(
+            rule.materialize_until(NaiveDate::from_ymd(2020, 3, 3))
+                .len(),
+            1
+        );
+    }
+}
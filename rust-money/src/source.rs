@@ -0,0 +1,220 @@
+//! # Detection of account files changed by another process.
+use chrono::{Local, NaiveDateTime};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// State of a file when it was last read or written.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FileStamp {
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+    /// Digest of the content.
+    pub hash: u64,
+    /// Local time the file was read or written: orders changed later are
+    /// changes of this session.
+    pub synced_at: NaiveDateTime,
+}
+
+impl FileStamp {
+    /// Stamps a file from its known content.
+    pub fn new(path: &Path, content: &[u8]) -> FileStamp {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+
+        FileStamp {
+            path: path.into(),
+            modified: std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok(),
+            hash: hasher.finish(),
+            synced_at: Local::now().naive_local(),
+        }
+    }
+
+    /// Returns `true` if the file changed since stamped.
+    /// The content is only read when the modification time differs.
+    pub fn is_outdated(&self) -> io::Result<bool> {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        if modified.is_some() && modified == self.modified {
+            Ok(false)
+        } else {
+            match std::fs::read(&self.path) {
+                Ok(content) => Ok(FileStamp::new(&self.path, &content).hash != self.hash),
+                // Deleted files can be written again
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+                Err(error) => Err(error),
+            }
+        }
+    }
+}
+
+/// Error raised instead of overwriting a file changed since loaded.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SaveConflict {
+    pub path: PathBuf,
+}
+
+impl fmt::Display for SaveConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} changed since loaded: overwrite it or reload and merge",
+            self.path.display()
+        )
+    }
+}
+
+impl std::error::Error for SaveConflict {}
+
+/// Failure to save an account file, which is then left untouched.
+#[derive(Debug)]
+pub enum SaveError {
+    /// The file changed since loaded or last saved.
+    Conflict(SaveConflict),
+    /// Account data cannot be written in the requested format.
+    Encoding(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Conflict(conflict) => conflict.fmt(f),
+            SaveError::Encoding(reason) => write!(f, "cannot encode account data: {}", reason),
+            SaveError::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::Conflict(conflict) => Some(conflict),
+            SaveError::Encoding(_) => None,
+            SaveError::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(error: io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+/// Replaces the content of a file, keeping either the old or the new one
+/// whenever writing is interrupted.
+///
+/// Content is written to a temporary file of the same directory, flushed to
+/// disk, then renamed over the target.
+pub fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file path"))?;
+    let directory = path
+        .parent()
+        .filter(|directory| !directory.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let temporary = directory.join(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let result = (|| {
+        let mut file = File::create(&temporary)?;
+        if let Ok(metadata) = fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.write_all(content)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&temporary, path)?;
+        // Persists the rename itself where directories can be opened
+        if let Ok(directory) = File::open(directory) {
+            directory.sync_all().ok();
+        }
+        Ok(())
+    })();
+
+    if result.is_err() {
+        fs::remove_file(&temporary).ok();
+    }
+    result
+}
+
+/// Ways to save over a file changed since loaded.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ConflictResolution {
+    /// Discards changes of the file.
+    Overwrite,
+    /// Merges changes of the file by order id, then saves.
+    ReloadAndMerge,
+}
+
+/// Stamp of the file an account comes from, updated on save.
+///
+/// Ignored by comparisons as it is not part of account data.
+#[derive(Default, Debug)]
+pub struct SourceStamp(RefCell<Option<FileStamp>>);
+
+impl PartialEq for SourceStamp {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl SourceStamp {
+    /// Returns the current stamp, if any.
+    pub fn get(&self) -> Option<FileStamp> {
+        self.0.borrow().clone()
+    }
+
+    /// Replaces the stamp.
+    pub fn set(&self, stamp: FileStamp) {
+        *self.0.borrow_mut() = Some(stamp);
+    }
+
+    /// Fails with a `SaveConflict` if the file at `path` changed since
+    /// stamped. Other files are never in conflict.
+    pub fn check(&self, path: &Path) -> Result<(), SaveError> {
+        match self.get() {
+            Some(stamp) if stamp.path == path && stamp.is_outdated()? => {
+                Err(SaveError::Conflict(SaveConflict { path: path.into() }))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_directory;
+
+    #[test]
+    fn replace_file_atomically() {
+        let directory = test_directory("replace_file_atomically");
+        let path = directory.join("money.yml");
+
+        write_atomic(&path, b"label: A").unwrap();
+        write_atomic(&path, b"label: B").unwrap();
+        let content = fs::read(&path).unwrap();
+        let files = fs::read_dir(&directory).unwrap().count();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(content, b"label: B");
+        assert_eq!(files, 1);
+        assert_eq!(write_atomic(&path, b"label: C").is_err(), true);
+        assert_eq!(write_atomic(Path::new("/"), b"label: C").is_err(), true);
+    }
+}
@@ -0,0 +1,867 @@
+//! # Spending statistics over time.
+use crate::amount::Amount;
+use crate::columnar::OrderColumns;
+use crate::ext::{CategoryType, RequestFailure};
+use crate::order::{Order, OrderKind};
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+#[cfg(feature = "wasmbind")]
+use wasm_bindgen::prelude::*;
+
+/// Length of compared periods.
+#[cfg_attr(feature = "wasmbind", wasm_bindgen)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Period {
+    /// From Monday to Sunday.
+    Week,
+    /// Calendar month.
+    Month,
+    /// Three calendar months, from January, April, July or October.
+    Quarter,
+}
+
+/// Spending change of a tag between two periods.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct TagMover {
+    pub tag: String,
+    pub current: Amount,
+    pub previous: Amount,
+    pub delta: Amount,
+}
+
+/// Balance at the end of a month.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub struct MonthlyBalance {
+    /// First day of the month.
+    pub month: NaiveDate,
+    /// Balance of done orders.
+    pub current: Amount,
+    /// Balance once all orders are done.
+    pub expected: Amount,
+}
+
+/// Spending of a tag within a date range.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct TagSpending {
+    pub tag: String,
+    /// Spent amount, as a positive value.
+    pub spent: Amount,
+    /// Percentage of all spending, untagged included.
+    pub share: f64,
+}
+
+/// Relative change beyond which spending is rising or falling.
+const TREND_THRESHOLD: f64 = 0.1;
+
+/// Direction of spending over recent months.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub enum Trend {
+    Rising,
+    Stable,
+    Falling,
+}
+
+/// Spending of a month along with the average of the last months.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub struct MovingAverage {
+    /// First day of the month.
+    pub month: NaiveDate,
+    /// Spent amount, as a positive value.
+    pub spent: Amount,
+    pub average: Amount,
+}
+
+/// Rolling averages of a *category* and their latest direction.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct SpendingTrend {
+    pub averages: Vec<MovingAverage>,
+    /// Compares the last two averages.
+    pub trend: Trend,
+}
+
+/// Orders of a payee within a date range.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct PayeeStats {
+    pub payee: String,
+    pub count: usize,
+    /// Signed sum, negative when spent.
+    pub total: Amount,
+    /// Average amount of an order.
+    pub average: Amount,
+}
+
+/// Balances of a resource at regular dates, to draw a small chart.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Sparkline {
+    pub resource: String,
+    /// Date of the first balance.
+    pub start: NaiveDate,
+    /// Number of days between two balances.
+    pub step: u32,
+    /// Balances of done orders, oldest first.
+    pub balances: Vec<Amount>,
+}
+
+/// Fewest orders of a tag whose amounts are checked for outliers.
+const OUTLIER_MIN_ORDERS: usize = 5;
+/// Multiple of the interquartile range beyond which an amount is an outlier.
+const OUTLIER_FENCE: f64 = 3.0;
+
+/// Order whose amount is far from usual amounts of one of its tags.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct AmountOutlier {
+    pub id: usize,
+    pub tag: String,
+    pub amount: Amount,
+    /// Lowest usual amount of the tag.
+    pub low: Amount,
+    /// Highest usual amount of the tag.
+    pub high: Amount,
+}
+
+/// Money in and out of a period, as signed sums.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub struct CashFlow {
+    /// First day of the period.
+    pub start: NaiveDate,
+    /// Last day of the period.
+    pub end: NaiveDate,
+    pub income: Amount,
+    pub expenses: Amount,
+    /// Negative when more money went out than in.
+    pub net: Amount,
+}
+
+/// Spending of a period compared with the previous one.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct PeriodComparison {
+    /// First day of the current period.
+    pub start: NaiveDate,
+    /// Last day of the current period.
+    pub end: NaiveDate,
+    /// Spent amounts, as positive values.
+    pub current: Amount,
+    pub previous: Amount,
+    pub delta: Amount,
+    /// Relative change, unknown without previous spending.
+    pub ratio: Option<f64>,
+    /// Tags whose spending changed, largest changes first.
+    pub movers: Vec<TagMover>,
+}
+
+impl Period {
+    /// Returns the first and last days of the period holding a date.
+    pub fn bounds(self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            Period::Week => {
+                let start = date - Duration::days(i64::from(date.weekday().num_days_from_monday()));
+                (start, start + Duration::days(6))
+            }
+            Period::Month => {
+                let start = date.with_day(1).unwrap();
+                let end = start + Months::new(1) - Duration::days(1);
+                (start, end)
+            }
+            Period::Quarter => {
+                let start =
+                    NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1).unwrap();
+                let end = start + Months::new(3) - Duration::days(1);
+                (start, end)
+            }
+        }
+    }
+
+    /// Returns the bounds of each period overlapping a date range.
+    pub fn split(self, start: NaiveDate, end: NaiveDate) -> Vec<(NaiveDate, NaiveDate)> {
+        let mut result = Vec::new();
+        let mut bounds = self.bounds(start);
+
+        while bounds.0 <= end {
+            result.push(bounds);
+            bounds = self.bounds(bounds.1 + Duration::days(1));
+        }
+        result
+    }
+
+    /// Returns the period before the one holding a date.
+    fn previous_bounds(self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        self.bounds(self.bounds(date).0 - Duration::days(1))
+    }
+}
+
+/// Sums spent amounts of visible orders within a period, per tag.
+/// Untagged orders are only part of the total.
+fn spending(
+    columns: &OrderColumns,
+    (start, end): (NaiveDate, NaiveDate),
+) -> Result<(Amount, BTreeMap<&str, Amount>), RequestFailure> {
+    let mut total = Amount::ZERO;
+    let mut tags = BTreeMap::new();
+
+    for row in columns
+        .rows_between(Some(start), Some(end))
+        .filter(|&row| columns.amount(row).is_negative())
+    {
+        let spent = columns.amount(row).abs();
+        total = total.checked_add(spent).ok_or(RequestFailure::Overflow)?;
+        for tag in columns.row_tags(row) {
+            let amount = tags.entry(tag).or_insert(Amount::ZERO);
+            *amount = amount.checked_add(spent).ok_or(RequestFailure::Overflow)?;
+        }
+    }
+
+    Ok((total, tags))
+}
+
+/// Returns up to `count` tags with the largest spending of visible orders
+/// within a date range, largest first. Orders owning several tags count for
+/// each one, so shares may sum up beyond 100.
+///
+/// Fails with `RequestFailure::Overflow` if a sum overflows.
+pub fn top_spending_tags(
+    columns: &OrderColumns,
+    (start, end): (NaiveDate, NaiveDate),
+    count: usize,
+) -> Result<Vec<TagSpending>, RequestFailure> {
+    let (total, tags) = spending(columns, (start, end))?;
+
+    let mut result = tags
+        .into_iter()
+        .map(|(tag, spent)| TagSpending {
+            tag: tag.to_string(),
+            spent,
+            share: spent.cents() as f64 * 100.0 / total.cents() as f64,
+        })
+        .collect::<Vec<TagSpending>>();
+    // Stable sort keeps ties by name
+    result.sort_by_key(|item| std::cmp::Reverse(item.spent));
+    result.truncate(count);
+    Ok(result)
+}
+
+/// Computes, for each month overlapping a date range, the spending of visible
+/// orders of a *category* and its average over the last `window` months.
+/// Spending is `Rising` or `Falling` when the last average differs from the
+/// previous one by more than 10%.
+///
+/// Fails with `RequestFailure::IncorrectArgument` if `window` is 0 or if its
+/// months fall outside supported dates, and with `RequestFailure::Overflow` if
+/// a sum overflows.
+pub fn spending_trend(
+    columns: &OrderColumns,
+    (kind, category): (CategoryType, &str),
+    (start, end): (NaiveDate, NaiveDate),
+    window: usize,
+) -> Result<SpendingTrend, RequestFailure> {
+    if window == 0 || end.checked_add_months(Months::new(1)).is_none() {
+        return Err(RequestFailure::IncorrectArgument);
+    }
+    // Earlier months fill the first windows
+    let first = u32::try_from(window - 1)
+        .ok()
+        .and_then(|count| start.checked_sub_months(Months::new(count)))
+        .ok_or(RequestFailure::IncorrectArgument)?;
+    let months = Period::Month.split(first, end);
+    let mut spent = vec![Amount::ZERO; months.len()];
+
+    for row in columns.category_rows(kind, category, (None, None)) {
+        let amount = columns.amount(row);
+        if let Some(date) = columns.date(row).filter(|_| amount.is_negative()) {
+            let index = months.partition_point(|bounds| bounds.1 < date);
+            if months.get(index).is_some_and(|bounds| bounds.0 <= date) {
+                spent[index] = spent[index]
+                    .checked_add(amount.abs())
+                    .ok_or(RequestFailure::Overflow)?;
+            }
+        }
+    }
+
+    let averages = (window - 1..months.len())
+        .map(|index| {
+            Amount::checked_sum(spent[index + 1 - window..=index].iter().copied())
+                .map(|total| MovingAverage {
+                    month: months[index].0,
+                    spent: spent[index],
+                    average: Amount::from_cents(total.cents() / window as i64),
+                })
+                .ok_or(RequestFailure::Overflow)
+        })
+        .collect::<Result<Vec<MovingAverage>, RequestFailure>>()?;
+    let trend = match averages.as_slice() {
+        [.., previous, last] if previous.average == Amount::ZERO => {
+            if last.average > Amount::ZERO {
+                Trend::Rising
+            } else {
+                Trend::Stable
+            }
+        }
+        [.., previous, last] => {
+            let change = last
+                .average
+                .checked_sub(previous.average)
+                .ok_or(RequestFailure::Overflow)?
+                .cents() as f64
+                / previous.average.cents() as f64;
+            if change > TREND_THRESHOLD {
+                Trend::Rising
+            } else if change < -TREND_THRESHOLD {
+                Trend::Falling
+            } else {
+                Trend::Stable
+            }
+        }
+        _ => Trend::Stable,
+    };
+
+    Ok(SpendingTrend { averages, trend })
+}
+
+/// Computes totals of counted orders of each payee within a date range, the
+/// largest spending first. Currencies are ignored.
+///
+/// Fails with `RequestFailure::Overflow` if a sum overflows.
+pub fn payee_stats(
+    orders: &[Order],
+    (start, end): (NaiveDate, NaiveDate),
+) -> Result<Vec<PayeeStats>, RequestFailure> {
+    let mut payees = BTreeMap::<&str, (usize, Amount)>::new();
+
+    for order in orders.iter().filter(|order| {
+        order.is_counted(&[]) && order.date.is_some_and(|date| date >= start && date <= end)
+    }) {
+        if let Some(payee) = &order.payee {
+            let (count, total) = payees.entry(payee).or_insert((0, Amount::ZERO));
+            *count += 1;
+            *total = total
+                .checked_add(order.amount)
+                .ok_or(RequestFailure::Overflow)?;
+        }
+    }
+
+    let mut result = payees
+        .into_iter()
+        .map(|(payee, (count, total))| PayeeStats {
+            payee: payee.to_string(),
+            count,
+            total,
+            average: Amount::from_cents(total.cents() / count as i64),
+        })
+        .collect::<Vec<PayeeStats>>();
+    // Stable sort keeps ties by name
+    result.sort_by_key(|item| item.total);
+    Ok(result)
+}
+
+/// Returns the quantile of sorted values, interpolating between them.
+fn quantile(sorted: &[i64], ratio: f64) -> f64 {
+    let position = ratio * (sorted.len() - 1) as f64;
+    let (below, above) = (position.floor() as usize, position.ceil() as usize);
+
+    sorted[below] as f64 + (sorted[above] - sorted[below]) as f64 * (position - below as f64)
+}
+
+/// Flags visible orders whose amount lies beyond three interquartile ranges
+/// from the quartiles of their tag, like data-entry errors. Tags of less than
+/// five orders are not checked.
+pub fn amount_outliers(orders: &[Order]) -> Vec<AmountOutlier> {
+    let mut tags = BTreeMap::<&str, Vec<&Order>>::new();
+    orders
+        .iter()
+        .filter(|order| order.is_visible())
+        .for_each(|order| {
+            order
+                .tags
+                .iter()
+                .for_each(|tag| tags.entry(tag).or_default().push(order))
+        });
+
+    let mut result = tags
+        .into_iter()
+        .filter(|(_, orders)| orders.len() >= OUTLIER_MIN_ORDERS)
+        .flat_map(|(tag, orders)| {
+            let mut cents = orders
+                .iter()
+                .map(|order| order.amount.cents())
+                .collect::<Vec<i64>>();
+            cents.sort_unstable();
+            let (first, third) = (quantile(&cents, 0.25), quantile(&cents, 0.75));
+            let range = third - first;
+            let low = Amount::from_cents((first - OUTLIER_FENCE * range).ceil() as i64);
+            let high = Amount::from_cents((third + OUTLIER_FENCE * range).floor() as i64);
+
+            orders
+                .into_iter()
+                .filter(move |order| order.amount < low || order.amount > high)
+                .map(move |order| AmountOutlier {
+                    id: order.id,
+                    tag: tag.to_string(),
+                    amount: order.amount,
+                    low,
+                    high,
+                })
+        })
+        .collect::<Vec<AmountOutlier>>();
+    result.sort_by_key(|outlier| outlier.id);
+    result
+}
+
+/// Computes the cash flow of visible orders in each period overlapping a
+/// date range, for a resource or, if `None`, for all orders. Transfers only
+/// count for a resource, as money stays within the account otherwise.
+/// Currencies are ignored.
+///
+/// Fails with `RequestFailure::Overflow` if a sum overflows.
+pub fn cash_flow(
+    orders: &[Order],
+    period: Period,
+    (start, end): (NaiveDate, NaiveDate),
+    resource: Option<&str>,
+) -> Result<Vec<CashFlow>, RequestFailure> {
+    let mut flows = period
+        .split(start, end)
+        .into_iter()
+        .map(|(start, end)| CashFlow {
+            start,
+            end,
+            income: Amount::ZERO,
+            expenses: Amount::ZERO,
+            net: Amount::ZERO,
+        })
+        .collect::<Vec<CashFlow>>();
+
+    for order in orders.iter().filter(|order| {
+        order.is_visible()
+            && resource.is_none_or(|resource| order.resource.as_deref() == Some(resource))
+    }) {
+        let date = match order.date {
+            Some(date) => date,
+            None => continue,
+        };
+        // Periods are sorted and contiguous
+        let index = flows.partition_point(|flow| flow.end < date);
+        let flow = match flows.get_mut(index) {
+            Some(flow) if flow.start <= date => flow,
+            _ => continue,
+        };
+        let total = match order.effective_kind() {
+            OrderKind::Transfer if resource.is_none() => continue,
+            OrderKind::Transfer if order.amount.is_positive() => &mut flow.income,
+            OrderKind::Transfer | OrderKind::Expense => &mut flow.expenses,
+            OrderKind::Income => &mut flow.income,
+        };
+        *total = total
+            .checked_add(order.amount)
+            .ok_or(RequestFailure::Overflow)?;
+        flow.net = flow
+            .net
+            .checked_add(order.amount)
+            .ok_or(RequestFailure::Overflow)?;
+    }
+
+    Ok(flows)
+}
+
+/// Compares spending of the period holding a date with the previous one.
+///
+/// Fails with `RequestFailure::Overflow` if a sum overflows.
+pub fn period_vs_previous(
+    columns: &OrderColumns,
+    period: Period,
+    date: NaiveDate,
+) -> Result<PeriodComparison, RequestFailure> {
+    let (start, end) = period.bounds(date);
+    let (current, current_tags) = spending(columns, (start, end))?;
+    let (previous, previous_tags) = spending(columns, period.previous_bounds(date))?;
+
+    let mut movers = Vec::new();
+    for tag in current_tags
+        .keys()
+        .chain(previous_tags.keys())
+        .collect::<BTreeSet<&&str>>()
+    {
+        let current = current_tags.get(tag).copied().unwrap_or_default();
+        let previous = previous_tags.get(tag).copied().unwrap_or_default();
+        let delta = current
+            .checked_sub(previous)
+            .ok_or(RequestFailure::Overflow)?;
+        if delta != Amount::ZERO {
+            movers.push(TagMover {
+                tag: tag.to_string(),
+                current,
+                previous,
+                delta,
+            });
+        }
+    }
+    movers.sort_by_key(|mover| std::cmp::Reverse(mover.delta.abs()));
+    let delta = current
+        .checked_sub(previous)
+        .ok_or(RequestFailure::Overflow)?;
+
+    Ok(PeriodComparison {
+        start,
+        end,
+        current,
+        previous,
+        delta,
+        ratio: if previous != Amount::ZERO {
+            Some(delta.cents() as f64 / previous.cents() as f64)
+        } else {
+            None
+        },
+        movers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(date: NaiveDate, cents: i64, tag: &str) -> Order {
+        Order {
+            date: Some(date),
+            amount: Amount::from_cents(cents),
+            tags: vec![tag.into()],
+            ..Order::default()
+        }
+    }
+
+    #[test]
+    fn compute_week_bounds() {
+        assert_eq!(
+            Period::Week.bounds(NaiveDate::from_ymd(2021, 3, 3)),
+            (
+                NaiveDate::from_ymd(2021, 3, 1),
+                NaiveDate::from_ymd(2021, 3, 7)
+            )
+        );
+        assert_eq!(
+            Period::Quarter.bounds(NaiveDate::from_ymd(2021, 12, 3)),
+            (
+                NaiveDate::from_ymd(2021, 10, 1),
+                NaiveDate::from_ymd(2021, 12, 31)
+            )
+        );
+        assert_eq!(
+            Period::Month.previous_bounds(NaiveDate::from_ymd(2021, 3, 31)),
+            (
+                NaiveDate::from_ymd(2021, 2, 1),
+                NaiveDate::from_ymd(2021, 2, 28)
+            )
+        );
+    }
+
+    #[test]
+    fn split_date_range() {
+        let months = Period::Month.split(
+            NaiveDate::from_ymd(2021, 1, 31),
+            NaiveDate::from_ymd(2021, 3, 1),
+        );
+
+        assert_eq!(
+            months
+                .iter()
+                .map(|bounds| bounds.1)
+                .collect::<Vec<NaiveDate>>(),
+            [
+                NaiveDate::from_ymd(2021, 1, 31),
+                NaiveDate::from_ymd(2021, 2, 28),
+                NaiveDate::from_ymd(2021, 3, 31)
+            ]
+        );
+        assert_eq!(
+            Period::Week.split(
+                NaiveDate::from_ymd(2021, 3, 8),
+                NaiveDate::from_ymd(2021, 3, 1)
+            ),
+            []
+        );
+    }
+
+    #[test]
+    fn find_top_spending_tags() {
+        let mut orders = vec![
+            order(NaiveDate::from_ymd(2021, 3, 2), -6000, "Food"),
+            order(NaiveDate::from_ymd(2021, 3, 5), -2000, "Transport"),
+            order(NaiveDate::from_ymd(2021, 3, 8), -2000, "Food"),
+            order(NaiveDate::from_ymd(2021, 3, 9), -2000, "Gifts"),
+            order(NaiveDate::from_ymd(2021, 3, 9), 250000, "Salary"),
+            order(NaiveDate::from_ymd(2021, 4, 1), -9000, "Rent"),
+        ];
+        orders[3].tags.clear();
+
+        let top = top_spending_tags(
+            &OrderColumns::from(orders.as_slice()),
+            (
+                NaiveDate::from_ymd(2021, 3, 1),
+                NaiveDate::from_ymd(2021, 3, 31),
+            ),
+            2,
+        )
+        .unwrap();
+        assert_eq!(
+            top.iter()
+                .map(|item| (item.tag.as_str(), item.spent.cents(), item.share))
+                .collect::<Vec<(&str, i64, f64)>>(),
+            [
+                ("Food", 8000, 66.66666666666667),
+                ("Transport", 2000, 16.666666666666668)
+            ]
+        );
+        assert_eq!(
+            top_spending_tags(
+                &OrderColumns::from(orders.as_slice()),
+                (
+                    NaiveDate::from_ymd(2021, 5, 1),
+                    NaiveDate::from_ymd(2021, 5, 31),
+                ),
+                2
+            )
+            .unwrap(),
+            []
+        );
+    }
+
+    #[test]
+    fn detect_rising_spending() {
+        let orders = [(1, -3000), (2, -6000), (3, -3000), (4, -9000), (4, 1000)]
+            .iter()
+            .map(|&(month, cents)| order(NaiveDate::from_ymd(2021, month, 10), cents, "Food"))
+            .collect::<Vec<Order>>();
+        let range = |first, last| {
+            (
+                NaiveDate::from_ymd(2021, first, 1),
+                NaiveDate::from_ymd(2021, last, 1),
+            )
+        };
+
+        let result = spending_trend(
+            &OrderColumns::from(orders.as_slice()),
+            (CategoryType::Tag, "Food"),
+            range(3, 4),
+            3,
+        )
+        .unwrap();
+        assert_eq!(
+            result
+                .averages
+                .iter()
+                .map(|item| (item.month.month(), item.spent.cents(), item.average.cents()))
+                .collect::<Vec<(u32, i64, i64)>>(),
+            [(3, 3000, 4000), (4, 9000, 6000)]
+        );
+        assert_eq!(result.trend, Trend::Rising);
+        assert_eq!(
+            spending_trend(
+                &OrderColumns::from(orders.as_slice()),
+                (CategoryType::Tag, "Food"),
+                range(2, 3),
+                1
+            )
+            .unwrap()
+            .trend,
+            Trend::Falling
+        );
+        assert_eq!(
+            spending_trend(
+                &OrderColumns::from(orders.as_slice()),
+                (CategoryType::Resource, "Food"),
+                range(1, 4),
+                2
+            )
+            .unwrap()
+            .trend,
+            Trend::Stable
+        );
+        let overflowing = [Amount::MAX, Amount::MAX]
+            .iter()
+            .map(|amount| order(NaiveDate::from_ymd(2021, 3, 2), -amount.cents(), "Food"))
+            .collect::<Vec<Order>>();
+        assert_eq!(
+            spending_trend(
+                &OrderColumns::from(overflowing.as_slice()),
+                (CategoryType::Tag, "Food"),
+                range(3, 3),
+                1
+            ),
+            Err(RequestFailure::Overflow)
+        );
+        for window in [0, usize::MAX] {
+            assert_eq!(
+                spending_trend(
+                    &OrderColumns::from(orders.as_slice()),
+                    (CategoryType::Tag, "Food"),
+                    range(3, 4),
+                    window
+                ),
+                Err(RequestFailure::IncorrectArgument)
+            );
+        }
+        assert_eq!(
+            spending_trend(
+                &OrderColumns::from(orders.as_slice()),
+                (CategoryType::Tag, "Food"),
+                (NaiveDate::MIN, NaiveDate::MAX),
+                2
+            ),
+            Err(RequestFailure::IncorrectArgument)
+        );
+    }
+
+    #[test]
+    fn compute_payee_stats() {
+        let orders = [
+            (3, -4550, Some("Grocer")),
+            (8, -3000, Some("Baker")),
+            (12, -2001, Some("Grocer")),
+            (15, -9000, None),
+            (40, -1000, Some("Baker")),
+        ]
+        .iter()
+        .map(|&(day, cents, payee)| Order {
+            payee: payee.map(String::from),
+            ..order(
+                NaiveDate::from_ymd(2021, 3, 1) + Duration::days(day),
+                cents,
+                "Food",
+            )
+        })
+        .collect::<Vec<Order>>();
+
+        let stats = payee_stats(
+            &orders,
+            (
+                NaiveDate::from_ymd(2021, 3, 1),
+                NaiveDate::from_ymd(2021, 3, 31),
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            stats,
+            [
+                PayeeStats {
+                    payee: "Grocer".into(),
+                    count: 2,
+                    total: Amount::from_cents(-6551),
+                    average: Amount::from_cents(-3275),
+                },
+                PayeeStats {
+                    payee: "Baker".into(),
+                    count: 1,
+                    total: Amount::from_cents(-3000),
+                    average: Amount::from_cents(-3000),
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn flag_amount_outliers() {
+        let mut orders = [-999, -999, -1050, -999, -9990, -1020, -999]
+            .iter()
+            .enumerate()
+            .map(|(id, &cents)| Order {
+                id,
+                ..order(NaiveDate::from_ymd(2021, 3, 1), cents, "Streaming")
+            })
+            .collect::<Vec<Order>>();
+
+        assert_eq!(
+            amount_outliers(&orders),
+            [AmountOutlier {
+                id: 4,
+                tag: "Streaming".into(),
+                amount: Amount::from_cents(-9990),
+                low: Amount::from_cents(-1143),
+                high: Amount::from_cents(-891),
+            }]
+        );
+        // Too few orders
+        orders.truncate(5);
+        orders.remove(0);
+        assert_eq!(amount_outliers(&orders), []);
+    }
+
+    #[test]
+    fn compute_quarterly_cash_flow() {
+        let mut orders = vec![
+            order(NaiveDate::from_ymd(2021, 1, 5), 250000, "Salary"),
+            order(NaiveDate::from_ymd(2021, 2, 9), -90000, "Rent"),
+            order(NaiveDate::from_ymd(2021, 4, 2), -300000, "Travel"),
+            order(NaiveDate::from_ymd(2021, 5, 2), 50000, "Savings"),
+            order(NaiveDate::from_ymd(2022, 1, 1), -1000, "Food"),
+        ];
+        orders[2].resource = Some("Card".into());
+        orders[3].resource = Some("Card".into());
+        orders[3].set_kind(Some(OrderKind::Transfer));
+
+        let flows = cash_flow(
+            &orders,
+            Period::Quarter,
+            (
+                NaiveDate::from_ymd(2021, 2, 1),
+                NaiveDate::from_ymd(2021, 9, 30),
+            ),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            flows
+                .iter()
+                .map(|flow| (flow.start, flow.net.cents()))
+                .collect::<Vec<(NaiveDate, i64)>>(),
+            [
+                (NaiveDate::from_ymd(2021, 1, 1), 160000),
+                (NaiveDate::from_ymd(2021, 4, 1), -300000),
+                (NaiveDate::from_ymd(2021, 7, 1), 0)
+            ]
+        );
+
+        let flows = cash_flow(
+            &orders,
+            Period::Quarter,
+            (
+                NaiveDate::from_ymd(2021, 4, 1),
+                NaiveDate::from_ymd(2021, 6, 30),
+            ),
+            Some("Card"),
+        )
+        .unwrap();
+        assert_eq!(flows[0].income, Amount::from_cents(50000));
+        assert_eq!(flows[0].expenses, Amount::from_cents(-300000));
+        assert_eq!(flows[0].net, Amount::from_cents(-250000));
+    }
+
+    #[test]
+    fn compare_with_previous_month() {
+        let orders = [
+            order(NaiveDate::from_ymd(2021, 2, 5), -10000, "Food"),
+            order(NaiveDate::from_ymd(2021, 2, 9), -5000, "Transport"),
+            order(NaiveDate::from_ymd(2021, 3, 2), -9000, "Food"),
+            order(NaiveDate::from_ymd(2021, 3, 8), -8700, "Transport"),
+            order(NaiveDate::from_ymd(2021, 3, 9), 250000, "Salary"),
+        ];
+
+        let comparison = period_vs_previous(
+            &OrderColumns::from(&orders[..]),
+            Period::Month,
+            NaiveDate::from_ymd(2021, 3, 15),
+        )
+        .unwrap();
+
+        assert_eq!(comparison.current, Amount::from_cents(17700));
+        assert_eq!(comparison.previous, Amount::from_cents(15000));
+        assert_eq!(comparison.delta, Amount::from_cents(2700));
+        assert_eq!(comparison.ratio, Some(0.18));
+        assert_eq!(
+            comparison
+                .movers
+                .iter()
+                .map(|mover| (mover.tag.as_str(), mover.delta.cents()))
+                .collect::<Vec<(&str, i64)>>(),
+            [("Transport", 3700), ("Food", -1000)]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! # Persistence of accounts behind interchangeable backends.
+#[cfg(all(feature = "sqlite", not(target_arch = "wasm32")))]
+pub mod sqlite;
+
+use crate::backup;
+use crate::source::{SaveConflict, SaveError};
+use crate::Account;
+use chrono::{Local, NaiveDateTime};
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// Copy of a saved account, kept to be loaded later.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Snapshot {
+    /// Identifier given by the backend.
+    pub id: i64,
+    pub taken_at: NaiveDateTime,
+}
+
+/// Failure of a storage backend.
+#[derive(Debug)]
+pub enum StorageError {
+    /// Nothing has been saved, or the snapshot does not exist.
+    NotFound,
+    /// Stored data cannot be read or written as an account.
+    InvalidData(String),
+    /// The stored account changed since loaded or last saved.
+    Conflict(SaveConflict),
+    /// Failure of the backend, like I/O or database errors.
+    Backend(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageError::NotFound => write!(f, "no saved account"),
+            StorageError::InvalidData(reason) => write!(f, "invalid account data: {}", reason),
+            StorageError::Conflict(conflict) => conflict.fmt(f),
+            StorageError::Backend(reason) => write!(f, "storage failure: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<SaveError> for StorageError {
+    fn from(error: SaveError) -> Self {
+        match error {
+            SaveError::Conflict(conflict) => StorageError::Conflict(conflict),
+            SaveError::Encoding(reason) => StorageError::InvalidData(reason),
+            SaveError::Io(error) => StorageError::Backend(error.to_string()),
+        }
+    }
+}
+
+/// Place where an account is saved, along with its snapshots.
+pub trait Storage {
+    /// Returns the saved account.
+    fn load(&self) -> Result<Account, StorageError>;
+    /// Saves an account over the previous one.
+    fn save(&mut self, account: &Account) -> Result<(), StorageError>;
+    /// Keeps a copy of the saved account.
+    fn create_snapshot(&mut self) -> Result<Snapshot, StorageError>;
+    /// Returns the kept copies, from the oldest.
+    fn snapshots(&self) -> Result<Vec<Snapshot>, StorageError>;
+    /// Returns the account as it was when a copy was kept.
+    fn load_snapshot(&self, id: i64) -> Result<Account, StorageError>;
+}
+
+/// Name of the folder holding snapshots, next to the file. Kept apart from
+/// backups so that rotating backups never removes them.
+pub const SNAPSHOT_FOLDER: &str = ".snapshots";
+
+/// Account saved as a whole file, with snapshots kept in its `.snapshots/`
+/// folder.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FileStorage {
+    pub path: PathBuf,
+}
+
+impl FileStorage {
+    pub fn new(path: PathBuf) -> FileStorage {
+        FileStorage { path }
+    }
+
+    /// Returns the folder of the snapshots, never removed.
+    fn directory(&self) -> PathBuf {
+        self.path
+            .parent()
+            .unwrap_or_else(|| Path::new(""))
+            .join(SNAPSHOT_FOLDER)
+    }
+
+    /// Returns copies along with their snapshot.
+    fn copies(&self) -> Result<Vec<(PathBuf, Snapshot)>, StorageError> {
+        Ok(backup::list_in(&self.directory(), &self.path)
+            .map_err(|error| StorageError::Backend(error.to_string()))?
+            .into_iter()
+            .filter_map(|copy| {
+                let taken_at = backup::timestamp(&self.path, &copy)?;
+                let id = taken_at.and_utc().timestamp_micros();
+                Some((copy, Snapshot { id, taken_at }))
+            })
+            .collect())
+    }
+}
+
+/// Reads an account file, telling missing files apart.
+fn load_file(path: &Path) -> Result<Account, StorageError> {
+    Account::load_file(path).map_err(|error| match error.kind() {
+        ErrorKind::NotFound => StorageError::NotFound,
+        ErrorKind::InvalidData => StorageError::InvalidData(error.to_string()),
+        _ => StorageError::Backend(error.to_string()),
+    })
+}
+
+impl Storage for FileStorage {
+    fn load(&self) -> Result<Account, StorageError> {
+        load_file(&self.path)
+    }
+
+    fn save(&mut self, account: &Account) -> Result<(), StorageError> {
+        Ok(account.save_file(&self.path)?)
+    }
+
+    fn create_snapshot(&mut self) -> Result<Snapshot, StorageError> {
+        let copy = backup::copy_into(&self.directory(), &self.path, Local::now().naive_local())
+            .map_err(|error| StorageError::Backend(error.to_string()))?
+            .ok_or(StorageError::NotFound)?;
+        let taken_at = backup::timestamp(&self.path, &copy).ok_or(StorageError::NotFound)?;
+
+        Ok(Snapshot {
+            id: taken_at.and_utc().timestamp_micros(),
+            taken_at,
+        })
+    }
+
+    fn snapshots(&self) -> Result<Vec<Snapshot>, StorageError> {
+        Ok(self
+            .copies()?
+            .into_iter()
+            .map(|(_, snapshot)| snapshot)
+            .collect())
+    }
+
+    fn load_snapshot(&self, id: i64) -> Result<Account, StorageError> {
+        let (copy, _) = self
+            .copies()?
+            .into_iter()
+            .find(|(_, snapshot)| snapshot.id == id)
+            .ok_or(StorageError::NotFound)?;
+        load_file(&copy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::BackupPolicy;
+    use crate::tests::test_directory;
+
+    #[test]
+    fn store_account_file_with_snapshots() {
+        let directory = test_directory("store_account_file_with_snapshots");
+        let mut storage = FileStorage::new(directory.join("money.yml"));
+        let mut account = Account::create();
+
+        let missing = storage.load().map(|_| ());
+        let no_snapshot = storage.create_snapshot().map(|_| ());
+        account.set_label("First");
+        account.save_to(&mut storage).unwrap();
+        let snapshot = storage.create_snapshot().unwrap();
+        account.set_label("Second");
+        account.save_to(&mut storage).unwrap();
+        let loaded = Account::load_from(&storage).unwrap();
+        let snapshots = storage.snapshots().unwrap();
+        let restored = storage.load_snapshot(snapshot.id).unwrap();
+        let unknown = storage.load_snapshot(snapshot.id + 1).map(|_| ());
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(matches!(missing, Err(StorageError::NotFound)), true);
+        assert_eq!(matches!(no_snapshot, Err(StorageError::NotFound)), true);
+        assert_eq!(loaded.label(), "Second");
+        assert_eq!(snapshots, vec![snapshot]);
+        assert_eq!(restored.label(), "First");
+        assert_eq!(matches!(unknown, Err(StorageError::NotFound)), true);
+    }
+
+    #[test]
+    fn keep_snapshots_apart_from_backups() {
+        let directory = test_directory("keep_snapshots_apart_from_backups");
+        let mut storage = FileStorage::new(directory.join("money.yml"));
+        let mut account = Account::create();
+        account.set_backup_policy(Some(BackupPolicy {
+            keep: 1,
+            folder: true,
+        }));
+
+        account.save_to(&mut storage).unwrap();
+        let snapshots = (0..3)
+            .map(|_| {
+                let snapshot = storage.create_snapshot().unwrap();
+                account.save_to(&mut storage).unwrap();
+                snapshot
+            })
+            .collect::<Vec<Snapshot>>();
+        let listed = storage.snapshots().unwrap();
+        let backups = account.backups(&storage.path).unwrap();
+        let restored = snapshots
+            .iter()
+            .map(|snapshot| storage.load_snapshot(snapshot.id).is_ok())
+            .collect::<Vec<bool>>();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(listed, snapshots);
+        assert_eq!(backups.len(), 1);
+        assert_eq!(restored, [true, true, true]);
+    }
+}
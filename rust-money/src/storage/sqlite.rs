@@ -0,0 +1,261 @@
+//! # Accounts stored in a SQLite database.
+//!
+//! Orders are rows of their own, only written when changed, which can be
+//! queried by date, amount or resource. Other account data is stored as a
+//! single JSON document.
+use super::{Snapshot, Storage, StorageError};
+use crate::Account;
+use chrono::{Local, NaiveDateTime};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Tables, created when missing. Amounts are stored in cents.
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS account (
+        id INTEGER PRIMARY KEY CHECK (id = 0),
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS orders (
+        id INTEGER PRIMARY KEY,
+        position INTEGER NOT NULL,
+        date TEXT,
+        description TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        resource TEXT,
+        data TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS snapshots (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        taken_at TEXT NOT NULL,
+        data TEXT NOT NULL
+    );";
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(error: rusqlite::Error) -> Self {
+        StorageError::Backend(error.to_string())
+    }
+}
+
+fn invalid_data(error: serde_json::Error) -> StorageError {
+    StorageError::InvalidData(error.to_string())
+}
+
+/// Account saved in a SQLite database, with snapshots kept in a table.
+pub struct SqliteStorage {
+    connection: Connection,
+}
+
+impl SqliteStorage {
+    /// Opens a database file, created if missing.
+    pub fn open(path: &Path) -> Result<SqliteStorage, StorageError> {
+        SqliteStorage::with_connection(Connection::open(path)?)
+    }
+
+    /// Creates a database living in memory.
+    pub fn open_in_memory() -> Result<SqliteStorage, StorageError> {
+        SqliteStorage::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<SqliteStorage, StorageError> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(SqliteStorage { connection })
+    }
+
+    /// Returns the database, to query orders.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Returns the saved account as a JSON document.
+    fn document(&self) -> Result<String, StorageError> {
+        let data = self
+            .connection
+            .query_row("SELECT data FROM account WHERE id = 0", [], |row| {
+                row.get::<_, String>(0)
+            })
+            .optional()?
+            .ok_or(StorageError::NotFound)?;
+        let mut document = serde_json::from_str::<Value>(&data).map_err(invalid_data)?;
+        let mut statement = self
+            .connection
+            .prepare("SELECT data FROM orders ORDER BY position")?;
+        let orders = statement
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|data| serde_json::from_str::<Value>(&data?).map_err(invalid_data))
+            .collect::<Result<Vec<Value>, StorageError>>()?;
+
+        match document.as_object_mut() {
+            Some(fields) => fields.insert("orders".into(), Value::Array(orders)),
+            None => return Err(StorageError::InvalidData("not an account".into())),
+        };
+        Ok(document.to_string())
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load(&self) -> Result<Account, StorageError> {
+        Account::from_json(&self.document()?).map_err(invalid_data)
+    }
+
+    fn save(&mut self, account: &Account) -> Result<(), StorageError> {
+        let mut document = serde_json::to_value(account).map_err(invalid_data)?;
+        let orders = match document
+            .as_object_mut()
+            .and_then(|fields| fields.remove("orders"))
+        {
+            Some(Value::Array(orders)) => orders,
+            _ => Vec::new(),
+        };
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "INSERT OR REPLACE INTO account (id, data) VALUES (0, ?1)",
+            [document.to_string()],
+        )?;
+
+        let stored = {
+            let mut statement = transaction.prepare("SELECT id, position, data FROM orders")?;
+            let rows = statement.query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, (row.get(1)?, row.get(2)?)))
+            })?;
+            rows.collect::<Result<HashMap<i64, (i64, String)>, rusqlite::Error>>()?
+        };
+        let mut ids = HashSet::new();
+
+        for (position, (order, data)) in account.orders().iter().zip(orders).enumerate() {
+            let (id, row) = (order.id() as i64, (position as i64, data.to_string()));
+            ids.insert(id);
+            if stored.get(&id) != Some(&row) {
+                transaction.execute(
+                    "INSERT OR REPLACE INTO orders \
+                     (id, position, date, description, amount, resource, data) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        id,
+                        row.0,
+                        order.date.map(|date| date.to_string()),
+                        order.description.as_str(),
+                        order.amount.cents(),
+                        order.resource.as_deref(),
+                        row.1
+                    ],
+                )?;
+            }
+        }
+        stored
+            .keys()
+            .filter(|id| !ids.contains(id))
+            .try_for_each(|id| {
+                transaction
+                    .execute("DELETE FROM orders WHERE id = ?1", [id])
+                    .map(|_| ())
+            })?;
+
+        Ok(transaction.commit()?)
+    }
+
+    fn create_snapshot(&mut self) -> Result<Snapshot, StorageError> {
+        let data = self.document()?;
+        let taken_at = Local::now().naive_local();
+        self.connection.execute(
+            "INSERT INTO snapshots (taken_at, data) VALUES (?1, ?2)",
+            params![taken_at.format(TIMESTAMP_FORMAT).to_string(), data],
+        )?;
+
+        Ok(Snapshot {
+            id: self.connection.last_insert_rowid(),
+            taken_at,
+        })
+    }
+
+    fn snapshots(&self) -> Result<Vec<Snapshot>, StorageError> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT id, taken_at FROM snapshots ORDER BY id")?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        rows.map(|row| {
+            let (id, taken_at) = row?;
+            NaiveDateTime::parse_from_str(&taken_at, TIMESTAMP_FORMAT)
+                .map(|taken_at| Snapshot { id, taken_at })
+                .map_err(|error| StorageError::InvalidData(error.to_string()))
+        })
+        .collect()
+    }
+
+    fn load_snapshot(&self, id: i64) -> Result<Account, StorageError> {
+        let data = self
+            .connection
+            .query_row("SELECT data FROM snapshots WHERE id = ?1", [id], |row| {
+                row.get::<_, String>(0)
+            })
+            .optional()?
+            .ok_or(StorageError::NotFound)?;
+        Account::from_json(&data).map_err(invalid_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::Amount;
+
+    #[test]
+    fn write_changed_orders_only() {
+        let mut storage = SqliteStorage::open_in_memory().unwrap();
+        let mut account = Account::create();
+        account.add_resource("Bank");
+        let resources = account.resources();
+        (1..=3).for_each(|index| {
+            let id = account.add_order();
+            let mut order = account.get_order_mut(id).unwrap();
+            order.description = format!("Order {}", index).into();
+            order.amount = Amount::from_cents(-100 * index);
+            order.set_resource("Bank", &resources);
+        });
+
+        assert_eq!(matches!(storage.load(), Err(StorageError::NotFound)), true);
+        storage.save(&account).unwrap();
+        let snapshot = storage.create_snapshot().unwrap();
+        assert_eq!(storage.load().unwrap(), account);
+
+        // Only the account document and the edited order are written
+        account.get_order_mut(1).unwrap().description = "Edited".into();
+        let changes = storage.connection().total_changes();
+        storage.save(&account).unwrap();
+        assert_eq!(storage.connection().total_changes() - changes, 2);
+
+        assert_eq!(account.delete_order(0), true);
+        storage.save(&account).unwrap();
+        let loaded = storage.load().unwrap();
+
+        assert_eq!(loaded.orders(), account.orders());
+        assert_eq!(
+            storage
+                .connection()
+                .query_row(
+                    "SELECT SUM(amount) FROM orders WHERE resource = 'Bank'",
+                    [],
+                    |row| row.get::<_, i64>(0)
+                )
+                .unwrap(),
+            -500
+        );
+        assert_eq!(storage.snapshots().unwrap(), vec![snapshot.clone()]);
+        assert_eq!(
+            storage.load_snapshot(snapshot.id).unwrap().orders().len(),
+            3
+        );
+        assert_eq!(
+            matches!(
+                storage.load_snapshot(snapshot.id + 1),
+                Err(StorageError::NotFound)
+            ),
+            true
+        );
+    }
+}
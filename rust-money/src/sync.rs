@@ -0,0 +1,432 @@
+//! # Synchronization of an `Account` with banks and remote storage.
+use crate::import::connector::{self, BankConnector, ConnectorError};
+use crate::Account;
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Copy of the account file kept outside of the device (cloud drive, server, ..).
+pub trait RemoteStore {
+    /// Replaces the remote copy.
+    fn push(&mut self, content: &[u8]) -> Result<(), SyncError>;
+}
+
+/// Defines synchronization error types.
+#[derive(Clone, PartialEq, Debug)]
+pub enum SyncError {
+    /// Last run is too recent.
+    RateLimited,
+    /// The bank connector failed.
+    Connector(ConnectorError),
+    /// The account could not be saved.
+    Storage(String),
+    /// The remote copy could not be updated.
+    Remote(String),
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SyncError::RateLimited => write!(f, "last synchronization is too recent"),
+            SyncError::Connector(error) => write!(f, "{}", error),
+            SyncError::Storage(error) => write!(f, "save failed: {}", error),
+            SyncError::Remote(error) => write!(f, "push failed: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
+
+impl SyncError {
+    /// Returns `true` if trying again may succeed.
+    fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            SyncError::Connector(ConnectorError::Network(_)) | SyncError::Remote(_)
+        )
+    }
+}
+
+/// Exponential backoff between attempts.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RetryPolicy {
+    /// Attempts of a step, including the first one.
+    pub max_attempts: u32,
+    pub initial_delay: std::time::Duration,
+    pub max_delay: std::time::Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            initial_delay: std::time::Duration::from_secs(1),
+            max_delay: std::time::Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the delay before a given retry, starting from 1.
+    pub fn delay(&self, retry: u32) -> std::time::Duration {
+        self.initial_delay
+            .checked_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+/// Bank account fetched into the `Account`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SyncSource {
+    /// Identifier given by the connector.
+    pub account_id: String,
+    /// Resource assigned to imported orders.
+    pub resource: Option<String>,
+    /// Transactions are fetched from this date.
+    pub since: NaiveDate,
+}
+
+/// Steps of a synchronization.
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+pub enum SyncStage {
+    Idle,
+    Fetching,
+    ApplyingRules,
+    Saving,
+    Pushing,
+    Done,
+    Failed,
+}
+
+/// Progress and outcome of the last synchronization.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct SyncStatus {
+    pub stage: SyncStage,
+    pub last_run: Option<NaiveDateTime>,
+    pub last_success: Option<NaiveDateTime>,
+    /// Created orders, including recurring ones.
+    pub imported: usize,
+    /// Transactions already recorded.
+    pub duplicates: usize,
+    /// Retries during the last run.
+    pub retries: u32,
+    pub error: Option<String>,
+}
+
+impl Default for SyncStatus {
+    fn default() -> Self {
+        SyncStatus {
+            stage: SyncStage::Idle,
+            last_run: None,
+            last_success: None,
+            imported: 0,
+            duplicates: 0,
+            retries: 0,
+            error: None,
+        }
+    }
+}
+
+/// Coordinates fetch, deduplication, recurring rules, save and push.
+pub struct SyncEngine<C: BankConnector> {
+    connector: C,
+    sources: Vec<SyncSource>,
+    path: Option<PathBuf>,
+    remote: Option<Box<dyn RemoteStore>>,
+    retry: RetryPolicy,
+    /// Minimum duration between two runs.
+    min_interval: Duration,
+    sleep: fn(std::time::Duration),
+    status: SyncStatus,
+}
+
+impl<C: BankConnector> SyncEngine<C> {
+    /// Creates an engine without sources, file nor remote storage.
+    pub fn new(connector: C) -> SyncEngine<C> {
+        SyncEngine {
+            connector,
+            sources: Vec::new(),
+            path: None,
+            remote: None,
+            retry: RetryPolicy::default(),
+            min_interval: Duration::minutes(15),
+            sleep: std::thread::sleep,
+            status: SyncStatus::default(),
+        }
+    }
+
+    /// Adds a bank account to fetch.
+    pub fn add_source(&mut self, source: SyncSource) {
+        self.sources.push(source);
+    }
+
+    /// Saves the account into a given file after each run.
+    pub fn set_path(&mut self, path: PathBuf) {
+        self.path = Some(path);
+    }
+
+    /// Pushes the account to a remote storage after each run.
+    pub fn set_remote(&mut self, remote: Box<dyn RemoteStore>) {
+        self.remote = Some(remote);
+    }
+
+    /// Overrides the default retry policy.
+    pub fn set_retry_policy(&mut self, retry: RetryPolicy) {
+        self.retry = retry;
+    }
+
+    /// Overrides the minimum duration between two runs.
+    pub fn set_min_interval(&mut self, min_interval: Duration) {
+        self.min_interval = min_interval;
+    }
+
+    /// Overrides how to wait between attempts.
+    pub fn set_sleep(&mut self, sleep: fn(std::time::Duration)) {
+        self.sleep = sleep;
+    }
+
+    /// Gets the status of the last run.
+    pub fn status(&self) -> &SyncStatus {
+        &self.status
+    }
+
+    /// Runs a step until it succeeds, fails for good or runs out of attempts.
+    fn with_retry<T>(
+        retry: &RetryPolicy,
+        sleep: fn(std::time::Duration),
+        retries: &mut u32,
+        mut step: impl FnMut() -> Result<T, SyncError>,
+    ) -> Result<T, SyncError> {
+        let mut attempt = 1;
+
+        loop {
+            match step() {
+                Err(error) if error.is_transient() && attempt < retry.max_attempts => {
+                    sleep(retry.delay(attempt));
+                    attempt += 1;
+                    *retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Runs all steps at a given time.
+    fn run_steps(&mut self, account: &mut Account, now: NaiveDateTime) -> Result<(), SyncError> {
+        let retry = self.retry;
+        let sleep = self.sleep;
+
+        self.status.stage = SyncStage::Fetching;
+        for source in self.sources.iter_mut() {
+            let connector = &mut self.connector;
+            let preview = Self::with_retry(&retry, sleep, &mut self.status.retries, || {
+                connector::fetch_preview(connector, account, &source.account_id, source.since)
+                    .map_err(SyncError::Connector)
+            })?;
+
+            self.status.imported += account
+                .apply_import(&preview, source.resource.as_deref())
+                .len();
+            self.status.duplicates += preview.duplicates.len();
+            // Keep an overlap as recent transactions may still change
+            if let Some(latest) = preview.new.iter().filter_map(|item| item.date).max() {
+                source.since = source.since.max(latest - Duration::days(7));
+            }
+        }
+
+        self.status.stage = SyncStage::ApplyingRules;
+        self.status.imported += account.materialize_recurring_until(now.date()).len();
+
+        if let Some(path) = &self.path {
+            self.status.stage = SyncStage::Saving;
+            account
+                .save_file(path)
+                .map_err(|error| SyncError::Storage(error.to_string()))?;
+        }
+
+        if let Some(remote) = self.remote.as_mut() {
+            self.status.stage = SyncStage::Pushing;
+            let content = serde_yaml::to_string(account)
+                .map_err(|error| SyncError::Storage(error.to_string()))?;
+            Self::with_retry(&retry, sleep, &mut self.status.retries, || {
+                remote.push(content.as_bytes())
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Synchronizes the account at a given time.
+    ///
+    /// Runs closer than the minimum interval are rejected.
+    pub fn run(&mut self, account: &mut Account, now: NaiveDateTime) -> Result<(), SyncError> {
+        if let Some(last_run) = self.status.last_run {
+            if now - last_run < self.min_interval {
+                return Err(SyncError::RateLimited);
+            }
+        }
+
+        self.status = SyncStatus {
+            last_run: Some(now),
+            last_success: self.status.last_success,
+            ..SyncStatus::default()
+        };
+
+        match self.run_steps(account, now) {
+            Ok(()) => {
+                self.status.stage = SyncStage::Done;
+                self.status.last_success = Some(now);
+                Ok(())
+            }
+            Err(error) => {
+                self.status.stage = SyncStage::Failed;
+                self.status.error = Some(error.to_string());
+                Err(error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::Amount;
+    use crate::import::connector::BankAccount;
+    use crate::import::preview::ImportedTransaction;
+    use crate::order::{Order, TransactionState};
+    use crate::recurring::RecurringRule;
+    use std::cell::RefCell;
+    use std::convert::TryFrom;
+    use std::rc::Rc;
+
+    /// Fails a given number of times before serving transactions.
+    struct FlakyConnector {
+        failures: u32,
+        transactions: Vec<ImportedTransaction>,
+    }
+
+    impl BankConnector for FlakyConnector {
+        fn list_accounts(&mut self) -> Result<Vec<BankAccount>, ConnectorError> {
+            Ok(Vec::new())
+        }
+
+        fn fetch_transactions(
+            &mut self,
+            _: &str,
+            since: NaiveDate,
+        ) -> Result<Vec<ImportedTransaction>, ConnectorError> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                Err(ConnectorError::Network("timeout".into()))
+            } else {
+                Ok(self
+                    .transactions
+                    .iter()
+                    .filter(|transaction| transaction.date >= Some(since))
+                    .cloned()
+                    .collect())
+            }
+        }
+    }
+
+    /// Keeps pushed content in memory.
+    struct MemoryStore(Rc<RefCell<Vec<u8>>>);
+
+    impl RemoteStore for MemoryStore {
+        fn push(&mut self, content: &[u8]) -> Result<(), SyncError> {
+            *self.0.borrow_mut() = content.to_vec();
+            Ok(())
+        }
+    }
+
+    fn no_sleep(_: std::time::Duration) {}
+
+    fn engine(failures: u32) -> SyncEngine<FlakyConnector> {
+        let mut engine = SyncEngine::new(FlakyConnector {
+            failures,
+            transactions: (1..=3)
+                .map(|day| ImportedTransaction {
+                    external_id: Some(format!("TX-{}", day)),
+                    date: Some(NaiveDate::from_ymd(2021, 3, day)),
+                    description: "Bakery".into(),
+                    amount: Amount::from_cents(-420),
+                    currency: None,
+                    state: TransactionState::Done,
+                })
+                .collect(),
+        });
+        engine.set_sleep(no_sleep);
+        engine.add_source(SyncSource {
+            account_id: "main".into(),
+            resource: Some("Bank".into()),
+            since: NaiveDate::from_ymd(2021, 3, 1),
+        });
+        engine
+    }
+
+    #[test]
+    fn back_off_exponentially() {
+        let retry = RetryPolicy::default();
+
+        assert_eq!(retry.delay(1), std::time::Duration::from_secs(1));
+        assert_eq!(retry.delay(3), std::time::Duration::from_secs(4));
+        assert_eq!(retry.delay(10), std::time::Duration::from_secs(30));
+        assert_eq!(retry.delay(100), std::time::Duration::from_secs(30));
+    }
+
+    #[test]
+    fn sync_with_retries() {
+        let remote = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = engine(2);
+        engine.set_remote(Box::new(MemoryStore(remote.clone())));
+        let mut account = Account::create();
+        account.add_resource("Bank");
+        account.add_recurring_rule(RecurringRule::new(
+            "Rent",
+            NaiveDate::from_ymd(2021, 3, 5),
+            Order::default(),
+        ));
+        let now = NaiveDate::from_ymd(2021, 3, 10).and_hms(8, 0, 0);
+
+        assert_eq!(engine.run(&mut account, now), Ok(()));
+        assert_eq!(engine.status().stage, SyncStage::Done);
+        assert_eq!(engine.status().retries, 2);
+        assert_eq!(engine.status().imported, 4);
+        assert_eq!(account.orders()[0].resource, Some("Bank".into()));
+        assert_eq!(
+            Account::try_from(String::from_utf8(remote.borrow().clone()).unwrap().as_str())
+                .unwrap(),
+            account
+        );
+
+        // Too early
+        assert_eq!(
+            engine.run(&mut account, now + Duration::minutes(5)),
+            Err(SyncError::RateLimited)
+        );
+
+        // Already known transactions are skipped
+        assert_eq!(engine.run(&mut account, now + Duration::hours(1)), Ok(()));
+        assert_eq!(engine.status().imported, 0);
+        assert_eq!(engine.status().duplicates, 3);
+        assert_eq!(account.orders().len(), 4);
+    }
+
+    #[test]
+    fn give_up_after_max_attempts() {
+        let mut engine = engine(10);
+        let mut account = Account::create();
+        let now = NaiveDate::from_ymd(2021, 3, 10).and_hms(8, 0, 0);
+
+        assert_eq!(
+            engine.run(&mut account, now),
+            Err(SyncError::Connector(ConnectorError::Network(
+                "timeout".into()
+            )))
+        );
+        assert_eq!(engine.status().stage, SyncStage::Failed);
+        assert_eq!(engine.status().retries, 3);
+        assert_eq!(engine.status().last_success, None);
+    }
+}
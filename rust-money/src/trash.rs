@@ -0,0 +1,11 @@
+//! # Recoverable deleted orders.
+use crate::order::Order;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Order moved to the trash, keeping its identifier.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TrashedOrder {
+    pub order: Order,
+    pub deleted_at: NaiveDateTime,
+}
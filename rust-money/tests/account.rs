@@ -3,7 +3,7 @@ mod account {
     use rust_money::ext::OrderListExt;
     use rust_money::filter::category::Category;
     use rust_money::filter::{Filter, ItemSelector, VisibilityFilter};
-    use rust_money::order::{Order, TransactionState};
+    use rust_money::order::{HiddenReason, Order, TransactionState};
     use rust_money::Account;
 
     #[test]
@@ -21,7 +21,7 @@ mod account {
             account
                 .orders()
                 .iter()
-                .map(|x| (x.description.clone(), x.visible))
+                .map(|x| (x.description.to_string(), x.is_visible()))
                 .collect::<Vec<(String, bool)>>(),
             [
                 (String::from("Car gas"), true),
@@ -30,14 +30,14 @@ mod account {
             ]
         );
 
-        account.get_order_mut(0).unwrap().visible = false;
-        account.get_order_mut(1).unwrap().visible = false;
+        account.get_order_mut(0).unwrap().hide(HiddenReason::Manual);
+        account.get_order_mut(1).unwrap().hide(HiddenReason::Manual);
 
         assert_eq!(
             account
                 .orders()
                 .iter()
-                .map(|x| (x.description.clone(), x.visible))
+                .map(|x| (x.description.to_string(), x.is_visible()))
                 .collect::<Vec<(String, bool)>>(),
             [
                 (String::from("Car gas"), false),
@@ -51,12 +51,15 @@ mod account {
     fn check_filtered_orders() {
         let mut filter = Filter::default();
         let mut account = Account::create();
-        let mut expected_orders: Vec<(usize, Order)> = vec![
-            (0, Order::default()),
-            (1, Order::default()),
-            (2, Order::default()),
-            (3, Order::default()),
-        ];
+        let mut reference_account = Account::create();
+        (0..4).for_each(|_| {
+            reference_account.add_order();
+        });
+        let mut expected_orders: Vec<(usize, Order)> = reference_account
+            .orders()
+            .iter()
+            .map(|order| (order.id(), order.clone()))
+            .collect();
         let resources = [String::from("Bank"), String::from("Cash")];
         let tags = [
             String::from("Food"),
@@ -229,8 +232,8 @@ mod account {
         );
 
         filter.visibility = VisibilityFilter::VisibleOnly;
-        account.get_order_mut(1).unwrap().visible = false;
-        account.get_order_mut(2).unwrap().visible = false;
+        account.get_order_mut(1).unwrap().hide(HiddenReason::Manual);
+        account.get_order_mut(2).unwrap().hide(HiddenReason::Manual);
 
         assert_eq!(
             account.orders().apply_filter(&filter),
@@ -240,8 +243,8 @@ mod account {
             ]
         );
 
-        account.get_order_mut(1).unwrap().visible = true;
-        account.get_order_mut(2).unwrap().visible = true;
+        account.get_order_mut(1).unwrap().show();
+        account.get_order_mut(2).unwrap().show();
         account
             .get_order_mut(0)
             .unwrap()
@@ -0,0 +1,324 @@
+//! Scenario driving the public API through a year of usage, checking
+//! invariants and report figures at the end of each month.
+#![cfg(all(feature = "import", feature = "reporting", feature = "rules"))]
+
+use chrono::{Datelike, NaiveDate};
+use rust_money::allocation::Allocation;
+use rust_money::amount::Amount;
+use rust_money::category::ResourceKind;
+use rust_money::ext::{CategoryType, RequestFailure};
+use rust_money::filter::OptionNaiveDateRange;
+use rust_money::import::ImportedTransaction;
+use rust_money::lint::LintIssue;
+use rust_money::milestone::ThresholdDirection;
+use rust_money::order::{Order, OrderKind, OrderPatch, TransactionState};
+use rust_money::recurring::RecurringRule;
+use rust_money::stats::Period;
+use rust_money::Account;
+use std::collections::HashSet;
+
+const YEAR: i32 = 2021;
+const SALARY: i64 = 250000;
+const RENT: i64 = -90000;
+const GROCERIES: i64 = -8540;
+const MILESTONE: i64 = 1000000;
+
+fn date(month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(YEAR, month, day).unwrap()
+}
+
+fn end_of_month(month: u32) -> NaiveDate {
+    if month == 12 {
+        date(12, 31)
+    } else {
+        date(month + 1, 1).pred_opt().unwrap()
+    }
+}
+
+/// Account being driven, along with figures expected from the scenario.
+struct Scenario {
+    account: Account,
+    bank: Amount,
+    /// Bank balance at the end of the previous month.
+    previous_bank: Amount,
+    card: Amount,
+    unallocated: Amount,
+    /// Expenses of each month, as negative amounts.
+    expenses: Vec<Amount>,
+}
+
+impl Scenario {
+    /// Declares categories, recurring orders and goals.
+    fn new() -> Scenario {
+        let mut account = Account::create();
+        account.set_label("Household");
+        ["Salary", "Rent", "Food", "Travel", "Transfer"]
+            .iter()
+            .for_each(|tag| assert_eq!(account.add_tag(tag), None));
+        account.add_resource("Bank");
+        account.add_resource("Card");
+        account.set_resource_kind("Bank", Some(ResourceKind::Bank));
+        account.set_resource_kind("Card", Some(ResourceKind::CreditCard));
+        assert_eq!(
+            account.add_milestone("Bank", ThresholdDirection::Above, 10000.0),
+            None
+        );
+
+        let (tags, resources) = (account.tags(), account.resources());
+        [("Salary", SALARY, 1), ("Rent", RENT, 5)]
+            .iter()
+            .for_each(|&(label, cents, day)| {
+                let mut template = Order::default();
+                template.description = label.into();
+                template.amount = Amount::from_cents(cents);
+                template.add_tag(label, &tags);
+                template.set_resource("Bank", &resources);
+                assert_eq!(
+                    account.add_recurring_rule(RecurringRule::new(label, date(1, day), template)),
+                    None
+                );
+            });
+
+        Scenario {
+            account,
+            bank: Amount::ZERO,
+            previous_bank: Amount::ZERO,
+            card: Amount::ZERO,
+            unallocated: Amount::ZERO,
+            expenses: Vec::new(),
+        }
+    }
+
+    /// Imports the bank statement of a month, twice as users do.
+    fn import_statement(&mut self, month: u32) -> Vec<usize> {
+        let statement = (0..4)
+            .map(|week| ImportedTransaction {
+                external_id: Some(format!("{}-{}", month, week)),
+                date: Some(date(month, 3 + 7 * week)),
+                description: "Groceries".into(),
+                amount: Amount::from_cents(GROCERIES),
+                currency: None,
+                state: TransactionState::Done,
+            })
+            .collect::<Vec<ImportedTransaction>>();
+        let preview = self.account.preview_import(statement.clone());
+        let ids = self.account.apply_import(&preview, Some("Bank"));
+
+        assert_eq!(ids.len(), 4);
+        assert_eq!(self.account.preview_import(statement).duplicates.len(), 4);
+
+        self.account
+            .bulk_update(&ids, &OrderPatch::AddTag("Food".into()))
+            .iter()
+            .for_each(|failure| assert_eq!(*failure, None));
+        ids
+    }
+
+    /// Creates orders of recurring rules and confirms them.
+    fn run_recurring(&mut self, month: u32) -> Vec<usize> {
+        let ids = self
+            .account
+            .materialize_recurring_until(end_of_month(month));
+
+        assert_eq!(ids.len(), 2);
+        self.account
+            .bulk_update(&ids, &OrderPatch::SetState(TransactionState::Done))
+            .iter()
+            .for_each(|failure| assert_eq!(*failure, None));
+        ids
+    }
+
+    /// Spends on the credit card during a trip, with a few edits.
+    fn go_on_trip(&mut self) {
+        self.account.add_project("Summer trip");
+        self.account.set_project_budget("Summer trip", 1500.0);
+        let (tags, resources, projects) = (
+            self.account.tags(),
+            self.account.resources(),
+            self.account.projects().clone(),
+        );
+        let ids = [-60000, -45000, -19000, -5000]
+            .iter()
+            .enumerate()
+            .map(|(index, &cents)| {
+                let id = self.account.add_order();
+                let mut order = self.account.get_order_mut(id).unwrap();
+                order.date = Some(date(7, 10 + index as u32));
+                order.description = "Trip".into();
+                order.amount = Amount::from_cents(cents);
+                order.add_tag("Travel", &tags);
+                order.set_resource("Card", &resources);
+                order.set_project("Summer trip", &projects);
+                order.set_state(TransactionState::Done);
+                id
+            })
+            .collect::<Vec<usize>>();
+
+        // Typo fixed, accidental deletion undone, duplicate removed
+        self.account.get_order_mut(ids[0]).unwrap().description = "Hotel".into();
+        assert!(self.account.delete_order(ids[1]));
+        assert!(self.account.restore_order(ids[1]));
+        assert!(self.account.delete_order(ids[3]));
+
+        self.card = Amount::from_cents(124000);
+        self.expenses[6] -= self.card;
+
+        let summary = self.account.project_summary("Summer trip").unwrap();
+        assert_eq!(summary.remaining, Some(Amount::from_cents(26000)));
+    }
+
+    /// Pays the credit card off from the bank account.
+    fn pay_card(&mut self, month: u32) {
+        let (tags, resources) = (self.account.tags(), self.account.resources());
+
+        [("Bank", -self.card), ("Card", self.card)]
+            .iter()
+            .for_each(|&(resource, amount)| {
+                let id = self.account.add_order();
+                let mut order = self.account.get_order_mut(id).unwrap();
+                order.date = Some(date(month, 2));
+                order.description = "Card payment".into();
+                order.amount = amount;
+                order.set_kind(Some(OrderKind::Transfer));
+                order.add_tag("Transfer", &tags);
+                order.set_resource(resource, &resources);
+                order.set_state(TransactionState::Done);
+            });
+
+        self.bank -= self.card;
+        self.card = Amount::ZERO;
+    }
+
+    /// Drives a month of usage.
+    fn run_month(&mut self, month: u32) {
+        self.previous_bank = self.bank;
+        self.expenses.push(Amount::from_cents(RENT + 4 * GROCERIES));
+        let recurring = self.run_recurring(month);
+        let imported = self.import_statement(month);
+
+        self.bank += Amount::from_cents(SALARY + RENT + 4 * GROCERIES);
+        assert_eq!(
+            self.account.allocate(
+                recurring[0],
+                vec![
+                    Allocation {
+                        envelope: "Rent".into(),
+                        amount: Amount::from_cents(-RENT),
+                    },
+                    Allocation {
+                        envelope: "Food".into(),
+                        amount: Amount::from_cents(40000),
+                    },
+                ]
+            ),
+            None
+        );
+        self.unallocated += Amount::from_cents(SALARY + RENT - 40000);
+
+        match month {
+            7 => self.go_on_trip(),
+            8 => self.pay_card(month),
+            _ => {}
+        }
+
+        self.check_month(month, recurring.len() + imported.len(), recurring[1]);
+    }
+
+    /// Checks invariants and figures at the end of a month.
+    fn check_month(&mut self, month: u32, new_bank_orders: usize, rent: usize) {
+        let end = end_of_month(month);
+        let account = &mut self.account;
+        let ids = account
+            .orders()
+            .iter()
+            .map(|order| order.id())
+            .collect::<HashSet<usize>>();
+
+        assert_eq!(ids.len(), account.orders().len());
+        assert_eq!(
+            account
+                .lint()
+                .iter()
+                .filter(|issue| matches!(issue, LintIssue::UntaggedOrder { .. }))
+                .count(),
+            0
+        );
+
+        let worth = account.net_worth(end).unwrap();
+        assert_eq!(worth.assets, self.bank);
+        assert_eq!(worth.liabilities, self.card);
+        assert_eq!(worth.net, self.bank - self.card);
+        assert_eq!(
+            account.allocation_summary().unwrap().unallocated,
+            self.unallocated
+        );
+
+        let crossed = account.check_milestones_at(end);
+        let expected = self.previous_bank <= Amount::from_cents(MILESTONE)
+            && self.bank > Amount::from_cents(MILESTONE);
+        assert_eq!(crossed.len(), usize::from(expected), "month {}", month);
+
+        // Closing the month locks its bank orders
+        let reconciliation = account.reconcile("Bank", self.bank, end).unwrap();
+        let payments = usize::from(month == 8);
+        assert_eq!(reconciliation.reconciled, new_bank_orders + payments);
+        assert_eq!(
+            account.bulk_update(&[rent], &OrderPatch::SetState(TransactionState::Pending)),
+            vec![Some(RequestFailure::IncorrectArgument)]
+        );
+    }
+}
+
+#[test]
+fn simulate_year_of_usage() {
+    let mut scenario = Scenario::new();
+
+    (1..=12).for_each(|month| scenario.run_month(month));
+
+    let account = &mut scenario.account;
+
+    // Yearly reports
+    let months = account
+        .income_expense(Period::Month, date(1, 1), date(12, 31))
+        .unwrap();
+    assert_eq!(months.len(), 12);
+    months
+        .iter()
+        .zip(&scenario.expenses)
+        .for_each(|(month, expenses)| {
+            assert_eq!(month.income, Amount::from_cents(SALARY));
+            assert_eq!(month.expenses, *expenses);
+            assert_eq!(month.start.year(), YEAR);
+        });
+    let progress = account
+        .categorization_progress(OptionNaiveDateRange(None, None))
+        .unwrap();
+    assert_eq!(progress.untagged.count, 0);
+    assert_eq!(
+        account
+            .category_amount(CategoryType::Tag, "Food", OptionNaiveDateRange(None, None))
+            .unwrap()
+            .map(|amount| amount.current),
+        Some(Amount::from_cents(48 * GROCERIES))
+    );
+
+    // Archives
+    assert_eq!(account.purge_trash(), 1);
+    assert_eq!(account.orders().len(), 12 * 6 + 3 + 2);
+    assert_eq!(
+        account.export_changes().orders.len(),
+        account.orders().len()
+    );
+    assert_eq!(account.export_changes().orders.len(), 0);
+
+    let path = std::env::temp_dir().join(format!("money-{}-year.yml", std::process::id()));
+    account.save_file(&path).unwrap();
+    let loaded = Account::load_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(&loaded, account);
+    assert_eq!(
+        loaded.net_worth(date(12, 31)).map(|worth| worth.net),
+        Some(scenario.bank)
+    );
+}
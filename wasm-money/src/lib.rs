@@ -1,18 +1,30 @@
 //! # Money - WebAssembly API
 //!
 //! `money` is a collection of utilities to make tracking money expenses.
+#![cfg_attr(test, allow(clippy::bool_assert_comparison))]
 
 #[macro_use]
 mod utils;
 
 use chrono::NaiveDate;
 use js_sys::Array;
-use rust_money::ext::CategoryType::Resource;
-use rust_money::ext::{CategoryAmount, CategoryType};
-use rust_money::ext::{ExclusiveItemExt, OrderListExt};
+use rust_money::allocation::Allocation;
+use rust_money::amount::Amount;
+use rust_money::attachment::Attachment;
+#[cfg(feature = "reporting")]
+use rust_money::export::AmountFormat;
+use rust_money::ext::CategoryType::{Payee, Project, Resource, Tag};
+use rust_money::ext::ExclusiveItemExt;
+use rust_money::ext::{CategoryAmount, CategoryType, OrderingPreference, RequestFailure};
 use rust_money::filter::category::{Category, CategoryFilter};
 use rust_money::filter::{Filter, ItemSelector, OptionNaiveDateRange};
-use rust_money::order::{Order, TransactionState};
+#[cfg(feature = "import")]
+use rust_money::import::{encoding, CsvMapping, TextEncoding};
+use rust_money::merge::MergeStrategy;
+use rust_money::order::{
+    ComponentKind, HiddenReason, Order, OrderKind, OrderPatch, TransactionState,
+};
+use rust_money::stats::Period;
 pub use rust_money::Account;
 use std::convert::TryFrom;
 use std::str::FromStr;
@@ -40,6 +52,55 @@ pub fn load_account_data(account: &mut Account, data: &str) -> bool {
     }
 }
 
+/// Resets an existing account from **YAML** bytes, gzip-compressed or not.
+/// Returns `true` if operation succeded, `false` otherwise.
+#[wasm_bindgen]
+pub fn load_account_bytes(account: &mut Account, data: &[u8]) -> bool {
+    match Account::from_bytes(data) {
+        Ok(new) => {
+            *account = new;
+            true
+        }
+        Err(error) => {
+            log!("{}", error);
+            false
+        }
+    }
+}
+
+/// Resets an existing account from **JSON** data.
+/// Returns `true` if operation succeded, `false` otherwise.
+#[wasm_bindgen]
+pub fn load_account_json(account: &mut Account, data: &str) -> bool {
+    match Account::from_json(data) {
+        Ok(new) => {
+            *account = new;
+            true
+        }
+        Err(error) => {
+            log!("{}", error);
+            false
+        }
+    }
+}
+
+/// Resets an existing account from YAML data encrypted under a passphrase.
+/// Returns `true` if operation succeded, `false` otherwise.
+#[cfg(feature = "encryption")]
+#[wasm_bindgen]
+pub fn load_account_encrypted(account: &mut Account, data: &[u8], passphrase: &str) -> bool {
+    match Account::from_encrypted(data, passphrase) {
+        Ok(new) => {
+            *account = new;
+            true
+        }
+        Err(failure) => {
+            log!("{:?}", failure);
+            false
+        }
+    }
+}
+
 /// Returns the labek as a `JsValue`.
 #[wasm_bindgen]
 pub fn get_account_label(account: &Account) -> JsValue {
@@ -47,47 +108,318 @@ pub fn get_account_label(account: &Account) -> JsValue {
 }
 
 /// Returns all categories of a given type as `JsValues`.
+/// Archived tags and resources are skipped unless `include_archived`.
 #[wasm_bindgen]
-pub fn get_account_categories(account: &Account, category_type: CategoryType) -> Array {
-    if let Resource = category_type {
-        account.resources().sorted_keys()
-    } else {
-        account.tags().sorted_keys()
+pub fn get_account_categories(
+    account: &Account,
+    category_type: CategoryType,
+    include_archived: bool,
+) -> Array {
+    match category_type {
+        Resource => account.resource_entries().sorted_keys(include_archived),
+        Tag => account.tag_entries().sorted_keys(include_archived),
+        Payee => account.payees().sorted_keys(include_archived),
+        Project => account
+            .projects()
+            .iter()
+            .map(|project| project.name.clone())
+            .collect::<Vec<String>>()
+            .sorted_keys(include_archived),
     }
 }
 
-/// Exports filtered orders as `Array`.
+/// Returns tags or resources with their color, icon and description, sorted
+/// by name, as `Array` of JSON.
+#[wasm_bindgen]
+pub fn get_account_category_entries(account: &Account, category_type: CategoryType) -> Array {
+    let mut entries = match category_type {
+        Resource => account.resource_entries().clone(),
+        Tag => account.tag_entries().clone(),
+        Payee | Project => Vec::new(),
+    };
+    entries.sort_by_key(|entry| entry.name.to_lowercase());
+
+    entries
+        .iter()
+        .map(|entry| {
+            JsValue::from(
+                serde_json::json!({
+                    "name": entry.name,
+                    "color": entry.color,
+                    "icon": entry.icon,
+                    "description": entry.description,
+                    "kind": entry.kind,
+                    "opening": entry.opening,
+                    "currency": entry.currency,
+                    "archived": entry.archived,
+                    "limit": entry.limit,
+                })
+                .to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Exports months holding orders as an `Array` of JSON objects.
+#[wasm_bindgen]
+pub fn get_account_periods_with_data(account: &Account) -> Array {
+    account
+        .periods_with_data()
+        .iter()
+        .map(|period| JsValue::from(serde_json::json!(period).to_string()))
+        .collect()
+}
+
+/// Exports migrations applied when loading the account as an `Array` of JSON
+/// objects.
+#[wasm_bindgen]
+pub fn get_account_applied_migrations(account: &Account) -> Array {
+    account
+        .applied_migrations()
+        .iter()
+        .map(|migration| JsValue::from(serde_json::json!(migration).to_string()))
+        .collect()
+}
+
+/// Exports issues found when loading the account as an `Array` of JSON
+/// objects.
+#[wasm_bindgen]
+pub fn get_account_load_warnings(account: &Account) -> Array {
+    account
+        .load_warnings()
+        .iter()
+        .map(|warning| JsValue::from(serde_json::json!(warning).to_string()))
+        .collect()
+}
+
+/// Exports filtered orders as `Array`. Sorted by date, each one holds the
+/// running `balance`.
 #[wasm_bindgen]
 pub fn get_account_filtered_orders(account: &Account, filter: &Filter) -> Array {
+    // Sorted by date, rows show the running balance
+    if let OrderingPreference::ByDate = filter.ordering {
+        if let Ok(orders) = account.filtered_orders_with_running_balance(filter) {
+            return orders
+                .iter()
+                .map(|(id, order, balance)| {
+                    let mut json_order = order_as_json(account, *id, order);
+                    json_order["balance"] = serde_json::json!(balance);
+                    JsValue::from(json_order.to_string())
+                })
+                .collect();
+        }
+    }
+
     account
-        .orders()
-        .apply_filter(filter)
+        .filtered_orders(filter)
+        .iter()
+        .map(|(id, order)| serialize_order_as_json(account, *id, order))
+        .collect()
+}
+
+/// Exports a page of filtered orders along with the number of filtered ones,
+/// as JSON.
+#[wasm_bindgen]
+pub fn get_account_filtered_orders_page(
+    account: &Account,
+    filter: &Filter,
+    offset: usize,
+    limit: usize,
+) -> String {
+    let page = account.filtered_orders_paged(filter, offset, limit);
+
+    serde_json::json!({
+        "total": page.total,
+        "orders": page
+            .orders
+            .iter()
+            .map(|(id, order)| order_as_json(account, *id, order))
+            .collect::<Vec<serde_json::Value>>(),
+    })
+    .to_string()
+}
+
+/// Counts filtered orders of each state, as JSON.
+#[wasm_bindgen]
+pub fn get_account_filtered_state_counts(account: &Account, filter: &Filter) -> String {
+    serde_json::json!(account.count_filtered_orders_by_state(filter)).to_string()
+}
+
+/// Tells whether an order is allowed by the filter along with the criteria
+/// rejecting it, as JSON; `null` for an unknown order.
+#[wasm_bindgen]
+pub fn explain_account_order_rejection(account: &Account, filter: &Filter, id: usize) -> String {
+    serde_json::json!(account.get_order(id).map(|order| filter.explain(order))).to_string()
+}
+
+/// Samples filtered orders to audit, returning their identifiers.
+#[cfg(feature = "reporting")]
+#[wasm_bindgen]
+pub fn sample_account_orders(
+    account: &Account,
+    filter: &Filter,
+    count: usize,
+    weighted: bool,
+    seed: u32,
+) -> Array {
+    account
+        .audit_sample(filter, count, weighted, seed.into())
+        .into_iter()
+        .map(JsValue::from)
+        .collect()
+}
+
+/// Exports unpaid orders due within a number of days as `Array`.
+#[wasm_bindgen]
+pub fn get_account_upcoming_orders(account: &Account, days: u32) -> Array {
+    account
+        .upcoming_orders(days)
+        .iter()
+        .map(|order| serialize_order_as_json(account, order.id(), order))
+        .collect()
+}
+
+/// Applies a change to orders selected by an `Array` of IDs.
+/// Returns an `Array` holding `undefined` or a `RequestFailure` per order.
+fn bulk_update(account: &mut Account, ids: Array, patch: OrderPatch) -> Array {
+    let ids = ids
         .iter()
-        .map(|(id, order)| serialize_order_as_json(*id, order))
+        .filter_map(|id| id.as_f64())
+        .map(|id| id as usize)
+        .collect::<Vec<usize>>();
+
+    account
+        .bulk_update(&ids, &patch)
+        .into_iter()
+        .map(|failure| failure.map_or(JsValue::UNDEFINED, JsValue::from))
         .collect()
 }
 
-/// Deletes a selected order.
+/// Sets the state of several orders.
+#[wasm_bindgen]
+pub fn bulk_set_account_orders_state(
+    account: &mut Account,
+    ids: Array,
+    state: TransactionState,
+) -> Array {
+    bulk_update(account, ids, OrderPatch::SetState(state))
+}
+
+/// Sets the resource of several orders.
+#[wasm_bindgen]
+pub fn bulk_set_account_orders_resource(
+    account: &mut Account,
+    ids: Array,
+    resource: &str,
+) -> Array {
+    bulk_update(account, ids, OrderPatch::SetResource(resource.into()))
+}
+
+/// Adds a tag to several orders.
+#[wasm_bindgen]
+pub fn bulk_add_account_orders_tag(account: &mut Account, ids: Array, tag: &str) -> Array {
+    bulk_update(account, ids, OrderPatch::AddTag(tag.into()))
+}
+
+/// Removes a tag from several orders.
+#[wasm_bindgen]
+pub fn bulk_remove_account_orders_tag(account: &mut Account, ids: Array, tag: &str) -> Array {
+    bulk_update(account, ids, OrderPatch::RemoveTag(tag.into()))
+}
+
+/// Toggles the visibility of several orders.
+#[wasm_bindgen]
+pub fn bulk_toggle_account_orders_visibility(account: &mut Account, ids: Array) -> Array {
+    bulk_update(account, ids, OrderPatch::ToggleVisibility)
+}
+
+/// Flags a selected order as a future one.
+#[wasm_bindgen]
+pub fn set_account_order_scheduled(account: &mut Account, id: usize, scheduled: bool) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.set_scheduled(scheduled);
+        true
+    } else {
+        false
+    }
+}
+
+/// Toggles the visibility of a selected order.
+#[wasm_bindgen]
+pub fn toggle_account_order_visibility(account: &mut Account, id: usize) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        if order.is_visible() {
+            order.hide(HiddenReason::Manual);
+        } else {
+            order.show();
+        }
+        true
+    } else {
+        false
+    }
+}
+
+/// Hides a selected order for a given reason.
 #[wasm_bindgen]
-pub fn toggle_account_order_visibility(account: &mut Account, index: usize) -> bool {
-    if let Some(order) = account.get_order_mut(index) {
-        order.visible = !order.visible;
+pub fn hide_account_order(account: &mut Account, id: usize, reason: HiddenReason) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.hide(reason);
         true
     } else {
         false
     }
 }
 
-/// Deletes a selected order.
+/// Returns the reason why a selected order is hidden, if any.
+#[wasm_bindgen]
+pub fn get_account_order_hidden_reason(account: &Account, id: usize) -> Option<HiddenReason> {
+    account
+        .get_order(id)
+        .and_then(|order| order.hidden_reason())
+}
+
+/// Moves a selected order to the trash.
+#[wasm_bindgen]
+pub fn delete_account_order(account: &mut Account, id: usize) -> bool {
+    account.delete_order(id)
+}
+
+/// Exports order templates as an `Array` of JSON objects.
+#[wasm_bindgen]
+pub fn get_account_templates(account: &Account) -> Array {
+    account
+        .templates()
+        .iter()
+        .map(|template| JsValue::from(serde_json::json!(template).to_string()))
+        .collect()
+}
+
+/// Moves a selected order back from the trash.
+#[wasm_bindgen]
+pub fn restore_account_order(account: &mut Account, id: usize) -> bool {
+    account.restore_order(id)
+}
+
+/// Deletes all orders of the trash permanently.
+#[wasm_bindgen]
+pub fn purge_account_trash(account: &mut Account) -> usize {
+    account.purge_trash()
+}
+
+/// Exports deleted orders as an `Array` of JSON objects.
 #[wasm_bindgen]
-pub fn delete_account_order(account: &mut Account, index: usize) -> bool {
-    account.delete_order(index)
+pub fn get_account_trash(account: &Account) -> Array {
+    account
+        .trash()
+        .iter()
+        .map(|item| JsValue::from(serde_json::json!(item).to_string()))
+        .collect()
 }
 
 /// Sets date of a selected order.
 #[wasm_bindgen]
-pub fn set_account_order_date(account: &mut Account, index: usize, date: &str) -> bool {
-    if let Some(order) = account.get_order_mut(index) {
+pub fn set_account_order_date(account: &mut Account, id: usize, date: &str) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
         if date.is_empty() {
             order.date = None;
             true
@@ -107,12 +439,8 @@ pub fn set_account_order_date(account: &mut Account, index: usize, date: &str) -
 
 /// Sets description of a selected order.
 #[wasm_bindgen]
-pub fn set_account_order_description(
-    account: &mut Account,
-    index: usize,
-    description: &str,
-) -> bool {
-    if let Some(order) = account.get_order_mut(index) {
+pub fn set_account_order_description(account: &mut Account, id: usize, description: &str) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
         order.description = description.into();
         true
     } else {
@@ -120,218 +448,1226 @@ pub fn set_account_order_description(
     }
 }
 
-/// Sets amount of a selected order.
+/// Sets notes of a selected order.
 #[wasm_bindgen]
-pub fn set_account_order_amount(account: &mut Account, index: usize, amount: f32) -> bool {
-    if let Some(order) = account.get_order_mut(index) {
-        if !amount.is_nan() {
-            order.amount = amount;
-            true
-        } else {
-            false
-        }
+pub fn set_account_order_notes(account: &mut Account, id: usize, notes: &str) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.set_notes(notes);
+        true
     } else {
         false
     }
 }
 
-/// Sets resource of a selected order.
+/// Sets amount of a selected order.
 #[wasm_bindgen]
-pub fn set_account_order_resource(account: &mut Account, index: usize, resource: &str) -> bool {
-    // Extract available strings.
-    let available_resources = account.resources().clone();
-
-    if let Some(order) = account.get_order_mut(index) {
-        order.set_resource(resource, available_resources.as_slice());
-        true
+pub fn set_account_order_amount(account: &mut Account, id: usize, amount: f64) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        if let Some(amount) = Amount::from_f64(amount) {
+            order.set_amount(amount)
+        } else {
+            false
+        }
     } else {
         false
     }
 }
 
-/// Sets tags of a selected order.
-///
-/// # Return
-/// * `false` if at least one tag does not match with available ones, but correct tags are still added.
-/// * `true` otherwise.
+/// Sets a component of a selected order, which updates its amount.
+/// A zero amount removes the component.
 #[wasm_bindgen]
-pub fn set_account_order_tags(account: &mut Account, index: usize, tags: Array) -> bool {
-    // Extract available strings.
-    let available_tags = account.tags().clone();
-
-    if let Some(order) = account.get_order_mut(index) {
-        // Clear all tags
-        order.clear_tags();
-        // Add each tag and make sure that no error happens
-        let available_tags = available_tags.as_slice();
-        !tags.iter().fold(false, |acc, value| {
-            acc | if let Some(tag) = value.as_string() {
-                !order.add_tag(tag.as_str(), available_tags)
-            } else {
-                false
-            }
-        })
+pub fn set_account_order_component(
+    account: &mut Account,
+    id: usize,
+    kind: ComponentKind,
+    amount: f64,
+) -> bool {
+    if let (Some(mut order), Some(amount)) = (account.get_order_mut(id), Amount::from_f64(amount)) {
+        order.set_component(kind, amount)
     } else {
         false
     }
 }
 
-/// Sets state of a selected order.
+/// Removes a component of a selected order.
 #[wasm_bindgen]
-pub fn set_account_order_state(
+pub fn remove_account_order_component(
     account: &mut Account,
-    index: usize,
-    state: TransactionState,
+    id: usize,
+    kind: ComponentKind,
 ) -> bool {
-    if let Some(order) = account.get_order_mut(index) {
-        order.set_state(state);
-        true
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.remove_component(kind)
     } else {
         false
     }
 }
 
-/// Disables filtering of all categories of a given type.
+/// Returns components of a selected order as JSON.
 #[wasm_bindgen]
-pub fn clear_filter_categories(filter: &mut Filter, category_type: CategoryType) {
-    if let Resource = category_type {
-        *filter.get_resource_option_mut() = CategoryFilter::CategoryIgnored;
-    } else {
-        *filter.get_tag_option_mut() = CategoryFilter::CategoryIgnored;
-    }
+pub fn get_account_order_components(account: &Account, id: usize) -> Option<String> {
+    account
+        .get_order(id)
+        .map(|order| serde_json::json!(order.components()).to_string())
 }
 
-/// Initializes each incoming category to `Selected`.
+/// Sums a component of visible orders within a date range.
+/// Throws `RequestFailure.Overflow` if the sum overflows.
 #[wasm_bindgen]
-pub fn set_filter_categories(filter: &mut Filter, category_type: CategoryType, names: &Array) {
-    let filter_option: &mut CategoryFilter;
-
-    if let Resource = category_type {
-        filter_option = filter.get_resource_option_mut();
-    } else {
-        filter_option = filter.get_tag_option_mut();
-    }
-
-    filter_option.set(
-        names
-            .iter()
-            .filter_map(|category_name| {
-                if let Some(category_string) = category_name.as_string() {
-                    Some(Category(category_string, ItemSelector::Selected))
-                } else {
-                    None
-                }
-            })
-            .collect::<Vec<Category>>()
-            .into_iter(),
-    );
+pub fn get_account_component_total(
+    account: &Account,
+    kind: ComponentKind,
+    start_date: &str,
+    end_date: &str,
+) -> Result<f64, JsValue> {
+    Ok(account
+        .component_total(
+            kind,
+            OptionNaiveDateRange(
+                NaiveDate::from_str(start_date).ok(),
+                NaiveDate::from_str(end_date).ok(),
+            ),
+        )?
+        .as_f64())
 }
 
-/// Adds a new category to filter options.
+/// Sets currency of a selected order.
+/// An empty code selects the currency of the account.
 #[wasm_bindgen]
-pub fn add_filter_category(filter: &mut Filter, category_type: CategoryType, name: &str) {
-    if let Resource = category_type {
-        filter
-            .get_resource_option_mut()
-            .add(Category(name.into(), ItemSelector::Selected));
+pub fn set_account_order_currency(account: &mut Account, id: usize, currency: &str) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        if currency.is_empty() {
+            order.clear_currency();
+            true
+        } else {
+            order.set_currency(currency)
+        }
     } else {
-        filter
-            .get_tag_option_mut()
-            .add(Category(name.into(), ItemSelector::Discarded));
+        false
     }
 }
 
-/// Removes a category from filtering options.
+/// Sets the amount charged to the resource of a selected order in its
+/// currency. An empty code removes the conversion.
+#[cfg(feature = "fx")]
 #[wasm_bindgen]
-pub fn remove_filter_category(
-    filter: &mut Filter,
-    category_type: CategoryType,
-    name: &str,
+pub fn set_account_order_conversion(
+    account: &mut Account,
+    id: usize,
+    currency: &str,
+    amount: f64,
 ) -> bool {
-    if let Resource = category_type {
-        filter.get_resource_option_mut().remove(name)
-    } else {
-        filter.get_tag_option_mut().remove(name)
+    match (account.get_order_mut(id), Amount::from_f64(amount)) {
+        (Some(mut order), _) if currency.is_empty() => {
+            order.clear_conversion();
+            true
+        }
+        (Some(mut order), Some(amount)) => order.set_conversion(currency, amount),
+        _ => false,
     }
 }
 
-/// Returns the filtering option for a given *tag*, if available.
+/// Sets payee of a selected order.
+/// An empty payee removes it.
 #[wasm_bindgen]
-pub fn get_filter_category_state(
-    filter: &mut Filter,
-    category_type: CategoryType,
-    name: &str,
-) -> Option<ItemSelector> {
-    let filter_option: &CategoryFilter;
-
-    if let Resource = category_type {
-        filter_option = filter.resource_option();
-    } else {
-        filter_option = filter.tag_option();
-    }
+pub fn set_account_order_payee(account: &mut Account, id: usize, payee: &str) -> bool {
+    // Extract available strings.
+    let available_payees = account.payees().clone();
 
-    if let CategoryFilter::Enabled(items) = filter_option {
-        if let Some(index) = items.iter().position(|item| item.0 == name) {
-            Some(items[index].1)
+    if let Some(mut order) = account.get_order_mut(id) {
+        if payee.is_empty() {
+            order.clear_payee();
+            true
         } else {
-            None
+            order.set_payee(payee, available_payees.as_slice())
         }
     } else {
-        None
+        false
     }
 }
 
-/// Toggles selected category filtering.
+/// Returns payee of a selected order.
 #[wasm_bindgen]
-pub fn toggle_filter_category(
-    filter: &mut Filter,
-    category_type: CategoryType,
-    name: &str,
-) -> Option<ItemSelector> {
-    let filter_option: &mut CategoryFilter;
+pub fn get_account_order_payee(account: &Account, id: usize) -> Option<String> {
+    account
+        .get_order(id)
+        .and_then(|order| order.payee().cloned())
+}
 
-    if let Resource = category_type {
-        filter_option = filter.get_resource_option_mut();
-    } else {
-        filter_option = filter.get_tag_option_mut();
-    }
+/// Returns the amount of a selected order once its refunds are deducted.
+#[wasm_bindgen]
+pub fn get_account_order_net_amount(account: &Account, id: usize) -> Option<f64> {
+    account.net_amount(id).map(Amount::as_f64)
+}
+
+/// Returns declared custom fields as JSON.
+#[wasm_bindgen]
+pub fn get_account_custom_fields(account: &Account) -> Array {
+    account
+        .custom_fields()
+        .iter()
+        .map(|field| JsValue::from(serde_json::json!(field).to_string()))
+        .collect()
+}
+
+/// Sets the value of a custom field of a selected order, parsed according to
+/// the field type. An empty value removes it.
+#[wasm_bindgen]
+pub fn set_account_order_field(account: &mut Account, id: usize, name: &str, value: &str) -> bool {
+    // Extract available fields.
+    let available_fields = account.custom_fields().clone();
+    let kind = available_fields
+        .iter()
+        .find(|field| field.name == name)
+        .map(|field| field.kind);
+
+    match (account.get_order_mut(id), kind) {
+        (Some(mut order), Some(_)) if value.is_empty() => {
+            order.remove_field(name);
+            true
+        }
+        (Some(mut order), Some(kind)) => kind
+            .parse(value)
+            .is_some_and(|value| order.set_field(name, value, available_fields.as_slice())),
+        _ => false,
+    }
+}
+
+/// Returns values of custom fields of a selected order as JSON.
+#[wasm_bindgen]
+pub fn get_account_order_fields(account: &Account, id: usize) -> Option<String> {
+    account
+        .get_order(id)
+        .map(|order| serde_json::json!(order.fields()).to_string())
+}
+
+/// Sets resource of a selected order.
+#[wasm_bindgen]
+pub fn set_account_order_resource(account: &mut Account, id: usize, resource: &str) -> bool {
+    // Extract available strings.
+    let available_resources = account.resources().clone();
+
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.set_resource(resource, available_resources.as_slice());
+        true
+    } else {
+        false
+    }
+}
+
+/// Sets project of a selected order, or detaches it if `project` is empty.
+#[wasm_bindgen]
+pub fn set_account_order_project(account: &mut Account, id: usize, project: &str) -> bool {
+    // Extract available projects.
+    let available_projects = account.projects().clone();
+
+    if let Some(mut order) = account.get_order_mut(id) {
+        if project.is_empty() {
+            order.clear_project();
+            true
+        } else {
+            order.set_project(project, available_projects.as_slice())
+        }
+    } else {
+        false
+    }
+}
+
+/// Sets tags of a selected order.
+///
+/// # Return
+/// * `false` if at least one tag does not match with available ones, but correct tags are still added.
+/// * `true` otherwise.
+#[wasm_bindgen]
+pub fn set_account_order_tags(account: &mut Account, id: usize, tags: Array) -> bool {
+    // Extract available tags and aliases.
+    let available_tags = account.tag_catalog();
+
+    if let Some(mut order) = account.get_order_mut(id) {
+        // Clear all tags
+        order.clear_tags();
+        // Add each tag and make sure that no error happens
+        !tags.iter().fold(false, |acc, value| {
+            acc | if let Some(tag) = value.as_string() {
+                !order.add_tag(tag.as_str(), &available_tags)
+            } else {
+                false
+            }
+        })
+    } else {
+        false
+    }
+}
+
+/// Sets state of a selected order.
+#[wasm_bindgen]
+pub fn set_account_order_state(account: &mut Account, id: usize, state: TransactionState) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.set_state(state);
+        true
+    } else {
+        false
+    }
+}
+
+/// Inserts or updates a metadata value of a selected order.
+#[wasm_bindgen]
+pub fn set_account_order_metadata(
+    account: &mut Account,
+    id: usize,
+    key: &str,
+    value: &str,
+) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.set_metadata(key, value)
+    } else {
+        false
+    }
+}
+
+/// Removes a metadata key of a selected order.
+#[wasm_bindgen]
+pub fn remove_account_order_metadata(account: &mut Account, id: usize, key: &str) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.remove_metadata(key)
+    } else {
+        false
+    }
+}
+
+/// Adds an attachment to a selected order.
+/// Empty hash or URL are ignored.
+#[wasm_bindgen]
+pub fn add_account_order_attachment(
+    account: &mut Account,
+    id: usize,
+    filename: &str,
+    mime: &str,
+    hash: &str,
+    url: &str,
+) -> bool {
+    let optional = |value: &str| Some(value.to_string()).filter(|value| !value.is_empty());
+
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.add_attachment(Attachment {
+            hash: optional(hash),
+            url: optional(url),
+            ..Attachment::new(filename, mime)
+        })
+    } else {
+        false
+    }
+}
+
+/// Removes an attachment of a selected order.
+#[wasm_bindgen]
+pub fn remove_account_order_attachment(account: &mut Account, id: usize, filename: &str) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.remove_attachment(filename)
+    } else {
+        false
+    }
+}
+
+/// Returns attachments of a selected order as JSON.
+#[wasm_bindgen]
+pub fn get_account_order_attachments(account: &Account, id: usize) -> Array {
+    account
+        .get_order(id)
+        .map(|order| {
+            order
+                .attachments()
+                .iter()
+                .map(|attachment| JsValue::from(serde_json::json!(attachment).to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Sets location of a selected order.
+#[wasm_bindgen]
+pub fn set_account_order_location(
+    account: &mut Account,
+    id: usize,
+    latitude: f64,
+    longitude: f64,
+) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.set_location(latitude, longitude)
+    } else {
+        false
+    }
+}
+
+/// Removes location of a selected order.
+#[wasm_bindgen]
+pub fn clear_account_order_location(account: &mut Account, id: usize) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.clear_location();
+        true
+    } else {
+        false
+    }
+}
+
+/// Serializes all filtering options, to restore the view later.
+#[wasm_bindgen]
+pub fn serialize_filter_as_json(filter: &Filter) -> String {
+    serde_json::json!(filter).to_string()
+}
+
+/// Resets a filter from **JSON** data written by `serialize_filter_as_json`.
+/// Returns `true` if operation succeded, `false` otherwise.
+#[wasm_bindgen]
+pub fn load_filter_from_json(filter: &mut Filter, data: &str) -> bool {
+    match serde_json::from_str::<Filter>(data) {
+        Ok(new) => {
+            *filter = new;
+            true
+        }
+        Err(error) => {
+            log!("{}", error);
+            false
+        }
+    }
+}
+
+/// Disables filtering of all categories of a given type.
+#[wasm_bindgen]
+pub fn clear_filter_categories(filter: &mut Filter, category_type: CategoryType) {
+    *filter.get_category_option_mut(category_type) = CategoryFilter::CategoryIgnored;
+}
+
+/// Allows orders whose description or notes contain a given text only.
+/// Returns `false` for a blank text.
+#[wasm_bindgen]
+pub fn set_filter_text(filter: &mut Filter, text: &str) -> bool {
+    filter.set_text_option(text)
+}
+
+/// Stops searching orders by text.
+#[wasm_bindgen]
+pub fn clear_filter_text(filter: &mut Filter) {
+    filter.disable_text_option();
+}
+
+/// Allows orders satisfying an expression like
+/// `(tag Food OR tag Restaurant) AND NOT resource Cash` only.
+/// Returns `false` for a malformed expression.
+#[wasm_bindgen]
+pub fn set_filter_expression(filter: &mut Filter, text: &str) -> bool {
+    filter.set_expression(text)
+}
+
+/// Stops filtering orders by expression.
+#[wasm_bindgen]
+pub fn clear_filter_expression(filter: &mut Filter) {
+    filter.disable_expression();
+}
+
+/// Allows orders whose description or notes match a regular expression only,
+/// ignoring case. Returns `false` for a blank or invalid pattern.
+#[cfg(feature = "regex")]
+#[wasm_bindgen]
+pub fn set_filter_text_pattern(filter: &mut Filter, pattern: &str) -> bool {
+    filter.set_text_pattern(pattern)
+}
+
+/// Initializes each incoming category to `Selected`.
+#[wasm_bindgen]
+pub fn set_filter_categories(filter: &mut Filter, category_type: CategoryType, names: &Array) {
+    filter.get_category_option_mut(category_type).set(
+        names
+            .iter()
+            .filter_map(|category_name| {
+                category_name
+                    .as_string()
+                    .map(|category_string| Category(category_string, ItemSelector::Selected))
+            })
+            .collect::<Vec<Category>>()
+            .into_iter(),
+    );
+}
+
+/// Adds a new category to filter options.
+#[wasm_bindgen]
+pub fn add_filter_category(filter: &mut Filter, category_type: CategoryType, name: &str) {
+    // Tags are cumulative: a new selected one would reject all orders
+    let selector = if let Tag = category_type {
+        ItemSelector::Discarded
+    } else {
+        ItemSelector::Selected
+    };
+
+    filter
+        .get_category_option_mut(category_type)
+        .add(Category(name.into(), selector));
+}
+
+/// Removes a category from filtering options.
+#[wasm_bindgen]
+pub fn remove_filter_category(
+    filter: &mut Filter,
+    category_type: CategoryType,
+    name: &str,
+) -> bool {
+    filter.get_category_option_mut(category_type).remove(name)
+}
+
+/// Returns the filtering option for a given *tag*, if available.
+#[wasm_bindgen]
+pub fn get_filter_category_state(
+    filter: &mut Filter,
+    category_type: CategoryType,
+    name: &str,
+) -> Option<ItemSelector> {
+    if let CategoryFilter::Enabled(items) = filter.category_option(category_type) {
+        items
+            .iter()
+            .position(|item| item.0 == name)
+            .map(|index| items[index].1)
+    } else {
+        None
+    }
+}
+
+/// Toggles selected category filtering.
+#[wasm_bindgen]
+pub fn toggle_filter_category(
+    filter: &mut Filter,
+    category_type: CategoryType,
+    name: &str,
+) -> Option<ItemSelector> {
+    filter
+        .get_category_option_mut(category_type)
+        .toggle(name)
+        .copied()
+}
+
+/// Selects each category of a given type in filter options.
+#[wasm_bindgen]
+pub fn select_all_filter_categories(filter: &mut Filter, category_type: CategoryType) {
+    filter.get_category_option_mut(category_type).select_all();
+}
+
+/// Discards each category of a given type in filter options.
+#[wasm_bindgen]
+pub fn select_no_filter_categories(filter: &mut Filter, category_type: CategoryType) {
+    filter.get_category_option_mut(category_type).select_none();
+}
+
+/// Toggles each category of a given type in filter options.
+#[wasm_bindgen]
+pub fn invert_filter_categories(filter: &mut Filter, category_type: CategoryType) {
+    filter.get_category_option_mut(category_type).invert();
+}
+
+/// Sets the currency of orders without their own one, usually the account
+/// one. Returns `false` for an invalid code.
+#[wasm_bindgen]
+pub fn set_filter_base_currency(filter: &mut Filter, code: &str) -> bool {
+    filter.set_base_currency(code)
+}
+
+/// Adds a selected currency to filter options.
+#[wasm_bindgen]
+pub fn add_filter_currency(filter: &mut Filter, code: &str) {
+    filter
+        .get_currency_option_mut()
+        .add(Category(code.into(), ItemSelector::Selected));
+}
+
+/// Removes a currency from filtering options.
+#[wasm_bindgen]
+pub fn remove_filter_currency(filter: &mut Filter, code: &str) -> bool {
+    filter.get_currency_option_mut().remove(code)
+}
 
-    if let Some(selector) = filter_option.toggle(name) {
-        Some(*selector)
+/// Returns the filtering option for a given currency, if available.
+#[wasm_bindgen]
+pub fn get_filter_currency_state(filter: &Filter, code: &str) -> Option<ItemSelector> {
+    if let CategoryFilter::Enabled(items) = filter.currency_option() {
+        items.iter().find(|item| item.0 == code).map(|item| item.1)
     } else {
         None
     }
 }
 
+/// Toggles selected currency filtering.
+#[wasm_bindgen]
+pub fn toggle_filter_currency(filter: &mut Filter, code: &str) -> Option<ItemSelector> {
+    filter.get_currency_option_mut().toggle(code).copied()
+}
+
+/// Disables filtering of currencies.
+#[wasm_bindgen]
+pub fn clear_filter_currencies(filter: &mut Filter) {
+    *filter.get_currency_option_mut() = CategoryFilter::CategoryIgnored;
+}
+
 /// Converts account data into YAML string.
 #[wasm_bindgen]
 pub fn serialize_account_as_yaml(account: &Account) -> JsValue {
     JsValue::from(serde_yaml::to_string(account).unwrap())
 }
 
+/// Converts account data into gzip-compressed YAML bytes.
+#[cfg(feature = "compression")]
+#[wasm_bindgen]
+pub fn serialize_account_compressed(account: &Account) -> Vec<u8> {
+    account.to_compressed()
+}
+
+/// Converts account data into compact JSON string.
+#[wasm_bindgen]
+pub fn serialize_account_as_json(account: &Account) -> String {
+    account.to_json()
+}
+
+/// Converts account data into YAML bytes encrypted under a passphrase.
+/// Throws `RequestFailure.EmptyArgument` if the passphrase is empty.
+#[cfg(feature = "encryption")]
+#[wasm_bindgen]
+pub fn serialize_account_encrypted(
+    account: &Account,
+    passphrase: &str,
+) -> Result<Vec<u8>, JsValue> {
+    Ok(account.to_encrypted(passphrase)?)
+}
+
+/// Exports visible orders as CSV.
+#[cfg(feature = "reporting")]
+#[wasm_bindgen]
+pub fn export_account_orders_as_csv(account: &Account, format: AmountFormat) -> String {
+    account.export_csv(format)
+}
+
+/// Exports visible orders as a JSON array.
+/// Amounts are fixed-point strings with `AmountFormat.Text`.
+#[cfg(feature = "reporting")]
+#[wasm_bindgen]
+pub fn export_account_orders_as_json(account: &Account, format: AmountFormat) -> String {
+    let orders = account
+        .orders()
+        .iter()
+        .filter(|order| order.is_visible())
+        .map(|order| {
+            let mut json_order = serde_json::json!(order);
+            if format == AmountFormat::Text {
+                json_order["amount"] = serde_json::json!(order.amount.to_string());
+                order
+                    .components()
+                    .iter()
+                    .enumerate()
+                    .for_each(|(index, component)| {
+                        json_order["components"][index]["amount"] =
+                            serde_json::json!(component.amount.to_string())
+                    });
+            }
+            json_order
+        })
+        .collect::<Vec<serde_json::Value>>();
+
+    serde_json::json!(orders).to_string()
+}
+
+/// Exports orders added or changed since the last call as JSON, then
+/// updates the stored marker.
+#[cfg(feature = "reporting")]
+#[wasm_bindgen]
+pub fn export_account_changes(account: &mut Account) -> String {
+    serde_json::json!(account.export_changes()).to_string()
+}
+
 /// Converts `Order` to string with its corresponding ID.
-fn serialize_order_as_json(id: usize, order: &Order) -> JsValue {
-    let json_order = serde_json::json!({"id": id, "order": order});
+fn serialize_order_as_json(account: &Account, id: usize, order: &Order) -> JsValue {
+    JsValue::from(order_as_json(account, id, order).to_string())
+}
+
+fn order_as_json(account: &Account, id: usize, order: &Order) -> serde_json::Value {
+    let mut json_order = serde_json::json!({"id": id, "order": order});
+    // Keep the flag expected by the web application
+    json_order["order"]["visible"] = serde_json::json!(order.is_visible());
+
+    // Link refunds with their original order
+    let related_orders = account.related_orders(id);
+    if !related_orders.is_empty() {
+        json_order["related_orders"] = serde_json::json!(related_orders
+            .iter()
+            .map(|related| related.id())
+            .collect::<Vec<usize>>());
+        json_order["net_amount"] = serde_json::json!(account.net_amount(id));
+    }
+
+    json_order
+}
+
+/// Converts raw imported bytes into text, detecting the encoding unless forced.
+#[cfg(feature = "import")]
+#[wasm_bindgen]
+pub fn decode_import_data(data: &[u8], forced: Option<TextEncoding>) -> JsValue {
+    let decoded = encoding::decode(data, forced);
+
+    if decoded.lossy {
+        log!(
+            "Some characters could not be decoded as {:?}",
+            decoded.encoding
+        );
+    }
+
+    JsValue::from(decoded.content)
+}
+
+/// Creates orders from a CSV export given a **JSON** column mapping, and
+/// returns created ids, duplicates and row errors as JSON.
+/// Throws `RequestFailure.IncorrectArgument` if the mapping is malformed.
+#[cfg(feature = "import")]
+#[wasm_bindgen]
+pub fn import_account_csv(
+    account: &mut Account,
+    data: &str,
+    mapping: &str,
+) -> Result<String, JsValue> {
+    let mapping = serde_json::from_str::<CsvMapping>(mapping)
+        .map_err(|_| RequestFailure::IncorrectArgument)?;
+
+    Ok(serde_json::json!(account.import_csv(data, &mapping)).to_string())
+}
+
+/// Creates pending orders on a resource from an OFX/QFX statement and
+/// returns their ids as JSON. Throws a `RequestFailure` on error.
+#[cfg(feature = "import")]
+#[wasm_bindgen]
+pub fn import_account_ofx(
+    account: &mut Account,
+    data: &str,
+    resource: &str,
+) -> Result<String, JsValue> {
+    Ok(serde_json::json!(account.import_ofx(data, resource)?).to_string())
+}
+
+/// Adds categories and orders of another account from **YAML** data, and
+/// returns added ids, duplicates and conflicts as JSON.
+/// Throws `RequestFailure.IncorrectArgument` if the data is malformed.
+#[wasm_bindgen]
+pub fn merge_account(
+    account: &mut Account,
+    data: &str,
+    strategy: MergeStrategy,
+) -> Result<String, JsValue> {
+    let other = Account::try_from(data).map_err(|_| RequestFailure::IncorrectArgument)?;
+
+    Ok(serde_json::json!(account.merge(other, strategy)).to_string())
+}
+
+/// Returns changes made to an account since a previous version given as
+/// **YAML** data, such as a backup, as JSON.
+/// Throws `RequestFailure.IncorrectArgument` if the data is malformed.
+#[wasm_bindgen]
+pub fn get_account_changes_since(account: &Account, data: &str) -> Result<String, JsValue> {
+    let previous = Account::try_from(data).map_err(|_| RequestFailure::IncorrectArgument)?;
+
+    Ok(serde_json::json!(previous.diff(account)).to_string())
+}
+
+/// Returns the spend of each place within a date range as JSON.
+/// Throws `RequestFailure.Overflow` if a sum overflows.
+#[wasm_bindgen]
+pub fn get_account_spend_by_place(
+    account: &Account,
+    start_date: &str,
+    end_date: &str,
+) -> Result<JsValue, JsValue> {
+    let spend = account.spend_by_place(OptionNaiveDateRange(
+        NaiveDate::from_str(start_date).ok(),
+        NaiveDate::from_str(end_date).ok(),
+    ))?;
+
+    Ok(JsValue::from(serde_json::json!(spend).to_string()))
+}
+
+/// Returns the overview of a project as JSON.
+/// Throws a `RequestFailure` if the project doesn't exist or if a sum
+/// overflows.
+#[wasm_bindgen]
+pub fn get_account_project_summary(account: &Account, project: &str) -> Result<String, JsValue> {
+    Ok(serde_json::json!(account.project_summary(project)?).to_string())
+}
+
+/// Compares spending limits of tags with their expenses within the periods
+/// holding a date, as JSON. Throws a `RequestFailure` on error.
+#[wasm_bindgen]
+pub fn get_account_budget_report(account: &Account, date: &str) -> Result<String, JsValue> {
+    let date = NaiveDate::from_str(date).map_err(|_| RequestFailure::IncorrectArgument)?;
+
+    Ok(serde_json::json!(account.budget_report(date)?).to_string())
+}
+
+/// Returns the results of a category within a date range for each currency,
+/// as JSON. Throws `RequestFailure.Overflow` if a sum overflows.
+#[wasm_bindgen]
+pub fn get_account_category_amount_by_currency(
+    account: &Account,
+    kind: CategoryType,
+    category: &str,
+    start_date: &str,
+    end_date: &str,
+) -> Result<JsValue, JsValue> {
+    let amounts = account.category_amount_by_currency(
+        kind,
+        category,
+        OptionNaiveDateRange(
+            NaiveDate::from_str(start_date).ok(),
+            NaiveDate::from_str(end_date).ok(),
+        ),
+    )?;
+
+    Ok(JsValue::from(serde_json::json!(amounts).to_string()))
+}
+
+/// Returns the results of every category within a date range, as a JSON
+/// object keyed by name. Categories mixing currencies are left out.
+/// Throws `RequestFailure.Overflow` if a sum overflows.
+#[wasm_bindgen]
+pub fn get_account_all_category_amounts(
+    account: &Account,
+    kind: CategoryType,
+    start_date: &str,
+    end_date: &str,
+) -> Result<String, JsValue> {
+    let amounts = account.all_category_amounts(
+        kind,
+        OptionNaiveDateRange(
+            NaiveDate::from_str(start_date).ok(),
+            NaiveDate::from_str(end_date).ok(),
+        ),
+    )?;
+
+    Ok(serde_json::json!(amounts).to_string())
+}
+
+/// Sets the nature of a selected order; `undefined` lets the amount sign
+/// decide.
+#[wasm_bindgen]
+pub fn set_account_order_kind(account: &mut Account, id: usize, kind: Option<OrderKind>) -> bool {
+    if let Some(mut order) = account.get_order_mut(id) {
+        order.set_kind(kind);
+        true
+    } else {
+        false
+    }
+}
+
+/// Returns the balance at the end of each month within a date range, as a
+/// JSON array of `{month, current, expected}` objects. An empty resource
+/// stands for the whole account. Throws a `RequestFailure` on error.
+#[wasm_bindgen]
+pub fn get_account_monthly_balances(
+    account: &Account,
+    resource: &str,
+    start_date: &str,
+    end_date: &str,
+) -> Result<String, JsValue> {
+    let (start, end) = match (
+        NaiveDate::from_str(start_date),
+        NaiveDate::from_str(end_date),
+    ) {
+        (Ok(start), Ok(end)) => (start, end),
+        _ => return Err(RequestFailure::IncorrectArgument.into()),
+    };
+    let resource = Some(resource).filter(|resource| !resource.is_empty());
+
+    Ok(serde_json::json!(account.monthly_balances(resource, start, end)?).to_string())
+}
+
+/// Returns the cash flow of each period within a date range, as a JSON array
+/// of `{start, end, income, expenses, net}` objects. An empty resource stands
+/// for the whole account. Throws a `RequestFailure` on error.
+#[wasm_bindgen]
+pub fn get_account_cash_flow(
+    account: &Account,
+    period: Period,
+    start_date: &str,
+    end_date: &str,
+    resource: &str,
+) -> Result<String, JsValue> {
+    let (start, end) = match (
+        NaiveDate::from_str(start_date),
+        NaiveDate::from_str(end_date),
+    ) {
+        (Ok(start), Ok(end)) => (start, end),
+        _ => return Err(RequestFailure::IncorrectArgument.into()),
+    };
+    let resource = Some(resource).filter(|resource| !resource.is_empty());
+
+    Ok(serde_json::json!(account.cash_flow(period, start, end, resource)?).to_string())
+}
+
+/// Returns the net worth at a date along with the balance of each resource,
+/// as JSON. `undefined` if the date is incorrect, if resources hold different
+/// currencies, or if a sum overflows.
+#[wasm_bindgen]
+pub fn get_account_net_worth(account: &Account, date: &str) -> Option<String> {
+    let date = NaiveDate::from_str(date).ok()?;
+
+    account
+        .net_worth(date)
+        .map(|worth| serde_json::json!(worth).to_string())
+}
+
+/// Returns up to `count` tags with the largest spending within a date range,
+/// as a JSON array of `{tag, spent, share}` objects. `undefined` if a date is
+/// incorrect or if a sum overflows.
+#[wasm_bindgen]
+pub fn get_account_top_spending_tags(
+    account: &Account,
+    start_date: &str,
+    end_date: &str,
+    count: usize,
+) -> Option<String> {
+    let start = NaiveDate::from_str(start_date).ok()?;
+    let end = NaiveDate::from_str(end_date).ok()?;
+
+    let tags = account.top_spending_tags(start, end, count).ok()?;
+
+    Some(serde_json::json!(tags).to_string())
+}
+
+/// Returns the monthly spending of a category within a date range, averaged
+/// over the last `window` months, along with its trend, as JSON. `undefined`
+/// if a date or the window is incorrect or if a sum overflows.
+#[wasm_bindgen]
+pub fn get_account_spending_trend(
+    account: &Account,
+    kind: CategoryType,
+    category: &str,
+    start_date: &str,
+    end_date: &str,
+    window: usize,
+) -> Option<String> {
+    let start = NaiveDate::from_str(start_date).ok()?;
+    let end = NaiveDate::from_str(end_date).ok()?;
+
+    let trend = account
+        .spending_trend(kind, category, start, end, window)
+        .ok()?;
+
+    Some(serde_json::json!(trend).to_string())
+}
+
+/// Returns the projected balances of each resource at the end of the current
+/// month and of the `months` following ones, as JSON. Throws a
+/// `RequestFailure` on error.
+#[wasm_bindgen]
+pub fn get_account_balance_forecast(account: &Account, months: u32) -> Result<String, JsValue> {
+    Ok(serde_json::json!(account.balance_forecast(months)?).to_string())
+}
+
+/// Returns totals, counts and average amounts of orders of each payee within
+/// a date range, as JSON. Throws a `RequestFailure` on error.
+#[wasm_bindgen]
+pub fn get_account_payee_stats(
+    account: &Account,
+    start_date: &str,
+    end_date: &str,
+) -> Result<String, JsValue> {
+    let (start, end) = match (
+        NaiveDate::from_str(start_date),
+        NaiveDate::from_str(end_date),
+    ) {
+        (Ok(start), Ok(end)) => (start, end),
+        _ => return Err(RequestFailure::IncorrectArgument.into()),
+    };
+
+    Ok(serde_json::json!(account.payee_stats(start, end)?).to_string())
+}
+
+/// Returns balances of each resource not archived over the last `days` days,
+/// one per day or per week, as a JSON array of
+/// `{resource, start, step, balances}` objects. Throws a `RequestFailure` on
+/// error.
+#[wasm_bindgen]
+pub fn get_account_sparklines(
+    account: &Account,
+    days: u32,
+    weekly: bool,
+) -> Result<String, JsValue> {
+    Ok(serde_json::json!(account.sparklines(days, weekly)?).to_string())
+}
+
+/// Returns orders to review as their amount is far from usual amounts of one
+/// of their tags, as a JSON array of `{id, tag, amount, low, high}` objects.
+#[wasm_bindgen]
+pub fn get_account_amount_outliers(account: &Account) -> String {
+    serde_json::json!(account.amount_outliers()).to_string()
+}
+
+/// Returns income and expenses of each period within a date range as JSON.
+/// Throws `RequestFailure.Overflow` if a sum overflows.
+#[wasm_bindgen]
+pub fn get_account_income_expense(
+    account: &Account,
+    period: Period,
+    start_date: &str,
+    end_date: &str,
+) -> Result<String, JsValue> {
+    let (start, end) = match (
+        NaiveDate::from_str(start_date),
+        NaiveDate::from_str(end_date),
+    ) {
+        (Ok(start), Ok(end)) => (start, end),
+        _ => return Err(RequestFailure::IncorrectArgument.into()),
+    };
+
+    Ok(serde_json::json!(account.income_expense(period, start, end)?).to_string())
+}
+
+/// Returns the share of categorized orders within a date range as JSON.
+/// Throws `RequestFailure.Overflow` if a sum overflows.
+#[wasm_bindgen]
+pub fn get_account_categorization_progress(
+    account: &Account,
+    start_date: &str,
+    end_date: &str,
+) -> Result<String, JsValue> {
+    let progress = account.categorization_progress(OptionNaiveDateRange(
+        NaiveDate::from_str(start_date).ok(),
+        NaiveDate::from_str(end_date).ok(),
+    ))?;
+
+    Ok(serde_json::json!(progress).to_string())
+}
+
+/// Returns resources breaking their balance limit within a date range, as a
+/// JSON array.
+#[wasm_bindgen]
+pub fn get_account_resource_alerts(account: &Account, start_date: &str, end_date: &str) -> String {
+    let alerts = account.resource_alerts(OptionNaiveDateRange(
+        NaiveDate::from_str(start_date).ok(),
+        NaiveDate::from_str(end_date).ok(),
+    ));
+
+    serde_json::json!(alerts).to_string()
+}
+
+/// Removes a tag or resource, moving its orders to another one unless `new`
+/// is empty, and returns the number of rewired orders.
+#[wasm_bindgen]
+pub fn remove_account_category_with_reassign(
+    account: &mut Account,
+    category_type: CategoryType,
+    old: &str,
+    new: &str,
+) -> Result<usize, JsValue> {
+    let new = Some(new).filter(|new| !new.is_empty());
+
+    match category_type {
+        Tag => Ok(account.remove_tag_with_reassign(old, new)?),
+        Resource => Ok(account.remove_resource_with_reassign(old, new)?),
+        Payee | Project => Err(RequestFailure::IncorrectArgument.into()),
+    }
+}
+
+/// Returns aliases with their canonical tag, as a JSON object.
+#[wasm_bindgen]
+pub fn get_account_tag_aliases(account: &Account) -> String {
+    serde_json::json!(account.tag_aliases()).to_string()
+}
+
+/// Returns groups of tags with their amounts within a date range, as a JSON
+/// array. Amounts are null without orders or if currencies differ.
+/// Throws `RequestFailure.Overflow` if a sum overflows.
+#[wasm_bindgen]
+pub fn get_account_tag_group_amounts(
+    account: &Account,
+    start_date: &str,
+    end_date: &str,
+) -> Result<String, JsValue> {
+    let groups = account
+        .tag_groups()
+        .iter()
+        .map(|group| {
+            let amount = account.tag_group_amount(
+                &group.name,
+                OptionNaiveDateRange(
+                    NaiveDate::from_str(start_date).ok(),
+                    NaiveDate::from_str(end_date).ok(),
+                ),
+            )?;
+
+            Ok(serde_json::json!({
+                "name": group.name,
+                "tags": group.tags,
+                "amount": amount,
+            }))
+        })
+        .collect::<Result<Vec<serde_json::Value>, RequestFailure>>()?;
+
+    Ok(serde_json::json!(groups).to_string())
+}
+
+/// Returns categories whose names only differ by case or spacing, as a JSON
+/// array.
+#[wasm_bindgen]
+pub fn get_account_category_collisions(account: &Account) -> String {
+    serde_json::json!(account.category_collisions()).to_string()
+}
+
+/// Returns how each category of a type is used, as a JSON array.
+/// Throws `RequestFailure.Overflow` if a sum overflows.
+#[wasm_bindgen]
+pub fn get_account_category_usage(
+    account: &Account,
+    category_type: CategoryType,
+) -> Result<String, JsValue> {
+    let usage = account.category_usage(category_type)?;
+
+    Ok(serde_json::json!(usage).to_string())
+}
+
+/// Returns suggestions to tidy the account up, as JSON.
+#[cfg(feature = "reporting")]
+#[wasm_bindgen]
+pub fn get_account_lint(account: &Account) -> Array {
+    account
+        .lint()
+        .iter()
+        .map(|issue| JsValue::from(serde_json::json!(issue).to_string()))
+        .collect()
+}
+
+/// Assigns an income order to envelopes, given as tags and amounts of the
+/// same length.
+#[wasm_bindgen]
+pub fn allocate_account_income(
+    account: &mut Account,
+    id: usize,
+    envelopes: Array,
+    amounts: Array,
+) -> Option<RequestFailure> {
+    if envelopes.length() != amounts.length() {
+        return Some(RequestFailure::IncorrectArgument);
+    }
+
+    let allocations = envelopes
+        .iter()
+        .zip(amounts.iter())
+        .map(|(envelope, amount)| {
+            Some(Allocation {
+                envelope: envelope.as_string()?,
+                amount: Amount::from_f64(amount.as_f64()?)?,
+            })
+        })
+        .collect::<Option<Vec<Allocation>>>();
+
+    match allocations {
+        Some(allocations) => account.allocate(id, allocations),
+        None => Some(RequestFailure::IncorrectArgument),
+    }
+}
+
+/// Returns funds of budget envelopes and income left to allocate, as JSON.
+#[wasm_bindgen]
+pub fn get_account_allocation_summary(account: &Account) -> Result<String, JsValue> {
+    Ok(serde_json::json!(account.allocation_summary()?).to_string())
+}
+
+/// Exports balance thresholds of resources as `Array` of JSON.
+#[wasm_bindgen]
+pub fn get_account_milestones(account: &Account) -> Array {
+    account
+        .milestones()
+        .iter()
+        .map(|milestone| JsValue::from(serde_json::json!(milestone).to_string()))
+        .collect()
+}
+
+/// Returns thresholds crossed by balances since the last check, as JSON.
+#[wasm_bindgen]
+pub fn check_account_milestones(account: &mut Account) -> Array {
+    account
+        .check_milestones()
+        .iter()
+        .map(|crossed| JsValue::from(serde_json::json!(crossed).to_string()))
+        .collect()
+}
+
+/// Compares spending of the current period with the previous one, as JSON.
+/// Throws `RequestFailure.Overflow` if a sum overflows.
+#[wasm_bindgen]
+pub fn get_account_period_vs_previous(
+    account: &Account,
+    period: Period,
+) -> Result<String, JsValue> {
+    Ok(serde_json::json!(account.period_vs_previous(period)?).to_string())
+}
+
+/// Compares the balance of a resource with a bank statement and reconciles
+/// its orders once they match. Returns the outcome as JSON.
+/// Throws a `RequestFailure` on error.
+#[wasm_bindgen]
+pub fn reconcile_account_resource(
+    account: &mut Account,
+    resource: &str,
+    statement_balance: f64,
+    date: &str,
+) -> Result<String, JsValue> {
+    let statement_balance =
+        Amount::from_f64(statement_balance).ok_or(RequestFailure::IncorrectArgument)?;
+    let date = NaiveDate::from_str(date).map_err(|_| RequestFailure::IncorrectArgument)?;
+
+    Ok(serde_json::json!(account.reconcile(resource, statement_balance, date)?).to_string())
+}
+
+/// Returns sub-ledgers as JSON.
+#[wasm_bindgen]
+pub fn get_account_sub_ledgers(account: &Account) -> Array {
+    account
+        .sub_ledgers()
+        .iter()
+        .map(|ledger| JsValue::from(serde_json::json!(ledger).to_string()))
+        .collect()
+}
+
+/// Returns the overview of a sub-ledger within a date range as JSON.
+/// Throws a `RequestFailure` if the sub-ledger doesn't exist or if a sum
+/// overflows.
+#[wasm_bindgen]
+pub fn get_account_sub_ledger_report(
+    account: &Account,
+    name: &str,
+    start_date: &str,
+    end_date: &str,
+) -> Result<String, JsValue> {
+    let report = account.sub_ledger_report(
+        name,
+        OptionNaiveDateRange(
+            NaiveDate::from_str(start_date).ok(),
+            NaiveDate::from_str(end_date).ok(),
+        ),
+    )?;
 
-    JsValue::from(json_order.to_string())
+    Ok(serde_json::json!(report).to_string())
 }
 
 /// Returns the results of a category at a specific date.
+/// Throws `RequestFailure.Overflow` if a sum overflows.
 #[wasm_bindgen]
 pub fn get_account_absolute_category_amount_by_date(
     account: &Account,
     kind: CategoryType,
     category: &str,
     date: &str,
-) -> Option<CategoryAmount> {
-    account.orders().calculate_category_amount(
+) -> Result<Option<CategoryAmount>, JsValue> {
+    Ok(account.category_amount(
         kind,
         category,
         OptionNaiveDateRange(None, NaiveDate::from_str(date).ok()),
-    )
+    )?)
 }
 
 /// Returns the results of a category within a date range.
+/// Throws `RequestFailure.Overflow` if a sum overflows.
 #[wasm_bindgen]
 pub fn get_account_relative_category_amount_by_date(
     account: &Account,
@@ -339,15 +1675,15 @@ pub fn get_account_relative_category_amount_by_date(
     category: &str,
     start_date: &str,
     end_date: &str,
-) -> Option<CategoryAmount> {
-    account.orders().calculate_category_amount(
+) -> Result<Option<CategoryAmount>, JsValue> {
+    Ok(account.category_amount(
         kind,
         category,
         OptionNaiveDateRange(
             NaiveDate::from_str(start_date).ok(),
             NaiveDate::from_str(end_date).ok(),
         ),
-    )
+    )?)
 }
 
 #[cfg(test)]
@@ -362,7 +1698,7 @@ mod tests {
             .iter_mut()
             .enumerate()
             .for_each(|(id, order)| {
-                order.description = format!("Order {}", id);
+                order.description = format!("Order {}", id).into();
                 account.add_order();
                 account.get_order_mut(id).unwrap().description = order.description.clone();
             });
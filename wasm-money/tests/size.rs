@@ -0,0 +1,38 @@
+//! Size budget of the WebAssembly binary.
+//!
+//! Ignored by default as it needs a binary built for `wasm32-unknown-unknown`:
+//!
+//! ```zsh
+//! wasm-pack build wasm-money
+//! cargo test -p wasm-money --test size -- --ignored
+//! ```
+//!
+//! Set `MONEY_WASM_BINARY` to check another build. When the budget is
+//! exceeded, `twiggy top -n 20 <binary>` lists the largest functions.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::path::PathBuf;
+
+/// Maximum size of the release binary built with default features, in bytes.
+const BUDGET: u64 = 1536 * 1024;
+
+#[test]
+#[ignore]
+fn binary_within_budget() {
+    let path = std::env::var_os("MONEY_WASM_BINARY").map_or(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("pkg/wasm_money_bg.wasm"),
+        PathBuf::from,
+    );
+    let size = std::fs::metadata(&path)
+        .unwrap_or_else(|_| panic!("{} is missing, build it first", path.display()))
+        .len();
+
+    assert!(
+        size <= BUDGET,
+        "{} is {} bytes, {} over budget",
+        path.display(),
+        size,
+        size - BUDGET
+    );
+}
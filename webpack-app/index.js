@@ -109,7 +109,7 @@ const refreshCategoryList = (type) => {
     }
 
     if (error == false) {
-        const list = get_account_categories(account, categoryType)
+        const list = get_account_categories(account, categoryType, true)
         if (list != undefined) {
             // Combobox
             refreshCategoryCombobox(combobox, list)
@@ -170,7 +170,7 @@ const clearBalanceChart = () => {
 }
 
 const refreshBalanceChart = (days, account) => {
-    const resourceList = get_account_categories(account, resourceCategoryType)
+    const resourceList = get_account_categories(account, resourceCategoryType, true)
     const lastDayPreviousMonth = lastDayPreviousMonthString(new Date(days[0]))
     const allDays = [lastDayPreviousMonth, ...days]
     var totalAbsolutePrev = undefined
@@ -326,7 +326,7 @@ const clearResourceChart = () => {
 }
 
 const refreshResourceChart = (filteredPlots, sortedDates, account) => {
-    const resourceList = get_account_categories(account, resourceCategoryType)
+    const resourceList = get_account_categories(account, resourceCategoryType, true)
     const validDates = sortedDates.filter(date => date != null)
     const nbDates = validDates.length
     var total = []
@@ -474,7 +474,7 @@ const clearTagChart = () => {
 }
 
 const refreshTagChart = (filteredPlots, sortedDates, account) => {
-    const tagList = get_account_categories(account, tagCategoryType)
+    const tagList = get_account_categories(account, tagCategoryType, true)
     const validDates = sortedDates.filter(date => date != null)
     const nbDates = validDates.length
 
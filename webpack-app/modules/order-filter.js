@@ -197,8 +197,8 @@ const initDateRangeFilter = (account, filter, render_func) => {
             if (!filter.set_date_beginning(begin.value)) {
                 begin.value = ""
             }
-            refreshCategoryTable(account, "Resource", get_account_categories(account, resourceCategoryType), resourceCategoryType)
-            refreshCategoryTable(account, "Tag", get_account_categories(account, tagCategoryType), tagCategoryType)
+            refreshCategoryTable(account, "Resource", get_account_categories(account, resourceCategoryType, true), resourceCategoryType)
+            refreshCategoryTable(account, "Tag", get_account_categories(account, tagCategoryType, true), tagCategoryType)
             requestAnimationFrame(render_func)
         }
     })
@@ -214,8 +214,8 @@ const initDateRangeFilter = (account, filter, render_func) => {
             if (!filter.set_date_end(end.value)) {
                 end.value = ""
             }
-            refreshCategoryTable(account, "Resource", get_account_categories(account, resourceCategoryType), resourceCategoryType)
-            refreshCategoryTable(account, "Tag", get_account_categories(account, tagCategoryType), tagCategoryType)
+            refreshCategoryTable(account, "Resource", get_account_categories(account, resourceCategoryType, true), resourceCategoryType)
+            refreshCategoryTable(account, "Tag", get_account_categories(account, tagCategoryType, true), tagCategoryType)
             requestAnimationFrame(render_func)
         }
     })
@@ -246,7 +246,7 @@ const initFilter = (account, filter, render_func) => {
         if (!resourcesHideFilter) {
             resourcesFilterButton.textContent = "disable filter"
 
-            const resources = get_account_categories(account, categoryType)
+            const resources = get_account_categories(account, categoryType, true)
             set_filter_categories(filter, categoryType, resources)
             initCategoryFilter(filter, "Resource", resources, render_func)
         } else {
@@ -267,7 +267,7 @@ const initFilter = (account, filter, render_func) => {
         if (!tagsHideFilter) {
             tagsFilterButton.textContent = "disable filter"
 
-            const tags = get_account_categories(account, categoryType)
+            const tags = get_account_categories(account, categoryType, true)
             set_filter_categories(filter, categoryType, tags)
             initCategoryFilter(filter, "Tag", tags, render_func)
         } else {
@@ -290,8 +290,8 @@ const initFilter = (account, filter, render_func) => {
             dateFilterButton.textContent = "enable filter"
             removeChildNodesByTagName(dateCluster, "DIV")
             filter.disable_date_option()
-            refreshCategoryTable(account, "Resource", get_account_categories(account, resourceCategoryType), resourceCategoryType)
-            refreshCategoryTable(account, "Tag", get_account_categories(account, tagCategoryType), tagCategoryType)
+            refreshCategoryTable(account, "Resource", get_account_categories(account, resourceCategoryType, true), resourceCategoryType)
+            refreshCategoryTable(account, "Tag", get_account_categories(account, tagCategoryType, true), tagCategoryType)
             requestAnimationFrame(render_func)
         }
     })
@@ -35,8 +35,11 @@ const ordersTable = document.getElementById("orders")
 
 const addOrderRow = (order, account, filter, render_func) => {
     const row = ordersTable.insertRow()
-    const resourceList = get_account_categories(account, resourceCategoryType)
-    const tagList = get_account_categories(account, tagCategoryType)
+    const resourceList = get_account_categories(account, resourceCategoryType, true)
+    const tagList = get_account_categories(account, tagCategoryType, true)
+    // Archived categories are only offered to orders already using them
+    const resourceChoices = get_account_categories(account, resourceCategoryType, false)
+    const tagChoices = get_account_categories(account, tagCategoryType, false)
 
     // Date
     var date = document.createElement("input")
@@ -110,7 +113,7 @@ const addOrderRow = (order, account, filter, render_func) => {
     empty_option.text = "-"
     empty_option.disabled = true
     resource.appendChild(empty_option)
-    resourceList.forEach(function(item) {
+    resourceList.filter(item => resourceChoices.includes(item) || item == order.order.resource).forEach(function(item) {
         var option = document.createElement("option")
         option.value = item
         option.text = item
@@ -133,7 +136,7 @@ const addOrderRow = (order, account, filter, render_func) => {
     // Tags
     var tags = document.createElement("select")
     tags.multiple = true
-    tagList.forEach(function(item) {
+    tagList.filter(item => tagChoices.includes(item) || order.order.tags.includes(item)).forEach(function(item) {
         var option = document.createElement("option")
         option.value = item
         option.text = item